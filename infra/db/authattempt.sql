@@ -1,5 +0,0 @@
-CREATE TABLE authattempt (  
-    id VARCHAR(36) NOT NULL PRIMARY KEY,
-    phone VARCHAR(25),
-    created DATETIME
-);
\ No newline at end of file
@@ -1,8 +0,0 @@
-CREATE TABLE pic (  
-    id varchar(36) NOT NULL PRIMARY KEY,
-    review_id varchar(36),
-    created DATETIME,
-    pic TINYINT UNSIGNED,
-    width SMALLINT UNSIGNED,
-    height SMALLINT UNSIGNED
-);
\ No newline at end of file
@@ -1,3 +0,0 @@
-CREATE TABLE ping (  
-    id VARCHAR(36) NOT NULL PRIMARY KEY
-);
\ No newline at end of file
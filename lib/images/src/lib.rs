@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-pub use rusoto_core::{ByteStream, HttpClient};
+pub use rusoto_core::{ByteStream, HttpClient, RusotoError};
 use rusoto_credential::{AwsCredentials, ProvideAwsCredentials};
 pub use rusoto_s3::*;
 use rusoto_signature::Region;
@@ -202,9 +202,9 @@ pub fn validate_reply_text(text: &str) -> Result<(), String> {
 }
 
 /// ```
-/// assert!(validation::validate_reply_text("Test :D").is_ok());
-/// assert!(validation::validate_reply_text(&"1".repeat(451)).is_err());
-/// assert!(validation::validate_reply_text(&"1".repeat(450)).is_ok());
+/// assert!(validation::validate_review_text("Test :D").is_ok());
+/// assert!(validation::validate_review_text(&"1".repeat(451)).is_err());
+/// assert!(validation::validate_review_text(&"1".repeat(450)).is_ok());
 /// ```
 pub fn validate_review_text(text: &str) -> Result<(), String> {
     if text.chars().count() > 450 {
@@ -214,6 +214,37 @@ pub fn validate_review_text(text: &str) -> Result<(), String> {
     return Ok(());
 }
 
+/// ```
+/// assert!(validation::validate_report_title("App crashes on launch").is_ok());
+/// assert!(validation::validate_report_title("").is_err());
+/// assert!(validation::validate_report_title(&"1".repeat(64)).is_ok());
+/// assert!(validation::validate_report_title(&"1".repeat(65)).is_err());
+/// ```
+pub fn validate_report_title(title: &str) -> Result<(), String> {
+    if title.chars().count() > 64 {
+        return Err("title too long - max 64 chars".to_string());
+    }
+
+    if title.trim().is_empty() {
+        return Err("title is required".to_string());
+    }
+
+    return Ok(());
+}
+
+/// ```
+/// assert!(validation::validate_report_description("").is_ok());
+/// assert!(validation::validate_report_description(&"1".repeat(1024)).is_ok());
+/// assert!(validation::validate_report_description(&"1".repeat(1025)).is_err());
+/// ```
+pub fn validate_report_description(description: &str) -> Result<(), String> {
+    if description.chars().count() > 1024 {
+        return Err("description too long - max 1024 chars".to_string());
+    }
+
+    return Ok(());
+}
+
 /// ```
 /// assert!(validation::validate_name("Test :D").is_err());
 /// assert!(validation::validate_name("test").is_ok());
@@ -0,0 +1,135 @@
+-- Base tables as they stood before the first tracked migration.
+-- IF NOT EXISTS lets databases created before migrations were tracked record this version without changes.
+CREATE TABLE IF NOT EXISTS ping (
+  id varchar(36) NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE TABLE IF NOT EXISTS user (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  name varchar(30) NOT NULL,
+  display_name varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin NOT NULL,
+  phone varchar(25) NOT NULL,
+  pic_id varchar(36) NOT NULL,
+  device_token varchar(64) DEFAULT NULL,
+  email varchar(320) DEFAULT NULL,
+  disabled tinyint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id),
+  KEY idx_user_name (name),
+  KEY idx_user_phone (phone),
+  KEY idx_user_email (email)
+);
+
+CREATE TABLE IF NOT EXISTS phoneauth (
+  id varchar(36) NOT NULL,
+  phone varchar(25) NOT NULL,
+  created datetime NOT NULL,
+  ip varchar(45) NOT NULL,
+  code varchar(9) NOT NULL,
+  used tinyint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id),
+  KEY idx_phoneauth_phone (phone)
+);
+
+CREATE TABLE IF NOT EXISTS authattempt (
+  id varchar(36) NOT NULL,
+  phone varchar(25) NOT NULL,
+  created datetime NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_authattempt_phone (phone)
+);
+
+CREATE TABLE IF NOT EXISTS friend (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  friend_id varchar(36) NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_friend_user_id (user_id),
+  KEY idx_friend_friend_id (friend_id)
+);
+
+CREATE TABLE IF NOT EXISTS friendrequest (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  friend_id varchar(36) NOT NULL,
+  ignored tinyint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id),
+  KEY idx_friendrequest_user_id (user_id),
+  KEY idx_friendrequest_friend_id (friend_id)
+);
+
+CREATE TABLE IF NOT EXISTS review (
+  id varchar(36) NOT NULL,
+  user_id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  category varchar(30) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  text varchar(450) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  stars tinyint unsigned NOT NULL,
+  location_name varchar(96) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  location point NOT NULL /*!80003 SRID 0 */,
+  is_custom tinyint NOT NULL DEFAULT 0,
+  delivered tinyint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id),
+  KEY idx_review_location_name (location_name),
+  KEY idx_review_user_id (user_id),
+  KEY idx_review_category (category),
+  KEY idx_review_created (created),
+  SPATIAL KEY idx_review_location (location)
+);
+
+CREATE TABLE IF NOT EXISTS pic (
+  id varchar(36) NOT NULL,
+  review_id varchar(36) DEFAULT NULL,
+  created datetime NOT NULL,
+  pic_handler tinyint unsigned NOT NULL,
+  width smallint unsigned NOT NULL,
+  height smallint unsigned NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_pic_review_id (review_id)
+);
+
+CREATE TABLE IF NOT EXISTS likes (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_likes_user_id (user_id),
+  KEY idx_likes_review_id (review_id)
+);
+
+CREATE TABLE IF NOT EXISTS reply (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  text varchar(450) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  reply_to_id varchar(36) DEFAULT NULL,
+  PRIMARY KEY (id),
+  KEY idx_reply_review_id (review_id)
+);
+
+CREATE TABLE IF NOT EXISTS notification (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  review_user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  user_id varchar(36) NOT NULL,
+  action_type tinyint unsigned NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_notification_review_user_id (review_user_id)
+);
+
+CREATE TABLE IF NOT EXISTS reports (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  reporter_id varchar(36) NOT NULL,
+  report_type tinyint unsigned NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_reports_user_id (user_id),
+  KEY idx_reports_reporter_id (reporter_id)
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE `mob`.`phoneauth`
+MODIFY COLUMN `ip` VARCHAR(45);
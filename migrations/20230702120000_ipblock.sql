@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE ipblock (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  created_by varchar(36) NOT NULL,
+  action varchar(8) NOT NULL,
+  cidr varchar(49) DEFAULT NULL,
+  asn int unsigned DEFAULT NULL,
+  country varchar(2) DEFAULT NULL,
+  reason varchar(256) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  expires datetime DEFAULT NULL,
+  PRIMARY KEY (id),
+  KEY idx_ipblock_expires (expires)
+);
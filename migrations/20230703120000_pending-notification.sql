@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE pendingnotification (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  notification_value varchar(36) DEFAULT NULL,
+  message varchar(1024) CHARACTER SET utf8mb4 COLLATE utf8mb4_0900_ai_ci NOT NULL,
+  notification_type varchar(16) NOT NULL,
+  PRIMARY KEY (id)
+);
@@ -0,0 +1,16 @@
+-- Add migration script here
+ALTER TABLE review ADD COLUMN updated datetime DEFAULT NULL;
+UPDATE review SET updated = created;
+ALTER TABLE review MODIFY COLUMN updated datetime NOT NULL;
+CREATE INDEX idx_review_updated ON review (updated);
+
+CREATE TABLE tombstone (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  item_type varchar(16) NOT NULL,
+  item_id varchar(36) NOT NULL,
+  owner_id varchar(36) NOT NULL,
+  PRIMARY KEY (id),
+  KEY idx_tombstone_created (created),
+  KEY idx_tombstone_owner_id (owner_id)
+);
@@ -0,0 +1,14 @@
+-- Add migration script here
+CREATE TABLE idempotencykey (
+  id varchar(36) NOT NULL,
+  created datetime NOT NULL,
+  user_id varchar(36) NOT NULL,
+  idempotency_key varchar(255) NOT NULL,
+  fingerprint varchar(64) NOT NULL,
+  status smallint unsigned DEFAULT NULL,
+  headers text DEFAULT NULL,
+  body mediumblob DEFAULT NULL,
+  PRIMARY KEY (id),
+  UNIQUE KEY idx_idempotencykey_user_key (user_id, idempotency_key),
+  KEY idx_idempotencykey_created (created)
+);
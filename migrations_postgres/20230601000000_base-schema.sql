@@ -0,0 +1,194 @@
+-- The full schema as of the MySQL migrations up to pendingnotification, in one step.
+-- Flags stay SMALLINT rather than BOOLEAN so rows map onto the same types as MySQL.
+CREATE EXTENSION IF NOT EXISTS postgis;
+
+CREATE TABLE ping (
+  id varchar(36) NOT NULL,
+  PRIMARY KEY (id)
+);
+
+-- the row `/ping` and readiness checks read.
+INSERT INTO ping (id) VALUES ('123');
+
+CREATE TABLE "user" (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  name varchar(30) NOT NULL,
+  display_name varchar(30) NOT NULL,
+  phone varchar(25) NOT NULL,
+  pic_id varchar(36) NOT NULL,
+  device_token varchar(64) DEFAULT NULL,
+  email varchar(320) DEFAULT NULL,
+  disabled smallint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_user_name ON "user" (name);
+CREATE INDEX idx_user_phone ON "user" (phone);
+CREATE INDEX idx_user_email ON "user" (email);
+
+CREATE TABLE phoneauth (
+  id varchar(36) NOT NULL,
+  phone varchar(25) NOT NULL,
+  created timestamp NOT NULL,
+  ip varchar(45) NOT NULL,
+  code varchar(9) NOT NULL,
+  used smallint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_phoneauth_phone ON phoneauth (phone);
+
+CREATE TABLE authattempt (
+  id varchar(36) NOT NULL,
+  phone varchar(25) NOT NULL,
+  created timestamp NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_authattempt_phone ON authattempt (phone);
+
+CREATE TABLE friend (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  friend_id varchar(36) NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_friend_user_id ON friend (user_id);
+CREATE INDEX idx_friend_friend_id ON friend (friend_id);
+
+CREATE TABLE friendrequest (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  friend_id varchar(36) NOT NULL,
+  ignored smallint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_friendrequest_user_id ON friendrequest (user_id);
+CREATE INDEX idx_friendrequest_friend_id ON friendrequest (friend_id);
+
+CREATE TABLE review (
+  id varchar(36) NOT NULL,
+  user_id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  category varchar(30) NOT NULL,
+  text varchar(450) NOT NULL,
+  stars smallint NOT NULL,
+  location_name varchar(96) NOT NULL,
+  location geometry(Point) NOT NULL,
+  is_custom smallint NOT NULL DEFAULT 0,
+  delivered smallint NOT NULL DEFAULT 0,
+  recommended smallint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_review_location_name ON review (location_name);
+CREATE INDEX idx_review_user_id ON review (user_id);
+CREATE INDEX idx_review_category ON review (category);
+CREATE INDEX idx_review_created ON review (created);
+CREATE INDEX idx_review_user_id_recommended ON review (user_id, recommended);
+CREATE INDEX idx_review_location ON review USING GIST (location);
+
+CREATE TABLE pic (
+  id varchar(36) NOT NULL,
+  review_id varchar(36) DEFAULT NULL,
+  created timestamp NOT NULL,
+  pic_handler smallint NOT NULL,
+  width integer NOT NULL,
+  height integer NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_pic_review_id ON pic (review_id);
+
+CREATE TABLE likes (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  like_type smallint NOT NULL DEFAULT 0,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_likes_user_id ON likes (user_id);
+CREATE INDEX idx_likes_review_id ON likes (review_id);
+
+CREATE TABLE reply (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  text varchar(450) NOT NULL,
+  reply_to_id varchar(36) DEFAULT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_reply_review_id ON reply (review_id);
+
+CREATE TABLE notification (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  review_user_id varchar(36) NOT NULL,
+  review_id varchar(36) NOT NULL,
+  user_id varchar(36) NOT NULL,
+  action_type smallint NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_notification_review_user_id ON notification (review_user_id);
+
+CREATE TABLE reports (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  reporter_id varchar(36) NOT NULL,
+  report_type smallint NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_reports_user_id ON reports (user_id);
+CREATE INDEX idx_reports_reporter_id ON reports (reporter_id);
+
+CREATE TABLE bookmark (
+  id varchar(36) NOT NULL,
+  user_id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  category varchar(30) NOT NULL,
+  location_name varchar(96) NOT NULL,
+  location geometry(Point) NOT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_bookmark_location_name ON bookmark (location_name);
+CREATE INDEX idx_bookmark_user_id ON bookmark (user_id);
+CREATE INDEX idx_bookmark_created ON bookmark (created);
+CREATE INDEX idx_bookmark_location ON bookmark USING GIST (location);
+
+CREATE TABLE ipblock (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  created_by varchar(36) NOT NULL,
+  action varchar(8) NOT NULL,
+  cidr varchar(49) DEFAULT NULL,
+  asn bigint DEFAULT NULL,
+  country varchar(2) DEFAULT NULL,
+  reason varchar(256) NOT NULL,
+  expires timestamp DEFAULT NULL,
+  PRIMARY KEY (id)
+);
+
+CREATE INDEX idx_ipblock_expires ON ipblock (expires);
+
+CREATE TABLE pendingnotification (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  notification_value varchar(36) DEFAULT NULL,
+  message varchar(1024) NOT NULL,
+  notification_type varchar(16) NOT NULL,
+  PRIMARY KEY (id)
+);
@@ -0,0 +1,16 @@
+-- Matches the MySQL sync migration: `review.updated` and the tombstone table `/sync` reads.
+ALTER TABLE review ADD COLUMN updated timestamp DEFAULT NULL;
+UPDATE review SET updated = created;
+ALTER TABLE review ALTER COLUMN updated SET NOT NULL;
+CREATE INDEX idx_review_updated ON review (updated);
+
+CREATE TABLE tombstone (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  item_type varchar(16) NOT NULL,
+  item_id varchar(36) NOT NULL,
+  owner_id varchar(36) NOT NULL,
+  PRIMARY KEY (id)
+);
+CREATE INDEX idx_tombstone_created ON tombstone (created);
+CREATE INDEX idx_tombstone_owner_id ON tombstone (owner_id);
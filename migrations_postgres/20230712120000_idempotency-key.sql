@@ -0,0 +1,14 @@
+-- Matches the MySQL idempotency key migration: stored responses replayed for a retried `Idempotency-Key`.
+CREATE TABLE idempotencykey (
+  id varchar(36) NOT NULL,
+  created timestamp NOT NULL,
+  user_id varchar(36) NOT NULL,
+  idempotency_key varchar(255) NOT NULL,
+  fingerprint varchar(64) NOT NULL,
+  status integer DEFAULT NULL,
+  headers text DEFAULT NULL,
+  body bytea DEFAULT NULL,
+  PRIMARY KEY (id),
+  UNIQUE (user_id, idempotency_key)
+);
+CREATE INDEX idx_idempotencykey_created ON idempotencykey (created);
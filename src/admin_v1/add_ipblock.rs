@@ -0,0 +1,169 @@
+use crate::{
+    admin_v1::{AdminPermission, AdminUser, IpBlockPub},
+    client_ip::parse_ip_net,
+    db::IpBlock,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    ipblock::{IpBlockAction, IpBlockStore},
+    repo::IpBlockRepo,
+    tracing::add_error_span,
+};
+use actix_web::{
+    post,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Ten years, well past any block worth setting and far from what a `Duration` can hold.
+const MAX_EXPIRES_IN_SECONDS: i64 = 10 * 365 * 24 * 60 * 60;
+
+#[derive(Deserialize, ToSchema)]
+pub struct AddIpBlockRequest {
+    /// Either `deny` or `allow`.
+    action: String,
+    /// An ip address or CIDR.
+    cidr: Option<String>,
+    /// An autonomous system number.
+    asn: Option<u32>,
+    /// A two letter ISO country code.
+    country: Option<String>,
+    reason: String,
+    /// Seconds until the rule stops applying, up to ten years. Never expires when missing.
+    expires_in_seconds: Option<i64>,
+}
+
+/// Adds a deny or allow rule for auth traffic.
+/// Exactly one of `cidr`, `asn`, or `country` must be set.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "created block", body = IpBlockPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/ipblock")]
+pub async fn add_ipblock(
+    admin_user: ReqData<AdminUser>,
+    ipblock_repo: Data<dyn IpBlockRepo>,
+    store: Data<IpBlockStore>,
+    add_request: Json<AddIpBlockRequest>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Moderate) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing moderate permission",
+        ));
+    }
+
+    let action = match IpBlockAction::parse(&add_request.action) {
+        Some(action) => action,
+        None => {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidInput,
+                "action must be deny or allow",
+            ))
+        }
+    };
+
+    let targets = [
+        add_request.cidr.is_some(),
+        add_request.asn.is_some(),
+        add_request.country.is_some(),
+    ];
+    if targets.iter().filter(|is_set| **is_set).count() != 1 {
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidInput,
+            "exactly one of cidr, asn, or country must be set",
+        ));
+    }
+
+    let cidr = match &add_request.cidr {
+        Some(cidr) => match parse_ip_net(cidr) {
+            Some(net) => Some(net.to_string()),
+            None => {
+                return Err(AppError::bad_request(
+                    ErrorCode::InvalidInput,
+                    "invalid cidr",
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let country = match &add_request.country {
+        Some(country) => {
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(AppError::bad_request(
+                    ErrorCode::InvalidInput,
+                    "country must be a two letter code",
+                ));
+            }
+            Some(country.to_uppercase())
+        }
+        None => None,
+    };
+
+    let reason = add_request.reason.trim();
+    if reason.is_empty() || reason.chars().count() > 256 {
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidInput,
+            "reason must be between 1 and 256 characters",
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+
+    let expires = match add_request.expires_in_seconds {
+        Some(seconds) if seconds > 0 && seconds <= MAX_EXPIRES_IN_SECONDS => {
+            match now.checked_add_signed(Duration::seconds(seconds)) {
+                Some(expires) => Some(expires),
+                None => {
+                    return Err(AppError::bad_request(
+                        ErrorCode::InvalidInput,
+                        "expires_in_seconds is too far in the future",
+                    ))
+                }
+            }
+        }
+        Some(_) => {
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidInput,
+                format!(
+                    "expires_in_seconds must be between 1 and {}",
+                    MAX_EXPIRES_IN_SECONDS
+                ),
+            ))
+        }
+        None => None,
+    };
+
+    let ipblock = IpBlock {
+        id: Uuid::new_v4().to_string(),
+        created: now,
+        created_by: admin_user.user_id.clone(),
+        action: action.as_str().to_string(),
+        cidr,
+        asn: add_request.asn,
+        country,
+        reason: reason.to_string(),
+        expires,
+    };
+
+    if let Err(error) = ipblock_repo.create_ipblock(&ipblock).await {
+        return Err(AppError::internal("failed to create ipblock", error));
+    }
+
+    // the background refresh will pick this up eventually if this fails.
+    if let Err(error) = store.refresh(ipblock_repo.get_ref()).await {
+        add_error_span(&error);
+    }
+
+    let ipblock_pub: IpBlockPub = ipblock.into();
+    return Ok(Json(ipblock_pub));
+}
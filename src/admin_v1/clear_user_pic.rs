@@ -0,0 +1,75 @@
+use crate::{
+    admin_v1::{AdminPermission, AdminUser},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    pic_v1::shared_utils::best_effort_delete_pic,
+    repo::{PicRepo, UserRepo},
+};
+use actix_web::{
+    post,
+    web::{Data, Query, ReqData},
+    HttpResponse, Responder, Result,
+};
+use images::{S3Client, DEFAULT_PIC_ID};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClearUserPicRequest {
+    user_id: String,
+}
+
+/// Sets a users profile pic back to the default and removes the old pic.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "pic was cleared"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/user/clear_pic")]
+pub async fn clear_user_pic(
+    admin_user: ReqData<AdminUser>,
+    user_repo: Data<dyn UserRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    s3_client: Data<S3Client>,
+    clear_request: Query<ClearUserPicRequest>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Moderate) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing moderate permission",
+        ));
+    }
+
+    let previous_pic_id: String;
+    match user_repo.get_user(&clear_request.user_id).await {
+        Ok(user_opt) => {
+            if let Some(user) = user_opt {
+                previous_pic_id = user.pic_id;
+            } else {
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
+            }
+        }
+        Err(error) => {
+            return Err(AppError::internal("unable to get user", error));
+        }
+    }
+
+    if let Err(error) = user_repo
+        .update_user_pic_id(DEFAULT_PIC_ID, &clear_request.user_id)
+        .await
+    {
+        return Err(AppError::internal("unable to clear profile pic", error));
+    }
+
+    best_effort_delete_pic(&s3_client, pic_repo.get_ref(), &previous_pic_id).await; // best effort - we can clean up stored images later
+
+    return Ok(HttpResponse::Ok().finish());
+}
@@ -1,20 +1,39 @@
-use crate::{admin_v1::ReportPub, authorization::AuthenticatedUser, db};
+use crate::{
+    admin_v1::{AdminPermission, AdminUser, ReportPub},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::ReportRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, ReqData},
     Responder, Result,
 };
-use sqlx::MySqlPool;
 
 /// Searches for users by name.
 /// Returns a list of the results.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "every filed report", body = [ReportPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/all_reports")]
 pub async fn get_all_reports(
-    _authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let report_res = db::get_all_reports(&pool).await;
+    admin_user: ReqData<AdminUser>,
+    report_repo: Data<dyn ReportRepo>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Inspect) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing inspect permission",
+        ));
+    }
+
+    let report_res = report_repo.get_all_reports().await;
 
     match report_res {
         Ok(results) => {
@@ -24,6 +43,6 @@ pub async fn get_all_reports(
                 .collect();
             Ok(Json(reports_pub))
         }
-        Err(_) => return Err(ErrorInternalServerError("could not fetch reports")),
+        Err(error) => return Err(AppError::internal("could not fetch reports", error)),
     }
 }
@@ -0,0 +1,47 @@
+use crate::{
+    admin_v1::{AdminPermission, AdminUser, IpBlockPub},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::IpBlockRepo,
+};
+use actix_web::{
+    get,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+
+/// Gets every ipblock rule, including expired ones.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "every block, including expired ones", body = [IpBlockPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[get("/ipblock")]
+pub async fn get_ipblocks(
+    admin_user: ReqData<AdminUser>,
+    ipblock_repo: Data<dyn IpBlockRepo>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Inspect) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing inspect permission",
+        ));
+    }
+
+    match ipblock_repo.get_all_ipblocks().await {
+        Ok(ipblocks) => {
+            let ipblocks_pub: Vec<IpBlockPub> = ipblocks
+                .into_iter()
+                .map(|f| -> IpBlockPub { f.into() })
+                .collect();
+            return Ok(Json(ipblocks_pub));
+        }
+        Err(error) => {
+            return Err(AppError::internal("could not fetch ipblocks", error));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    admin_v1::{AdminPermission, AdminUser, MigrationPub, SchemaPub},
+    db::SchemaMigration,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::DatabaseRepo,
+};
+use actix_web::{
+    get,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+use sqlx::migrate::Migrator;
+
+/// Reports which migrations the database has applied,
+/// compared against the migrations embedded in this build.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "migration history of the database", body = SchemaPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[get("/schema")]
+pub async fn get_schema(
+    admin_user: ReqData<AdminUser>,
+    database_repo: Data<dyn DatabaseRepo>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Inspect) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing inspect permission",
+        ));
+    }
+
+    match database_repo.get_applied_migrations().await {
+        Ok(applied) => {
+            return Ok(Json(build_schema_pub(database_repo.migrator(), applied)));
+        }
+        Err(error) => {
+            return Err(AppError::internal("could not fetch migrations", error));
+        }
+    }
+}
+
+fn build_schema_pub(migrator: &Migrator, applied: Vec<SchemaMigration>) -> SchemaPub {
+    let mut applied: BTreeMap<i64, SchemaMigration> = applied
+        .into_iter()
+        .map(|migration| (migration.version, migration))
+        .collect();
+
+    let mut migrations: Vec<MigrationPub> = migrator
+        .iter()
+        .map(|embedded| match applied.remove(&embedded.version) {
+            Some(migration) => MigrationPub {
+                version: embedded.version,
+                description: embedded.description.to_string(),
+                embedded: true,
+                applied: true,
+                success: Some(migration.success),
+                installed: Some(migration.installed_on.timestamp_millis()),
+                execution_ms: Some(migration.execution_time / 1_000_000),
+                checksum_matches: Some(migration.checksum == embedded.checksum.as_ref()),
+            },
+            None => MigrationPub {
+                version: embedded.version,
+                description: embedded.description.to_string(),
+                embedded: true,
+                applied: false,
+                success: None,
+                installed: None,
+                execution_ms: None,
+                checksum_matches: None,
+            },
+        })
+        .collect();
+
+    // anything left was applied by a newer build, or from a file that has since been removed.
+    migrations.extend(applied.into_values().map(|migration| MigrationPub {
+        version: migration.version,
+        description: migration.description,
+        embedded: false,
+        applied: true,
+        success: Some(migration.success),
+        installed: Some(migration.installed_on.timestamp_millis()),
+        execution_ms: Some(migration.execution_time / 1_000_000),
+        checksum_matches: None,
+    }));
+
+    migrations.sort_by_key(|migration| migration.version);
+
+    let up_to_date = migrations.iter().all(|migration| {
+        !migration.embedded
+            || (migration.applied
+                && migration.success == Some(true)
+                && migration.checksum_matches == Some(true))
+    });
+
+    return SchemaPub {
+        up_to_date,
+        migrations,
+    };
+}
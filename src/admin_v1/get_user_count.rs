@@ -1,30 +1,50 @@
-use crate::{authorization::AuthenticatedUser, db::get_total_user_count};
+use crate::{
+    admin_v1::{AdminPermission, AdminUser},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::UserRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Serialize;
-use sqlx::MySqlPool;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
-struct Count {
+#[derive(Serialize, ToSchema)]
+pub struct Count {
     count: i64,
 }
 
 /// Gets the total users registered for the app.
+#[utoipa::path(
+    context_path = "/admin/",
+    tag = "admin",
+    responses(
+        (status = 200, description = "total number of users", body = Count),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("total_user_count")]
 pub async fn get_user_count(
-    _authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let reply_res = get_total_user_count(&pool).await;
+    admin_user: ReqData<AdminUser>,
+    user_repo: Data<dyn UserRepo>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Inspect) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing inspect permission",
+        ));
+    }
+
+    let reply_res = user_repo.get_total_user_count().await;
 
     match reply_res {
         Ok(count) => {
             return Ok(Json(Count { count }));
         }
-        Err(_) => return Err(ErrorInternalServerError("unable to get likes".to_string())),
+        Err(error) => return Err(AppError::internal("unable to get likes", error)),
     }
 }
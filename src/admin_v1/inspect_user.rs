@@ -0,0 +1,166 @@
+use crate::{
+    admin_v1::{
+        AdminPermission, AdminUser, AuthAttemptPub, PhoneAuthPub, ReportPub, UserInspectionPub,
+    },
+    db::User,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    friend_v1::FriendPub,
+    repo::{AuthRepo, FriendRepo, ReportRepo, ReviewRepo, UserRepo},
+    review_v1::ReviewPub,
+};
+use actix_web::{
+    get,
+    web::{Data, Json, Query, ReqData},
+    Responder, Result,
+};
+use serde::Deserialize;
+use sqlx::Error;
+use utoipa::IntoParams;
+
+/// How many of each recent record type to include.
+const RECENT_LIMIT: u32 = 20;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InspectUserRequest {
+    id: Option<String>,
+    name: Option<String>,
+    phone: Option<String>,
+    email: Option<String>,
+}
+
+/// Looks up a user by id, name, phone, or email for support requests.
+/// Returns the profile alongside recent auth activity, friends, reports, and reviews.
+/// This is read-only; account changes have their own admin routes.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "account details for the matched user", body = UserInspectionPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[get("/user")]
+pub async fn inspect_user(
+    admin_user: ReqData<AdminUser>,
+    user_repo: Data<dyn UserRepo>,
+    auth_repo: Data<dyn AuthRepo>,
+    friend_repo: Data<dyn FriendRepo>,
+    review_repo: Data<dyn ReviewRepo>,
+    report_repo: Data<dyn ReportRepo>,
+    inspect_request: Query<InspectUserRequest>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Inspect) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing inspect permission",
+        ));
+    }
+
+    let user_res = if let Some(id) = &inspect_request.id {
+        user_repo.get_user(id).await
+    } else if let Some(name) = &inspect_request.name {
+        user_repo.get_user_from_name(&name.to_lowercase()).await
+    } else if let Some(phone) = &inspect_request.phone {
+        user_repo.get_user_by_phone(phone).await
+    } else if let Some(email) = &inspect_request.email {
+        user_repo.get_user_by_email(email).await
+    } else {
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidInput,
+            "one of id, name, phone, or email is required",
+        ));
+    };
+
+    let user: User;
+    match user_res {
+        Ok(user_opt) => {
+            if let Some(user_tmp) = user_opt {
+                user = user_tmp;
+            } else {
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
+            }
+        }
+        Err(error) => {
+            return Err(AppError::internal("unable to get user", error));
+        }
+    }
+
+    match gather_user_inspection(
+        user_repo.get_ref(),
+        auth_repo.get_ref(),
+        friend_repo.get_ref(),
+        review_repo.get_ref(),
+        report_repo.get_ref(),
+        user,
+    )
+    .await
+    {
+        Ok(inspection) => Ok(Json(inspection)),
+        Err(error) => {
+            return Err(AppError::internal(
+                "failed to gather user inspection",
+                error,
+            ));
+        }
+    }
+}
+
+async fn gather_user_inspection(
+    user_repo: &dyn UserRepo,
+    auth_repo: &dyn AuthRepo,
+    friend_repo: &dyn FriendRepo,
+    review_repo: &dyn ReviewRepo,
+    report_repo: &dyn ReportRepo,
+    user: User,
+) -> Result<UserInspectionPub, Error> {
+    let counts = user_repo.get_user_activity_counts(&user.id).await?;
+
+    let recent_phoneauths = auth_repo
+        .get_recent_phoneauths(&user.phone, RECENT_LIMIT)
+        .await?;
+    let recent_authattempts = auth_repo
+        .get_recent_authattempts(&user.phone, RECENT_LIMIT)
+        .await?;
+
+    let friends = friend_repo.get_current_friends(&user.id).await?;
+    let reports_filed = report_repo.get_reports_filed(&user.id).await?;
+    let reports_received = report_repo.get_reports_received(&user.id).await?;
+    let recent_reviews = review_repo
+        .get_recent_reviews_by_user(&user.id, RECENT_LIMIT)
+        .await?;
+
+    return Ok(UserInspectionPub {
+        user: user.into(),
+        counts: counts.into(),
+        recent_phoneauths: recent_phoneauths
+            .into_iter()
+            .map(|f| -> PhoneAuthPub { f.into() })
+            .collect(),
+        recent_authattempts: recent_authattempts
+            .into_iter()
+            .map(|f| -> AuthAttemptPub { f.into() })
+            .collect(),
+        friends: friends
+            .into_iter()
+            .map(|f| -> FriendPub { f.into() })
+            .collect(),
+        reports_filed: reports_filed
+            .into_iter()
+            .map(|f| -> ReportPub { f.into() })
+            .collect(),
+        reports_received: reports_received
+            .into_iter()
+            .map(|f| -> ReportPub { f.into() })
+            .collect(),
+        recent_reviews: recent_reviews
+            .into_iter()
+            .map(|f| -> ReviewPub { f.into() })
+            .collect(),
+    });
+}
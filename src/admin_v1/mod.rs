@@ -4,5 +4,29 @@ pub mod get_user_count;
 pub use get_all_reports::*;
 pub mod get_all_reports;
 
+pub use inspect_user::*;
+pub mod inspect_user;
+
+pub use reset_user_name::*;
+pub mod reset_user_name;
+
+pub use clear_user_pic::*;
+pub mod clear_user_pic;
+
+pub use get_ipblocks::*;
+pub mod get_ipblocks;
+
+pub use add_ipblock::*;
+pub mod add_ipblock;
+
+pub use remove_ipblock::*;
+pub mod remove_ipblock;
+
+pub use get_schema::*;
+pub mod get_schema;
+
+pub use permissions::*;
+pub mod permissions;
+
 pub use types::*;
 pub mod types;
@@ -0,0 +1,43 @@
+/// Permissions an admin can be granted.
+/// Read-only support tooling is kept separate from actions that change another users account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdminPermission {
+    /// View reports, counts, and inspect user accounts.
+    Inspect,
+
+    /// Modify another users account, such as resetting their name or pic.
+    Moderate,
+}
+
+/// A user who has passed authentication checks and is listed as an admin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminUser {
+    /// The user_id for the admin.
+    pub user_id: String,
+
+    /// Everything this admin is allowed to do.
+    pub permissions: Vec<AdminPermission>,
+}
+
+impl AdminUser {
+    pub fn has_permission(&self, permission: AdminPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+/// Only I am allowed uwuu~~~ 🥰 - add more entries here if more admins are needed.
+const ADMINS: [(&str, &[AdminPermission]); 1] = [(
+    "70bf5ab0-a51a-4f2a-b07d-009f571f62da",
+    &[AdminPermission::Inspect, AdminPermission::Moderate],
+)];
+
+/// Gets the admin record for a user_id, and will return `None` if the user is not an admin.
+pub fn get_admin_user(user_id: &str) -> Option<AdminUser> {
+    ADMINS
+        .iter()
+        .find(|(admin_id, _)| *admin_id == user_id)
+        .map(|(admin_id, permissions)| AdminUser {
+            user_id: admin_id.to_string(),
+            permissions: permissions.to_vec(),
+        })
+}
@@ -0,0 +1,65 @@
+use crate::{
+    admin_v1::{AdminPermission, AdminUser},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    ipblock::IpBlockStore,
+    repo::IpBlockRepo,
+    tracing::add_error_span,
+};
+use actix_web::{
+    post,
+    web::{Data, Query, ReqData},
+    HttpResponse, Responder, Result,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RemoveIpBlockRequest {
+    id: String,
+}
+
+/// Removes an ipblock rule, taking effect immediately on this instance.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "block was removed"),
+        (status = 404, description = "no block has the id"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/ipblock/remove")]
+pub async fn remove_ipblock(
+    admin_user: ReqData<AdminUser>,
+    ipblock_repo: Data<dyn IpBlockRepo>,
+    store: Data<IpBlockStore>,
+    remove_request: Query<RemoveIpBlockRequest>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Moderate) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing moderate permission",
+        ));
+    }
+
+    match ipblock_repo.remove_ipblock(&remove_request.id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(AppError::not_found(
+                ErrorCode::IpBlockNotFound,
+                "no ipblock with that id",
+            ))
+        }
+        Err(error) => return Err(AppError::internal("failed to remove ipblock", error)),
+    }
+
+    // the background refresh will pick this up eventually if this fails.
+    if let Err(error) = store.refresh(ipblock_repo.get_ref()).await {
+        add_error_span(&error);
+    }
+
+    return Ok(HttpResponse::Ok().finish());
+}
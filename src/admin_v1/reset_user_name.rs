@@ -0,0 +1,72 @@
+use crate::{
+    admin_v1::{AdminPermission, AdminUser},
+    auth::get_new_user_name,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::UserRepo,
+};
+use actix_web::{
+    post,
+    web::{Data, Query, ReqData},
+    HttpResponse, Responder, Result,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResetUserNameRequest {
+    user_id: String,
+}
+
+/// Resets a users name and display name to a generated default.
+/// Used when a user picks something offensive.
+#[utoipa::path(
+    context_path = "/admin",
+    tag = "admin",
+    responses(
+        (status = 200, description = "name was reset"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/user/reset_name")]
+pub async fn reset_user_name(
+    admin_user: ReqData<AdminUser>,
+    user_repo: Data<dyn UserRepo>,
+    reset_request: Query<ResetUserNameRequest>,
+) -> Result<impl Responder, AppError> {
+    if !admin_user.has_permission(AdminPermission::Moderate) {
+        return Err(AppError::forbidden(
+            ErrorCode::MissingPermission,
+            "missing moderate permission",
+        ));
+    }
+
+    match user_repo.does_user_exist(&reset_request.user_id).await {
+        Ok(exists) => {
+            if !exists {
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
+            }
+        }
+        Err(error) => {
+            return Err(AppError::internal("unable to get user", error));
+        }
+    }
+
+    let new_name = get_new_user_name();
+
+    let update_res = user_repo
+        .update_usernames(&reset_request.user_id, &new_name, &new_name)
+        .await;
+
+    match update_res {
+        Ok(_) => return Ok(HttpResponse::Ok().finish()),
+        Err(error) => {
+            return Err(AppError::internal("failed to reset user name", error));
+        }
+    }
+}
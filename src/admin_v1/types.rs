@@ -1,11 +1,17 @@
-use crate::db::Report;
+use crate::{
+    db::{AuthAttempt, IpBlock, PhoneAuth, Report, User, UserActivityCounts},
+    friend_v1::FriendPub,
+    pic_v1::get_digital_ocean_url,
+    review_v1::ReviewPub,
+};
 use chrono::NaiveDateTime;
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReportPub {
     /// Guid unique identifier.
     pub id: String,
@@ -30,3 +36,177 @@ impl From<Report> for ReportPub {
         }
     }
 }
+
+/// Full view of a user for support requests.
+/// Unlike `UserPub` this exposes contact details, so it must only be returned from admin routes.
+#[derive(Serialize, ToSchema)]
+pub struct AdminUserPub {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+    pub phone: String,
+    pub email: Option<String>,
+    pub created: i64,
+    pub pic_id: String,
+    pub pic_url: String,
+    pub disabled: bool,
+    pub has_device_token: bool,
+}
+
+impl From<User> for AdminUserPub {
+    fn from(user: User) -> AdminUserPub {
+        AdminUserPub {
+            id: user.id,
+            name: user.name,
+            display_name: user.display_name,
+            phone: user.phone,
+            email: user.email,
+            created: user.created.timestamp_millis(),
+            pic_url: get_digital_ocean_url(&user.pic_id),
+            pic_id: user.pic_id,
+            disabled: user.disabled == 1,
+            has_device_token: user.device_token.is_some(),
+        }
+    }
+}
+
+/// DB Types are purposefuly not serialized.
+/// We require DTO objects suffixed with 'Pub'
+/// to trim database object appropriately.
+#[derive(Serialize, ToSchema)]
+pub struct UserActivityCountsPub {
+    pub reviews: i64,
+    pub friends: i64,
+    pub incoming_requests: i64,
+    pub outgoing_requests: i64,
+    pub likes: i64,
+    pub replies: i64,
+    pub bookmarks: i64,
+    pub notifications: i64,
+}
+
+impl From<UserActivityCounts> for UserActivityCountsPub {
+    fn from(counts: UserActivityCounts) -> UserActivityCountsPub {
+        UserActivityCountsPub {
+            reviews: counts.reviews,
+            friends: counts.friends,
+            incoming_requests: counts.incoming_requests,
+            outgoing_requests: counts.outgoing_requests,
+            likes: counts.likes,
+            replies: counts.replies,
+            bookmarks: counts.bookmarks,
+            notifications: counts.notifications,
+        }
+    }
+}
+
+/// DB Types are purposefuly not serialized.
+/// The auth code is intentionally left out, admins never need to see it.
+#[derive(Serialize, ToSchema)]
+pub struct PhoneAuthPub {
+    pub id: String,
+    pub created: i64,
+    pub ip: String,
+    pub used: bool,
+}
+
+impl From<PhoneAuth> for PhoneAuthPub {
+    fn from(phone_auth: PhoneAuth) -> PhoneAuthPub {
+        PhoneAuthPub {
+            id: phone_auth.id,
+            created: phone_auth.created.timestamp_millis(),
+            ip: phone_auth.ip,
+            used: phone_auth.used == 1,
+        }
+    }
+}
+
+/// DB Types are purposefuly not serialized.
+/// We require DTO objects suffixed with 'Pub'
+/// to trim database object appropriately.
+#[derive(Serialize, ToSchema)]
+pub struct AuthAttemptPub {
+    pub id: String,
+    pub created: i64,
+}
+
+impl From<AuthAttempt> for AuthAttemptPub {
+    fn from(auth_attempt: AuthAttempt) -> AuthAttemptPub {
+        AuthAttemptPub {
+            id: auth_attempt.id,
+            created: auth_attempt.created.timestamp_millis(),
+        }
+    }
+}
+
+/// All in one return type for inspecting a user account.
+#[derive(Serialize, ToSchema)]
+pub struct UserInspectionPub {
+    pub user: AdminUserPub,
+    pub counts: UserActivityCountsPub,
+    pub recent_phoneauths: Vec<PhoneAuthPub>,
+    pub recent_authattempts: Vec<AuthAttemptPub>,
+    pub friends: Vec<FriendPub>,
+    pub reports_filed: Vec<ReportPub>,
+    pub reports_received: Vec<ReportPub>,
+    pub recent_reviews: Vec<ReviewPub>,
+}
+
+/// DB Types are purposefuly not serialized.
+/// We require DTO objects suffixed with 'Pub'
+/// to trim database object appropriately.
+#[derive(Serialize, ToSchema)]
+pub struct IpBlockPub {
+    pub id: String,
+    pub created: i64,
+    pub created_by: String,
+    pub action: String,
+    pub cidr: Option<String>,
+    pub asn: Option<u32>,
+    pub country: Option<String>,
+    pub reason: String,
+    pub expires: Option<i64>,
+}
+
+impl From<IpBlock> for IpBlockPub {
+    fn from(ipblock: IpBlock) -> IpBlockPub {
+        IpBlockPub {
+            id: ipblock.id,
+            created: ipblock.created.timestamp_millis(),
+            created_by: ipblock.created_by,
+            action: ipblock.action,
+            cidr: ipblock.cidr,
+            asn: ipblock.asn,
+            country: ipblock.country,
+            reason: ipblock.reason,
+            expires: ipblock.expires.map(|expires| expires.timestamp_millis()),
+        }
+    }
+}
+
+/// DB Types are purposefuly not serialized.
+/// We require DTO objects suffixed with 'Pub'
+/// to trim database object appropriately.
+#[derive(Serialize, ToSchema)]
+pub struct MigrationPub {
+    pub version: i64,
+    pub description: String,
+    /// Whether this build ships the migration.
+    pub embedded: bool,
+    /// Whether the database has a record of the migration.
+    pub applied: bool,
+    /// False when the migration failed part way through.
+    pub success: Option<bool>,
+    pub installed: Option<i64>,
+    pub execution_ms: Option<i64>,
+    /// False when the applied migration differs from the one this build ships.
+    pub checksum_matches: Option<bool>,
+}
+
+/// Migration history for the connected database, compared against this build.
+#[derive(Serialize, ToSchema)]
+pub struct SchemaPub {
+    /// True when every embedded migration is applied successfully with a matching checksum.
+    pub up_to_date: bool,
+    pub migrations: Vec<MigrationPub>,
+}
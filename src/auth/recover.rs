@@ -1,55 +1,80 @@
 use crate::{
-    db::{create_phoneauth, get_current_phoneauths, get_user_by_phone, User},
+    client_ip::ClientIp,
+    db::User,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{AuthRepo, UserRepo},
+    tracing::WithTraceContext,
     Config,
 };
-use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
-    post,
-    web::{Data, Query},
-    HttpResponse, Result,
-};
+use actix_web::{post, web::Data, HttpResponse, Result};
 use opentelemetry::{
     global,
     trace::{Span, Status, Tracer},
 };
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use validation;
 
 use super::get_new_auth_code;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RequestCodeRequest {
     phone: String,
 }
 
+impl Validate for RequestCodeRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("phone", validation::validate_phone(&self.phone));
+
+        errors.finish()
+    }
+}
+
 /// Endpoint for requesting an auth code for recovery.
 /// The auth code is sent to the accounts email.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(RequestCodeRequest),
+    responses(
+        (status = 200, description = "recovery email was sent"),
+        ErrorResponses
+    )
+)]
 #[post("/recovery_code")]
 pub async fn recovery_code(
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    auth_repo: Data<dyn AuthRepo>,
     config: Data<Config>,
     http_client: Data<Client>,
-    request_code_request: Query<RequestCodeRequest>,
-) -> Result<HttpResponse> {
-    let valid_phone = validation::validate_phone(&request_code_request.phone);
-    if let Err(phone_err) = valid_phone {
-        return Err(ErrorBadRequest(phone_err));
-    }
-
-    let phoneauths_res = get_current_phoneauths(&pool, &request_code_request.phone).await;
+    client_ip: ClientIp,
+    request_code_request: ValidQuery<RequestCodeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let phoneauths_res = auth_repo
+        .get_current_phoneauths(&request_code_request.phone)
+        .await;
 
     match phoneauths_res {
         Ok(phoneauths) => {
             if phoneauths.len() >= 3 {
-                return Err(ErrorBadRequest("too many auth attempts"));
+                return Err(AppError::bad_request(
+                    ErrorCode::TooManyAuthAttempts,
+                    "too many auth attempts",
+                ));
             }
         }
-        Err(_) => return Err(ErrorInternalServerError("unable to fetch auths")),
+        Err(error) => return Err(AppError::internal("unable to fetch auths", error)),
     }
 
-    let user_res = get_user_by_phone(&pool, &request_code_request.phone).await;
+    let user_res = user_repo
+        .get_user_by_phone(&request_code_request.phone)
+        .await;
 
     let existing_user: User;
 
@@ -58,11 +83,14 @@ pub async fn recovery_code(
             if let Some(user) = user_opt {
                 existing_user = user;
             } else {
-                return Err(ErrorBadRequest("user doesn't exist"));
+                return Err(AppError::bad_request(
+                    ErrorCode::UserNotFound,
+                    "user doesn't exist",
+                ));
             }
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError("error fetching user"));
+        Err(error) => {
+            return Err(AppError::internal("error fetching user", error));
         }
     };
 
@@ -70,18 +98,21 @@ pub async fn recovery_code(
     if let Some(email) = existing_user.email {
         existing_email = email;
     } else {
-        return Err(ErrorBadRequest(
+        return Err(AppError::bad_request(
+            ErrorCode::RecoveryEmailMissing,
             "recovery email not setup, please contact support.",
         ));
     }
 
     let auth_code = get_new_auth_code();
-    let phoneauth_res = create_phoneauth(&pool, &existing_user.phone, &auth_code).await;
+    let phoneauth_res = auth_repo
+        .create_phoneauth(&existing_user.phone, &auth_code, &client_ip.to_string())
+        .await;
 
     match phoneauth_res {
         Ok(_) => {}
-        Err(_) => {
-            return Err(ErrorInternalServerError("error creating auth"));
+        Err(error) => {
+            return Err(AppError::internal("error creating auth", error));
         }
     }
 
@@ -104,7 +135,7 @@ pub async fn recovery_code(
         Err(err) => {
             span.set_status(Status::error(err.clone()));
             span.end();
-            return Ok(HttpResponse::InternalServerError().body("failed to send auth request"));
+            return Err(AppError::internal("failed to send auth request", err));
         }
     }
 }
@@ -152,6 +183,7 @@ async fn send_auth_email(
         .header("Content-Type", "application/json")
         .header("authorization", format!("bearer {}", &secret))
         .body(body)
+        .with_trace_context()
         .send()
         .await;
 
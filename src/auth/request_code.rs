@@ -1,35 +1,46 @@
 use std::collections::HashMap;
 
 use crate::{
-    db::{create_phoneauth, create_user, get_current_phoneauths, get_user_by_phone, User},
-    tracing::add_error_span,
+    client_ip::ClientIp,
+    db::User,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    metrics::SMS_SENT_TOTAL,
+    repo::{AuthRepo, UserRepo},
+    tracing::WithTraceContext,
     Config,
 };
-use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
-    post,
-    web::{Data, Query},
-    HttpResponse, Result,
-};
+use actix_web::{post, web::Data, HttpResponse, Result};
 use chrono::Utc;
 use opentelemetry::{
     global,
     trace::{Span, Status, Tracer},
 };
-use rand::Rng;
 use reqwest::{Client, StatusCode};
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use uuid::Uuid;
 use validation;
 
-use super::get_new_auth_code;
+use super::{get_new_auth_code, get_new_user_name};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RequestCodeRequest {
     phone: String,
 }
 
+impl Validate for RequestCodeRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("phone", validation::validate_phone(&self.phone));
+
+        errors.finish()
+    }
+}
+
 /// Endpoint for requesting an auth code.
 /// The auth code is sent to the request phone number.
 /// We track when we send a code, and try to prevent abuse
@@ -37,33 +48,45 @@ pub struct RequestCodeRequest {
 ///
 /// We may want to add IP limits here as well. SMS is expensive
 /// when abused, and can be annoying for targets of said abuse.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(RequestCodeRequest),
+    responses(
+        (status = 200, description = "code was sent"),
+        ErrorResponses
+    )
+)]
 #[post("/requestcode")]
 pub async fn request_code(
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    auth_repo: Data<dyn AuthRepo>,
     config: Data<Config>,
     http_client: Data<Client>,
-    request_code_request: Query<RequestCodeRequest>,
-) -> Result<HttpResponse> {
-    let valid_phone = validation::validate_phone(&request_code_request.phone);
-    if let Err(phone_err) = valid_phone {
-        return Err(ErrorBadRequest(phone_err));
-    }
-
-    let phoneauths_res = get_current_phoneauths(&pool, &request_code_request.phone).await;
+    client_ip: ClientIp,
+    request_code_request: ValidQuery<RequestCodeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let phoneauths_res = auth_repo
+        .get_current_phoneauths(&request_code_request.phone)
+        .await;
 
     match phoneauths_res {
         Ok(phoneauths) => {
             if phoneauths.len() >= 3 {
-                return Err(ErrorBadRequest("too many auth attempts"));
+                return Err(AppError::bad_request(
+                    ErrorCode::TooManyAuthAttempts,
+                    "too many auth attempts",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to fetch auths"));
+            return Err(AppError::internal("unable to fetch auths", error));
         }
     }
 
-    let user_res = get_user_by_phone(&pool, &request_code_request.phone).await;
+    let user_res = user_repo
+        .get_user_by_phone(&request_code_request.phone)
+        .await;
 
     let existing_user: User;
 
@@ -85,35 +108,37 @@ pub async fn request_code(
                     disabled: 0,
                 };
 
-                let create_res = create_user(&pool, &existing_user).await;
+                let create_res = user_repo.create_user(&existing_user).await;
 
                 match create_res {
                     Ok(_) => {}
                     Err(error) => {
-                        add_error_span(&error);
-                        return Err(ErrorInternalServerError("error creating user"));
+                        return Err(AppError::internal("error creating user", error));
                     }
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("error fetching user"));
+            return Err(AppError::internal("error fetching user", error));
         }
     };
 
     if existing_user.disabled == 1 {
-        return Err(ErrorBadRequest("user is disabled"));
+        return Err(AppError::bad_request(
+            ErrorCode::UserDisabled,
+            "user is disabled",
+        ));
     }
 
     let auth_code = get_new_auth_code();
-    let phoneauth_res = create_phoneauth(&pool, &existing_user.phone, &auth_code).await;
+    let phoneauth_res = auth_repo
+        .create_phoneauth(&existing_user.phone, &auth_code, &client_ip.to_string())
+        .await;
 
     match phoneauth_res {
         Ok(_) => {}
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("error creating auth"));
+            return Err(AppError::internal("error creating auth", error));
         }
     }
 
@@ -128,6 +153,9 @@ pub async fn request_code(
     )
     .await;
 
+    let sms_result = if auth_res.is_ok() { "ok" } else { "error" };
+    SMS_SENT_TOTAL.with_label_values(&[sms_result]).inc();
+
     match auth_res {
         Ok(_) => {
             span.end();
@@ -135,7 +163,7 @@ pub async fn request_code(
         }
         Err(err) => {
             span.set_status(Status::error(err.clone()));
-            return Ok(HttpResponse::InternalServerError().body("failed to send auth request"));
+            return Err(AppError::internal("failed to send auth request", err));
         }
     }
 }
@@ -165,6 +193,7 @@ async fn send_auth(
         .header("Content-Type", "application/x-www-form-urlencoded")
         .form(&params)
         .basic_auth("AC0094c61aa39fc9c673130f6e28e43bad", Some(twilio_secret))
+        .with_trace_context()
         .send()
         .await;
 
@@ -180,17 +209,3 @@ async fn send_auth(
         Err(err) => Err(err.to_string()),
     }
 }
-
-/// Gets a name for a new user to default to.
-/// The user is expected to be able to set this to anything not already taken.
-fn get_new_user_name() -> String {
-    let mut rng = rand::thread_rng();
-    let mut user_name = String::from("newuser");
-
-    for _ in 0..9 {
-        let num = rng.gen_range(0..9);
-        user_name.push_str(&num.to_string());
-    }
-
-    return user_name;
-}
@@ -1,5 +1,8 @@
 use rand::Rng;
 
+/// User that `/auth/signin-demo` signs in as, for app store review.
+pub const DEMO_USER_ID: &str = "226f982d-1971-4085-a8a8-bc0074de0b84";
+
 /// Generates a new 9 digit auth code authenticated via phone.
 /// I believe this generation is OK. The endpoint for validating
 /// the code restricts the code to a limited lifetime and we limit
@@ -15,3 +18,17 @@ pub fn get_new_auth_code() -> String {
 
     return code;
 }
+
+/// Gets a name for a new user to default to.
+/// The user is expected to be able to set this to anything not already taken.
+pub fn get_new_user_name() -> String {
+    let mut rng = rand::thread_rng();
+    let mut user_name = String::from("newuser");
+
+    for _ in 0..9 {
+        let num = rng.gen_range(0..9);
+        user_name.push_str(&num.to_string());
+    }
+
+    return user_name;
+}
@@ -1,70 +1,82 @@
 use crate::{
-    db::{
-        create_authattempt, get_current_phoneauths, get_phoneauth_attempts, get_user_by_phone,
-        update_authattempt_used, PhoneAuth, User,
-    },
-    tracing::add_error_span,
+    auth::DEMO_USER_ID,
+    db::{PhoneAuth, User},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{AuthRepo, UserRepo},
     Config,
 };
-use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
-    post,
-    web::{Data, Query},
-    Responder, Result,
-};
+use actix_web::{post, web::Data, Responder, Result};
 use jwt::mint_jwt;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use validation;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SignInRequest {
     phone: String,
     code: String,
 }
 
+impl Validate for SignInRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("phone", validation::validate_phone(&self.phone));
+        errors.check("code", validation::validate_code(&self.code));
+
+        errors.finish()
+    }
+}
+
 /// Returns the user JWT for future requests.
 ///
 /// The passed phone and code are validated.
 ///
 /// We validate rate constraints with the persistent auth attempt records.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(SignInRequest),
+    responses(
+        (status = 200, description = "JWT for the signed in user", body = String, content_type = "text/plain"),
+        ErrorResponses
+    )
+)]
 #[post("/signin")]
 pub async fn sign_in(
     config: Data<Config>,
-    pool: Data<MySqlPool>,
-    sign_in_request: Query<SignInRequest>,
-) -> Result<impl Responder> {
-    let valid_phone = validation::validate_phone(&sign_in_request.phone);
-    if let Err(phone_err) = valid_phone {
-        return Err(ErrorBadRequest(phone_err.to_string()));
-    }
-
-    let valid_code = validation::validate_code(&sign_in_request.code);
-    if let Err(code_err) = valid_code {
-        return Err(ErrorBadRequest(code_err.to_string()));
-    }
-
-    let create_authattempt_res = create_authattempt(&pool, &sign_in_request.phone).await;
-    if let Err(_) = create_authattempt_res {
-        return Err(ErrorInternalServerError("unable to start auth attempt"));
+    user_repo: Data<dyn UserRepo>,
+    auth_repo: Data<dyn AuthRepo>,
+    sign_in_request: ValidQuery<SignInRequest>,
+) -> Result<impl Responder, AppError> {
+    let create_authattempt_res = auth_repo.create_authattempt(&sign_in_request.phone).await;
+    if let Err(error) = create_authattempt_res {
+        return Err(AppError::internal("unable to start auth attempt", error));
     }
 
-    let phone_auth_attemps_res = get_phoneauth_attempts(&pool, &sign_in_request.phone).await;
+    let phone_auth_attemps_res = auth_repo
+        .get_phoneauth_attempts(&sign_in_request.phone)
+        .await;
     match phone_auth_attemps_res {
         Ok(phone_auth_attempts) => {
             if phone_auth_attempts.len() >= 4 {
-                return Err(ErrorBadRequest(
+                return Err(AppError::bad_request(
+                    ErrorCode::TooManyAuthAttempts,
                     "too many auth attempts - wait a bit before trying again",
                 ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get auth attempts"));
+            return Err(AppError::internal("unable to get auth attempts", error));
         }
     }
 
-    let phone_auth_res = get_current_phoneauths(&pool, &sign_in_request.phone).await;
+    let phone_auth_res = auth_repo
+        .get_current_phoneauths(&sign_in_request.phone)
+        .await;
     let phone_auths: Vec<PhoneAuth>;
 
     match phone_auth_res {
@@ -72,8 +84,10 @@ pub async fn sign_in(
             phone_auths = phone_auths_tmp;
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get current phoneauths"));
+            return Err(AppError::internal(
+                "unable to get current phoneauths",
+                error,
+            ));
         }
     }
 
@@ -84,40 +98,50 @@ pub async fn sign_in(
 
     if matched_phoneauth.len() == 1 {
         let user: User;
-        let user_res = get_user_by_phone(&pool, &sign_in_request.phone).await;
+        let user_res = user_repo.get_user_by_phone(&sign_in_request.phone).await;
 
         match user_res {
             Ok(user_opt) => {
                 if let Some(user_tmp) = user_opt {
                     user = user_tmp;
                 } else {
-                    return Err(ErrorInternalServerError("unable to find user"));
+                    return Err(AppError::internal_message("unable to find user"));
                 }
             }
             Err(error) => {
-                add_error_span(&error);
-                return Err(ErrorInternalServerError("error fetching user"));
+                return Err(AppError::internal("error fetching user", error));
             }
         }
 
-        let authattempt_update_res =
-            update_authattempt_used(&pool, &matched_phoneauth.first().unwrap().id).await;
+        let authattempt_update_res = auth_repo
+            .update_authattempt_used(&matched_phoneauth.first().unwrap().id)
+            .await;
         if let Err(error) = authattempt_update_res {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to update authattempt"));
+            return Err(AppError::internal("unable to update authattempt", error));
         }
 
         let jwt = mint_jwt(&config.signing_keys, &user.id);
 
         Ok(jwt)
     } else {
-        return Err(ErrorBadRequest("invalid code"));
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidCode,
+            "invalid code",
+        ));
     }
 }
 
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    responses(
+        (status = 200, description = "JWT for the demo user", body = String, content_type = "text/plain"),
+        ErrorResponses
+    )
+)]
 #[post("/signin-demo")]
-pub async fn sign_in_demo(config: Data<Config>) -> Result<impl Responder> {
-    let jwt = mint_jwt(&config.signing_keys, "226f982d-1971-4085-a8a8-bc0074de0b84");
+pub async fn sign_in_demo(config: Data<Config>) -> Result<impl Responder, AppError> {
+    let jwt = mint_jwt(&config.signing_keys, DEMO_USER_ID);
 
     Ok(jwt)
 }
@@ -1,23 +1,19 @@
 use crate::{
-    db::{
-        create_authattempt, get_current_phoneauths, get_phoneauth_attempts, get_user_by_phone,
-        update_authattempt_used, update_user_phone, PhoneAuth, User,
-    },
-    tracing::add_error_span,
+    db::{PhoneAuth, User},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{AuthRepo, UserRepo},
     Config,
 };
-use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
-    post,
-    web::{Data, Query},
-    Responder, Result,
-};
+use actix_web::{post, web::Data, Responder, Result};
 use jwt::mint_jwt;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use validation;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SignInRequest {
     /// Old Account Phone Number.
     phone: String,
@@ -29,81 +25,100 @@ pub struct SignInRequest {
     new_phone_code: String,
 }
 
+impl Validate for SignInRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("phone", validation::validate_phone(&self.phone));
+        errors.check("new_phone", validation::validate_phone(&self.new_phone));
+        errors.check("code", validation::validate_code(&self.code));
+        errors.check(
+            "new_phone_code",
+            validation::validate_code(&self.new_phone_code),
+        );
+
+        errors.finish()
+    }
+}
+
 /// Returns the user JWT for future requests.
 ///
 /// The passed phone, new_phone, and code are validated.
 ///
 /// We validate rate constraints with the persistent auth attempt records.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(SignInRequest),
+    responses(
+        (status = 200, description = "JWT for the updated user", body = String, content_type = "text/plain"),
+        ErrorResponses
+    )
+)]
 #[post("/update_phone")]
 pub async fn update_phone(
     config: Data<Config>,
-    pool: Data<MySqlPool>,
-    sign_in_request: Query<SignInRequest>,
-) -> Result<impl Responder> {
+    user_repo: Data<dyn UserRepo>,
+    auth_repo: Data<dyn AuthRepo>,
+    sign_in_request: ValidQuery<SignInRequest>,
+) -> Result<impl Responder, AppError> {
     if &sign_in_request.phone == &sign_in_request.new_phone {
-        return Err(ErrorBadRequest("phone numbers are identical"));
-    }
-
-    let valid_phone = validation::validate_phone(&sign_in_request.phone);
-    if let Err(phone_err) = valid_phone {
-        return Err(ErrorBadRequest(phone_err.to_string()));
+        return Err(AppError::bad_request(
+            ErrorCode::PhoneUnchanged,
+            "phone numbers are identical",
+        ));
     }
 
-    let valid_new_phone = validation::validate_phone(&sign_in_request.new_phone);
-    if let Err(phone_err) = valid_new_phone {
-        return Err(ErrorBadRequest(phone_err.to_string()));
-    }
-
-    let valid_code = validation::validate_code(&sign_in_request.code);
-    if let Err(code_err) = valid_code {
-        return Err(ErrorBadRequest(code_err.to_string()));
-    }
-
-    let new_phone_valid_code = validation::validate_code(&sign_in_request.new_phone_code);
-    if let Err(code_err) = new_phone_valid_code {
-        return Err(ErrorBadRequest(code_err.to_string()));
-    }
-
-    let create_authattempt_res = create_authattempt(&pool, &sign_in_request.phone).await;
-    if let Err(_) = create_authattempt_res {
-        return Err(ErrorInternalServerError("unable to start auth attempt"));
+    let create_authattempt_res = auth_repo.create_authattempt(&sign_in_request.phone).await;
+    if let Err(error) = create_authattempt_res {
+        return Err(AppError::internal("unable to start auth attempt", error));
     }
 
-    let phone_auth_attemps_res = get_phoneauth_attempts(&pool, &sign_in_request.phone).await;
+    let phone_auth_attemps_res = auth_repo
+        .get_phoneauth_attempts(&sign_in_request.phone)
+        .await;
     if let Ok(phone_auth_attempts) = phone_auth_attemps_res {
         if phone_auth_attempts.len() >= 4 {
-            return Err(ErrorBadRequest(
+            return Err(AppError::bad_request(
+                ErrorCode::TooManyAuthAttempts,
                 "too many auth attempts - wait a bit before trying again",
             ));
         }
     } else {
-        return Err(ErrorInternalServerError("unable to get auth attempts"));
+        return Err(AppError::internal_message("unable to get auth attempts"));
     }
 
-    let create_authattempt_res = create_authattempt(&pool, &sign_in_request.new_phone).await;
+    let create_authattempt_res = auth_repo
+        .create_authattempt(&sign_in_request.new_phone)
+        .await;
     if let Err(error) = create_authattempt_res {
-        add_error_span(&error);
-        return Err(ErrorInternalServerError("unable to start auth attempt"));
+        return Err(AppError::internal("unable to start auth attempt", error));
     }
 
-    let new_phone_auth_attemps_res =
-        get_phoneauth_attempts(&pool, &sign_in_request.new_phone).await;
+    let new_phone_auth_attemps_res = auth_repo
+        .get_phoneauth_attempts(&sign_in_request.new_phone)
+        .await;
     if let Ok(phone_auth_attempts) = new_phone_auth_attemps_res {
         if phone_auth_attempts.len() >= 4 {
-            return Err(ErrorBadRequest(
+            return Err(AppError::bad_request(
+                ErrorCode::TooManyAuthAttempts,
                 "too many auth attempts - wait a bit before trying again",
             ));
         }
     } else {
-        return Err(ErrorInternalServerError("unable to get auth attempts"));
+        return Err(AppError::internal_message("unable to get auth attempts"));
     }
 
-    let phone_auth_res = get_current_phoneauths(&pool, &sign_in_request.phone).await;
+    let phone_auth_res = auth_repo
+        .get_current_phoneauths(&sign_in_request.phone)
+        .await;
     let phone_auths: Vec<PhoneAuth>;
     if let Ok(phone_auths_tmp) = phone_auth_res {
         phone_auths = phone_auths_tmp;
     } else {
-        return Err(ErrorInternalServerError("unable to get current phoneauths"));
+        return Err(AppError::internal_message(
+            "unable to get current phoneauths",
+        ));
     }
 
     let matched_phoneauth = phone_auths
@@ -113,12 +128,16 @@ pub async fn update_phone(
 
     if matched_phoneauth.len() == 1 {
         // Validate a phone auth code for the new phone number is received.
-        let new_phone_auth_res = get_current_phoneauths(&pool, &sign_in_request.new_phone).await;
+        let new_phone_auth_res = auth_repo
+            .get_current_phoneauths(&sign_in_request.new_phone)
+            .await;
         let new_phone_auths: Vec<PhoneAuth>;
         if let Ok(new_phone_auths_tmp) = new_phone_auth_res {
             new_phone_auths = new_phone_auths_tmp;
         } else {
-            return Err(ErrorInternalServerError("unable to get current phoneauths"));
+            return Err(AppError::internal_message(
+                "unable to get current phoneauths",
+            ));
         }
 
         let matched_new_phoneauth = new_phone_auths
@@ -127,54 +146,65 @@ pub async fn update_phone(
             .collect::<Vec<&PhoneAuth>>();
 
         if matched_new_phoneauth.len() != 1 {
-            return Err(ErrorInternalServerError("invalid code"));
+            return Err(AppError::bad_request(
+                ErrorCode::InvalidCode,
+                "invalid code",
+            ));
         }
 
         let old_user: User;
-        let user_res = get_user_by_phone(&pool, &sign_in_request.phone).await;
+        let user_res = user_repo.get_user_by_phone(&sign_in_request.phone).await;
         if let Ok(user_opt) = user_res {
             if let Some(user_tmp) = user_opt {
                 old_user = user_tmp;
             } else {
-                return Err(ErrorInternalServerError(
+                return Err(AppError::internal_message(
                     "unable to find user for given phone",
                 ));
             }
         } else {
-            return Err(ErrorInternalServerError("error fetching user by phone"));
+            return Err(AppError::internal_message("error fetching user by phone"));
         }
 
-        let new_user_res = get_user_by_phone(&pool, &sign_in_request.new_phone).await;
+        let new_user_res = user_repo
+            .get_user_by_phone(&sign_in_request.new_phone)
+            .await;
         if let Ok(new_user_opt) = new_user_res {
             if let Some(user_tmp) = new_user_opt {
                 // remove phone number from new user if it exists
-                let res = update_user_phone(&pool, &user_tmp.id, "").await;
-                if let Err(_) = res {
-                    return Err(ErrorInternalServerError("error clearing existing account"));
+                let res = user_repo.update_user_phone(&user_tmp.id, "").await;
+                if let Err(error) = res {
+                    return Err(AppError::internal("error clearing existing account", error));
                 }
             }
         } else {
-            return Err(ErrorInternalServerError("error fetching user by phone"));
+            return Err(AppError::internal_message("error fetching user by phone"));
         }
 
-        let res = update_user_phone(&pool, &old_user.id, &sign_in_request.new_phone).await;
-        if let Err(_) = res {
-            return Err(ErrorInternalServerError(
+        let res = user_repo
+            .update_user_phone(&old_user.id, &sign_in_request.new_phone)
+            .await;
+        if let Err(error) = res {
+            return Err(AppError::internal(
                 "error updating account phone number",
+                error,
             ));
         }
 
-        let authattempt_update_res =
-            update_authattempt_used(&pool, &matched_phoneauth.first().unwrap().id).await;
+        let authattempt_update_res = auth_repo
+            .update_authattempt_used(&matched_phoneauth.first().unwrap().id)
+            .await;
         if let Err(error) = authattempt_update_res {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to update authattempt"));
+            return Err(AppError::internal("unable to update authattempt", error));
         }
 
         let jwt = mint_jwt(&config.signing_keys, &old_user.id);
 
         Ok(jwt)
     } else {
-        return Err(ErrorBadRequest("invalid code"));
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidCode,
+            "invalid code",
+        ));
     }
 }
@@ -1,10 +1,14 @@
-use crate::Config;
+use crate::{
+    admin_v1::get_admin_user,
+    error::{AppError, ErrorCode},
+    Config,
+};
 use actix_web::{
     body::EitherBody,
     dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
     http::header,
     web::Data,
-    Error, HttpMessage, HttpResponse,
+    Error, HttpMessage, ResponseError,
 };
 use futures_util::future::LocalBoxFuture;
 use std::future::{ready, Ready};
@@ -52,7 +56,10 @@ where
     dev::forward_ready!(service);
 
     fn call(&self, request: ServiceRequest) -> Self::Future {
-        if request.path().starts_with("/auth") || request.path().starts_with("/ping") {
+        if request.path().starts_with("/auth")
+            || request.path().starts_with("/ping")
+            || request.path().starts_with("/docs")
+        {
             let res = self.service.call(request);
 
             return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
@@ -65,7 +72,9 @@ where
         } else {
             let (request, _pl) = request.into_parts();
 
-            let response = HttpResponse::Unauthorized().finish().map_into_right_body();
+            let response = AppError::unauthorized("missing authorization header")
+                .error_response()
+                .map_into_right_body();
 
             return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
         }
@@ -75,13 +84,19 @@ where
                 if let Some(user_id) = jwt::validate_jwt(&config.signing_keys, token) {
                     // Additional check if we are routing to an admin route.
                     // This is after the jwt validation, and only issued JWTs are valid.
-                    // Only I am allowed uwuu~~~ 🥰 - maybe improve this later if more admins are needed
+                    // Individual admin routes check the specific permission they need.
                     if request.path().starts_with("/admin") {
-                        if user_id != "70bf5ab0-a51a-4f2a-b07d-009f571f62da" {
+                        if let Some(admin_user) = get_admin_user(&user_id) {
+                            request.extensions_mut().insert(admin_user);
+                        } else {
                             let (request, _pl) = request.into_parts();
 
-                            let response =
-                                HttpResponse::Unauthorized().finish().map_into_right_body();
+                            let response = AppError::forbidden(
+                                ErrorCode::MissingPermission,
+                                "admin access required",
+                            )
+                            .error_response()
+                            .map_into_right_body();
 
                             return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
                         }
@@ -100,7 +115,9 @@ where
 
         // fall-through means something went wrong
         let (request, _pl) = request.into_parts();
-        let response = HttpResponse::Unauthorized().finish().map_into_right_body();
+        let response = AppError::unauthorized("invalid or expired token")
+            .error_response()
+            .map_into_right_body();
         return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
     }
 }
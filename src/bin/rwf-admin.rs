@@ -0,0 +1,550 @@
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use images::{create_s3_client, S3Client, DEFAULT_PIC_ID};
+use jwt::mint_apn_jwt;
+use reqwest::ClientBuilder;
+#[cfg(not(feature = "postgres"))]
+use reviewwithfriends_backend::repo::MySqlRepo;
+#[cfg(feature = "postgres")]
+use reviewwithfriends_backend::repo::PgRepo;
+use reviewwithfriends_backend::{
+    auth::{get_new_user_name, DEMO_USER_ID},
+    config::load_config,
+    db::User,
+    notifications_v1::{APNClient, NotificationType},
+    pic_v1::{set_spaces_bucket, shared_utils::best_effort_delete_pic},
+    repo::{FriendRepo, NotificationRepo, PicRepo, ReportRepo, UserRepo},
+    Config,
+};
+#[cfg(not(feature = "postgres"))]
+use sqlx::MySqlPool;
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+use std::{process::ExitCode, sync::Mutex, time::Duration};
+use uuid::Uuid;
+use validation::{validate_display_name, validate_name, validate_phone};
+
+/// Ops tasks against the database and services in the server's config.
+/// Loads config the same way the server does.
+#[derive(Parser)]
+#[command(name = "rwf-admin")]
+struct Cli {
+    /// Print what would change without changing anything.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates a user for a phone number, as if they had requested a sign in code.
+    CreateUser {
+        #[arg(long)]
+        phone: String,
+        /// Defaults to a generated name.
+        #[arg(long)]
+        name: Option<String>,
+        /// Defaults to the name.
+        #[arg(long)]
+        display_name: Option<String>,
+    },
+    /// Stops a user from requesting sign in codes.
+    DisableUser {
+        user_id: String,
+        /// Re-enables the user instead.
+        #[arg(long)]
+        enable: bool,
+    },
+    /// Sets a users name and display name. Both default to a generated name.
+    RenameUser {
+        user_id: String,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        display_name: Option<String>,
+    },
+    /// Removes everything the demo account has done and restores its names.
+    ResetDemo {
+        #[arg(long, default_value = "demo")]
+        name: String,
+        #[arg(long, default_value = "Demo")]
+        display_name: String,
+    },
+    /// Sends a push notification to a user directly, skipping the queue.
+    ResendPush {
+        user_id: String,
+        #[arg(long)]
+        message: String,
+        /// One of Favorite, Reply, Add or Post.
+        #[arg(long)]
+        notification_type: String,
+        /// Review or user id the app navigates to.
+        #[arg(long)]
+        notification_value: Option<String>,
+    },
+    /// Lists every report, oldest first.
+    ListReports,
+    /// Deletes pics no review or user points at, from the database and storage.
+    PurgeOrphanedPics,
+    /// Fills in data older rows are missing after a migration.
+    Backfill {
+        #[command(subcommand)]
+        task: BackfillTask,
+    },
+}
+
+#[derive(Subcommand)]
+enum BackfillTask {
+    /// Adds the self friend record users need to see their own posts.
+    SelfFriends,
+}
+
+#[actix_web::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Err(error) = run(cli).await {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+
+    return ExitCode::SUCCESS;
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let config = load_config().map_err(|error| error.to_string())?;
+
+    set_spaces_bucket(&config.spaces_bucket);
+
+    let repo = connect_repo(&config).await?;
+
+    if cli.dry_run {
+        println!("dry run, nothing will be changed");
+    }
+
+    let dry_run = cli.dry_run;
+
+    match cli.command {
+        Command::CreateUser {
+            phone,
+            name,
+            display_name,
+        } => create_user_command(&repo, dry_run, phone, name, display_name).await,
+        Command::DisableUser { user_id, enable } => {
+            disable_user_command(&repo, dry_run, &user_id, !enable).await
+        }
+        Command::RenameUser {
+            user_id,
+            name,
+            display_name,
+        } => rename_user_command(&repo, dry_run, &user_id, name, display_name).await,
+        Command::ResetDemo { name, display_name } => {
+            let s3_client = create_s3_client(&config.spaces_key, &config.spaces_secret);
+            reset_demo_command(&repo, &repo, &s3_client, dry_run, name, display_name).await
+        }
+        Command::ResendPush {
+            user_id,
+            message,
+            notification_type,
+            notification_value,
+        } => {
+            resend_push_command(
+                &repo,
+                &repo,
+                &config,
+                dry_run,
+                &user_id,
+                &message,
+                &notification_type,
+                notification_value,
+            )
+            .await
+        }
+        Command::ListReports => list_reports_command(&repo).await,
+        Command::PurgeOrphanedPics => {
+            let s3_client = create_s3_client(&config.spaces_key, &config.spaces_secret);
+            purge_orphaned_pics_command(&repo, &s3_client, dry_run).await
+        }
+        Command::Backfill { task } => match task {
+            BackfillTask::SelfFriends => backfill_self_friends_command(&repo, &repo, dry_run).await,
+        },
+    }
+}
+
+/// Connects to MySQL, the default backend. Everything goes to the primary, a replica isn't needed here.
+#[cfg(not(feature = "postgres"))]
+async fn connect_repo(config: &Config) -> Result<MySqlRepo, String> {
+    let pool = MySqlPool::connect(&config.db_connection_string)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(MySqlRepo::new(pool));
+}
+
+/// Connects to PostgreSQL when built with the `postgres` feature.
+#[cfg(feature = "postgres")]
+async fn connect_repo(config: &Config) -> Result<PgRepo, String> {
+    let pool = PgPool::connect(&config.db_connection_string)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(PgRepo::new(pool));
+}
+
+async fn create_user_command(
+    user_repo: &dyn UserRepo,
+    dry_run: bool,
+    phone: String,
+    name: Option<String>,
+    display_name: Option<String>,
+) -> Result<(), String> {
+    validate_phone(&phone)?;
+
+    if let Some(existing_user) = user_repo
+        .get_user_by_phone(&phone)
+        .await
+        .map_err(|error| error.to_string())?
+    {
+        return Err(format!("{} already has user {}", phone, existing_user.id));
+    }
+
+    let name = name
+        .map(|name| name.to_lowercase())
+        .unwrap_or_else(get_new_user_name);
+    let display_name = display_name.unwrap_or_else(|| name.clone());
+
+    check_new_names(user_repo, &name, &display_name).await?;
+
+    let user = User {
+        id: Uuid::new_v4().to_string(),
+        name,
+        display_name,
+        phone,
+        created: Utc::now().naive_utc(),
+        pic_id: DEFAULT_PIC_ID.to_string(),
+        device_token: None,
+        email: None,
+        disabled: 0,
+    };
+
+    println!(
+        "create user {} name={} display_name={} phone={}",
+        user.id, user.name, user.display_name, user.phone
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    user_repo
+        .create_user(&user)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(());
+}
+
+async fn disable_user_command(
+    user_repo: &dyn UserRepo,
+    dry_run: bool,
+    user_id: &str,
+    disabled: bool,
+) -> Result<(), String> {
+    let user = require_user(user_repo, user_id).await?;
+
+    if (user.disabled == 1) == disabled {
+        println!(
+            "user {} is already {}",
+            user.id,
+            if disabled { "disabled" } else { "enabled" }
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} user {} ({})",
+        if disabled { "disable" } else { "enable" },
+        user.id,
+        user.name
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    user_repo
+        .update_user_disabled(user_id, disabled)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(());
+}
+
+async fn rename_user_command(
+    user_repo: &dyn UserRepo,
+    dry_run: bool,
+    user_id: &str,
+    name: Option<String>,
+    display_name: Option<String>,
+) -> Result<(), String> {
+    let user = require_user(user_repo, user_id).await?;
+
+    let name = name
+        .map(|name| name.to_lowercase())
+        .unwrap_or_else(get_new_user_name);
+    let display_name = display_name.unwrap_or_else(|| name.clone());
+
+    if name != user.name {
+        check_new_names(user_repo, &name, &display_name).await?;
+    } else {
+        validate_display_name(&display_name).map_err(|error| format!("display_name: {}", error))?;
+    }
+
+    println!(
+        "rename user {} name={} -> {} display_name={} -> {}",
+        user.id, user.name, name, user.display_name, display_name
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    user_repo
+        .update_usernames(user_id, &display_name, &name)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(());
+}
+
+async fn reset_demo_command(
+    user_repo: &dyn UserRepo,
+    pic_repo: &dyn PicRepo,
+    s3_client: &S3Client,
+    dry_run: bool,
+    name: String,
+    display_name: String,
+) -> Result<(), String> {
+    let user = require_user(user_repo, DEMO_USER_ID).await?;
+
+    let pics = pic_repo
+        .get_review_pics_by_user(DEMO_USER_ID)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let mut pic_ids: Vec<String> = pics.into_iter().map(|pic| pic.id).collect();
+
+    if user.pic_id != DEFAULT_PIC_ID {
+        pic_ids.push(user.pic_id.clone());
+    }
+
+    for pic_id in &pic_ids {
+        println!("delete pic {}", pic_id);
+    }
+
+    println!(
+        "remove reviews, likes, replies, friends, bookmarks and notifications of the demo user"
+    );
+
+    if name != user.name {
+        check_new_names(user_repo, &name, &display_name).await?;
+    }
+
+    println!(
+        "rename demo user name={} -> {} display_name={} -> {}",
+        user.name, name, user.display_name, display_name
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    for pic_id in &pic_ids {
+        best_effort_delete_pic(s3_client, pic_repo, pic_id).await;
+    }
+
+    user_repo
+        .remove_user_activity(DEMO_USER_ID)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    user_repo
+        .update_usernames(DEMO_USER_ID, &display_name, &name)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    return Ok(());
+}
+
+async fn resend_push_command(
+    user_repo: &dyn UserRepo,
+    notification_repo: &dyn NotificationRepo,
+    config: &Config,
+    dry_run: bool,
+    user_id: &str,
+    message: &str,
+    notification_type: &str,
+    notification_value: Option<String>,
+) -> Result<(), String> {
+    let notification_type = NotificationType::parse(notification_type)
+        .ok_or_else(|| format!("unknown notification type {}", notification_type))?;
+
+    let user = require_user(user_repo, user_id).await?;
+
+    let device_token = user
+        .device_token
+        .ok_or_else(|| format!("user {} has no device token", user.id))?;
+
+    let badge_count = notification_repo.get_notification_count(&user.id).await;
+
+    println!(
+        "send {} push to user {} message={:?} value={} badge={}",
+        notification_type,
+        user.id,
+        message,
+        notification_value.as_deref().unwrap_or(""),
+        badge_count
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let apn_client = APNClient {
+        client: ClientBuilder::new()
+            .http2_prior_knowledge()
+            .timeout(Duration::new(5, 0))
+            .build()
+            .map_err(|error| error.to_string())?,
+        key: config.apn_key.clone(),
+        topic: config.apn_topic.clone(),
+        token: Mutex::new(mint_apn_jwt(&config.apn_key)),
+        issued_time: Mutex::new(Utc::now().timestamp()),
+    };
+
+    apn_client
+        .send_notification(
+            &device_token,
+            message,
+            notification_type,
+            notification_value,
+            badge_count,
+        )
+        .await?;
+
+    return Ok(());
+}
+
+/// Read only, so dry runs print the same thing.
+async fn list_reports_command(report_repo: &dyn ReportRepo) -> Result<(), String> {
+    let mut reports = report_repo
+        .get_all_reports()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    reports.sort_by_key(|report| report.created);
+
+    println!("id\tcreated\tuser_id\treporter_id\treport_type");
+
+    for report in reports {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            report.id, report.created, report.user_id, report.reporter_id, report.report_type
+        );
+    }
+
+    return Ok(());
+}
+
+async fn purge_orphaned_pics_command(
+    pic_repo: &dyn PicRepo,
+    s3_client: &S3Client,
+    dry_run: bool,
+) -> Result<(), String> {
+    let pics = pic_repo
+        .get_orphaned_pics()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    for pic in &pics {
+        println!(
+            "delete pic {} review_id={} created={}",
+            pic.id,
+            pic.review_id.as_deref().unwrap_or(""),
+            pic.created
+        );
+    }
+
+    println!("{} orphaned pics", pics.len());
+
+    if dry_run {
+        return Ok(());
+    }
+
+    for pic in &pics {
+        best_effort_delete_pic(s3_client, pic_repo, &pic.id).await;
+    }
+
+    return Ok(());
+}
+
+async fn backfill_self_friends_command(
+    user_repo: &dyn UserRepo,
+    friend_repo: &dyn FriendRepo,
+    dry_run: bool,
+) -> Result<(), String> {
+    let users = user_repo
+        .get_users_missing_self_friend()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    for user in &users {
+        println!("create self friend for user {} ({})", user.id, user.name);
+    }
+
+    println!("{} users missing a self friend", users.len());
+
+    if dry_run {
+        return Ok(());
+    }
+
+    for user in &users {
+        friend_repo
+            .create_self_friend(&user.id)
+            .await
+            .map_err(|error| error.to_string())?;
+    }
+
+    return Ok(());
+}
+
+async fn require_user(user_repo: &dyn UserRepo, user_id: &str) -> Result<User, String> {
+    match user_repo.get_user(user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(format!("could not find user {}", user_id)),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Applies the same checks as `update_user`, since the app can't fix names it would have rejected.
+async fn check_new_names(
+    user_repo: &dyn UserRepo,
+    name: &str,
+    display_name: &str,
+) -> Result<(), String> {
+    validate_name(name).map_err(|error| format!("name: {}", error))?;
+    validate_display_name(display_name).map_err(|error| format!("display_name: {}", error))?;
+
+    let exists = user_repo
+        .does_user_exist_by_name(name)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    if exists {
+        return Err(format!("name {} is already taken", name));
+    }
+
+    return Ok(());
+}
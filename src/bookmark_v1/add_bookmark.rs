@@ -1,20 +1,26 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{self, Bookmark},
-    tracing::add_error_span,
+    db::Bookmark,
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::BookmarkRepo,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
-    web::{Data, Query, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use chrono::Utc;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use uuid::Uuid;
+use validation::{
+    validate_latitude, validate_location_name, validate_longitude, validate_review_category,
+};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AddBookmarkRequest {
     pub location_name: String,
     pub category: String,
@@ -22,29 +28,52 @@ pub struct AddBookmarkRequest {
     pub longitude: f64,
 }
 
+impl Validate for AddBookmarkRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("location_name", validate_location_name(&self.location_name));
+        errors.check("category", validate_review_category(&self.category));
+        errors.check("latitude", validate_latitude(self.latitude));
+        errors.check("longitude", validate_longitude(self.longitude));
+
+        errors.finish()
+    }
+}
+
 /// Gets all the replies for a given review.
+#[utoipa::path(
+    context_path = "/api/v1/bookmark",
+    tag = "bookmark",
+    params(AddBookmarkRequest),
+    responses(
+        (status = 200, description = "bookmark was created"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("")]
 pub async fn add_bookmark(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    add_bookmark_request: Query<AddBookmarkRequest>,
-) -> Result<impl Responder> {
-    let does_bookmark_exist = db::does_bookmark_exist(
-        &pool,
-        &authenticated_user.0,
-        &add_bookmark_request.location_name,
-        add_bookmark_request.latitude,
-        add_bookmark_request.longitude,
-    )
-    .await;
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    add_bookmark_request: ValidQuery<AddBookmarkRequest>,
+) -> Result<impl Responder, AppError> {
+    let does_bookmark_exist = bookmark_repo
+        .does_bookmark_exist(
+            &authenticated_user.0,
+            &add_bookmark_request.location_name,
+            add_bookmark_request.latitude,
+            add_bookmark_request.longitude,
+        )
+        .await;
 
     if let Ok(bookmark_exists) = does_bookmark_exist {
         if bookmark_exists {
             return Ok(HttpResponse::Ok().finish());
         }
     } else {
-        return Err(ErrorInternalServerError(
-            "unable to get existing bookmarks".to_string(),
+        return Err(AppError::internal_message(
+            "unable to get existing bookmarks",
         ));
     }
 
@@ -58,17 +87,14 @@ pub async fn add_bookmark(
         longitude: add_bookmark_request.longitude,
     };
 
-    let add_bookmark_res = db::create_bookmark(&pool, &bookmark).await;
+    let add_bookmark_res = bookmark_repo.create_bookmark(&bookmark).await;
 
     match add_bookmark_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to create bookmark".to_string(),
-            ));
+            return Err(AppError::internal("unable to create bookmark", error));
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::db::Bookmark;
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct BookmarkPub {
     pub id: String,
     pub user_id: String,
@@ -1,33 +1,41 @@
-use crate::{authorization::AuthenticatedUser, db};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::BookmarkRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::bookmark_types::BookmarkPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetUserBookmarksRequest {
     user_id: String,
 }
 
 /// Gets all the replies for a given review.
+#[utoipa::path(
+    context_path = "/api/v1/bookmark",
+    tag = "bookmark",
+    responses(
+        (status = 200, description = "bookmarks of the user", body = [BookmarkPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/all_by_user")]
 pub async fn get_all_bookmarks(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
     get_user_bookmarks_request: Query<GetUserBookmarksRequest>,
-) -> Result<impl Responder> {
-    let bookmark_res = db::get_all_bookmarks(
-        &pool,
-        &authenticated_user.0,
-        &get_user_bookmarks_request.user_id,
-    )
-    .await;
+) -> Result<impl Responder, AppError> {
+    let bookmark_res = bookmark_repo
+        .get_all_bookmarks(&authenticated_user.0, &get_user_bookmarks_request.user_id)
+        .await;
 
     match bookmark_res {
         Ok(bookmarks) => {
@@ -37,10 +45,6 @@ pub async fn get_all_bookmarks(
                 .collect();
             return Ok(Json(bookmark_pubs));
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
-                "unable to get bookmarks".to_string(),
-            ))
-        }
+        Err(error) => return Err(AppError::internal("unable to get bookmarks", error)),
     }
 }
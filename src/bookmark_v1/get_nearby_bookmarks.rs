@@ -1,52 +1,78 @@
-use crate::{authorization::AuthenticatedUser, db};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{PagedBookmarkPub, PagedPub, BOOKMARK_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::BookmarkRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::bookmark_types::BookmarkPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct NearbyBookmarksRequest {
     latitude: f64,
     longitude: f64,
     user_id: String,
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for NearbyBookmarksRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        BOOKMARK_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Gets all the replies for a given review.
+#[utoipa::path(
+    context_path = "/api/v1/bookmark",
+    tag = "bookmark",
+    params(NearbyBookmarksRequest),
+    responses(
+        (status = 200, description = "page of bookmarks near the location", body = PagedBookmarkPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/all_nearby_bookmarks")]
 pub async fn get_nearby_all_bookmarks(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    nearby_bookmarks_request: Query<NearbyBookmarksRequest>,
-) -> Result<impl Responder> {
-    let bookmark_res = db::get_nearby_bookmarks(
-        &pool,
-        &authenticated_user.0,
-        &nearby_bookmarks_request.user_id,
-        nearby_bookmarks_request.page,
-        nearby_bookmarks_request.latitude,
-        nearby_bookmarks_request.longitude,
-    )
-    .await;
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    nearby_bookmarks_request: ValidQuery<NearbyBookmarksRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = BOOKMARK_PAGE_LIMITS.page_request(
+        &nearby_bookmarks_request.cursor,
+        nearby_bookmarks_request.limit,
+    );
+
+    let bookmark_res = bookmark_repo
+        .get_nearby_bookmarks(
+            &authenticated_user.0,
+            &nearby_bookmarks_request.user_id,
+            &page_request,
+            nearby_bookmarks_request.latitude,
+            nearby_bookmarks_request.longitude,
+        )
+        .await;
 
     match bookmark_res {
         Ok(bookmarks) => {
-            let bookmark_pubs: Vec<BookmarkPub> = bookmarks
-                .into_iter()
-                .map(|f| -> BookmarkPub { f.into() })
-                .collect();
+            let bookmark_pubs: PagedPub<BookmarkPub> = bookmarks.into();
             return Ok(Json(bookmark_pubs));
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
-                "unable to get bookmarks".to_string(),
-            ))
-        }
+        Err(error) => return Err(AppError::internal("unable to get bookmarks", error)),
     }
 }
@@ -1,14 +1,16 @@
-use crate::{authorization::AuthenticatedUser, db, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::BookmarkRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RemoveBookmarkRequest {
     pub location_name: String,
     pub latitude: f64,
@@ -16,30 +18,36 @@ pub struct RemoveBookmarkRequest {
 }
 
 /// Gets all the replies for a given review.
+#[utoipa::path(
+    context_path = "/api/v1/bookmark",
+    tag = "bookmark",
+    responses(
+        (status = 200, description = "bookmark was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/remove_bookmark")]
 pub async fn remove_bookmark(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
     remove_bookmark_request: Query<RemoveBookmarkRequest>,
-) -> Result<impl Responder> {
-    let remove_bookmark_res = db::remove_bookmark(
-        &pool,
-        &authenticated_user.0,
-        &remove_bookmark_request.location_name,
-        remove_bookmark_request.latitude,
-        remove_bookmark_request.longitude,
-    )
-    .await;
+) -> Result<impl Responder, AppError> {
+    let remove_bookmark_res = bookmark_repo
+        .remove_bookmark(
+            &authenticated_user.0,
+            &remove_bookmark_request.location_name,
+            remove_bookmark_request.latitude,
+            remove_bookmark_request.longitude,
+        )
+        .await;
 
     match remove_bookmark_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to remove bookmark".to_string(),
-            ));
+            return Err(AppError::internal("unable to remove bookmark", error));
         }
     }
 }
@@ -0,0 +1,138 @@
+use crate::Config;
+use actix_web::{
+    dev::Payload, http::header::HeaderMap, web::Data, Error, FromRequest, HttpMessage, HttpRequest,
+};
+use ipnet::IpNet;
+use std::{
+    fmt,
+    future::{ready, Ready},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
+
+/// The resolved ip address of the client making a request.
+///
+/// Forwarding headers are only honored when the request reached us through a trusted proxy,
+/// otherwise a client could spoof `X-Forwarded-For` to pretend to be anyone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClientIp(pub IpAddr);
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromRequest for ClientIp {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(get_client_ip(request)))
+    }
+}
+
+/// Gets the client ip for a request, resolving it once and caching it in the request extensions.
+/// Use this anywhere the client ip is needed so every consumer agrees on the same address.
+pub fn get_client_ip(request: &HttpRequest) -> ClientIp {
+    if let Some(client_ip) = request.extensions().get::<ClientIp>() {
+        return *client_ip;
+    }
+
+    let trusted_proxies: &[IpNet] = match request.app_data::<Data<Config>>() {
+        Some(config) => &config.trusted_proxies,
+        None => &[],
+    };
+
+    let client_ip = ClientIp(resolve_client_ip(
+        request.peer_addr().map(|addr| addr.ip()),
+        request.headers(),
+        trusted_proxies,
+    ));
+
+    request.extensions_mut().insert(client_ip);
+
+    return client_ip;
+}
+
+/// Works out the real client ip from the connecting peer and the `X-Forwarded-For` header.
+///
+/// The header is walked from right to left, since each proxy appends the address it received from.
+/// The first address that isn't a trusted proxy is the client. If the peer itself is not trusted,
+/// the header is ignored entirely.
+fn resolve_client_ip(
+    peer_ip: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNet],
+) -> IpAddr {
+    // missing peer info only happens in tests; group them somehow....
+    let peer_ip = peer_ip.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    if !is_trusted(&peer_ip, trusted_proxies) {
+        return peer_ip;
+    }
+
+    let forwarded_ips: Option<Vec<IpAddr>> = headers
+        .get_all("x-forwarded-for")
+        .map(|value| value.to_str().ok())
+        .collect::<Option<Vec<&str>>>()
+        .and_then(|values| {
+            values
+                .iter()
+                .flat_map(|value| value.split(','))
+                .map(parse_forwarded_ip)
+                .collect()
+        });
+
+    // a trusted proxy sent something we can't understand, so don't guess.
+    let forwarded_ips = match forwarded_ips {
+        Some(forwarded_ips) => forwarded_ips,
+        None => return peer_ip,
+    };
+
+    let mut client_ip = peer_ip;
+    for ip in forwarded_ips.into_iter().rev() {
+        client_ip = ip;
+
+        if !is_trusted(&ip, trusted_proxies) {
+            break;
+        }
+    }
+
+    return client_ip;
+}
+
+fn is_trusted(ip: &IpAddr, trusted_proxies: &[IpNet]) -> bool {
+    trusted_proxies.iter().any(|net| net.contains(ip))
+}
+
+/// Parses a single `X-Forwarded-For` entry, which some proxies suffix with a port.
+fn parse_forwarded_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+
+    if let Ok(ip) = value.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    value.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+}
+
+/// Parses a comma separated list of trusted proxies. Entries can be CIDRs or single addresses.
+pub fn parse_trusted_proxies(value: &str) -> Result<Vec<IpNet>, String> {
+    value
+        .split(',')
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| parse_ip_net(entry).ok_or(format!("invalid trusted proxy: {}", entry)))
+        .collect()
+}
+
+/// Parses either a CIDR like `10.0.0.0/8`, or a single address which is treated as a network of one.
+pub fn parse_ip_net(value: &str) -> Option<IpNet> {
+    let value = value.trim();
+
+    if let Ok(net) = value.parse::<IpNet>() {
+        return Some(net.trunc());
+    }
+
+    value.parse::<IpAddr>().ok().map(IpNet::from)
+}
@@ -0,0 +1,83 @@
+use crate::error::FieldError;
+use crate::extract::{FieldErrors, Validate};
+use crate::user_v1::UserPub;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use super::CompoundReviewPub;
+
+/// Most ids a single batch request can ask for.
+pub const MAX_BATCH_IDS: usize = 50;
+
+/// Ids to fetch in one request instead of one request per id.
+#[derive(Deserialize, ToSchema)]
+pub struct BatchRequest {
+    pub ids: Vec<String>,
+}
+
+impl BatchRequest {
+    /// `ids` in the order they were sent, without repeats.
+    pub fn unique_ids(&self) -> Vec<String> {
+        let mut unique_ids: Vec<String> = vec![];
+        for id in &self.ids {
+            if !unique_ids.contains(id) {
+                unique_ids.push(id.clone());
+            }
+        }
+
+        return unique_ids;
+    }
+}
+
+impl Validate for BatchRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if self.ids.is_empty() || self.ids.len() > MAX_BATCH_IDS {
+            errors.check::<()>(
+                "ids",
+                Err(format!("must have between 1 and {} ids", MAX_BATCH_IDS)),
+            );
+        }
+
+        errors.finish()
+    }
+}
+
+/// Result of a batch fetch.
+#[derive(Serialize, ToSchema)]
+#[aliases(
+    CompoundReviewBatchPub = BatchPub<CompoundReviewPub>,
+    UserBatchPub = BatchPub<UserPub>
+)]
+pub struct BatchPub<T> {
+    /// Everything that was found, in the order the ids were sent.
+    pub items: Vec<T>,
+
+    /// Ids that don't exist or that the caller isn't allowed to see.
+    /// The two aren't told apart, so a batch can't be used to probe for hidden ids.
+    pub missing: Vec<String>,
+}
+
+impl<T> BatchPub<T> {
+    /// Orders `found` like `requested_ids` and lists the requested ids that weren't found.
+    pub fn new<F>(requested_ids: &[String], found: Vec<T>, id: F) -> BatchPub<T>
+    where
+        F: Fn(&T) -> String,
+    {
+        let mut found_by_id: HashMap<String, T> =
+            found.into_iter().map(|item| (id(&item), item)).collect();
+
+        let mut items = vec![];
+        let mut missing = vec![];
+        for requested_id in requested_ids {
+            match found_by_id.remove(requested_id) {
+                Some(item) => items.push(item),
+                None => missing.push(requested_id.clone()),
+            }
+        }
+
+        return BatchPub { items, missing };
+    }
+}
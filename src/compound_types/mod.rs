@@ -1,2 +1,11 @@
 pub mod review_types;
 pub use review_types::*;
+
+pub mod paged_types;
+pub use paged_types::*;
+
+pub mod batch_types;
+pub use batch_types::*;
+
+pub mod sparse_types;
+pub use sparse_types::*;
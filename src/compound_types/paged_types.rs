@@ -0,0 +1,97 @@
+use crate::bookmark_v1::BookmarkPub;
+use crate::db::{Cursor, Page, PageRequest};
+use crate::extract::FieldErrors;
+use crate::review_v1::{ReviewAnnotationPub, ReviewPub};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::CompoundReviewPub;
+
+/// One page of a feed.
+/// Send `next_cursor` back as `cursor` to get the page after it.
+#[derive(Serialize, ToSchema)]
+#[aliases(
+    PagedReviewPub = PagedPub<ReviewPub>,
+    PagedCompoundReviewPub = PagedPub<CompoundReviewPub>,
+    PagedReviewAnnotationPub = PagedPub<ReviewAnnotationPub>,
+    PagedBookmarkPub = PagedPub<BookmarkPub>
+)]
+pub struct PagedPub<T> {
+    pub items: Vec<T>,
+
+    /// `null` once there is nothing left to fetch.
+    pub next_cursor: Option<String>,
+}
+
+impl<T> PagedPub<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<Cursor>) -> PagedPub<T> {
+        PagedPub {
+            items,
+            next_cursor: next_cursor.map(|cursor| cursor.encode()),
+        }
+    }
+}
+
+impl<T, U> From<Page<U>> for PagedPub<T>
+where
+    U: Into<T>,
+{
+    fn from(page: Page<U>) -> PagedPub<T> {
+        PagedPub::new(
+            page.items.into_iter().map(|item| item.into()).collect(),
+            page.next_cursor,
+        )
+    }
+}
+
+/// Page sizes a client may ask a feed for with `limit`.
+pub struct PageLimits {
+    /// Used when the request has no `limit`.
+    pub default: u32,
+    pub max: u32,
+}
+
+pub const REVIEW_PAGE_LIMITS: PageLimits = PageLimits {
+    default: 5,
+    max: 50,
+};
+
+/// Map annotations are small and fill a whole screen, so their pages are bigger.
+pub const ANNOTATION_PAGE_LIMITS: PageLimits = PageLimits {
+    default: 100,
+    max: 500,
+};
+
+pub const BOOKMARK_PAGE_LIMITS: PageLimits = PageLimits {
+    default: 5,
+    max: 50,
+};
+
+impl PageLimits {
+    /// Records problems with the `cursor` and `limit` query fields.
+    pub fn check(&self, errors: &mut FieldErrors, cursor: &Option<String>, limit: Option<u32>) {
+        if let Some(cursor) = cursor {
+            errors.check(
+                "cursor",
+                Cursor::decode(cursor).ok_or("invalid cursor".to_string()),
+            );
+        }
+
+        if let Some(limit) = limit {
+            if limit == 0 || limit > self.max {
+                errors.check::<()>(
+                    "limit",
+                    Err(format!("limit must be between 1 and {}", self.max)),
+                );
+            }
+        }
+    }
+
+    /// Expects the fields to have passed `check`, an undecodable cursor starts from the first page.
+    pub fn page_request(&self, cursor: &Option<String>, limit: Option<u32>) -> PageRequest {
+        PageRequest {
+            after: cursor.as_deref().and_then(Cursor::decode),
+            limit: limit.unwrap_or(self.default),
+        }
+    }
+}
@@ -3,17 +3,21 @@ use crate::pic_v1::PicPub;
 use crate::reply_v1::ReplyPub;
 use crate::review_v1::ReviewPub;
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
 ///
 /// This compound pub consists of other pubs.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CompoundReviewPub {
     pub review: ReviewPub,
     pub bookmarked: bool,
     pub likes: Vec<LikePub>,
+    pub like_count: u32,
+    /// Earliest first.
     pub replies: Vec<ReplyPub>,
+    pub reply_count: u32,
     pub pics: Vec<PicPub>,
 }
@@ -0,0 +1,358 @@
+use crate::error::FieldError;
+use crate::extract::{FieldErrors, Validate};
+use serde::{ser::Error, Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use super::CompoundReviewPub;
+
+/// Every field of `ReviewPub`.
+pub const REVIEW_FIELDS: &[&str] = &[
+    "id",
+    "user_id",
+    "created",
+    "category",
+    "text",
+    "stars",
+    "location_name",
+    "latitude",
+    "longitude",
+    "is_custom",
+    "delivered",
+    "recommended",
+];
+
+/// Every field of `CompoundReviewPub`.
+pub const COMPOUND_REVIEW_FIELDS: &[&str] = &[
+    "review",
+    "bookmarked",
+    "likes",
+    "like_count",
+    "replies",
+    "reply_count",
+    "pics",
+];
+
+/// Most replies a compound review can be asked to keep with `replies_limit`.
+pub const MAX_REPLIES_LIMIT: i64 = 50;
+
+/// Every field of `UserPub`.
+pub const USER_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "display_name",
+    "created",
+    "pic_id",
+    "recovery",
+    "pic_url",
+];
+
+/// Which fields of a response to keep, parsed from a `fields` query like `review.text,pics`.
+#[derive(Debug, Clone, Default)]
+pub struct FieldSelection {
+    /// `None` keeps every field.
+    only: Option<BTreeMap<String, FieldSelection>>,
+}
+
+impl FieldSelection {
+    pub fn all() -> FieldSelection {
+        FieldSelection { only: None }
+    }
+
+    /// Parses a comma separated list of field paths.
+    /// `fields` lists the top level names allowed, `nested` the names allowed under each object field.
+    pub fn parse(
+        value: &str,
+        fields: &[&str],
+        nested: &[(&str, &[&str])],
+    ) -> Result<FieldSelection, String> {
+        let mut selection = FieldSelection {
+            only: Some(BTreeMap::new()),
+        };
+
+        for path in value.split(',').map(str::trim) {
+            let known = match path.split_once('.') {
+                Some((parent, child)) => nested
+                    .iter()
+                    .any(|(name, children)| *name == parent && children.contains(&child)),
+                None => fields.contains(&path),
+            };
+
+            if !known {
+                return Err(format!("unknown field '{}'", path));
+            }
+
+            selection.insert(path);
+        }
+
+        return Ok(selection);
+    }
+
+    /// Keeps the field at the dotted `path` as well.
+    pub fn insert(&mut self, path: &str) {
+        let only = match &mut self.only {
+            Some(only) => only,
+            None => return,
+        };
+
+        match path.split_once('.') {
+            Some((parent, child)) => {
+                only.entry(parent.to_string())
+                    .or_insert(FieldSelection {
+                        only: Some(BTreeMap::new()),
+                    })
+                    .insert(child);
+            }
+            None => {
+                only.insert(path.to_string(), FieldSelection::all());
+            }
+        }
+    }
+
+    /// Drops the top level `field`.
+    /// `fields` is every field the response has, used when everything was selected.
+    pub fn remove(&mut self, field: &str, fields: &[&str]) {
+        let only = self.only.get_or_insert_with(|| {
+            fields
+                .iter()
+                .map(|name| (name.to_string(), FieldSelection::all()))
+                .collect()
+        });
+
+        only.remove(field);
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.only.is_none()
+    }
+
+    /// Removes the fields that weren't selected from a serialized response.
+    fn trim(&self, value: &mut serde_json::Value) {
+        let only = match &self.only {
+            Some(only) => only,
+            None => return,
+        };
+
+        if let serde_json::Value::Object(object) = value {
+            object.retain(|key, _| only.contains_key(key));
+
+            for (key, selection) in only {
+                if let Some(child) = object.get_mut(key) {
+                    selection.trim(child);
+                }
+            }
+        }
+    }
+}
+
+/// A response item serialized with only the selected fields.
+pub struct Sparse<T> {
+    item: T,
+    selection: Arc<FieldSelection>,
+}
+
+impl<T> Sparse<T> {
+    pub fn new(item: T, selection: Arc<FieldSelection>) -> Sparse<T> {
+        Sparse { item, selection }
+    }
+}
+
+impl<T> Serialize for Sparse<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.selection.is_all() {
+            return self.item.serialize(serializer);
+        }
+
+        let mut value = serde_json::to_value(&self.item).map_err(S::Error::custom)?;
+        self.selection.trim(&mut value);
+
+        return value.serialize(serializer);
+    }
+}
+
+fn wrap<T>(items: Vec<T>, selection: FieldSelection) -> Vec<Sparse<T>> {
+    let selection = Arc::new(selection);
+
+    return items
+        .into_iter()
+        .map(|item| Sparse::new(item, selection.clone()))
+        .collect();
+}
+
+/// Trims `ReviewPub` responses.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReviewFieldsRequest {
+    /// Comma separated `ReviewPub` fields to return, such as `text,stars`. `id` is always returned.
+    fields: Option<String>,
+}
+
+impl ReviewFieldsRequest {
+    fn selection(&self) -> FieldSelection {
+        let mut selection = match &self.fields {
+            Some(fields) => FieldSelection::parse(fields, REVIEW_FIELDS, &[]).unwrap_or_default(),
+            None => FieldSelection::all(),
+        };
+        selection.insert("id");
+
+        return selection;
+    }
+
+    pub fn sparse<T>(&self, item: T) -> Sparse<T> {
+        Sparse::new(item, Arc::new(self.selection()))
+    }
+
+    pub fn sparse_all<T>(&self, items: Vec<T>) -> Vec<Sparse<T>> {
+        wrap(items, self.selection())
+    }
+}
+
+impl Validate for ReviewFieldsRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(fields) = &self.fields {
+            errors.check("fields", FieldSelection::parse(fields, REVIEW_FIELDS, &[]));
+        }
+
+        errors.finish()
+    }
+}
+
+/// Trims `CompoundReviewPub` responses, and caps their likes and replies.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompoundReviewFieldsRequest {
+    /// Comma separated fields to return, such as `review,pics` or `review.text,replies`.
+    /// `review.id` is always returned.
+    fields: Option<String>,
+
+    /// Most replies to return on each review, earliest first, from 0 to 50. `reply_count` has the total.
+    /// Signed so a negative value is reported as a field error rather than failing to parse.
+    replies_limit: Option<i64>,
+
+    /// `count` returns only `like_count`, `full` (the default) every like as well.
+    likes: Option<String>,
+}
+
+impl CompoundReviewFieldsRequest {
+    fn selection(&self) -> FieldSelection {
+        let mut selection = match &self.fields {
+            Some(fields) => {
+                FieldSelection::parse(fields, COMPOUND_REVIEW_FIELDS, &[("review", REVIEW_FIELDS)])
+                    .unwrap_or_default()
+            }
+            None => FieldSelection::all(),
+        };
+        selection.insert("review.id");
+
+        if self.likes.as_deref() == Some("count") {
+            selection.remove("likes", COMPOUND_REVIEW_FIELDS);
+            selection.insert("like_count");
+        }
+
+        return selection;
+    }
+
+    fn limit(&self, compound_review: &mut CompoundReviewPub) {
+        if let Some(replies_limit) = self.replies_limit {
+            // validated to be in range, the clamp only guards against an unvalidated request.
+            let replies_limit = replies_limit.clamp(0, MAX_REPLIES_LIMIT);
+            compound_review.replies.truncate(replies_limit as usize);
+        }
+    }
+
+    pub fn sparse(&self, mut item: CompoundReviewPub) -> Sparse<CompoundReviewPub> {
+        self.limit(&mut item);
+
+        return Sparse::new(item, Arc::new(self.selection()));
+    }
+
+    pub fn sparse_all(&self, mut items: Vec<CompoundReviewPub>) -> Vec<Sparse<CompoundReviewPub>> {
+        for item in &mut items {
+            self.limit(item);
+        }
+
+        return wrap(items, self.selection());
+    }
+}
+
+impl Validate for CompoundReviewFieldsRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(fields) = &self.fields {
+            errors.check(
+                "fields",
+                FieldSelection::parse(fields, COMPOUND_REVIEW_FIELDS, &[("review", REVIEW_FIELDS)]),
+            );
+        }
+
+        if let Some(replies_limit) = self.replies_limit {
+            if !(0..=MAX_REPLIES_LIMIT).contains(&replies_limit) {
+                errors.check::<()>(
+                    "replies_limit",
+                    Err(format!(
+                        "replies_limit must be between 0 and {}",
+                        MAX_REPLIES_LIMIT
+                    )),
+                );
+            }
+        }
+
+        if let Some(likes) = &self.likes {
+            if likes != "count" && likes != "full" {
+                errors.check::<()>("likes", Err("likes must be 'count' or 'full'".to_string()));
+            }
+        }
+
+        errors.finish()
+    }
+}
+
+/// Trims `UserPub` responses.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UserFieldsRequest {
+    /// Comma separated `UserPub` fields to return, such as `display_name,pic_url`. `id` is always returned.
+    fields: Option<String>,
+}
+
+impl UserFieldsRequest {
+    fn selection(&self) -> FieldSelection {
+        let mut selection = match &self.fields {
+            Some(fields) => FieldSelection::parse(fields, USER_FIELDS, &[]).unwrap_or_default(),
+            None => FieldSelection::all(),
+        };
+        selection.insert("id");
+
+        return selection;
+    }
+
+    pub fn sparse<T>(&self, item: T) -> Sparse<T> {
+        Sparse::new(item, Arc::new(self.selection()))
+    }
+
+    pub fn sparse_all<T>(&self, items: Vec<T>) -> Vec<Sparse<T>> {
+        wrap(items, self.selection())
+    }
+}
+
+impl Validate for UserFieldsRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(fields) = &self.fields {
+            errors.check("fields", FieldSelection::parse(fields, USER_FIELDS, &[]));
+        }
+
+        errors.finish()
+    }
+}
@@ -0,0 +1,217 @@
+use base64::{engine::general_purpose, Engine};
+use ipnet::IpNet;
+use jwt::{encode_apn_jwt_secret, encode_jwt_secret, APNSigningKey, SigningKeys};
+use std::{env, net::SocketAddr, time::Duration};
+
+use super::{load_telemetry_config, ConfigError, ConfigSource, TelemetryConfig};
+use crate::{
+    client_ip::parse_trusted_proxies,
+    ratelimit::{default_policies, RateLimitPolicy},
+};
+
+/// Config file used when `CONFIG_PATH` is not set.
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// Config file used when `CONFIG_PATH` is not set and `MOB_DEV` is.
+const DEFAULT_DEV_CONFIG_PATH: &str = "config.dev.toml";
+
+/// Everything the service needs to start, validated up front.
+#[derive(Clone)]
+pub struct Config {
+    pub bind_address: SocketAddr,
+    pub cors_origins: Vec<String>,
+    /// Internal address `/metrics` is served on, kept off the public port.
+    pub metrics_bind_address: SocketAddr,
+    /// How long in-flight requests get to finish once shutdown starts.
+    pub shutdown_timeout: Duration,
+    /// How long the notification worker gets to send what's queued once shutdown starts.
+    pub notification_drain_timeout: Duration,
+    pub twilio_key: String,
+    pub db_connection_string: String,
+    /// Read replica heavy feed and map queries go to, everything stays on the primary when not set.
+    pub db_replica_connection_string: Option<String>,
+    /// How long a user's reads stay on the primary after they write, so they see their own changes.
+    pub read_your_writes_window: Duration,
+    /// Queries taking at least this long are written to the slow query log.
+    pub slow_query_threshold: Duration,
+    /// File the slow query log is appended to, stderr when not set.
+    pub slow_query_log: Option<String>,
+    /// Apply any pending embedded migrations before serving.
+    pub run_migrations: bool,
+    pub signing_keys: SigningKeys,
+    pub spaces_key: String,
+    pub spaces_secret: String,
+    pub spaces_bucket: String,
+    pub telemetry: TelemetryConfig,
+    pub apn_key: APNSigningKey,
+    pub apn_topic: String,
+    pub sendgrid_key: String,
+    pub github_key: String,
+    pub rate_limit_policies: Vec<RateLimitPolicy>,
+    pub trusted_proxies: Vec<IpNet>,
+    pub geoip_asn_db: Option<String>,
+    pub geoip_country_db: Option<String>,
+}
+
+/// Loads config from the TOML file at `CONFIG_PATH`, with environment variables overriding the file.
+pub fn load_config() -> Result<Config, ConfigError> {
+    let source = match env::var("CONFIG_PATH") {
+        Ok(path) => ConfigSource::from_file(&path, true)?,
+        Err(_) => {
+            let path = if env::var("MOB_DEV").is_ok() {
+                DEFAULT_DEV_CONFIG_PATH
+            } else {
+                DEFAULT_CONFIG_PATH
+            };
+            ConfigSource::from_file(path, false)?
+        }
+    };
+
+    let bind_address = source
+        .string("server.bind_address", &["BIND_ADDRESS"])?
+        .unwrap_or("0.0.0.0:80".to_string());
+    let bind_address =
+        bind_address
+            .parse::<SocketAddr>()
+            .map_err(|error| ConfigError::Invalid {
+                key: "server.bind_address".to_string(),
+                reason: error.to_string(),
+            })?;
+
+    let metrics_bind_address = source
+        .string("metrics.bind_address", &["METRICS_BIND_ADDRESS"])?
+        .unwrap_or("0.0.0.0:9090".to_string());
+    let metrics_bind_address = metrics_bind_address
+        .parse::<SocketAddr>()
+        .map_err(|error| ConfigError::Invalid {
+            key: "metrics.bind_address".to_string(),
+            reason: error.to_string(),
+        })?;
+    if metrics_bind_address.port() == bind_address.port() {
+        return Err(ConfigError::Invalid {
+            key: "metrics.bind_address".to_string(),
+            reason: "must use a different port than server.bind_address".to_string(),
+        });
+    }
+
+    let cors_origins = source
+        .string_list("server.cors_origins", &["CORS_ORIGINS"])?
+        .unwrap_or(vec![
+            "https://reviewwithfriends.com".to_string(),
+            "http://localhost:8000".to_string(),
+        ]);
+    if let Some(origin) = cors_origins
+        .iter()
+        .find(|origin| !origin.starts_with("http://") && !origin.starts_with("https://"))
+    {
+        return Err(ConfigError::Invalid {
+            key: "server.cors_origins".to_string(),
+            reason: format!("{} is not an http or https origin", origin),
+        });
+    }
+
+    let trusted_proxies = source
+        .string_list("server.trusted_proxies", &["TRUSTED_PROXIES"])?
+        .unwrap_or_default();
+    let trusted_proxies = parse_trusted_proxies(&trusted_proxies.join(",")).map_err(|reason| {
+        ConfigError::Invalid {
+            key: "server.trusted_proxies".to_string(),
+            reason,
+        }
+    })?;
+
+    let jwt_key = source.required_string("auth.jwt_key", &["JWT_KEY"])?;
+
+    let apn_key = source.required_string("apns.key", &["APN_KEY"])?;
+    if general_purpose::STANDARD.decode(&apn_key).is_err() {
+        return Err(ConfigError::Invalid {
+            key: "apns.key".to_string(),
+            reason: "expected base64 encoded DER".to_string(),
+        });
+    }
+
+    let rate_limit_policies: Vec<RateLimitPolicy> = source
+        .structured("rate_limit.policies", &["RATE_LIMIT_POLICIES"])?
+        .unwrap_or_else(default_policies);
+    if let Some(policy) = rate_limit_policies
+        .iter()
+        .find(|policy| policy.capacity == 0 || policy.period_seconds == 0)
+    {
+        return Err(ConfigError::Invalid {
+            key: "rate_limit.policies".to_string(),
+            reason: format!(
+                "policy {} needs a non-zero capacity and period",
+                policy.name
+            ),
+        });
+    }
+
+    return Ok(Config {
+        bind_address,
+        cors_origins,
+        metrics_bind_address,
+        shutdown_timeout: Duration::from_secs(
+            source
+                .u64(
+                    "server.shutdown_timeout_seconds",
+                    &["SHUTDOWN_TIMEOUT_SECONDS"],
+                )?
+                .unwrap_or(15),
+        ),
+        notification_drain_timeout: Duration::from_secs(
+            source
+                .u64(
+                    "notifications.drain_timeout_seconds",
+                    &["NOTIFICATION_DRAIN_TIMEOUT_SECONDS"],
+                )?
+                .unwrap_or(5),
+        ),
+        twilio_key: source.required_string("twilio.key", &["TWILIO"])?,
+        db_connection_string: source.required_string(
+            "database.connection_string",
+            &["DB_CONNECTION", "DATABASE_URL"],
+        )?,
+        db_replica_connection_string: source.string(
+            "database.replica_connection_string",
+            &["DB_REPLICA_CONNECTION"],
+        )?,
+        read_your_writes_window: Duration::from_secs(
+            source
+                .u64(
+                    "database.read_your_writes_seconds",
+                    &["READ_YOUR_WRITES_SECONDS"],
+                )?
+                .unwrap_or(5),
+        ),
+        slow_query_threshold: Duration::from_millis(
+            source
+                .u64(
+                    "database.slow_query_threshold_ms",
+                    &["SLOW_QUERY_THRESHOLD_MS"],
+                )?
+                .unwrap_or(500),
+        ),
+        slow_query_log: source.string("database.slow_query_log", &["SLOW_QUERY_LOG"])?,
+        run_migrations: source
+            .bool("database.run_migrations", &["RUN_MIGRATIONS"])?
+            .unwrap_or(false),
+        signing_keys: encode_jwt_secret(&jwt_key),
+        spaces_key: source.required_string("spaces.key", &["SPACES_KEY", "MOB_SPACES_KEY"])?,
+        spaces_secret: source
+            .required_string("spaces.secret", &["SPACES_SECRET", "MOB_SPACES_SECRET"])?,
+        spaces_bucket: source
+            .string("spaces.bucket", &["SPACES_BUCKET"])?
+            .unwrap_or("bout".to_string()),
+        telemetry: load_telemetry_config(&source)?,
+        apn_key: encode_apn_jwt_secret(&apn_key),
+        apn_topic: source
+            .string("apns.topic", &["APNS_TOPIC"])?
+            .unwrap_or("com.spacedoglabs.spotster".to_string()),
+        sendgrid_key: source.required_string("sendgrid.key", &["SENDGRID_KEY"])?,
+        github_key: source.required_string("github.key", &["GITHUB_KEY"])?,
+        rate_limit_policies,
+        trusted_proxies,
+        geoip_asn_db: source.string("geoip.asn_db", &["GEOIP_ASN_DB"])?,
+        geoip_country_db: source.string("geoip.country_db", &["GEOIP_COUNTRY_DB"])?,
+    });
+}
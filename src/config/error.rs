@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// Problems found while loading config, always naming the key at fault.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read or parsed.
+    File { path: String, reason: String },
+
+    /// A required key was not set in the file or environment.
+    Missing { key: String, env: Vec<String> },
+
+    /// A key was set, but to something we can't use.
+    Invalid { key: String, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, reason } => {
+                write!(f, "unable to load config file {}: {}", path, reason)
+            }
+            ConfigError::Missing { key, env } => {
+                if env.is_empty() {
+                    write!(f, "missing required config key `{}`", key)
+                } else {
+                    write!(
+                        f,
+                        "missing required config key `{}` (or env {})",
+                        key,
+                        env.join(" / ")
+                    )
+                }
+            }
+            ConfigError::Invalid { key, reason } => {
+                write!(f, "invalid value for config key `{}`: {}", key, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
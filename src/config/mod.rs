@@ -0,0 +1,11 @@
+pub mod app_config;
+pub use app_config::*;
+
+pub mod error;
+pub use error::*;
+
+pub mod telemetry_config;
+pub use telemetry_config::*;
+
+pub mod source;
+pub use source::*;
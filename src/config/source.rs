@@ -0,0 +1,229 @@
+use serde::de::DeserializeOwned;
+use std::{env, fs, io::ErrorKind};
+use toml::Value;
+
+use super::ConfigError;
+
+/// Values from the config file, with environment variables taking precedence.
+///
+/// Keys are dotted paths into the file, i.e. `spaces.bucket` reads `bucket` from the `[spaces]` table.
+pub struct ConfigSource {
+    file: Value,
+}
+
+impl ConfigSource {
+    /// Reads the TOML file at `path`.
+    /// A missing file is only an error when `required`, so env-only deployments keep working.
+    pub fn from_file(path: &str, required: bool) -> Result<Self, ConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound && !required => String::new(),
+            Err(error) => {
+                return Err(ConfigError::File {
+                    path: path.to_string(),
+                    reason: error.to_string(),
+                })
+            }
+        };
+
+        let file: Value = toml::from_str(&contents).map_err(|error| ConfigError::File {
+            path: path.to_string(),
+            reason: error.to_string(),
+        })?;
+
+        return Ok(ConfigSource { file });
+    }
+
+    fn file_value(&self, key: &str) -> Option<&Value> {
+        key.split('.')
+            .try_fold(&self.file, |value, part| value.get(part))
+    }
+
+    fn env_value(envs: &[&str]) -> Option<String> {
+        envs.iter().find_map(|name| env::var(name).ok())
+    }
+
+    /// Gets an optional string, checking each env var in order before the file.
+    pub fn string(&self, key: &str, envs: &[&str]) -> Result<Option<String>, ConfigError> {
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return Ok(Some(value));
+        }
+
+        match self.file_value(key) {
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(ConfigError::Invalid {
+                key: key.to_string(),
+                reason: "expected a string".to_string(),
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets an optional non-negative integer.
+    pub fn u64(&self, key: &str, envs: &[&str]) -> Result<Option<u64>, ConfigError> {
+        let invalid = || ConfigError::Invalid {
+            key: key.to_string(),
+            reason: "expected a non-negative integer".to_string(),
+        };
+
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return value.trim().parse::<u64>().map(Some).map_err(|_| invalid());
+        }
+
+        match self.file_value(key) {
+            Some(Value::Integer(value)) => u64::try_from(*value).map(Some).map_err(|_| invalid()),
+            Some(_) => Err(invalid()),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets an optional number.
+    pub fn f64(&self, key: &str, envs: &[&str]) -> Result<Option<f64>, ConfigError> {
+        let invalid = || ConfigError::Invalid {
+            key: key.to_string(),
+            reason: "expected a number".to_string(),
+        };
+
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return value.trim().parse::<f64>().map(Some).map_err(|_| invalid());
+        }
+
+        match self.file_value(key) {
+            Some(Value::Float(value)) => Ok(Some(*value)),
+            Some(Value::Integer(value)) => Ok(Some(*value as f64)),
+            Some(_) => Err(invalid()),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets an optional flag. The env var form accepts `true`/`false` or `1`/`0`.
+    pub fn bool(&self, key: &str, envs: &[&str]) -> Result<Option<bool>, ConfigError> {
+        let invalid = || ConfigError::Invalid {
+            key: key.to_string(),
+            reason: "expected true or false".to_string(),
+        };
+
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(Some(true)),
+                "false" | "0" => Ok(Some(false)),
+                _ => Err(invalid()),
+            };
+        }
+
+        match self.file_value(key) {
+            Some(Value::Boolean(value)) => Ok(Some(*value)),
+            Some(_) => Err(invalid()),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets a table of strings. The env var form is `key=value` pairs separated by commas,
+    /// matching the `OTEL_*` conventions.
+    pub fn string_map(
+        &self,
+        key: &str,
+        envs: &[&str],
+    ) -> Result<Option<Vec<(String, String)>>, ConfigError> {
+        let invalid = |reason: String| ConfigError::Invalid {
+            key: key.to_string(),
+            reason,
+        };
+
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return value
+                .split(',')
+                .map(|pair| pair.trim())
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| match pair.split_once('=') {
+                    Some((name, value)) => Ok((name.trim().to_string(), value.trim().to_string())),
+                    None => Err(invalid(format!("{} is not a key=value pair", pair))),
+                })
+                .collect::<Result<Vec<(String, String)>, ConfigError>>()
+                .map(Some);
+        }
+
+        match self.file_value(key) {
+            Some(Value::Table(table)) => table
+                .iter()
+                .map(|(name, value)| match value {
+                    Value::String(value) => Ok((name.clone(), value.clone())),
+                    _ => Err(invalid(format!("{} must be a string", name))),
+                })
+                .collect::<Result<Vec<(String, String)>, ConfigError>>()
+                .map(Some),
+            Some(_) => Err(invalid("expected a table of strings".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets a string that must be set and not empty.
+    pub fn required_string(&self, key: &str, envs: &[&str]) -> Result<String, ConfigError> {
+        match self.string(key, envs)? {
+            Some(value) if !value.trim().is_empty() => Ok(value),
+            _ => Err(ConfigError::Missing {
+                key: key.to_string(),
+                env: envs.iter().map(|name| name.to_string()).collect(),
+            }),
+        }
+    }
+
+    /// Gets a list of strings. The env var form is comma separated.
+    pub fn string_list(
+        &self,
+        key: &str,
+        envs: &[&str],
+    ) -> Result<Option<Vec<String>>, ConfigError> {
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return Ok(Some(
+                value
+                    .split(',')
+                    .map(|entry| entry.trim().to_string())
+                    .filter(|entry| !entry.is_empty())
+                    .collect(),
+            ));
+        }
+
+        match self.file_value(key) {
+            Some(value) => value
+                .clone()
+                .try_into::<Vec<String>>()
+                .map(Some)
+                .map_err(|_| ConfigError::Invalid {
+                    key: key.to_string(),
+                    reason: "expected a list of strings".to_string(),
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets a structured value. The env var form is JSON, since it has to fit on one line.
+    pub fn structured<T: DeserializeOwned>(
+        &self,
+        key: &str,
+        envs: &[&str],
+    ) -> Result<Option<T>, ConfigError> {
+        if let Some(value) = ConfigSource::env_value(envs) {
+            return serde_json::from_str(&value)
+                .map(Some)
+                .map_err(|error| ConfigError::Invalid {
+                    key: key.to_string(),
+                    reason: error.to_string(),
+                });
+        }
+
+        match self.file_value(key) {
+            Some(value) => {
+                value
+                    .clone()
+                    .try_into::<T>()
+                    .map(Some)
+                    .map_err(|error| ConfigError::Invalid {
+                        key: key.to_string(),
+                        reason: error.to_string(),
+                    })
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::{ConfigError, ConfigSource};
+
+/// New Relic's OTLP endpoint, used when only the legacy `NR_KEY` is set.
+const NEWRELIC_OTLP_ENDPOINT: &str = "https://otlp.nr-data.net";
+
+/// Wire protocol for the OTLP exporter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OtlpProtocol {
+    HttpProtobuf,
+    Grpc,
+}
+
+/// Where and how traces are exported.
+#[derive(Clone)]
+pub struct TelemetryConfig {
+    /// OTLP collector base url. Traces go to stdout when not set.
+    pub endpoint: Option<String>,
+    pub protocol: OtlpProtocol,
+    /// Sent with every export, usually for auth.
+    pub headers: Vec<(String, String)>,
+    /// Fraction of new traces to keep, between 0 and 1. Child spans follow their parent's decision.
+    pub sampler_ratio: f64,
+    pub service_name: String,
+    /// Extra resource attributes attached to everything exported.
+    pub resource_attributes: Vec<(String, String)>,
+}
+
+/// Loads telemetry config, using the standard `OTEL_*` env names for overrides.
+pub fn load_telemetry_config(source: &ConfigSource) -> Result<TelemetryConfig, ConfigError> {
+    let mut endpoint = source.string("telemetry.endpoint", &["OTEL_EXPORTER_OTLP_ENDPOINT"])?;
+    let mut headers = source
+        .string_map("telemetry.headers", &["OTEL_EXPORTER_OTLP_HEADERS"])?
+        .unwrap_or_default();
+
+    // keeps existing deployments exporting to new relic without any config changes.
+    if endpoint.is_none() {
+        if let Some(newrelic_key) = source.string("newrelic.key", &["NR_KEY"])? {
+            endpoint = Some(NEWRELIC_OTLP_ENDPOINT.to_string());
+            headers.push(("api-key".to_string(), newrelic_key));
+        }
+    }
+
+    if let Some(endpoint) = &endpoint {
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(ConfigError::Invalid {
+                key: "telemetry.endpoint".to_string(),
+                reason: "expected an http or https url".to_string(),
+            });
+        }
+    }
+
+    let protocol = match source
+        .string("telemetry.protocol", &["OTEL_EXPORTER_OTLP_PROTOCOL"])?
+        .as_deref()
+    {
+        None | Some("http/protobuf") => OtlpProtocol::HttpProtobuf,
+        Some("grpc") => OtlpProtocol::Grpc,
+        Some(protocol) => {
+            return Err(ConfigError::Invalid {
+                key: "telemetry.protocol".to_string(),
+                reason: format!("{} is not one of http/protobuf or grpc", protocol),
+            })
+        }
+    };
+
+    let sampler_ratio = source
+        .f64("telemetry.sampler_ratio", &["OTEL_TRACES_SAMPLER_ARG"])?
+        .unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&sampler_ratio) {
+        return Err(ConfigError::Invalid {
+            key: "telemetry.sampler_ratio".to_string(),
+            reason: "must be between 0 and 1".to_string(),
+        });
+    }
+
+    return Ok(TelemetryConfig {
+        endpoint,
+        protocol,
+        headers,
+        sampler_ratio,
+        service_name: source
+            .string("telemetry.service_name", &["OTEL_SERVICE_NAME"])?
+            .unwrap_or("bout-backend".to_string()),
+        resource_attributes: source
+            .string_map(
+                "telemetry.resource_attributes",
+                &["OTEL_RESOURCE_ATTRIBUTES"],
+            )?
+            .unwrap_or_default(),
+    });
+}
@@ -1,25 +1,333 @@
 use sqlx::{Error, MySqlPool, Row};
 
-use crate::db::Report;
+use crate::db::{
+    traced, AuthAttempt, IpBlock, PhoneAuth, Pic, Report, Review, SchemaMigration, User,
+    UserActivityCounts, NO_SUCH_TABLE,
+};
 
 /// Gets the total number of active users.
 pub async fn get_total_user_count(client: &MySqlPool) -> Result<i64, Error> {
-    let row = sqlx::query("SELECT count(*) FROM user")
-        .fetch_one(client)
-        .await?;
+    traced("get_total_user_count", async move {
+        let row = sqlx::query("SELECT count(*) FROM user")
+            .fetch_one(client)
+            .await?;
 
-    return Ok(row.get(0));
+        return Ok(row.get(0));
+    })
+    .await
 }
 
 /// Simply gets all report records.
 pub async fn get_all_reports(client: &MySqlPool) -> Result<Vec<Report>, Error> {
-    let reports = sqlx::query_as!(
-        Report,
-        "SELECT *
+    traced("get_all_reports", async move {
+        let reports = sqlx::query_as!(
+            Report,
+            "SELECT *
         FROM reports"
-    )
-    .fetch_all(client)
-    .await?;
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(reports);
+    })
+    .await
+}
+
+/// Tries to get a user by `user.email`, and will return `None` if not found.
+pub async fn get_user_by_email(client: &MySqlPool, email: &str) -> Result<Option<User>, Error> {
+    traced("get_user_by_email", async move {
+        let mut rows = sqlx::query_as!(
+            User,
+            "SELECT *
+        FROM   user
+        WHERE  email = ? ",
+            email
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.pop());
+    })
+    .await
+}
+
+/// Gets the most recent phoneauths for a phone, regardless of `phoneauth.used`.
+pub async fn get_recent_phoneauths(
+    client: &MySqlPool,
+    phone: &str,
+    limit: u32,
+) -> Result<Vec<PhoneAuth>, Error> {
+    traced("get_recent_phoneauths", async move {
+        let phone_auths = sqlx::query_as!(
+            PhoneAuth,
+            "SELECT *
+        FROM   phoneauth
+        WHERE  phone = ?
+        ORDER  BY created DESC
+        LIMIT  ? ",
+            phone,
+            limit
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(phone_auths);
+    })
+    .await
+}
+
+/// Gets the most recent authattempts for a phone.
+pub async fn get_recent_authattempts(
+    client: &MySqlPool,
+    phone: &str,
+    limit: u32,
+) -> Result<Vec<AuthAttempt>, Error> {
+    traced("get_recent_authattempts", async move {
+        let auth_attempts = sqlx::query_as!(
+            AuthAttempt,
+            "SELECT *
+        FROM   authattempt
+        WHERE  phone = ?
+        ORDER  BY created DESC
+        LIMIT  ? ",
+            phone,
+            limit
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(auth_attempts);
+    })
+    .await
+}
+
+/// Gets the number of records a user owns across the main tables.
+/// The friend count excludes the friend record every user has with themselves.
+pub async fn get_user_activity_counts(
+    client: &MySqlPool,
+    user_id: &str,
+) -> Result<UserActivityCounts, Error> {
+    traced("get_user_activity_counts", async move {
+        let row = sqlx::query(
+            "SELECT (SELECT count(*) FROM review WHERE user_id = ?)                       AS reviews,
+                (SELECT count(*) FROM friend WHERE user_id = ? AND friend_id != ?)    AS friends,
+                (SELECT count(*) FROM friendrequest WHERE friend_id = ?)              AS incoming_requests,
+                (SELECT count(*) FROM friendrequest WHERE user_id = ?)                AS outgoing_requests,
+                (SELECT count(*) FROM likes WHERE user_id = ?)                        AS likes,
+                (SELECT count(*) FROM reply WHERE user_id = ?)                        AS replies,
+                (SELECT count(*) FROM bookmark WHERE user_id = ?)                     AS bookmarks,
+                (SELECT count(*) FROM notification WHERE review_user_id = ?)          AS notifications ",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_one(client)
+        .await?;
+
+        return Ok((&row).into());
+    })
+    .await
+}
+
+/// Gets all reports a user has filed against other users.
+pub async fn get_reports_filed(
+    client: &MySqlPool,
+    reporter_id: &str,
+) -> Result<Vec<Report>, Error> {
+    traced("get_reports_filed", async move {
+        let reports = sqlx::query_as!(
+            Report,
+            "SELECT *
+        FROM   reports
+        WHERE  reporter_id = ?
+        ORDER  BY created DESC",
+            reporter_id
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(reports);
+    })
+    .await
+}
+
+/// Gets all reports other users have filed against a user.
+pub async fn get_reports_received(client: &MySqlPool, user_id: &str) -> Result<Vec<Report>, Error> {
+    traced("get_reports_received", async move {
+        let reports = sqlx::query_as!(
+            Report,
+            "SELECT *
+        FROM   reports
+        WHERE  user_id = ?
+        ORDER  BY created DESC",
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(reports);
+    })
+    .await
+}
+
+/// Gets the latest reviews made by a user.
+/// ## Does not account for friends, this is only for admin use.
+pub async fn get_recent_reviews_by_user(
+    client: &MySqlPool,
+    user_id: &str,
+    limit: u32,
+) -> Result<Vec<Review>, Error> {
+    traced("get_recent_reviews_by_user", async move {
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        St_x(r.location) AS longitude,
+        St_y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+ WHERE  r.user_id = ?
+ ORDER  BY r.created DESC
+ LIMIT  ? ",
+            user_id,
+            limit
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(reviews);
+    })
+    .await
+}
+
+/// Gets every ipblock rule, including expired ones.
+pub async fn get_all_ipblocks(client: &MySqlPool) -> Result<Vec<IpBlock>, Error> {
+    traced("get_all_ipblocks", async move {
+        let ipblocks = sqlx::query_as!(
+            IpBlock,
+            "SELECT *
+        FROM   ipblock
+        ORDER  BY created DESC"
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(ipblocks);
+    })
+    .await
+}
+
+/// Gets every migration recorded in the database, oldest first.
+/// A database that was never migrated has no history table, which reads as nothing applied.
+pub async fn get_applied_migrations(client: &MySqlPool) -> Result<Vec<SchemaMigration>, Error> {
+    traced("get_applied_migrations", async move {
+        let rows_res = sqlx::query(
+            "SELECT version, description, installed_on, success, checksum, execution_time
+        FROM   _sqlx_migrations
+        ORDER  BY version",
+        )
+        .fetch_all(client)
+        .await;
+
+        let rows = match rows_res {
+            Ok(rows) => rows,
+            Err(Error::Database(error)) if error.code().as_deref() == Some(NO_SUCH_TABLE) => {
+                return Ok(vec![]);
+            }
+            Err(error) => return Err(error),
+        };
+
+        return Ok(rows
+            .iter()
+            .map(|row| SchemaMigration {
+                version: row.get("version"),
+                description: row.get("description"),
+                installed_on: row.get("installed_on"),
+                success: row.get("success"),
+                checksum: row.get("checksum"),
+                execution_time: row.get("execution_time"),
+            })
+            .collect());
+    })
+    .await
+}
+
+/// Gets pics nothing points at anymore.
+/// Review pics whose review is gone, and profile pics no user has set.
+pub async fn get_orphaned_pics(client: &MySqlPool) -> Result<Vec<Pic>, Error> {
+    traced("get_orphaned_pics", async move {
+        let pics = sqlx::query_as!(
+            Pic,
+            "SELECT p.*
+        FROM   pic AS p
+        WHERE  ( p.review_id IS NOT NULL
+                 AND NOT EXISTS (SELECT 1
+                                 FROM   review AS r
+                                 WHERE  r.id = p.review_id) )
+                OR ( p.review_id IS NULL
+                     AND NOT EXISTS (SELECT 1
+                                     FROM   user AS u
+                                     WHERE  u.pic_id = p.id) )
+        ORDER  BY p.created ASC"
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(pics);
+    })
+    .await
+}
+
+/// Gets the pics attached to any review made by a user.
+pub async fn get_review_pics_by_user(client: &MySqlPool, user_id: &str) -> Result<Vec<Pic>, Error> {
+    traced("get_review_pics_by_user", async move {
+        let pics = sqlx::query_as!(
+            Pic,
+            "SELECT p.*
+        FROM   pic AS p
+               INNER JOIN review AS r
+                       ON p.review_id = r.id
+        WHERE  r.user_id = ? ",
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(pics);
+    })
+    .await
+}
+
+/// Gets users without a friend record for themselves.
+/// `create_user` adds one, but accounts created before it did are missing it.
+pub async fn get_users_missing_self_friend(client: &MySqlPool) -> Result<Vec<User>, Error> {
+    traced("get_users_missing_self_friend", async move {
+        let users = sqlx::query_as!(
+            User,
+            "SELECT u.*
+        FROM   user AS u
+        WHERE  NOT EXISTS (SELECT 1
+                           FROM   friend AS f
+                           WHERE  f.user_id = u.id
+                                  AND f.friend_id = u.id)"
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reports);
+        return Ok(users);
+    })
+    .await
 }
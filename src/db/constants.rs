@@ -1 +1,10 @@
 pub const USER_ACTION_TYPE: i32 = 0;
+
+/// SQLSTATE MySQL reports when a query references a table that doesn't exist.
+pub const NO_SUCH_TABLE: &str = "42S02";
+
+/// `tombstone.item_type` for each kind of deleted row `/sync` reports.
+pub const TOMBSTONE_REVIEW: &str = "review";
+pub const TOMBSTONE_REPLY: &str = "reply";
+pub const TOMBSTONE_LIKE: &str = "like";
+pub const TOMBSTONE_BOOKMARK: &str = "bookmark";
@@ -1,89 +1,144 @@
 use images::DEFAULT_PIC_ID;
-use sqlx::{types::chrono::Utc, Error, MySqlPool};
+use sqlx::{
+    types::chrono::{Duration, NaiveDateTime, Utc},
+    Error, MySql, MySqlPool, Transaction,
+};
 use uuid::Uuid;
 
-use crate::db::{Notification, Report};
+use crate::db::{IdempotencyKey, IpBlock, Notification, PendingNotification, Report};
+use crate::sync_v1::SYNC_TOKEN_MAX_AGE_DAYS;
+
+use super::{
+    traced, Bookmark, Friend, Pic, Review, User, TOMBSTONE_BOOKMARK, TOMBSTONE_LIKE,
+    TOMBSTONE_REPLY, TOMBSTONE_REVIEW, USER_ACTION_TYPE,
+};
+
+/// Records deleted rows so `/sync` can tell clients to drop them.
+/// Each item is the deleted row's id and the user whose friends could see it.
+/// Also prunes tombstones older than any sync token still accepted, as nothing can ask for them.
+async fn create_tombstones(
+    trans: &mut Transaction<'_, MySql>,
+    item_type: &str,
+    items: Vec<(String, String)>,
+) -> Result<(), Error> {
+    let created = Utc::now().naive_utc();
+    let expired_before = created - Duration::days(SYNC_TOKEN_MAX_AGE_DAYS);
+
+    sqlx::query!("DELETE FROM tombstone WHERE created < ?", expired_before)
+        .execute(&mut *trans)
+        .await?;
 
-use super::{Bookmark, Friend, Pic, Review, User, USER_ACTION_TYPE};
+    for (item_id, owner_id) in items {
+        sqlx::query!(
+            "INSERT INTO tombstone (id, created, item_type, item_id, owner_id) VALUES (?,?,?,?,?)",
+            Uuid::new_v4().to_string(),
+            created,
+            item_type,
+            item_id,
+            owner_id
+        )
+        .execute(&mut *trans)
+        .await?;
+    }
+
+    return Ok(());
+}
 
 /// Creates a user from the passed User struct.
 /// Sets the pic_id to `DEFAULT_PIC_ID`
 /// Does not generate a guid for `user.id`
 /// Does not set a date for `user.created`
 pub async fn create_user(client: &MySqlPool, user: &User) -> Result<(), Error> {
-    let mut trans = client.begin().await?;
-
-    sqlx::query!(
-        "INSERT INTO user (id, name, display_name, phone, created, pic_id) VALUES (?,?,?,?,?,?)",
-        &user.id,
-        &user.name,
-        &user.display_name,
-        &user.phone,
-        &user.created,
-        DEFAULT_PIC_ID
-    )
-    .execute(&mut trans)
-    .await?;
-
-    // This insert is important. We assume you are friends with yourself, otherwise you can't see your own posts.
-    sqlx::query!(
-        "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        &user.id,
-        &user.id,
-    )
-    .execute(&mut trans)
-    .await?;
-
-    trans.commit().await?;
+    traced("create_user", async move {
+        let mut trans = client.begin().await?;
 
-    return Ok(());
+        sqlx::query!(
+            "INSERT INTO user (id, name, display_name, phone, created, pic_id) VALUES (?,?,?,?,?,?)",
+            &user.id,
+            &user.name,
+            &user.display_name,
+            &user.phone,
+            &user.created,
+            DEFAULT_PIC_ID
+        )
+        .execute(&mut trans)
+        .await?;
+
+        // This insert is important. We assume you are friends with yourself, otherwise you can't see your own posts.
+        sqlx::query!(
+            "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            &user.id,
+            &user.id,
+        )
+        .execute(&mut trans)
+        .await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a phoneauth record for tracking and validating user auth attempts.
 /// ## Sets the `phoneauth.created` to `Utc::now().naive_utc()`
 /// ## Sets the `phoneauth.id` to `Uuid::new_v4().to_string()`
 /// ## Sets the `phoneauth.used` to `false`
-pub async fn create_phoneauth(client: &MySqlPool, phone: &str, code: &str) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO phoneauth (id, phone, created, ip, code, used) VALUES (?,?,?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        &phone,
-        Utc::now().naive_utc(),
-        "",
-        code,
-        false
-    )
-    .execute(client)
-    .await?;
+pub async fn create_phoneauth(
+    client: &MySqlPool,
+    phone: &str,
+    code: &str,
+    ip: &str,
+) -> Result<(), Error> {
+    traced("create_phoneauth", async move {
+        sqlx::query!(
+            "INSERT INTO phoneauth (id, phone, created, ip, code, used) VALUES (?,?,?,?,?,?)",
+            Uuid::new_v4().to_string(),
+            &phone,
+            Utc::now().naive_utc(),
+            ip,
+            code,
+            false
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Sets an `phoneauth.used` to `true` signalling it can no longer be used to generate a token.
 pub async fn update_authattempt_used(client: &MySqlPool, id: &str) -> Result<(), Error> {
-    sqlx::query!("UPDATE phoneauth SET used = TRUE WHERE id = ?", id)
-        .execute(client)
-        .await?;
+    traced("update_authattempt_used", async move {
+        sqlx::query!("UPDATE phoneauth SET used = TRUE WHERE id = ?", id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates an authattempt record for tracking attempts to auth as a user.
 /// ## Sets the `authattempt.created` to `Utc::now().naive_utc()`
 /// ## Sets the `authattempt.id` to `Uuid::new_v4().to_string()`
 pub async fn create_authattempt(client: &MySqlPool, phone: &str) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO authattempt (id, phone, created) VALUES (?,?,?)",
-        Uuid::new_v4().to_string(),
-        &phone,
-        Utc::now().naive_utc()
-    )
-    .execute(client)
-    .await?;
+    traced("create_authattempt", async move {
+        sqlx::query!(
+            "INSERT INTO authattempt (id, phone, created) VALUES (?,?,?)",
+            Uuid::new_v4().to_string(),
+            &phone,
+            Utc::now().naive_utc()
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Sets a phone for a given user account.
@@ -92,11 +147,14 @@ pub async fn update_user_phone(
     user_id: &str,
     phone: &str,
 ) -> Result<(), Error> {
-    sqlx::query!("UPDATE user SET phone = ? WHERE id = ?", phone, user_id)
-        .execute(client)
-        .await?;
+    traced("update_user_phone", async move {
+        sqlx::query!("UPDATE user SET phone = ? WHERE id = ?", phone, user_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a friend request as a user to another user.
@@ -107,17 +165,20 @@ pub async fn create_friend_request(
     user_id: &str,
     friend_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO friendrequest (id, created, user_id, friend_id, ignored) VALUES (?,?,?,?,FALSE)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        user_id,
-        friend_id
-    )
-    .execute(client)
-    .await?;
+    traced("create_friend_request", async move {
+        sqlx::query!(
+            "INSERT INTO friendrequest (id, created, user_id, friend_id, ignored) VALUES (?,?,?,?,FALSE)",
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            user_id,
+            friend_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Sets an incoming friend request for a user to ignored.
@@ -127,15 +188,18 @@ pub async fn ignore_friend_request(
     request_id: &str,
     friend_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE friendrequest SET ignored = true WHERE id = ? and friend_id = ?",
-        request_id,
-        friend_id
-    )
-    .execute(client)
-    .await?;
+    traced("ignore_friend_request", async move {
+        sqlx::query!(
+            "UPDATE friendrequest SET ignored = true WHERE id = ? and friend_id = ?",
+            request_id,
+            friend_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Deletes an incoming friend request for a user.
@@ -144,13 +208,16 @@ pub async fn decline_friend_request(
     request_id: &str,
     friend_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query("DELETE FROM friendrequest WHERE id = ? and friend_id = ?")
-        .bind(request_id)
-        .bind(friend_id)
-        .execute(client)
-        .await?;
+    traced("decline_friend_request", async move {
+        sqlx::query("DELETE FROM friendrequest WHERE id = ? and friend_id = ?")
+            .bind(request_id)
+            .bind(friend_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Deletes an outgoing friend request.
@@ -159,15 +226,18 @@ pub async fn cancel_friend_request(
     request_id: &str,
     user_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "DELETE FROM friendrequest WHERE id = ? and user_id = ?",
-        request_id,
-        user_id
-    )
-    .execute(client)
-    .await?;
+    traced("cancel_friend_request", async move {
+        sqlx::query!(
+            "DELETE FROM friendrequest WHERE id = ? and user_id = ?",
+            request_id,
+            user_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Accepts an incoming friend request from another user.
@@ -179,65 +249,70 @@ pub async fn accept_friend_request(
     user_id: &str,
     friend_id: &str,
 ) -> Result<(), Error> {
-    let mut trans = client.begin().await?; // encapsulate multiple actions into single transaction
-
-    sqlx::query!(
-        "DELETE FROM friendrequest WHERE user_id = ? and friend_id = ?",
-        user_id,
-        friend_id
-    )
-    .execute(&mut trans)
-    .await?;
-
-    sqlx::query!(
-        "DELETE FROM friendrequest WHERE user_id = ? and friend_id = ?",
-        friend_id,
-        user_id
-    )
-    .execute(&mut trans)
-    .await?;
-
-    let user_friends = sqlx::query_as!(Friend, "SELECT * FROM friend where user_id = ?", user_id)
-        .fetch_all(&mut trans)
-        .await?;
+    traced("accept_friend_request", async move {
+        let mut trans = client.begin().await?; // encapsulate multiple actions into single transaction
 
-    if !user_friends
-        .iter()
-        .any(|uf| -> bool { return uf.friend_id == friend_id })
-    {
         sqlx::query!(
-            "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
-            Uuid::new_v4().to_string(),
-            Utc::now().naive_utc(),
+            "DELETE FROM friendrequest WHERE user_id = ? and friend_id = ?",
             user_id,
             friend_id
         )
         .execute(&mut trans)
         .await?;
-    }
-
-    let user_friends = sqlx::query_as!(Friend, "SELECT * FROM friend where user_id = ?", friend_id)
-        .fetch_all(&mut trans)
-        .await?;
 
-    if !user_friends
-        .iter()
-        .any(|uf| -> bool { return uf.friend_id == user_id })
-    {
         sqlx::query!(
-            "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
-            Uuid::new_v4().to_string(),
-            Utc::now().naive_utc(),
+            "DELETE FROM friendrequest WHERE user_id = ? and friend_id = ?",
             friend_id,
             user_id
         )
         .execute(&mut trans)
         .await?;
-    }
 
-    trans.commit().await?; // commit this bitch
+        let user_friends =
+            sqlx::query_as!(Friend, "SELECT * FROM friend where user_id = ?", user_id)
+                .fetch_all(&mut trans)
+                .await?;
+
+        if !user_friends
+            .iter()
+            .any(|uf| -> bool { return uf.friend_id == friend_id })
+        {
+            sqlx::query!(
+                "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
+                Uuid::new_v4().to_string(),
+                Utc::now().naive_utc(),
+                user_id,
+                friend_id
+            )
+            .execute(&mut trans)
+            .await?;
+        }
+
+        let user_friends =
+            sqlx::query_as!(Friend, "SELECT * FROM friend where user_id = ?", friend_id)
+                .fetch_all(&mut trans)
+                .await?;
+
+        if !user_friends
+            .iter()
+            .any(|uf| -> bool { return uf.friend_id == user_id })
+        {
+            sqlx::query!(
+                "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
+                Uuid::new_v4().to_string(),
+                Utc::now().naive_utc(),
+                friend_id,
+                user_id
+            )
+            .execute(&mut trans)
+            .await?;
+        }
+
+        trans.commit().await?; // commit this bitch
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Removes a friend.
@@ -248,61 +323,82 @@ pub async fn remove_current_friend(
     user_id: &str,
     friend_id: &str,
 ) -> Result<(), Error> {
-    let mut trans = client.begin().await?;
-
-    sqlx::query!(
-        "DELETE FROM friend WHERE user_id = ? and friend_id = ?",
-        user_id,
-        friend_id
-    )
-    .execute(&mut trans)
-    .await?;
-
-    sqlx::query!(
-        "DELETE FROM friend WHERE user_id = ? and friend_id = ?",
-        friend_id,
-        user_id
-    )
-    .execute(&mut trans)
-    .await?;
-
-    trans.commit().await?;
+    traced("remove_current_friend", async move {
+        let mut trans = client.begin().await?;
 
-    return Ok(());
+        sqlx::query!(
+            "DELETE FROM friend WHERE user_id = ? and friend_id = ?",
+            user_id,
+            friend_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM friend WHERE user_id = ? and friend_id = ?",
+            friend_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a pic record in the database to be related to a user or review.
 /// ## Sets the `pic.created` to `Utc::now().naive_utc()`
 /// ## Sets the `pic.id` to `Uuid::new_v4().to_string()`
 /// ## Sets the `pic.pic_handler` to `1` for now.
+/// ## Marks the review as updated, so `/sync` resends its pics.
 pub async fn create_pic(
     client: &MySqlPool,
     review_id: Option<String>,
     width: u16,
     height: u16,
 ) -> Result<Pic, Error> {
-    let pic = Pic {
-        id: Uuid::new_v4().to_string(),
-        review_id,
-        created: Utc::now().naive_utc(),
-        pic_handler: 1,
-        width,
-        height,
-    };
-
-    sqlx::query!(
-        "INSERT INTO pic (id, created, pic_handler, review_id, width, height) VALUES (?,?,?,?,?,?)",
-        &pic.id,
-        &pic.created,
-        &pic.pic_handler,
-        &pic.review_id,
-        &pic.width,
-        &pic.height,
-    )
-    .execute(client)
-    .await?;
-
-    return Ok(pic);
+    traced("create_pic", async move {
+        let pic = Pic {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            created: Utc::now().naive_utc(),
+            pic_handler: 1,
+            width,
+            height,
+        };
+
+        let mut trans = client.begin().await?;
+
+        sqlx::query!(
+            "INSERT INTO pic (id, created, pic_handler, review_id, width, height) VALUES (?,?,?,?,?,?)",
+            &pic.id,
+            &pic.created,
+            &pic.pic_handler,
+            &pic.review_id,
+            &pic.width,
+            &pic.height,
+        )
+        .execute(&mut trans)
+        .await?;
+
+        if let Some(review_id) = &pic.review_id {
+            sqlx::query!(
+                "UPDATE review SET updated = ? WHERE id = ?",
+                &pic.created,
+                review_id
+            )
+            .execute(&mut trans)
+            .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(pic);
+    })
+    .await
 }
 
 /// Updates a `user.pic_id` for a given user.
@@ -312,63 +408,90 @@ pub async fn update_user_pic_id(
     pic_id: &str,
     user_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!("UPDATE user SET pic_id = ? WHERE id = ?", pic_id, user_id)
-        .execute(client)
-        .await?;
+    traced("update_user_pic_id", async move {
+        sqlx::query!("UPDATE user SET pic_id = ? WHERE id = ?", pic_id, user_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Deletes a pic record.
 /// Has no foreign key restrictions or harsh bindings to actual pic storage.
 pub async fn delete_pic(client: &MySqlPool, pic_id: &str) -> Result<(), Error> {
-    sqlx::query!("DELETE FROM pic WHERE id = ?", pic_id)
-        .execute(client)
-        .await?;
+    traced("delete_pic", async move {
+        sqlx::query!("DELETE FROM pic WHERE id = ?", pic_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a review from the given `Review`.
 /// Requires all fields to be set on incoming review. Validates nothing explicitly other than column constraints.
 pub async fn create_review(client: &MySqlPool, review: &Review) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO review
-        (id, user_id, created,text, stars, location_name, is_custom, category, location, delivered)
-        VALUES (?,?,?,?,?,?,?,?,Point(?,?),?)",
-        &review.id,
-        &review.user_id,
-        &review.created,
-        &review.text,
-        &review.stars,
-        &review.location_name,
-        &review.is_custom,
-        &review.category,
-        &review.longitude,
-        &review.latitude,
-        &review.delivered
-    )
-    .execute(client)
-    .await?;
+    traced("create_review", async move {
+        sqlx::query!(
+            "INSERT INTO review
+        (id, user_id, created, updated, text, stars, location_name, is_custom, category, location, delivered)
+        VALUES (?,?,?,?,?,?,?,?,?,Point(?,?),?)",
+            &review.id,
+            &review.user_id,
+            &review.created,
+            &review.created,
+            &review.text,
+            &review.stars,
+            &review.location_name,
+            &review.is_custom,
+            &review.category,
+            &review.longitude,
+            &review.latitude,
+            &review.delivered
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Sets a `review.pic_id` to NULL.
+/// Marks the review as updated, so `/sync` resends its pics.
+/// ## Transaction Based
 pub async fn remove_review_pic_id(
     client: &MySqlPool,
     pic_id: &str,
     review_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "DELETE FROM pic where id = ? and review_id = ?",
-        pic_id,
-        review_id
-    )
-    .execute(client)
-    .await?;
+    traced("remove_review_pic_id", async move {
+        let mut trans = client.begin().await?;
 
-    return Ok(());
+        sqlx::query!(
+            "DELETE FROM pic where id = ? and review_id = ?",
+            pic_id,
+            review_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE review SET updated = ? WHERE id = ?",
+            Utc::now().naive_utc(),
+            review_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a like record associated with a given review.
@@ -380,55 +503,99 @@ pub async fn create_like(
     review_id: &str,
     like_type: Option<i8>,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO likes
+    traced("create_like", async move {
+        sqlx::query!(
+            "INSERT INTO likes
         (id, created, user_id, review_id, like_type)
         VALUES (?,?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        user_id,
-        review_id,
-        like_type.unwrap_or(0)
-    )
-    .execute(client)
-    .await?;
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            user_id,
+            review_id,
+            like_type.unwrap_or(0)
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
-/// Removes a like record.
+/// Removes a like record, leaving a tombstone for it.
+/// ## Transaction Based
 pub async fn remove_like(client: &MySqlPool, user_id: &str, review_id: &str) -> Result<(), Error> {
-    sqlx::query!(
-        "DELETE FROM likes where user_id = ? and review_id = ?",
-        user_id,
-        review_id
-    )
-    .execute(client)
-    .await?;
+    traced("remove_like", async move {
+        let mut trans = client.begin().await?;
+
+        let likes = sqlx::query!(
+            "SELECT l.id, r.user_id
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+        WHERE  l.user_id = ?
+               AND l.review_id = ?",
+            user_id,
+            review_id
+        )
+        .fetch_all(&mut trans)
+        .await?;
 
-    return Ok(());
+        sqlx::query!(
+            "DELETE FROM likes where user_id = ? and review_id = ?",
+            user_id,
+            review_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        let items = likes
+            .into_iter()
+            .map(|like| (like.id, like.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_LIKE, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Removes a review and all likes/replies.
+/// Only the review gets a tombstone, clients drop its likes and replies with it.
 /// ## Transaction Based
 pub async fn remove_review_and_children(client: &MySqlPool, review_id: &str) -> Result<(), Error> {
-    let mut trans = client.begin().await?;
+    traced("remove_review_and_children", async move {
+        let mut trans = client.begin().await?;
 
-    sqlx::query!("DELETE FROM reply WHERE review_id = ?", review_id)
-        .execute(&mut trans)
-        .await?;
+        let reviews = sqlx::query!("SELECT id, user_id FROM review WHERE id = ?", review_id)
+            .fetch_all(&mut trans)
+            .await?;
 
-    sqlx::query!("DELETE FROM likes WHERE review_id = ?", review_id)
-        .execute(&mut trans)
-        .await?;
+        sqlx::query!("DELETE FROM reply WHERE review_id = ?", review_id)
+            .execute(&mut trans)
+            .await?;
 
-    sqlx::query!("DELETE FROM review WHERE id = ?", review_id)
-        .execute(&mut trans)
-        .await?;
+        sqlx::query!("DELETE FROM likes WHERE review_id = ?", review_id)
+            .execute(&mut trans)
+            .await?;
 
-    trans.commit().await?;
+        sqlx::query!("DELETE FROM review WHERE id = ?", review_id)
+            .execute(&mut trans)
+            .await?;
 
-    return Ok(());
+        let items = reviews
+            .into_iter()
+            .map(|review| (review.id, review.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_REVIEW, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a reply record against a given review.
@@ -441,40 +608,72 @@ pub async fn create_reply(
     text: &str,
     reply_to_id: Option<&String>,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO reply
+    traced("create_reply", async move {
+        sqlx::query!(
+            "INSERT INTO reply
         (id, created, user_id, review_id, text, reply_to_id)
         VALUES (?,?,?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        user_id,
-        review_id,
-        text,
-        reply_to_id
-    )
-    .execute(client)
-    .await?;
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            user_id,
+            review_id,
+            text,
+            reply_to_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
-/// Deletes a given reply by id.
+/// Deletes a given reply by id, leaving a tombstone for it.
+/// ## Transaction Based
 pub async fn delete_reply(
     client: &MySqlPool,
     reply_id: &str,
     review_id: &str,
     user_id: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "DELETE FROM reply WHERE id = ? AND review_id = ? and user_id = ?",
-        reply_id,
-        review_id,
-        user_id
-    )
-    .execute(client)
-    .await?;
+    traced("delete_reply", async move {
+        let mut trans = client.begin().await?;
+
+        let replies = sqlx::query!(
+            "SELECT rp.id, r.user_id
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+        WHERE  rp.id = ?
+               AND rp.review_id = ?
+               AND rp.user_id = ?",
+            reply_id,
+            review_id,
+            user_id
+        )
+        .fetch_all(&mut trans)
+        .await?;
 
-    return Ok(());
+        sqlx::query!(
+            "DELETE FROM reply WHERE id = ? AND review_id = ? and user_id = ?",
+            reply_id,
+            review_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        let items = replies
+            .into_iter()
+            .map(|reply| (reply.id, reply.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_REPLY, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
 
 /// Updates a review by setting the passed values.
@@ -485,16 +684,20 @@ pub async fn update_review(
     stars: u8,
     text: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE review SET stars = ?, text = ? WHERE id = ?",
-        stars,
-        text,
-        review_id
-    )
-    .execute(client)
-    .await?;
+    traced("update_review", async move {
+        sqlx::query!(
+            "UPDATE review SET stars = ?, text = ?, updated = ? WHERE id = ?",
+            stars,
+            text,
+            Utc::now().naive_utc(),
+            review_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Updates the delivered status for the review.
@@ -503,15 +706,19 @@ pub async fn update_review_delivered(
     review_id: &str,
     delivered: u8,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE review SET delivered = ? WHERE id = ?",
-        delivered,
-        review_id
-    )
-    .execute(client)
-    .await?;
+    traced("update_review_delivered", async move {
+        sqlx::query!(
+            "UPDATE review SET delivered = ?, updated = ? WHERE id = ?",
+            delivered,
+            Utc::now().naive_utc(),
+            review_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Updates a users names.
@@ -522,16 +729,19 @@ pub async fn update_usernames(
     display_name: &str,
     name: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE user SET display_name = ?, name = ? WHERE id = ?",
-        display_name,
-        name,
-        user_id
-    )
-    .execute(client)
-    .await?;
+    traced("update_usernames", async move {
+        sqlx::query!(
+            "UPDATE user SET display_name = ?, name = ? WHERE id = ?",
+            display_name,
+            name,
+            user_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Updates a users device token for push notifications.
@@ -540,15 +750,18 @@ pub async fn update_device_token(
     user_id: &str,
     device_token: &str,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE user SET device_token = ? WHERE id = ?",
-        device_token,
-        user_id
-    )
-    .execute(client)
-    .await?;
+    traced("update_device_token", async move {
+        sqlx::query!(
+            "UPDATE user SET device_token = ? WHERE id = ?",
+            device_token,
+            user_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Updates a users recovery for updating account phone numbers.
@@ -557,20 +770,26 @@ pub async fn update_recovery_email(
     user_id: &str,
     email: &str,
 ) -> Result<(), Error> {
-    sqlx::query!("UPDATE user SET email = ? WHERE id = ?", email, user_id)
-        .execute(client)
-        .await?;
+    traced("update_recovery_email", async move {
+        sqlx::query!("UPDATE user SET email = ? WHERE id = ?", email, user_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Deletes all notifications, which is essentially confirming them.
 pub async fn confirm_notifications(client: &MySqlPool, user_id: &str) -> Result<(), Error> {
-    sqlx::query!("DELETE FROM notification WHERE review_user_id = ?", user_id)
-        .execute(client)
-        .await?;
+    traced("confirm_notifications", async move {
+        sqlx::query!("DELETE FROM notification WHERE review_user_id = ?", user_id)
+            .execute(client)
+            .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a notification for a user as a response for the given action.
@@ -581,45 +800,48 @@ pub async fn create_notification(
     review_id: &str,
     action_type: u8,
 ) -> Result<(), Error> {
-    // No need to notify yourself of your own action.
-    if review_user_id.eq_ignore_ascii_case(user_id) {
-        return Ok(());
-    }
-
-    let notifications = sqlx::query_as!(
-        Notification,
-        "SELECT *
+    traced("create_notification", async move {
+        // No need to notify yourself of your own action.
+        if review_user_id.eq_ignore_ascii_case(user_id) {
+            return Ok(());
+        }
+
+        let notifications = sqlx::query_as!(
+            Notification,
+            "SELECT *
             FROM  notification AS n
         WHERE n.review_id = ?
             AND n.user_id = ?
             AND n.action_type = ?",
-        review_id,
-        user_id,
-        action_type
-    )
-    .fetch_all(client)
-    .await?;
+            review_id,
+            user_id,
+            action_type
+        )
+        .fetch_all(client)
+        .await?;
 
-    // If this type from this user on this review already exists, we can just early return.
-    if notifications.len() == 1 {
-        return Ok(());
-    }
+        // If this type from this user on this review already exists, we can just early return.
+        if notifications.len() == 1 {
+            return Ok(());
+        }
 
-    sqlx::query!(
-        "INSERT INTO notification
+        sqlx::query!(
+            "INSERT INTO notification
         (id, created, review_user_id, user_id, review_id, action_type)
         VALUES (?,?,?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        review_user_id,
-        user_id,
-        review_id,
-        action_type
-    )
-    .execute(client)
-    .await?;
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            review_user_id,
+            user_id,
+            review_id,
+            action_type
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a report record against a user, with a request id also.
@@ -628,45 +850,48 @@ pub async fn report_user(
     user_id: &str,
     reporter_id: &str,
 ) -> Result<(), Error> {
-    // No need to report yourself.
-    // This should probably be done by consumers but w/e.
-    if user_id.eq_ignore_ascii_case(reporter_id) {
-        return Ok(());
-    }
-
-    let reports = sqlx::query_as!(
-        Report,
-        "SELECT *
+    traced("report_user", async move {
+        // No need to report yourself.
+        // This should probably be done by consumers but w/e.
+        if user_id.eq_ignore_ascii_case(reporter_id) {
+            return Ok(());
+        }
+
+        let reports = sqlx::query_as!(
+            Report,
+            "SELECT *
             FROM  reports AS n
         WHERE n.user_id = ?
             AND n.reporter_id = ?
             AND n.report_type = ?",
-        user_id,
-        reporter_id,
-        USER_ACTION_TYPE
-    )
-    .fetch_all(client)
-    .await?;
+            user_id,
+            reporter_id,
+            USER_ACTION_TYPE
+        )
+        .fetch_all(client)
+        .await?;
 
-    // If this report already exists, just ignore this.
-    if reports.len() == 1 {
-        return Ok(());
-    }
+        // If this report already exists, just ignore this.
+        if reports.len() == 1 {
+            return Ok(());
+        }
 
-    sqlx::query!(
-        "INSERT INTO reports
+        sqlx::query!(
+            "INSERT INTO reports
         (id, created, user_id, reporter_id, report_type)
         VALUES (?,?,?,?,?)",
-        Uuid::new_v4().to_string(),
-        Utc::now().naive_utc(),
-        user_id,
-        reporter_id,
-        USER_ACTION_TYPE
-    )
-    .execute(client)
-    .await?;
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            user_id,
+            reporter_id,
+            USER_ACTION_TYPE
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Update whether a review is recommended or not.
@@ -676,40 +901,48 @@ pub async fn update_review_recommended(
     user_id: &str,
     recommended: bool,
 ) -> Result<(), Error> {
-    sqlx::query!(
-        "UPDATE review SET recommended = ? WHERE id = ? and user_id = ?",
-        recommended,
-        review_id,
-        user_id
-    )
-    .execute(client)
-    .await?;
+    traced("update_review_recommended", async move {
+        sqlx::query!(
+            "UPDATE review SET recommended = ?, updated = ? WHERE id = ? and user_id = ?",
+            recommended,
+            Utc::now().naive_utc(),
+            review_id,
+            user_id
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
 /// Creates a bookmark from the given `Bookmark`.
 /// Requires all fields to be set on incoming bookmark. Validates nothing explicitly other than column constraints.
 pub async fn create_bookmark(client: &MySqlPool, bookmark: &Bookmark) -> Result<(), Error> {
-    sqlx::query!(
-        "INSERT INTO bookmark
+    traced("create_bookmark", async move {
+        sqlx::query!(
+            "INSERT INTO bookmark
         (id, user_id, created, location_name, category, location)
         VALUES (?,?,?,?,?,Point(?,?))",
-        &bookmark.id,
-        &bookmark.user_id,
-        &bookmark.created,
-        &bookmark.location_name,
-        &bookmark.category,
-        &bookmark.longitude,
-        &bookmark.latitude,
-    )
-    .execute(client)
-    .await?;
+            &bookmark.id,
+            &bookmark.user_id,
+            &bookmark.created,
+            &bookmark.location_name,
+            &bookmark.category,
+            &bookmark.longitude,
+            &bookmark.latitude,
+        )
+        .execute(client)
+        .await?;
 
-    return Ok(());
+        return Ok(());
+    })
+    .await
 }
 
-/// Gets the existence of a bookmark by `bookmark.user_id` and `bookmark.id`. True if exists, false if not.
+/// Removes the user's bookmarks at a location, leaving a tombstone for each.
+/// ## Transaction Based
 pub async fn remove_bookmark(
     client: &MySqlPool,
     user_id: &str,
@@ -717,20 +950,398 @@ pub async fn remove_bookmark(
     latitude: f64,
     longitude: f64,
 ) -> Result<(), Error> {
-    const ACCURACY_SIZE: f64 = 0.001;
-    sqlx::query!(
-        "DELETE FROM bookmark as bm
+    traced("remove_bookmark", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let mut trans = client.begin().await?;
+
+        let bookmarks = sqlx::query!(
+            "SELECT bm.id, bm.user_id
+        FROM   bookmark AS bm
+        WHERE  bm.user_id = ?
+               AND bm.location_name = ?
+               AND ST_Contains(ST_Buffer(POINT(?, ?), ?), bm.location) = 1",
+            user_id,
+            location_name,
+            longitude,
+            latitude,
+            ACCURACY_SIZE
+        )
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM bookmark as bm
             WHERE  bm.user_id = ?
                AND bm.location_name = ?
                AND ST_Contains(ST_Buffer(POINT(?, ?), ?), bm.location) = 1",
-        user_id,
-        location_name,
-        longitude,
-        latitude,
-        ACCURACY_SIZE
-    )
-    .execute(client)
-    .await?;
+            user_id,
+            location_name,
+            longitude,
+            latitude,
+            ACCURACY_SIZE
+        )
+        .execute(&mut trans)
+        .await?;
 
-    return Ok(());
+        let items = bookmarks
+            .into_iter()
+            .map(|bookmark| (bookmark.id, bookmark.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_BOOKMARK, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates an ipblock rule from the passed IpBlock struct.
+pub async fn create_ipblock(client: &MySqlPool, ipblock: &IpBlock) -> Result<(), Error> {
+    traced("create_ipblock", async move {
+        sqlx::query!(
+            "INSERT INTO ipblock (id, created, created_by, action, cidr, asn, country, reason, expires) VALUES (?,?,?,?,?,?,?,?,?)",
+            &ipblock.id,
+            &ipblock.created,
+            &ipblock.created_by,
+            &ipblock.action,
+            &ipblock.cidr,
+            &ipblock.asn,
+            &ipblock.country,
+            &ipblock.reason,
+            &ipblock.expires
+        )
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes an ipblock rule, returning false when none has the id.
+pub async fn remove_ipblock(client: &MySqlPool, id: &str) -> Result<bool, Error> {
+    traced("remove_ipblock", async move {
+        let result = sqlx::query!("DELETE FROM ipblock WHERE id = ?", id)
+            .execute(client)
+            .await?;
+
+        return Ok(result.rows_affected() > 0);
+    })
+    .await
+}
+
+/// Persists queued notifications so they survive a restart.
+pub async fn create_pending_notifications(
+    client: &MySqlPool,
+    pending_notifications: &[PendingNotification],
+) -> Result<(), Error> {
+    traced("create_pending_notifications", async move {
+        let mut trans = client.begin().await?;
+
+        for pending in pending_notifications {
+            sqlx::query!(
+                "INSERT INTO pendingnotification (id, created, user_id, notification_value, message, notification_type) VALUES (?,?,?,?,?,?)",
+                &pending.id,
+                &pending.created,
+                &pending.user_id,
+                &pending.notification_value,
+                &pending.message,
+                &pending.notification_type
+            )
+            .execute(&mut trans)
+            .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Gets and removes every persisted notification.
+/// Rows are locked while being taken, so instances starting together won't both send them.
+pub async fn take_pending_notifications(
+    client: &MySqlPool,
+) -> Result<Vec<PendingNotification>, Error> {
+    traced("take_pending_notifications", async move {
+        let mut trans = client.begin().await?;
+
+        let pending_notifications = sqlx::query_as!(
+            PendingNotification,
+            "SELECT *
+        FROM   pendingnotification
+        ORDER  BY created ASC
+        FOR UPDATE"
+        )
+        .fetch_all(&mut trans)
+        .await?;
+
+        for pending in &pending_notifications {
+            sqlx::query!("DELETE FROM pendingnotification WHERE id = ?", &pending.id)
+                .execute(&mut trans)
+                .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(pending_notifications);
+    })
+    .await
+}
+
+/// Claims an idempotency key for the request about to run, first dropping every key used before `expired_before`,
+/// and the user's key if it was claimed before `abandoned_before` and never completed.
+/// Returns false when the user already holds the key, so another request got there first.
+pub async fn create_idempotency_key(
+    client: &MySqlPool,
+    key: &IdempotencyKey,
+    expired_before: &NaiveDateTime,
+    abandoned_before: &NaiveDateTime,
+) -> Result<bool, Error> {
+    traced("create_idempotency_key", async move {
+        sqlx::query!(
+            "DELETE FROM idempotencykey WHERE created < ? OR (user_id = ? AND idempotency_key = ? AND status IS NULL AND created < ?)",
+            expired_before,
+            &key.user_id,
+            &key.idempotency_key,
+            abandoned_before
+        )
+        .execute(client)
+        .await?;
+
+        let result = sqlx::query!(
+            "INSERT IGNORE INTO idempotencykey (id, created, user_id, idempotency_key, fingerprint) VALUES (?,?,?,?,?)",
+            &key.id,
+            &key.created,
+            &key.user_id,
+            &key.idempotency_key,
+            &key.fingerprint
+        )
+        .execute(client)
+        .await?;
+
+        return Ok(result.rows_affected() == 1);
+    })
+    .await
+}
+
+/// Stores the response to replay for a claimed idempotency key.
+pub async fn complete_idempotency_key(
+    client: &MySqlPool,
+    id: &str,
+    status: u16,
+    headers: &str,
+    body: &[u8],
+) -> Result<(), Error> {
+    traced("complete_idempotency_key", async move {
+        sqlx::query!(
+            "UPDATE idempotencykey SET status = ?, headers = ?, body = ? WHERE id = ?",
+            status,
+            headers,
+            body,
+            id
+        )
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Releases an idempotency key, so the request can be retried with it.
+pub async fn remove_idempotency_key(client: &MySqlPool, id: &str) -> Result<(), Error> {
+    traced("remove_idempotency_key", async move {
+        sqlx::query!("DELETE FROM idempotencykey WHERE id = ?", id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets `user.disabled`, which stops the user from requesting sign in codes.
+pub async fn update_user_disabled(
+    client: &MySqlPool,
+    user_id: &str,
+    disabled: bool,
+) -> Result<(), Error> {
+    traced("update_user_disabled", async move {
+        sqlx::query!(
+            "UPDATE user SET disabled = ? WHERE id = ?",
+            disabled,
+            user_id
+        )
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates the friend record a user needs to see their own posts.
+/// ## Sets the `friend.created` to `Utc::now().naive_utc()`
+/// ## Sets the `friend.id` to `Uuid::new_v4().to_string()`
+pub async fn create_self_friend(client: &MySqlPool, user_id: &str) -> Result<(), Error> {
+    traced("create_self_friend", async move {
+        sqlx::query!(
+            "INSERT INTO friend (id, created, user_id, friend_id) VALUES (?,?,?,?)",
+            Uuid::new_v4().to_string(),
+            Utc::now().naive_utc(),
+            user_id,
+            user_id,
+        )
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes everything a user has made or received, leaving just the account and its self friend record.
+/// Leaves tombstones for the user's reviews, bookmarks, and likes and replies on other users' reviews.
+/// Pic records are left alone, their storage has to be cleaned up first.
+/// ## Transaction Based
+pub async fn remove_user_activity(client: &MySqlPool, user_id: &str) -> Result<(), Error> {
+    traced("remove_user_activity", async move {
+        let mut trans = client.begin().await?;
+
+        let reviews = sqlx::query!("SELECT id, user_id FROM review WHERE user_id = ?", user_id)
+            .fetch_all(&mut trans)
+            .await?;
+
+        let replies = sqlx::query!(
+            "SELECT rp.id, r.user_id
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+        WHERE  rp.user_id = ?
+               AND r.user_id != ?",
+            user_id,
+            user_id
+        )
+        .fetch_all(&mut trans)
+        .await?;
+
+        let likes = sqlx::query!(
+            "SELECT l.id, r.user_id
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+        WHERE  l.user_id = ?
+               AND r.user_id != ?",
+            user_id,
+            user_id
+        )
+        .fetch_all(&mut trans)
+        .await?;
+
+        let bookmarks = sqlx::query!(
+            "SELECT id, user_id FROM bookmark WHERE user_id = ?",
+            user_id
+        )
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM reply
+        WHERE  user_id = ?
+                OR review_id IN (SELECT id
+                                 FROM   review
+                                 WHERE  user_id = ?)",
+            user_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM likes
+        WHERE  user_id = ?
+                OR review_id IN (SELECT id
+                                 FROM   review
+                                 WHERE  user_id = ?)",
+            user_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!("DELETE FROM review WHERE user_id = ?", user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query!(
+            "DELETE FROM notification WHERE review_user_id = ? OR user_id = ?",
+            user_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM friendrequest WHERE user_id = ? OR friend_id = ?",
+            user_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM friend
+        WHERE  ( user_id = ?
+                  OR friend_id = ? )
+               AND user_id != friend_id",
+            user_id,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query!("DELETE FROM bookmark WHERE user_id = ?", user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query!(
+            "UPDATE user SET pic_id = ?, device_token = NULL, email = NULL WHERE id = ?",
+            DEFAULT_PIC_ID,
+            user_id
+        )
+        .execute(&mut trans)
+        .await?;
+
+        let items = reviews
+            .into_iter()
+            .map(|review| (review.id, review.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_REVIEW, items).await?;
+
+        let items = replies
+            .into_iter()
+            .map(|reply| (reply.id, reply.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_REPLY, items).await?;
+
+        let items = likes
+            .into_iter()
+            .map(|like| (like.id, like.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_LIKE, items).await?;
+
+        let items = bookmarks
+            .into_iter()
+            .map(|bookmark| (bookmark.id, bookmark.user_id))
+            .collect();
+        create_tombstones(&mut trans, TOMBSTONE_BOOKMARK, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
 }
@@ -0,0 +1,162 @@
+use chrono::Utc;
+use opentelemetry::{
+    global,
+    trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+use sqlx::Error;
+use std::{
+    fs::{File, OpenOptions},
+    future::Future,
+    io::{self, Write},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use super::{Page, Pic, UserActivityCounts};
+
+/// Where and when slow queries get reported, set once from config at startup.
+static SLOW_QUERY_LOG: OnceLock<SlowQueryLog> = OnceLock::new();
+
+struct SlowQueryLog {
+    threshold: Duration,
+    /// Slow queries go to stderr when no file is configured.
+    file: Option<Mutex<File>>,
+}
+
+/// Sets up the slow query log. Only the first call has any effect.
+pub fn set_slow_query_log(threshold: Duration, path: Option<&str>) -> io::Result<()> {
+    let file = match path {
+        Some(path) => Some(Mutex::new(
+            OpenOptions::new().create(true).append(true).open(path)?,
+        )),
+        None => None,
+    };
+
+    let _ = SLOW_QUERY_LOG.set(SlowQueryLog { threshold, file });
+
+    return Ok(());
+}
+
+/// Number of rows a db function returned, recorded on its span.
+pub trait RowCount {
+    /// `None` when the function doesn't return rows, such as inserts.
+    fn row_count(&self) -> Option<usize>;
+}
+
+impl<T> RowCount for Vec<T> {
+    fn row_count(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> RowCount for Page<T> {
+    fn row_count(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<T> RowCount for Option<T> {
+    fn row_count(&self) -> Option<usize> {
+        Some(self.iter().count())
+    }
+}
+
+impl RowCount for () {
+    fn row_count(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Existence checks and counts still read a single row.
+macro_rules! single_row_count {
+    ($($type:ty),*) => {
+        $(impl RowCount for $type {
+            fn row_count(&self) -> Option<usize> {
+                Some(1)
+            }
+        })*
+    };
+}
+
+single_row_count!(bool, i64, String, Pic, UserActivityCounts);
+
+/// Runs a db function inside a span named after it, recording how long it took and how many rows came back.
+/// Anything slower than the configured threshold is also written to the slow query log.
+pub async fn traced<T, F>(name: &'static str, query: F) -> Result<T, Error>
+where
+    T: RowCount,
+    F: Future<Output = Result<T, Error>>,
+{
+    traced_system("mysql", name, query).await
+}
+
+/// Same as `traced`, for backends other than MySQL. `system` is the OpenTelemetry `db.system` value.
+pub async fn traced_system<T, F>(
+    system: &'static str,
+    name: &'static str,
+    query: F,
+) -> Result<T, Error>
+where
+    T: RowCount,
+    F: Future<Output = Result<T, Error>>,
+{
+    let tracer = global::tracer("db");
+    let span = tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_attributes(vec![
+            KeyValue::new("db.system", system),
+            KeyValue::new("db.operation", name),
+        ])
+        .start(&tracer);
+    let context = Context::current_with_span(span);
+
+    let start = Instant::now();
+    let result = query.with_context(context.clone()).await;
+    let duration = start.elapsed();
+
+    let span = context.span();
+    let rows = match &result {
+        Ok(value) => value.row_count(),
+        Err(error) => {
+            span.record_error(error);
+            span.set_status(Status::error(error.to_string()));
+            None
+        }
+    };
+
+    span.set_attribute(KeyValue::new("db.duration_ms", duration.as_millis() as i64));
+    if let Some(rows) = rows {
+        span.set_attribute(KeyValue::new("db.rows", rows as i64));
+    }
+
+    if let Some(log) = SLOW_QUERY_LOG.get() {
+        if duration >= log.threshold {
+            span.add_event("slow query", vec![]);
+            write_slow_query(log, name, duration, rows);
+        }
+    }
+
+    span.end();
+
+    return result;
+}
+
+fn write_slow_query(log: &SlowQueryLog, name: &str, duration: Duration, rows: Option<usize>) {
+    let line = format!(
+        "slow query: {} took {}ms, rows: {}",
+        name,
+        duration.as_millis(),
+        rows.map(|rows| rows.to_string()).unwrap_or("-".to_string())
+    );
+
+    match &log.file {
+        Some(file) => {
+            if let Ok(mut file) = file.lock() {
+                let _ = writeln!(file, "{} {}", Utc::now().to_rfc3339(), line);
+            }
+        }
+        None => eprintln!("{}", line),
+    }
+}
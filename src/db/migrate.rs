@@ -0,0 +1,14 @@
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    MySqlPool,
+};
+
+/// Every migration in `migrations/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Applies any embedded migrations the database is missing, in version order.
+/// sqlx holds a `GET_LOCK` advisory lock for the whole run,
+/// so replicas starting together wait on each other instead of racing.
+pub async fn run_migrations(client: &MySqlPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(client).await
+}
@@ -4,11 +4,26 @@ pub use types::*;
 pub mod query;
 pub use query::*;
 
+pub mod page;
+pub use page::*;
+
+pub mod version;
+pub use version::*;
+
+pub mod sync_query;
+pub use sync_query::*;
+
 pub mod admin_query;
 pub use admin_query::*;
 
 pub mod execute;
 pub use execute::*;
 
+pub mod instrument;
+pub use instrument::*;
+
 pub mod constants;
 pub use constants::*;
+
+pub mod migrate;
+pub use migrate::*;
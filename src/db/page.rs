@@ -0,0 +1,162 @@
+use base64::{engine::general_purpose, Engine};
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime};
+
+use super::{Bookmark, Review, ReviewAnnotation};
+
+/// Position of the last row a client has seen in a feed ordered by `(created, id)`, newest first.
+/// Orders like the feeds do, by `created` and then `id`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cursor {
+    pub created: NaiveDateTime,
+    pub id: String,
+}
+
+impl Cursor {
+    /// Opaque, URL safe form handed to clients as `next_cursor`.
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}.{}:{}",
+            self.created.timestamp(),
+            self.created.timestamp_subsec_nanos(),
+            self.id
+        );
+
+        return general_purpose::URL_SAFE_NO_PAD.encode(raw);
+    }
+
+    /// `None` for anything `encode` could not have produced.
+    pub fn decode(value: &str) -> Option<Cursor> {
+        let raw = general_purpose::URL_SAFE_NO_PAD.decode(value).ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+
+        let (timestamp, id) = raw.split_once(':')?;
+        let (seconds, nanos) = timestamp.split_once('.')?;
+        let created =
+            NaiveDateTime::from_timestamp_opt(seconds.parse().ok()?, nanos.parse().ok()?)?;
+
+        if id.is_empty() {
+            return None;
+        }
+
+        return Some(Cursor {
+            created,
+            id: id.to_string(),
+        });
+    }
+}
+
+/// Which page of a feed to fetch.
+#[derive(Debug, Clone)]
+pub struct PageRequest {
+    /// `None` for the first page.
+    pub after: Option<Cursor>,
+
+    /// Most items to return.
+    pub limit: u32,
+}
+
+impl PageRequest {
+    pub fn first(limit: u32) -> PageRequest {
+        PageRequest { after: None, limit }
+    }
+
+    /// Keyset bound for `created < ? OR (created = ? AND id < ?)`.
+    /// The first page uses the latest datetime MySQL can store, so every row is before it.
+    pub fn before(&self) -> (NaiveDateTime, &str) {
+        match &self.after {
+            Some(cursor) => (cursor.created, &cursor.id),
+            None => (
+                NaiveDate::from_ymd_opt(9999, 12, 31)
+                    .and_then(|date| date.and_hms_opt(23, 59, 59))
+                    .unwrap_or(NaiveDateTime::MAX),
+                "",
+            ),
+        }
+    }
+
+    /// Queries read one row past the limit to know whether there is a next page.
+    pub fn fetch_limit(&self) -> u32 {
+        self.limit + 1
+    }
+
+    /// Whether the row at `key` comes after the cursor.
+    /// Backends that filter in memory use this in place of the SQL bound.
+    pub fn includes(&self, key: &Cursor) -> bool {
+        match &self.after {
+            Some(cursor) => key < cursor,
+            None => true,
+        }
+    }
+}
+
+/// One page of a feed, and where the next one starts.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// `None` once the feed is exhausted.
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from rows fetched with `PageRequest::fetch_limit`, in feed order.
+    /// `key` gives the `(created, id)` the rows were ordered by.
+    pub fn from_rows<F>(mut rows: Vec<T>, page: &PageRequest, key: F) -> Page<T>
+    where
+        F: Fn(&T) -> Cursor,
+    {
+        let limit = page.limit as usize;
+
+        if rows.len() <= limit {
+            return Page {
+                items: rows,
+                next_cursor: None,
+            };
+        }
+
+        rows.truncate(limit);
+        let next_cursor = rows.last().map(key);
+
+        return Page {
+            items: rows,
+            next_cursor,
+        };
+    }
+
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl Review {
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created: self.created,
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl ReviewAnnotation {
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created: self.created,
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl Bookmark {
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created: self.created,
+            id: self.id.clone(),
+        }
+    }
+}
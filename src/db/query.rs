@@ -1,123 +1,173 @@
 use chrono::Duration;
-use sqlx::{types::chrono::Utc, Error, MySqlPool};
+use sqlx::{
+    types::chrono::{NaiveDateTime, Utc},
+    Error, MySqlPool, Row,
+};
 
 use super::{
-    AuthAttempt, Bookmark, ExpandedNotification, Friend, FriendRequest, Like, PhoneAuth, Pic,
-    Reply, Review, ReviewAnnotation, User,
+    traced, AuthAttempt, Bookmark, Cursor, DataVersion, ExpandedNotification, Friend,
+    FriendRequest, IdempotencyKey, IpBlock, Like, Page, PageRequest, PhoneAuth, Pic, Reply, Review,
+    ReviewAnnotation, User,
 };
 
+/// `?, ?, ?` for an `IN ( ... )` list of `count` values. `count` must not be zero.
+fn in_list(count: usize) -> String {
+    format!("?{}", ", ?".repeat(count - 1))
+}
+
 /// All query text constants defined in this file should be formatted with the following tool:
 /// https://www.dpriver.com/pp/sqlformat.htm
 
 /// Simply gets a ping record.
 pub async fn get_ping(client: &MySqlPool, id: &str) -> Result<String, Error> {
-    struct Ping {
-        id: String,
-    }
-    let ping = sqlx::query_as!(
-        Ping,
-        "SELECT *
+    traced("get_ping", async move {
+        struct Ping {
+            id: String,
+        }
+        let ping = sqlx::query_as!(
+            Ping,
+            "SELECT *
         FROM   ping
         WHERE  id = ? ",
-        id
-    )
-    .fetch_one(client)
-    .await?;
+            id
+        )
+        .fetch_one(client)
+        .await?;
 
-    return Ok(ping.id);
+        return Ok(ping.id);
+    })
+    .await
 }
 
 /// Tries to get a user by `user.id`, and will return `None` if not found.
 pub async fn get_user(client: &MySqlPool, id: &str) -> Result<Option<User>, Error> {
-    let user = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("get_user", async move {
+        let user = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM   user
         WHERE  id = ? ",
-        id
-    )
-    .fetch_optional(client)
-    .await?;
+            id
+        )
+        .fetch_optional(client)
+        .await?;
 
-    return Ok(user);
+        return Ok(user);
+    })
+    .await
+}
+
+/// Gets every user with one of the given ids, in no particular order.
+pub async fn get_users_by_ids(client: &MySqlPool, ids: &[String]) -> Result<Vec<User>, Error> {
+    traced("get_users_by_ids", async move {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query_str = format!("SELECT * FROM user WHERE id IN ( {} )", in_list(ids.len()));
+
+        let mut query = sqlx::query(&query_str);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(client).await?;
+
+        return Ok(rows.iter().map(User::from).collect());
+    })
+    .await
 }
 
 /// Tries to get a user by exact `user.name`, and will return `None` if not found.
 pub async fn get_user_from_name(client: &MySqlPool, name: &str) -> Result<Option<User>, Error> {
-    let user = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("get_user_from_name", async move {
+        let user = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM   user
         WHERE  name = ? ",
-        name
-    )
-    .fetch_optional(client)
-    .await?;
+            name
+        )
+        .fetch_optional(client)
+        .await?;
 
-    return Ok(user);
+        return Ok(user);
+    })
+    .await
 }
 
 /// Gets a list of users from the given `user.name`.
 /// This search is a trailing wildcard and limits to top 50 results.
 pub async fn search_user_from_name(client: &MySqlPool, name: &str) -> Result<Vec<User>, Error> {
-    let search_term = format!("{}%", name.replace("%", ""));
-    let users = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("search_user_from_name", async move {
+        let search_term = format!("{}%", name.replace("%", ""));
+        let users = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM   user
         WHERE  name LIKE ?
         LIMIT  50 ",
-        search_term,
-    )
-    .fetch_all(client)
-    .await?;
+            search_term,
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(users);
+        return Ok(users);
+    })
+    .await
 }
 
 /// Gets the existence of a user by `user.id`. True if exists, false if not.
 pub async fn does_user_exist(client: &MySqlPool, id: &str) -> Result<bool, Error> {
-    let users = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("does_user_exist", async move {
+        let users = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM   user
         WHERE  id = ? ",
-        id
-    )
-    .fetch_all(client)
-    .await?;
+            id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(users.len() == 1);
+        return Ok(users.len() == 1);
+    })
+    .await
 }
 
 /// Gets the existence of a user by `user.name`. True if exists, false if not.
 pub async fn does_user_exist_by_name(client: &MySqlPool, name: &str) -> Result<bool, Error> {
-    let users = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("does_user_exist_by_name", async move {
+        let users = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM   user
         WHERE  name = ? ",
-        name
-    )
-    .fetch_all(client)
-    .await?;
+            name
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(users.len() == 1);
+        return Ok(users.len() == 1);
+    })
+    .await
 }
 
 /// Gets a given user by `user.phone`, and will return `None` if not found.
 pub async fn get_user_by_phone(client: &MySqlPool, phone: &str) -> Result<Option<User>, Error> {
-    let mut rows = sqlx::query_as!(
-        User,
-        "SELECT *
+    traced("get_user_by_phone", async move {
+        let mut rows = sqlx::query_as!(
+            User,
+            "SELECT *
         FROM user
         WHERE phone = ? ",
-        phone
-    )
-    .fetch_all(client)
-    .await?;
+            phone
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(rows.pop());
+        return Ok(rows.pop());
+    })
+    .await
 }
 
 /// Gets the current phoneauths.
@@ -126,20 +176,23 @@ pub async fn get_current_phoneauths(
     client: &MySqlPool,
     phone: &str,
 ) -> Result<Vec<PhoneAuth>, Error> {
-    let phone_auths = sqlx::query_as!(
-        PhoneAuth,
-        "SELECT *
+    traced("get_current_phoneauths", async move {
+        let phone_auths = sqlx::query_as!(
+            PhoneAuth,
+            "SELECT *
         FROM   phoneauth
         WHERE  created > ?
                AND phone = ?
                AND used = false",
-        Utc::now().naive_utc() - Duration::hours(1),
-        phone
-    )
-    .fetch_all(client)
-    .await?;
+            Utc::now().naive_utc() - Duration::hours(1),
+            phone
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(phone_auths);
+        return Ok(phone_auths);
+    })
+    .await
 }
 
 /// Gets the current authattempts for a given `authattempt.phone` in the last 1 hour of `authattempt.created`.
@@ -147,19 +200,22 @@ pub async fn get_phoneauth_attempts(
     client: &MySqlPool,
     phone: &str,
 ) -> Result<Vec<AuthAttempt>, Error> {
-    let auth_attempts = sqlx::query_as!(
-        AuthAttempt,
-        "SELECT *
+    traced("get_phoneauth_attempts", async move {
+        let auth_attempts = sqlx::query_as!(
+            AuthAttempt,
+            "SELECT *
         FROM   authattempt
         WHERE  created > ?
             AND phone = ? ",
-        Utc::now().naive_utc() - Duration::hours(1),
-        phone
-    )
-    .fetch_all(client)
-    .await?;
+            Utc::now().naive_utc() - Duration::hours(1),
+            phone
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(auth_attempts);
+        return Ok(auth_attempts);
+    })
+    .await
 }
 
 /// Gets the users current incoming friend requests that `friendrequest.ignored` is false.
@@ -167,18 +223,21 @@ pub async fn get_incoming_friend_requests(
     client: &MySqlPool,
     user_id: &str,
 ) -> Result<Vec<FriendRequest>, Error> {
-    let friend_requests = sqlx::query_as!(
-        FriendRequest,
-        "SELECT *
+    traced("get_incoming_friend_requests", async move {
+        let friend_requests = sqlx::query_as!(
+            FriendRequest,
+            "SELECT *
         FROM   friendrequest
         WHERE  friend_id = ?
             AND ignored = false ",
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friend_requests);
+        return Ok(friend_requests);
+    })
+    .await
 }
 
 /// Gets the users current incoming friend requests that can be accepted. This included requests that have been ignored.
@@ -186,17 +245,20 @@ pub async fn get_acceptable_friend_requests(
     client: &MySqlPool,
     user_id: &str,
 ) -> Result<Vec<FriendRequest>, Error> {
-    let friend_requests = sqlx::query_as!(
-        FriendRequest,
-        "SELECT *
+    traced("get_acceptable_friend_requests", async move {
+        let friend_requests = sqlx::query_as!(
+            FriendRequest,
+            "SELECT *
         FROM   friendrequest
         WHERE  friend_id = ? ",
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friend_requests);
+        return Ok(friend_requests);
+    })
+    .await
 }
 
 /// Gets the users current incoming friend requests that `friendrequest.ignored` is true.
@@ -204,18 +266,21 @@ pub async fn get_incoming_ignored_friend_requests(
     client: &MySqlPool,
     user_id: &str,
 ) -> Result<Vec<FriendRequest>, Error> {
-    let friend_requests = sqlx::query_as!(
-        FriendRequest,
-        "SELECT *
+    traced("get_incoming_ignored_friend_requests", async move {
+        let friend_requests = sqlx::query_as!(
+            FriendRequest,
+            "SELECT *
         FROM   friendrequest
         WHERE  friend_id = ?
             AND ignored = true ",
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friend_requests);
+        return Ok(friend_requests);
+    })
+    .await
 }
 
 /// Gets the users outgoing friend requests.
@@ -223,48 +288,97 @@ pub async fn get_outgoing_friend_requests(
     client: &MySqlPool,
     user_id: &str,
 ) -> Result<Vec<FriendRequest>, Error> {
-    let friend_requests = sqlx::query_as!(
-        FriendRequest,
-        "SELECT *
+    traced("get_outgoing_friend_requests", async move {
+        let friend_requests = sqlx::query_as!(
+            FriendRequest,
+            "SELECT *
         FROM   friendrequest
         WHERE  user_id = ? ",
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(friend_requests);
+    })
+    .await
+}
+
+/// Versions of everything `full_friends` returns.
+/// Friends, then incoming, ignored and outgoing requests.
+/// Ignoring a request moves it between the incoming and ignored counts.
+pub async fn get_friends_version(
+    client: &MySqlPool,
+    user_id: &str,
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_friends_version", async move {
+        let rows = sqlx::query(
+            "SELECT 1 AS part, COUNT(*) AS count, MAX(created) AS latest
+        FROM   friend
+        WHERE  user_id = ?
+        UNION ALL
+        SELECT 2, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  friend_id = ?
+            AND ignored = false
+        UNION ALL
+        SELECT 3, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  friend_id = ?
+            AND ignored = true
+        UNION ALL
+        SELECT 4, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  user_id = ?
+        ORDER BY part",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friend_requests);
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
 }
 
 /// Gets the users current friends list.
 /// Not paged.
 pub async fn get_current_friends(client: &MySqlPool, user_id: &str) -> Result<Vec<Friend>, Error> {
-    let friends = sqlx::query_as!(
-        Friend,
-        "SELECT *
+    traced("get_current_friends", async move {
+        let friends = sqlx::query_as!(
+            Friend,
+            "SELECT *
         FROM   friend
         WHERE  user_id = ? ",
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friends);
+        return Ok(friends);
+    })
+    .await
 }
 
 /// Gets a pic record for a specific id, will return `None` if it doesn't exists.
 pub async fn get_pic(client: &MySqlPool, id: &str) -> Result<Option<Pic>, Error> {
-    let pic_opt = sqlx::query_as!(
-        Pic,
-        "SELECT *
+    traced("get_pic", async move {
+        let pic_opt = sqlx::query_as!(
+            Pic,
+            "SELECT *
         FROM   pic
         WHERE  id = ? ",
-        id
-    )
-    .fetch_optional(client)
-    .await?;
+            id
+        )
+        .fetch_optional(client)
+        .await?;
 
-    return Ok(pic_opt);
+        return Ok(pic_opt);
+    })
+    .await
 }
 
 /// Gets a single review for the given review_id.
@@ -274,9 +388,10 @@ pub async fn get_review(
     user_id: &str,
     review_id: &str,
 ) -> Result<Option<Review>, Error> {
-    let review_opt = sqlx::query_as!(
-        Review,
-        "SELECT r1.id,
+    traced("get_review", async move {
+        let review_opt = sqlx::query_as!(
+            Review,
+            "SELECT r1.id,
         r1.user_id,
         r1.created,
         r1.category,
@@ -309,15 +424,63 @@ pub async fn get_review(
         FROM   review AS r2
         WHERE  r2.user_id = ?
                AND r2.id = ? ",
-        user_id,
-        review_id,
-        user_id,
-        review_id
-    )
-    .fetch_optional(client)
-    .await?;
+            user_id,
+            review_id,
+            user_id,
+            review_id
+        )
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(review_opt);
+    })
+    .await
+}
+
+/// Gets every review with one of the given ids that the user can see, in no particular order.
+/// Same visibility as `get_review`: their own reviews and their friends'.
+pub async fn get_reviews_by_ids(
+    client: &MySqlPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<Review>, Error> {
+    traced("get_reviews_by_ids", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
 
-    return Ok(review_opt);
+        let query_str = format!(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+        WHERE  r.id IN ( {} )
+               AND ( r.user_id = ?
+                      OR EXISTS (SELECT 1
+                                 FROM   friend AS f
+                                 WHERE  f.user_id = ?
+                                        AND f.friend_id = r.user_id) )",
+            in_list(review_ids.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for review_id in review_ids {
+            query = query.bind(review_id);
+        }
+        let rows = query.bind(user_id).bind(user_id).fetch_all(client).await?;
+
+        return Ok(rows.iter().map(Review::from).collect());
+    })
+    .await
 }
 
 /// Gets all reviews from a given name, latitude, and longitude combination.
@@ -330,10 +493,11 @@ pub async fn get_reviews_from_location(
     latitude: f64,
     longitude: f64,
 ) -> Result<Vec<Review>, Error> {
-    const ACCURACY_SIZE: f64 = 0.001;
-    let reviews = sqlx::query_as!(
-        Review,
-        "SELECT r.id,
+    traced("get_reviews_from_location", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
         r.user_id,
         r.created,
         r.category,
@@ -351,16 +515,18 @@ pub async fn get_reviews_from_location(
         WHERE  f.user_id = ?
                AND r.location_name = ?
                AND ST_Contains(ST_Buffer(POINT(?, ?), ?), r.location) = 1",
-        user_id,
-        name,
-        longitude,
-        latitude,
-        ACCURACY_SIZE
-    )
-    .fetch_all(client)
-    .await?;
+            user_id,
+            name,
+            longitude,
+            latitude,
+            ACCURACY_SIZE
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reviews);
+        return Ok(reviews);
+    })
+    .await
 }
 
 /// Gets all reviews from a user.
@@ -369,15 +535,14 @@ pub async fn get_reviews_from_user(
     client: &MySqlPool,
     user_id: &str,
     target_user_id: &str,
-    page: u32,
-) -> Result<Vec<Review>, Error> {
-    const PAGE_SIZE: u32 = 5;
-
-    let lower_count = page * PAGE_SIZE;
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_reviews_from_user", async move {
+        let (before_created, before_id) = page.before();
 
-    let reviews = sqlx::query_as!(
-        Review,
-        "SELECT r.id,
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
         r.user_id,
         r.created,
         r.category,
@@ -394,17 +559,24 @@ pub async fn get_reviews_from_user(
                        ON r.user_id = f.friend_id
         WHERE  f.user_id = ?
             AND r.user_id = ?
-        ORDER BY r.created DESC
-        LIMIT  ? offset ? ",
-        user_id,
-        target_user_id,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
+            AND ( r.created < ?
+                   OR ( r.created = ?
+                        AND r.id < ? ) )
+        ORDER BY r.created DESC, r.id DESC
+        LIMIT  ? ",
+            user_id,
+            target_user_id,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reviews);
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
 }
 
 /// Gets all recommended reviews from a user.
@@ -413,15 +585,14 @@ pub async fn get_recommended_reviews_from_user(
     client: &MySqlPool,
     user_id: &str,
     target_user_id: &str,
-    page: u32,
-) -> Result<Vec<Review>, Error> {
-    const PAGE_SIZE: u32 = 5;
-
-    let lower_count = page * PAGE_SIZE;
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_recommended_reviews_from_user", async move {
+        let (before_created, before_id) = page.before();
 
-    let reviews = sqlx::query_as!(
-        Review,
-        "SELECT r.id,
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
         r.user_id,
         r.created,
         r.category,
@@ -439,17 +610,24 @@ pub async fn get_recommended_reviews_from_user(
         WHERE  f.user_id = ?
             AND r.user_id = ?
             AND r.recommended = true
-        ORDER BY r.created DESC
-        LIMIT  ? offset ? ",
-        user_id,
-        target_user_id,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
+            AND ( r.created < ?
+                   OR ( r.created = ?
+                        AND r.id < ? ) )
+        ORDER BY r.created DESC, r.id DESC
+        LIMIT  ? ",
+            user_id,
+            target_user_id,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reviews);
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
 }
 
 /// Gets reviews from a given bounding box.
@@ -462,14 +640,14 @@ pub async fn get_reviews_from_bounds(
     latitude_south: f64,
     longitude_west: f64,
     longitude_east: f64,
-    page: u32,
-) -> Result<Vec<ReviewAnnotation>, Error> {
-    const PAGE_SIZE: u32 = 100;
-    let lower_count = page * PAGE_SIZE;
-
-    let annotations = sqlx::query_as!(
-        ReviewAnnotation,
-        "SELECT r.id,
+    page: &PageRequest,
+) -> Result<Page<ReviewAnnotation>, Error> {
+    traced("get_reviews_from_bounds", async move {
+        let (before_created, before_id) = page.before();
+
+        let annotations = sqlx::query_as!(
+            ReviewAnnotation,
+            "SELECT r.id,
         r.user_id,
         r.created,
         p.id             AS pic_id,
@@ -491,19 +669,27 @@ pub async fn get_reviews_from_bounds(
                           LIMIT  1)
  WHERE  f.user_id = ?
         AND St_contains(St_makeenvelope(Point(?, ?), Point(?, ?)), r.location)
- LIMIT  ? offset ? ",
-        user_id,
-        longitude_west,
-        latitude_north,
-        longitude_east,
-        latitude_south,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
+        AND ( r.created < ?
+               OR ( r.created = ?
+                    AND r.id < ? ) )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  ? ",
+            user_id,
+            longitude_west,
+            latitude_north,
+            longitude_east,
+            latitude_south,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(annotations);
+        return Ok(Page::from_rows(annotations, page, ReviewAnnotation::cursor));
+    })
+    .await
 }
 
 /// Gets reviews from a given bounding box.
@@ -520,14 +706,14 @@ pub async fn get_reviews_from_bounds_with_exclusions(
     latitude_south_e: f64,
     longitude_west_e: f64,
     longitude_east_e: f64,
-    page: u32,
-) -> Result<Vec<ReviewAnnotation>, Error> {
-    const PAGE_SIZE: u32 = 100;
-    let lower_count = page * PAGE_SIZE;
-
-    let annotations = sqlx::query_as!(
-        ReviewAnnotation,
-        "SELECT r.id,
+    page: &PageRequest,
+) -> Result<Page<ReviewAnnotation>, Error> {
+    traced("get_reviews_from_bounds_with_exclusions", async move {
+        let (before_created, before_id) = page.before();
+
+        let annotations = sqlx::query_as!(
+            ReviewAnnotation,
+            "SELECT r.id,
         r.user_id,
         r.created,
         p.id             AS pic_id,
@@ -551,23 +737,31 @@ pub async fn get_reviews_from_bounds_with_exclusions(
         AND St_contains(St_makeenvelope(Point(?, ?), Point(?, ?)), r.location)
         AND NOT St_contains(St_makeenvelope(Point(?, ?), Point(?, ?)),
                 r.location)
- LIMIT  ? offset ? ",
-        user_id,
-        longitude_west,
-        latitude_north,
-        longitude_east,
-        latitude_south,
-        longitude_west_e,
-        latitude_north_e,
-        longitude_east_e,
-        latitude_south_e,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
-
-    return Ok(annotations);
+        AND ( r.created < ?
+               OR ( r.created = ?
+                    AND r.id < ? ) )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  ? ",
+            user_id,
+            longitude_west,
+            latitude_north,
+            longitude_east,
+            latitude_south,
+            longitude_west_e,
+            latitude_north_e,
+            longitude_east_e,
+            latitude_south_e,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(Page::from_rows(annotations, page, ReviewAnnotation::cursor));
+    })
+    .await
 }
 
 /// Fetches latest reviews from a given page.
@@ -576,19 +770,18 @@ pub async fn get_reviews_from_bounds_with_exclusions(
 pub async fn get_latest_reviews(
     client: &MySqlPool,
     user_id: &str,
-    page: u32,
+    page: &PageRequest,
     category: &Option<String>,
-) -> Result<Vec<Review>, Error> {
-    const PAGE_SIZE: u32 = 5;
-
-    let lower_count = page * PAGE_SIZE;
+) -> Result<Page<Review>, Error> {
+    traced("get_latest_reviews", async move {
+        let (before_created, before_id) = page.before();
 
-    let reviews;
+        let reviews;
 
-    if let Some(category) = category {
-        reviews = sqlx::query_as!(
-            Review,
-            "SELECT r.id,
+        if let Some(category) = category {
+            reviews = sqlx::query_as!(
+                Review,
+                "SELECT r.id,
             r.user_id,
             r.created,
             r.category,
@@ -605,19 +798,24 @@ pub async fn get_latest_reviews(
                     ON r.user_id = f.friend_id
      WHERE  f.user_id = ?
      AND r.category = ?
-     ORDER  BY r.created DESC
-     LIMIT  ? offset ? ",
-            user_id,
-            category,
-            PAGE_SIZE,
-            lower_count
-        )
-        .fetch_all(client)
-        .await?;
-    } else {
-        reviews = sqlx::query_as!(
-            Review,
-            "SELECT r.id,
+     AND ( r.created < ?
+            OR ( r.created = ?
+                 AND r.id < ? ) )
+     ORDER  BY r.created DESC, r.id DESC
+     LIMIT  ? ",
+                user_id,
+                category,
+                before_created,
+                before_created,
+                before_id,
+                page.fetch_limit()
+            )
+            .fetch_all(client)
+            .await?;
+        } else {
+            reviews = sqlx::query_as!(
+                Review,
+                "SELECT r.id,
             r.user_id,
             r.created,
             r.category,
@@ -633,17 +831,24 @@ pub async fn get_latest_reviews(
             INNER JOIN friend AS f
                     ON r.user_id = f.friend_id
     WHERE  f.user_id = ?
-    ORDER  BY r.created DESC
-    LIMIT  ? offset ? ",
-            user_id,
-            PAGE_SIZE,
-            lower_count
-        )
-        .fetch_all(client)
-        .await?;
-    }
-
-    return Ok(reviews);
+    AND ( r.created < ?
+           OR ( r.created = ?
+                AND r.id < ? ) )
+    ORDER  BY r.created DESC, r.id DESC
+    LIMIT  ? ",
+                user_id,
+                before_created,
+                before_created,
+                before_id,
+                page.fetch_limit()
+            )
+            .fetch_all(client)
+            .await?;
+        }
+
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
 }
 
 /// Fetches latest reviews from a given page.
@@ -653,15 +858,15 @@ pub async fn search_latest_reviews(
     client: &MySqlPool,
     user_id: &str,
     search_prefix: &str,
-    page: u32,
-) -> Result<Vec<Review>, Error> {
-    const PAGE_SIZE: u32 = 5;
-    let lower_count = page * PAGE_SIZE;
-    let search_term = format!("{}%", search_prefix.replace("%", ""));
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("search_latest_reviews", async move {
+        let (before_created, before_id) = page.before();
+        let search_term = format!("{}%", search_prefix.replace("%", ""));
 
-    let reviews = sqlx::query_as!(
-        Review,
-        "SELECT r.id,
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
         r.user_id,
         r.created,
         r.category,
@@ -678,41 +883,124 @@ pub async fn search_latest_reviews(
                 ON r.user_id = f.friend_id
  WHERE  f.user_id = ?
  AND r.location_name LIKE ?
- ORDER  BY r.created DESC
- LIMIT  ? offset ? ",
-        user_id,
-        search_term,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
+ AND ( r.created < ?
+        OR ( r.created = ?
+             AND r.id < ? ) )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  ? ",
+            user_id,
+            search_term,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reviews);
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
 }
 
 /// Gets all likes for a given review.
 /// ## Does not validate the review is able to be viewed by calling user.
 pub async fn get_all_likes(client: &MySqlPool, review_id: &str) -> Result<Vec<Like>, Error> {
-    let rows = sqlx::query_as!(Like, "SELECT * FROM likes WHERE  review_id = ? ", review_id)
-        .fetch_all(client)
-        .await?;
+    traced("get_all_likes", async move {
+        let rows = sqlx::query_as!(Like, "SELECT * FROM likes WHERE  review_id = ? ", review_id)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows);
+    })
+    .await
+}
 
-    return Ok(rows);
+/// Versions of what `gather_compound_reviews` adds to the given reviews.
+/// Likes, replies and pics on the reviews, then the user's bookmarks.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_compound_review_version(
+    client: &MySqlPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_compound_review_version", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let review_id_list = in_list(review_ids.len());
+        let query_str = format!(
+            "SELECT 1 AS part, COUNT(*) AS count, MAX(created) AS latest
+        FROM   likes
+        WHERE  review_id IN ( {review_id_list} )
+        UNION ALL
+        SELECT 2, COUNT(*), MAX(created)
+        FROM   reply
+        WHERE  review_id IN ( {review_id_list} )
+        UNION ALL
+        SELECT 3, COUNT(*), MAX(created)
+        FROM   pic
+        WHERE  review_id IN ( {review_id_list} )
+        UNION ALL
+        SELECT 4, COUNT(*), MAX(created)
+        FROM   bookmark
+        WHERE  user_id = ?
+        ORDER BY part"
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for _ in 0..3 {
+            for review_id in review_ids {
+                query = query.bind(review_id);
+            }
+        }
+        let rows = query.bind(user_id).fetch_all(client).await?;
+
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
 }
 
-/// Gets all likes that a user has made
+/// Gets all likes for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_likes_for_reviews(
+    client: &MySqlPool,
+    review_ids: &[String],
+) -> Result<Vec<Like>, Error> {
+    traced("get_likes_for_reviews", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query_str = format!(
+            "SELECT * FROM likes WHERE review_id IN ( {} )",
+            in_list(review_ids.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for review_id in review_ids {
+            query = query.bind(review_id);
+        }
+        let rows = query.fetch_all(client).await?;
+
+        return Ok(rows.iter().map(Like::from).collect());
+    })
+    .await
+}
+
+/// Gets all likes that a user has made, most recently liked first.
+/// Paged on the like rather than the review, so the cursor is the like's `(created, id)`.
 pub async fn get_liked_reviews(
     client: &MySqlPool,
     user_id: &str,
-    page: u32,
-) -> Result<Vec<Review>, Error> {
-    const PAGE_SIZE: u32 = 5;
-    let lower_count = page * PAGE_SIZE;
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_liked_reviews", async move {
+        let (before_created, before_id) = page.before();
 
-    let reviews = sqlx::query_as!(
-        Review,
-        "SELECT r.id,
+        let rows = sqlx::query!(
+            "SELECT r.id,
             r.user_id,
             r.created,
             r.category,
@@ -723,20 +1011,57 @@ pub async fn get_liked_reviews(
             St_y(r.location) AS latitude,
             r.is_custom,
             r.delivered,
-            r.recommended
+            r.recommended,
+            l.id AS like_id,
+            l.created AS like_created
             FROM   review as r
         INNER JOIN likes as l on r.id = l.review_id
             WHERE  l.user_id = ?
-        ORDER  BY l.created DESC
-        LIMIT  ? offset ? ",
-        user_id,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
+            AND ( l.created < ?
+                   OR ( l.created = ?
+                        AND l.id < ? ) )
+        ORDER  BY l.created DESC, l.id DESC
+        LIMIT  ? ",
+            user_id,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(reviews);
+        let liked = rows
+            .into_iter()
+            .map(|row| {
+                let cursor = Cursor {
+                    created: row.like_created,
+                    id: row.like_id,
+                };
+                let review = Review {
+                    id: row.id,
+                    user_id: row.user_id,
+                    created: row.created,
+                    category: row.category,
+                    text: row.text,
+                    stars: row.stars,
+                    location_name: row.location_name,
+                    longitude: row.longitude,
+                    latitude: row.latitude,
+                    is_custom: row.is_custom,
+                    delivered: row.delivered,
+                    recommended: row.recommended,
+                };
+
+                (cursor, review)
+            })
+            .collect();
+
+        let liked_page = Page::from_rows(liked, page, |(cursor, _)| cursor.clone());
+
+        return Ok(liked_page.map(|(_, review)| review));
+    })
+    .await
 }
 
 /// Gets whether a specific review is already liked by a user.
@@ -745,51 +1070,135 @@ pub async fn is_already_liked(
     user_id: &str,
     review_id: &str,
 ) -> Result<bool, Error> {
-    let likes = sqlx::query_as!(
-        Like,
-        "SELECT *
+    traced("is_already_liked", async move {
+        let likes = sqlx::query_as!(
+            Like,
+            "SELECT *
         FROM   likes
         WHERE  review_id = ?
             AND user_id = ? ",
-        review_id,
-        user_id
-    )
-    .fetch_all(client)
-    .await?;
+            review_id,
+            user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(likes.len() > 0);
+        return Ok(likes.len() > 0);
+    })
+    .await
 }
 
 /// Gets all the replies for a given review.
 /// ## Does not validate the review is able to be viewed by calling user.
 pub async fn get_all_replies(client: &MySqlPool, review_id: &str) -> Result<Vec<Reply>, Error> {
-    let replies = sqlx::query_as!(
-        Reply,
-        "SELECT *
+    traced("get_all_replies", async move {
+        let replies = sqlx::query_as!(
+            Reply,
+            "SELECT *
         FROM   reply
         WHERE  review_id = ? ",
-        review_id
-    )
-    .fetch_all(client)
-    .await?;
+            review_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(replies);
+        return Ok(replies);
+    })
+    .await
 }
 
 /// Gets all the pics for a given review.
 /// ## Does not validate the review is able to be viewed by calling user.
 pub async fn get_all_pics(client: &MySqlPool, review_id: &str) -> Result<Vec<Pic>, Error> {
-    let pics = sqlx::query_as!(
-        Pic,
-        "SELECT *
+    traced("get_all_pics", async move {
+        let pics = sqlx::query_as!(
+            Pic,
+            "SELECT *
         FROM   pic
         WHERE  review_id = ? ",
-        review_id
-    )
-    .fetch_all(client)
-    .await?;
+            review_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(pics);
+        return Ok(pics);
+    })
+    .await
+}
+
+/// Gets all the replies for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_replies_for_reviews(
+    client: &MySqlPool,
+    review_ids: &[String],
+) -> Result<Vec<Reply>, Error> {
+    traced("get_replies_for_reviews", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query_str = format!(
+            "SELECT * FROM reply WHERE review_id IN ( {} )",
+            in_list(review_ids.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for review_id in review_ids {
+            query = query.bind(review_id);
+        }
+        let rows = query.fetch_all(client).await?;
+
+        return Ok(rows.iter().map(Reply::from).collect());
+    })
+    .await
+}
+
+/// Gets all the pics for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_pics_for_reviews(
+    client: &MySqlPool,
+    review_ids: &[String],
+) -> Result<Vec<Pic>, Error> {
+    traced("get_pics_for_reviews", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query_str = format!(
+            "SELECT * FROM pic WHERE review_id IN ( {} )",
+            in_list(review_ids.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for review_id in review_ids {
+            query = query.bind(review_id);
+        }
+        let rows = query.fetch_all(client).await?;
+
+        return Ok(rows.iter().map(Pic::from).collect());
+    })
+    .await
+}
+
+/// Version of the notifications `get_notifications` reads from.
+pub async fn get_notifications_version(
+    client: &MySqlPool,
+    user_id: &str,
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_notifications_version", async move {
+        let rows = sqlx::query(
+            "SELECT COUNT(*) AS count, MAX(n.created) AS latest
+        FROM   notification AS n
+        INNER JOIN review as r on r.id = n.review_id
+    WHERE  n.review_user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
 }
 
 /// Gets the top 50 latest notifications for the user.
@@ -797,20 +1206,23 @@ pub async fn get_notifications(
     client: &MySqlPool,
     user_id: &str,
 ) -> Result<Vec<ExpandedNotification>, Error> {
-    let notifications = sqlx::query_as!(
-        ExpandedNotification,
-        "SELECT n.id, n.created, n.review_user_id, n.user_id, n.review_id, n.action_type, r.location_name AS review_location
+    traced("get_notifications", async move {
+        let notifications = sqlx::query_as!(
+            ExpandedNotification,
+            "SELECT n.id, n.created, n.review_user_id, n.user_id, n.review_id, n.action_type, r.location_name AS review_location
         FROM   notification AS n
         INNER JOIN review as r on r.id = n.review_id
     WHERE  n.review_user_id = ?
         ORDER BY n.created DESC
     LIMIT 50",
-    user_id
-    )
-    .fetch_all(client)
-    .await?;
+        user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(notifications);
+        return Ok(notifications);
+    })
+    .await
 }
 
 /// Gets the count of notifications the user has pending.
@@ -819,16 +1231,20 @@ pub async fn get_notification_count(client: &MySqlPool, user_id: &str) -> i64 {
         count: i64,
     }
 
-    let count_res = sqlx::query_as!(
-        Count,
-        "SELECT count(*) as count FROM notification WHERE review_user_id = ?",
-        user_id
-    )
-    .fetch_one(client)
+    let count_res = traced("get_notification_count", async move {
+        sqlx::query_as!(
+            Count,
+            "SELECT count(*) as count FROM notification WHERE review_user_id = ?",
+            user_id
+        )
+        .fetch_one(client)
+        .await
+        .map(|count| count.count)
+    })
     .await;
 
     match count_res {
-        Ok(count) => return count.count,
+        Ok(count) => return count,
         Err(_) => return 0,
     }
 }
@@ -840,18 +1256,23 @@ pub async fn phone_number_discovery(
     client: &MySqlPool,
     numbers: &Vec<&str>,
 ) -> Result<Vec<User>, Error> {
-    let params = format!("?{}", ", ?".repeat(numbers.len() - 1));
-    let query_str = format!("SELECT * FROM user WHERE phone IN ( {} )", params);
-
-    let mut query = sqlx::query(&query_str);
-    for i in numbers {
-        query = query.bind(i);
-    }
-    let rows = query.fetch_all(client).await?;
-
-    let out: Vec<User> = rows.iter().map(|row| row.into()).collect();
-
-    return Ok(out);
+    traced("phone_number_discovery", async move {
+        let query_str = format!(
+            "SELECT * FROM user WHERE phone IN ( {} )",
+            in_list(numbers.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for i in numbers {
+            query = query.bind(i);
+        }
+        let rows = query.fetch_all(client).await?;
+
+        let out: Vec<User> = rows.iter().map(|row| row.into()).collect();
+
+        return Ok(out);
+    })
+    .await
 }
 
 /// Gets whether users are friends with eachother.
@@ -861,19 +1282,22 @@ pub async fn are_users_friends(
     user_id: &str,
     other_user_id: &str,
 ) -> Result<bool, Error> {
-    let friends = sqlx::query_as!(
-        Friend,
-        "SELECT *
+    traced("are_users_friends", async move {
+        let friends = sqlx::query_as!(
+            Friend,
+            "SELECT *
         FROM   friend
         WHERE  user_id = ?
             AND friend_id = ? ",
-        user_id,
-        other_user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id,
+            other_user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(friends.len() > 0);
+        return Ok(friends.len() > 0);
+    })
+    .await
 }
 
 /// Gets a reply with a specific Id from a specific review.
@@ -882,19 +1306,22 @@ pub async fn get_reply(
     review_id: &str,
     reply_id: &str,
 ) -> Result<Option<Reply>, Error> {
-    let reply = sqlx::query_as!(
-        Reply,
-        "SELECT *
+    traced("get_reply", async move {
+        let reply = sqlx::query_as!(
+            Reply,
+            "SELECT *
         FROM   reply
         WHERE  id = ?
             AND review_id = ?",
-        reply_id,
-        review_id
-    )
-    .fetch_optional(client)
-    .await?;
+            reply_id,
+            review_id
+        )
+        .fetch_optional(client)
+        .await?;
 
-    return Ok(reply);
+        return Ok(reply);
+    })
+    .await
 }
 
 /// Gets all bookmarks fromm a friend of this user.
@@ -904,9 +1331,10 @@ pub async fn get_all_bookmarks(
     user_id: &str,
     target_user_id: &str,
 ) -> Result<Vec<Bookmark>, Error> {
-    let bookmarks = sqlx::query_as!(
-        Bookmark,
-        "SELECT bm.id,
+    traced("get_all_bookmarks", async move {
+        let bookmarks = sqlx::query_as!(
+            Bookmark,
+            "SELECT bm.id,
         bm.user_id,
         bm.created,
         bm.category,
@@ -919,13 +1347,15 @@ pub async fn get_all_bookmarks(
         WHERE  f.user_id = ?
             AND bm.user_id = ?
         ORDER BY bm.created DESC",
-        user_id,
-        target_user_id
-    )
-    .fetch_all(client)
-    .await?;
+            user_id,
+            target_user_id
+        )
+        .fetch_all(client)
+        .await?;
 
-    return Ok(bookmarks);
+        return Ok(bookmarks);
+    })
+    .await
 }
 
 /// Gets the existence of a bookmark by `bookmark.user_id` and `bookmark.id`. True if exists, false if not.
@@ -936,10 +1366,11 @@ pub async fn does_bookmark_exist(
     latitude: f64,
     longitude: f64,
 ) -> Result<bool, Error> {
-    const ACCURACY_SIZE: f64 = 0.001;
-    let bookmarks = sqlx::query_as!(
-        Bookmark,
-        "SELECT r.id,
+    traced("does_bookmark_exist", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let bookmarks = sqlx::query_as!(
+            Bookmark,
+            "SELECT r.id,
         r.user_id,
         r.created,
         r.category,
@@ -950,16 +1381,59 @@ pub async fn does_bookmark_exist(
         WHERE  r.user_id = ?
                AND r.location_name = ?
                AND ST_Contains(ST_Buffer(POINT(?, ?), ?), r.location) = 1",
-        user_id,
-        location_name,
-        longitude,
-        latitude,
-        ACCURACY_SIZE
-    )
-    .fetch_all(client)
-    .await?;
+            user_id,
+            location_name,
+            longitude,
+            latitude,
+            ACCURACY_SIZE
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(bookmarks.len() >= 1);
+    })
+    .await
+}
+
+/// Of the given reviews, the ids of those the user has bookmarked the location of.
+/// Matches the same way as `does_bookmark_exist`.
+pub async fn get_bookmarked_review_ids(
+    client: &MySqlPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<String>, Error> {
+    traced("get_bookmarked_review_ids", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
 
-    return Ok(bookmarks.len() >= 1);
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let query_str = format!(
+            "SELECT r.id
+        FROM   review AS r
+        WHERE  r.id IN ( {} )
+               AND EXISTS (SELECT 1
+                           FROM   bookmark AS bm
+                           WHERE  bm.user_id = ?
+                                  AND bm.location_name = r.location_name
+                                  AND ST_Contains(ST_Buffer(r.location, ?), bm.location) = 1)",
+            in_list(review_ids.len())
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for review_id in review_ids {
+            query = query.bind(review_id);
+        }
+        let rows = query
+            .bind(user_id)
+            .bind(ACCURACY_SIZE)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(|row| row.get("id")).collect());
+    })
+    .await
 }
 
 /// Gets all bookmarks from a nearby radius
@@ -968,18 +1442,18 @@ pub async fn get_nearby_bookmarks(
     client: &MySqlPool,
     calling_user_id: &str,
     user_id: &str,
-    page: u32,
+    page: &PageRequest,
     latitude: f64,
     longitude: f64,
-) -> Result<Vec<Bookmark>, Error> {
-    const PAGE_SIZE: u32 = 5;
-    let lower_count = page * PAGE_SIZE;
+) -> Result<Page<Bookmark>, Error> {
+    traced("get_nearby_bookmarks", async move {
+        const ACCURACY_SIZE: f64 = 0.025;
 
-    const ACCURACY_SIZE: f64 = 0.025;
+        let (before_created, before_id) = page.before();
 
-    let reviews = sqlx::query_as!(
-        Bookmark,
-        "SELECT bm.id,
+        let reviews = sqlx::query_as!(
+            Bookmark,
+            "SELECT bm.id,
         bm.user_id,
         bm.created,
         bm.category,
@@ -992,18 +1466,71 @@ pub async fn get_nearby_bookmarks(
         WHERE  f.user_id = ?
             AND bm.user_id = ?
                AND ST_Contains(ST_Buffer(POINT(?, ?), ?), bm.location) = 1
-            ORDER BY bm.created DESC
-               LIMIT  ? offset ? ",
-        calling_user_id,
-        user_id,
-        longitude,
-        latitude,
-        ACCURACY_SIZE,
-        PAGE_SIZE,
-        lower_count
-    )
-    .fetch_all(client)
-    .await?;
-
-    return Ok(reviews);
+               AND ( bm.created < ?
+                      OR ( bm.created = ?
+                           AND bm.id < ? ) )
+            ORDER BY bm.created DESC, bm.id DESC
+               LIMIT  ? ",
+            calling_user_id,
+            user_id,
+            longitude,
+            latitude,
+            ACCURACY_SIZE,
+            before_created,
+            before_created,
+            before_id,
+            page.fetch_limit()
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(Page::from_rows(reviews, page, Bookmark::cursor));
+    })
+    .await
+}
+
+/// Gets all ipblock rules that have not expired yet.
+pub async fn get_active_ipblocks(client: &MySqlPool) -> Result<Vec<IpBlock>, Error> {
+    traced("get_active_ipblocks", async move {
+        let ipblocks = sqlx::query_as!(
+            IpBlock,
+            "SELECT *
+        FROM   ipblock
+        WHERE  expires IS NULL
+                OR expires > ? ",
+            Utc::now().naive_utc()
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(ipblocks);
+    })
+    .await
+}
+
+/// Gets the user's idempotency key, unless it was first used before `since`.
+pub async fn get_idempotency_key(
+    client: &MySqlPool,
+    user_id: &str,
+    idempotency_key: &str,
+    since: &NaiveDateTime,
+) -> Result<Option<IdempotencyKey>, Error> {
+    traced("get_idempotency_key", async move {
+        let key = sqlx::query_as!(
+            IdempotencyKey,
+            "SELECT *
+        FROM   idempotencykey
+        WHERE  user_id = ?
+               AND idempotency_key = ?
+               AND created >= ? ",
+            user_id,
+            idempotency_key,
+            since
+        )
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(key);
+    })
+    .await
 }
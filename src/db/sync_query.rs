@@ -0,0 +1,170 @@
+//! Queries for `/sync`. Each returns what changed at or after `since` among the rows the user can see.
+//! Everything from a friendship made at or after `since` is included whatever its age, the client has never seen it.
+
+use sqlx::{types::chrono::NaiveDateTime, Error, MySqlPool};
+
+use super::{traced, Bookmark, Like, Reply, Review, Tombstone};
+
+/// Reviews from the user's friends created or edited since `since`.
+pub async fn get_reviews_changed_since(
+    client: &MySqlPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Review>, Error> {
+    traced("get_reviews_changed_since", async move {
+        let reviews = sqlx::query_as!(
+            Review,
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = ?
+               AND ( r.updated >= ?
+                      OR f.created >= ? )
+        ORDER  BY r.updated ASC",
+            user_id,
+            since,
+            since
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(reviews);
+    })
+    .await
+}
+
+/// Likes made since `since` on reviews from the user's friends.
+pub async fn get_likes_since(
+    client: &MySqlPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Like>, Error> {
+    traced("get_likes_since", async move {
+        let likes = sqlx::query_as!(
+            Like,
+            "SELECT l.*
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = ?
+               AND ( l.created >= ?
+                      OR f.created >= ? )
+        ORDER  BY l.created ASC",
+            user_id,
+            since,
+            since
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(likes);
+    })
+    .await
+}
+
+/// Replies made since `since` on reviews from the user's friends.
+pub async fn get_replies_since(
+    client: &MySqlPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Reply>, Error> {
+    traced("get_replies_since", async move {
+        let replies = sqlx::query_as!(
+            Reply,
+            "SELECT rp.*
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = ?
+               AND ( rp.created >= ?
+                      OR f.created >= ? )
+        ORDER  BY rp.created ASC",
+            user_id,
+            since,
+            since
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(replies);
+    })
+    .await
+}
+
+/// Bookmarks the user's friends made since `since`.
+pub async fn get_bookmarks_since(
+    client: &MySqlPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Bookmark>, Error> {
+    traced("get_bookmarks_since", async move {
+        let bookmarks = sqlx::query_as!(
+            Bookmark,
+            "SELECT bm.id,
+        bm.user_id,
+        bm.created,
+        bm.category,
+        bm.location_name,
+        ST_X(bm.location) as longitude,
+        ST_Y(bm.location) as latitude
+        FROM   bookmark AS bm
+               INNER JOIN friend AS f
+                       ON bm.user_id = f.friend_id
+        WHERE  f.user_id = ?
+               AND ( bm.created >= ?
+                      OR f.created >= ? )
+        ORDER  BY bm.created ASC",
+            user_id,
+            since,
+            since
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(bookmarks);
+    })
+    .await
+}
+
+/// Reviews, replies, likes and bookmarks deleted since `since` that the user's friends owned.
+pub async fn get_tombstones_since(
+    client: &MySqlPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Tombstone>, Error> {
+    traced("get_tombstones_since", async move {
+        let tombstones = sqlx::query_as!(
+            Tombstone,
+            "SELECT t.*
+        FROM   tombstone AS t
+               INNER JOIN friend AS f
+                       ON t.owner_id = f.friend_id
+        WHERE  f.user_id = ?
+               AND t.created >= ?
+        ORDER  BY t.created ASC",
+            user_id,
+            since
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(tombstones);
+    })
+    .await
+}
@@ -1,6 +1,11 @@
-use sqlx::{mysql::MySqlRow, types::chrono::NaiveDateTime, Row};
+use sqlx::{
+    mysql::MySqlRow,
+    types::chrono::{DateTime, NaiveDateTime, Utc},
+    Row,
+};
 
 /// A unique user in the application.
+#[derive(Clone)]
 pub struct User {
     /// Guid unique identifier.
     pub id: String,
@@ -47,6 +52,7 @@ impl From<&MySqlRow> for User {
 }
 
 /// Represents a specific phone authentication attempt.
+#[derive(Clone)]
 pub struct PhoneAuth {
     /// Guid unique identifier.
     pub id: String,
@@ -81,6 +87,7 @@ impl From<&MySqlRow> for PhoneAuth {
 }
 
 /// Represents an attempt to use a code provided by phone auth.
+#[derive(Clone)]
 pub struct AuthAttempt {
     /// Guid unique identifier.
     pub id: String,
@@ -105,6 +112,7 @@ impl From<&MySqlRow> for AuthAttempt {
 /// Represents one direction of a friend relationship.
 /// In a logical friendship, two friend records exist
 /// with user_id and friend_id flipped.
+#[derive(Clone)]
 pub struct Friend {
     /// Guid unique identifier.
     pub id: String,
@@ -132,6 +140,7 @@ impl From<&MySqlRow> for Friend {
 
 /// Represents a sent friend request.
 /// user_id is the sender
+#[derive(Clone)]
 pub struct FriendRequest {
     /// Guid unique identifier.
     pub id: String,
@@ -162,6 +171,7 @@ impl From<&MySqlRow> for FriendRequest {
 }
 
 /// Represents an uploaded image.
+#[derive(Clone)]
 pub struct Pic {
     /// Guid unique identifier.
     pub id: String,
@@ -197,6 +207,7 @@ impl From<&MySqlRow> for Pic {
 }
 
 /// Represents a review for a location.
+#[derive(Clone)]
 pub struct Review {
     /// Guid unique identifier.
     pub id: String,
@@ -310,6 +321,7 @@ impl From<&MySqlRow> for ReviewAnnotation {
 }
 
 /// Represents a like for a post.
+#[derive(Clone)]
 pub struct Like {
     /// Guid unique identifier.
     pub id: String,
@@ -327,7 +339,20 @@ pub struct Like {
     pub like_type: i8,
 }
 
+impl From<&MySqlRow> for Like {
+    fn from(row: &MySqlRow) -> Like {
+        Like {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            review_id: row.get("review_id"),
+            like_type: row.get("like_type"),
+        }
+    }
+}
+
 /// Represents a reply to a post.
+#[derive(Clone)]
 pub struct Reply {
     /// Guid unique identifier.
     pub id: String,
@@ -362,6 +387,7 @@ impl From<&MySqlRow> for Reply {
 }
 
 /// Represents a notification to a post.
+#[derive(Clone)]
 pub struct Notification {
     /// Guid unique identifier.
     pub id: String,
@@ -433,6 +459,7 @@ impl From<&MySqlRow> for ExpandedNotification {
 }
 
 /// Represents a report against a user.
+#[derive(Clone)]
 pub struct Report {
     /// Guid unique identifier.
     pub id: String,
@@ -450,6 +477,7 @@ pub struct Report {
     pub report_type: u8,
 }
 
+#[derive(Clone)]
 pub struct Bookmark {
     /// Guid unique identifier.
     pub id: String,
@@ -472,3 +500,195 @@ pub struct Bookmark {
     // Longitude of the reviewed location.
     pub longitude: f64,
 }
+
+/// A row that was deleted, kept so `/sync` can tell clients to drop their copy.
+#[derive(Clone)]
+pub struct Tombstone {
+    /// Guid unique identifier.
+    pub id: String,
+
+    /// Datetime the row was deleted.
+    pub created: NaiveDateTime,
+
+    /// Which kind of row was deleted, one of the `TOMBSTONE_` constants.
+    pub item_type: String,
+
+    /// Id of the deleted row.
+    pub item_id: String,
+
+    /// User whose friends could see the row. The review's author for reviews, replies and likes.
+    pub owner_id: String,
+}
+
+impl From<&MySqlRow> for Tombstone {
+    fn from(row: &MySqlRow) -> Tombstone {
+        Tombstone {
+            id: row.get("id"),
+            created: row.get("created"),
+            item_type: row.get("item_type"),
+            item_id: row.get("item_id"),
+            owner_id: row.get("owner_id"),
+        }
+    }
+}
+
+/// A write sent with an `Idempotency-Key`, and the response retries with the same key get back.
+#[derive(Clone)]
+pub struct IdempotencyKey {
+    /// Guid unique identifier.
+    pub id: String,
+
+    /// Datetime the key was first used. Keys are forgotten a day after.
+    pub created: NaiveDateTime,
+
+    /// User who sent the request. Keys are only unique per user.
+    pub user_id: String,
+
+    /// Value of the `Idempotency-Key` header.
+    pub idempotency_key: String,
+
+    /// Hex sha256 of the method, path, query and body of the first request.
+    pub fingerprint: String,
+
+    /// Status of the stored response. Null while the first request is still running.
+    pub status: Option<u16>,
+
+    /// Headers of the stored response, as a json array of `[name, value]` pairs.
+    pub headers: Option<String>,
+
+    /// Body of the stored response.
+    pub body: Option<Vec<u8>>,
+}
+
+impl From<&MySqlRow> for IdempotencyKey {
+    fn from(row: &MySqlRow) -> IdempotencyKey {
+        IdempotencyKey {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            idempotency_key: row.get("idempotency_key"),
+            fingerprint: row.get("fingerprint"),
+            status: row.get("status"),
+            headers: row.get("headers"),
+            body: row.get("body"),
+        }
+    }
+}
+
+/// Counts of the records a single user owns.
+/// Only used for admin tooling.
+pub struct UserActivityCounts {
+    /// Reviews the user has posted.
+    pub reviews: i64,
+
+    /// Friends the user has, not counting themselves.
+    pub friends: i64,
+
+    /// Friend requests sent to the user.
+    pub incoming_requests: i64,
+
+    /// Friend requests the user has sent.
+    pub outgoing_requests: i64,
+
+    /// Likes the user has given.
+    pub likes: i64,
+
+    /// Replies the user has written.
+    pub replies: i64,
+
+    /// Bookmarks the user has saved.
+    pub bookmarks: i64,
+
+    /// All notifications for the user, confirmed or not.
+    pub notifications: i64,
+}
+
+impl From<&MySqlRow> for UserActivityCounts {
+    fn from(row: &MySqlRow) -> UserActivityCounts {
+        UserActivityCounts {
+            reviews: row.get("reviews"),
+            friends: row.get("friends"),
+            incoming_requests: row.get("incoming_requests"),
+            outgoing_requests: row.get("outgoing_requests"),
+            likes: row.get("likes"),
+            replies: row.get("replies"),
+            bookmarks: row.get("bookmarks"),
+            notifications: row.get("notifications"),
+        }
+    }
+}
+
+/// A deny or allow rule for incoming traffic.
+/// Exactly one of `cidr`, `asn`, or `country` is set.
+#[derive(Clone)]
+pub struct IpBlock {
+    /// Guid unique identifier.
+    pub id: String,
+
+    /// Datetime the rule was created.
+    pub created: NaiveDateTime,
+
+    /// Id of the admin who created the rule.
+    pub created_by: String,
+
+    /// Either `deny` or `allow`.
+    pub action: String,
+
+    /// Network the rule matches. Single addresses are stored as a /32 or /128.
+    pub cidr: Option<String>,
+
+    /// Autonomous system number the rule matches.
+    pub asn: Option<u32>,
+
+    /// ISO country code the rule matches.
+    pub country: Option<String>,
+
+    /// Why the rule was added.
+    pub reason: String,
+
+    /// Datetime in UTC the rule stops applying. Never expires when empty.
+    pub expires: Option<NaiveDateTime>,
+}
+
+/// A push notification that was still queued when the service shut down.
+#[derive(Clone)]
+pub struct PendingNotification {
+    /// Guid unique identifier.
+    pub id: String,
+
+    /// Datetime the notification was persisted.
+    pub created: NaiveDateTime,
+
+    /// User Id who will receive the notification.
+    pub user_id: String,
+
+    /// A value for navigation used by the client.
+    pub notification_value: Option<String>,
+
+    /// User friendly message for the notification.
+    pub message: String,
+
+    /// The type of notification, as the `NotificationType` display name.
+    pub notification_type: String,
+}
+
+/// A migration recorded in `_sqlx_migrations`.
+pub struct SchemaMigration {
+    /// Timestamp prefix of the migration file.
+    pub version: i64,
+
+    /// Name of the migration file after the version.
+    pub description: String,
+
+    /// When the migration was applied.
+    pub installed_on: DateTime<Utc>,
+
+    /// False when the migration failed part way and the schema needs a manual look.
+    pub success: bool,
+
+    /// Checksum of the migration as it was applied.
+    pub checksum: Vec<u8>,
+
+    /// How long the migration took in nanoseconds.
+    pub execution_time: i64,
+}
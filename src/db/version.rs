@@ -0,0 +1,70 @@
+use sqlx::{mysql::MySqlRow, types::chrono::NaiveDateTime, Row};
+
+use super::{Review, User};
+
+/// How many rows some slice of a table has, and when the newest was created.
+/// The pair changes whenever the rows do, as long as they're only inserted or deleted.
+/// Rows with a column that's updated, like `friendrequest.ignored`, are split into one version per value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataVersion {
+    pub count: i64,
+
+    /// `None` when there are no rows.
+    pub latest: Option<NaiveDateTime>,
+}
+
+impl DataVersion {
+    /// Version of the rows with the given `created` times.
+    /// Backends that keep rows in memory use this in place of `COUNT(*)` and `MAX(created)`.
+    pub fn of<I>(created: I) -> DataVersion
+    where
+        I: IntoIterator<Item = NaiveDateTime>,
+    {
+        let mut version = DataVersion {
+            count: 0,
+            latest: None,
+        };
+
+        for created in created {
+            version.count += 1;
+            version.latest = version.latest.max(Some(created));
+        }
+
+        return version;
+    }
+}
+
+impl From<&MySqlRow> for DataVersion {
+    fn from(row: &MySqlRow) -> DataVersion {
+        DataVersion {
+            count: row.get("count"),
+            latest: row.get("latest"),
+        }
+    }
+}
+
+impl Review {
+    /// The id, and every field that can change after the review is posted.
+    pub fn version(&self) -> (&str, &str, u8, i8, i8) {
+        (
+            &self.id,
+            &self.text,
+            self.stars,
+            self.delivered,
+            self.recommended,
+        )
+    }
+}
+
+impl User {
+    /// The id, and every field of `UserPub` that can change after sign up.
+    pub fn version(&self) -> (&str, &str, &str, &str, bool) {
+        (
+            &self.id,
+            &self.name,
+            &self.display_name,
+            &self.pic_id,
+            self.email.is_some(),
+        )
+    }
+}
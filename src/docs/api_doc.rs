@@ -0,0 +1,171 @@
+use crate::{
+    admin_v1, auth, bookmark_v1, compound_types, error, friend_v1, likes_v1, notifications_v1,
+    pic_v1, ping_routes, reply_v1, report_v1, review_v1, sync_v1, user_v1,
+};
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    Modify, OpenApi,
+};
+
+/// Name of the security scheme that authenticated routes reference.
+pub const JWT_SECURITY: &str = "jwt";
+
+/// The OpenAPI document for every public route.
+/// Paths come from the `#[utoipa::path]` on each handler,
+/// so a new handler must be listed here to show up in the docs.
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        ping_routes::ping,
+        ping_routes::ping_error,
+        ping_routes::live,
+        ping_routes::ready,
+        auth::request_code,
+        auth::sign_in,
+        auth::sign_in_demo,
+        auth::recovery_code,
+        auth::update_phone,
+        admin_v1::get_user_count,
+        admin_v1::get_all_reports,
+        admin_v1::inspect_user,
+        admin_v1::reset_user_name,
+        admin_v1::clear_user_pic,
+        admin_v1::get_ipblocks,
+        admin_v1::add_ipblock,
+        admin_v1::remove_ipblock,
+        admin_v1::get_schema,
+        friend_v1::full_friends,
+        friend_v1::get_friends,
+        friend_v1::get_outgoing_friends,
+        friend_v1::get_incoming_friends,
+        friend_v1::get_ignored_friends,
+        friend_v1::add_friend,
+        friend_v1::accept_friend,
+        friend_v1::cancel_friend,
+        friend_v1::decline_friend,
+        friend_v1::remove_friend,
+        friend_v1::ignore_friend,
+        friend_v1::discover_friends,
+        friend_v1::get_user_friends::get_user_friends,
+        pic_v1::get_profile_pic,
+        pic_v1::add_profile_pic,
+        review_v1::get_latest,
+        review_v1::get_latest_full,
+        pic_v1::add_review_pic,
+        pic_v1::remove_review_pic,
+        review_v1::get_reviews_from_map_bounds,
+        review_v1::get_reviews_from_map_bounds_with_exclusions,
+        review_v1::get_reviews_from_loc,
+        review_v1::get_reviews_from_user,
+        review_v1::get_full_reviews_from_user,
+        review_v1::add_review,
+        review_v1::remove_review,
+        review_v1::get_review_by_id,
+        review_v1::get_review_batch,
+        review_v1::search_latest,
+        review_v1::edit_review,
+        review_v1::get_recommended_reviews_from_user,
+        review_v1::update_review_recommended_status,
+        user_v1::search_user_by_name,
+        user_v1::get_user_by_id,
+        user_v1::get_user_batch,
+        user_v1::get_user_by_name,
+        user_v1::update_user,
+        user_v1::get_me,
+        user_v1::update_user_device_token,
+        user_v1::update_user_recovery_email,
+        likes_v1::get_likes,
+        likes_v1::like_review,
+        likes_v1::unlike_review,
+        likes_v1::get_current_likes,
+        likes_v1::get_current_liked_reviews_full,
+        reply_v1::get_replies,
+        reply_v1::add_reply,
+        reply_v1::remove_reply,
+        notifications_v1::get_notifications,
+        notifications_v1::confirm_notifications,
+        report_v1::report_user,
+        report_v1::report_bug,
+        bookmark_v1::get_all_bookmarks,
+        bookmark_v1::add_bookmark,
+        bookmark_v1::remove_bookmark,
+        bookmark_v1::get_nearby_all_bookmarks,
+        sync_v1::get_sync,
+    ),
+    components(schemas(
+        error::AppErrorPub,
+        error::FieldError,
+        ping_routes::HealthPub,
+        ping_routes::DependencyCheckPub,
+        admin_v1::Count,
+        admin_v1::ReportPub,
+        admin_v1::AdminUserPub,
+        admin_v1::UserActivityCountsPub,
+        admin_v1::PhoneAuthPub,
+        admin_v1::AuthAttemptPub,
+        admin_v1::UserInspectionPub,
+        admin_v1::IpBlockPub,
+        admin_v1::AddIpBlockRequest,
+        admin_v1::MigrationPub,
+        admin_v1::SchemaPub,
+        friend_v1::FullFriendsPub,
+        friend_v1::FriendPub,
+        friend_v1::FriendRequestPub,
+        friend_v1::DiscoveryRequest,
+        review_v1::ReviewPub,
+        review_v1::ReviewAnnotationPub,
+        review_v1::AddReviewRequest,
+        review_v1::EditReviewRequest,
+        compound_types::CompoundReviewPub,
+        compound_types::PagedReviewPub,
+        compound_types::PagedCompoundReviewPub,
+        compound_types::PagedReviewAnnotationPub,
+        compound_types::PagedBookmarkPub,
+        compound_types::BatchRequest,
+        compound_types::CompoundReviewBatchPub,
+        compound_types::UserBatchPub,
+        user_v1::UserPub,
+        user_v1::UpdateUserRequest,
+        likes_v1::LikePub,
+        reply_v1::ReplyPub,
+        reply_v1::AddReplyRequest,
+        pic_v1::PicPub,
+        notifications_v1::NotificationPub,
+        report_v1::BugReportRequest,
+        bookmark_v1::BookmarkPub,
+        sync_v1::SyncPub,
+        sync_v1::TombstonePub,
+    )),
+    modifiers(&JwtSecurity),
+    tags(
+        (name = "ping", description = "Liveness and readiness checks"),
+        (name = "auth", description = "Phone sign in and account recovery"),
+        (name = "admin", description = "Moderation tools, limited to admin users"),
+        (name = "friends", description = "Friend requests and contact discovery"),
+        (name = "pic", description = "Profile and review pictures"),
+        (name = "review", description = "Reviews and review feeds"),
+        (name = "user", description = "User profiles"),
+        (name = "like", description = "Review likes"),
+        (name = "reply", description = "Review replies"),
+        (name = "notification", description = "In-app notifications"),
+        (name = "report", description = "User and bug reports"),
+        (name = "bookmark", description = "Bookmarked locations"),
+        (name = "sync", description = "Catching up on changes after being offline"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the JWT that `/auth/signin` returns.
+/// Clients send it as the raw `Authorization` header value, without a `Bearer` prefix.
+struct JwtSecurity;
+
+impl Modify for JwtSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                JWT_SECURITY,
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("Authorization"))),
+            );
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+use utoipa::{
+    openapi::{
+        response::{Response, ResponseBuilder, ResponsesBuilder},
+        Content, Ref, RefOr,
+    },
+    IntoResponses,
+};
+
+/// The error responses every endpoint can return.
+/// All of them carry an `AppErrorPub` body, so clients branch on `code` rather than on status.
+pub struct ErrorResponses;
+
+impl IntoResponses for ErrorResponses {
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        ResponsesBuilder::new()
+            .response("4XX", error_response("the request was rejected"))
+            .response(
+                "5XX",
+                error_response("the server failed to handle the request"),
+            )
+            .build()
+            .into()
+    }
+}
+
+fn error_response(description: &str) -> Response {
+    ResponseBuilder::new()
+        .description(description)
+        .content(
+            "application/json",
+            Content::new(Ref::from_schema_name("AppErrorPub")),
+        )
+        .build()
+}
@@ -0,0 +1,5 @@
+pub mod api_doc;
+pub use api_doc::*;
+
+pub mod error_responses;
+pub use error_responses::*;
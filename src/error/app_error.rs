@@ -0,0 +1,234 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use opentelemetry_api::{
+    trace::{get_active_span, Status},
+    KeyValue,
+};
+use serde::Serialize;
+use std::{error::Error, fmt};
+use utoipa::ToSchema;
+
+use super::{ErrorCode, FieldError};
+
+/// Underlying cause of an internal error. It's recorded on the span but never sent to clients.
+pub type ErrorSource = Box<dyn Error + Send + Sync>;
+
+/// Every way a request can fail.
+///
+/// 4XX errors are terminal and their message is shown to the user, so it should say how to fix the request.
+/// 5XX errors can be freely retried by clients.
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest {
+        code: ErrorCode,
+        message: String,
+    },
+    /// 400 for request fields that failed validation, listing each one so clients can point at the field.
+    InvalidFields {
+        message: String,
+        fields: Vec<FieldError>,
+    },
+    Unauthorized {
+        code: ErrorCode,
+        message: String,
+    },
+    Forbidden {
+        code: ErrorCode,
+        message: String,
+    },
+    NotFound {
+        code: ErrorCode,
+        message: String,
+    },
+    /// 409 for a request that clashes with one already made, such as a reused `Idempotency-Key`.
+    Conflict {
+        code: ErrorCode,
+        message: String,
+    },
+    TooManyRequests {
+        message: String,
+    },
+    Internal {
+        message: String,
+        source: Option<ErrorSource>,
+    },
+}
+
+impl AppError {
+    pub fn bad_request(code: ErrorCode, message: impl Into<String>) -> AppError {
+        AppError::BadRequest {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_fields(fields: Vec<FieldError>) -> AppError {
+        let message = fields
+            .iter()
+            .map(|field| format!("{}: {}", field.field, field.message))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        AppError::InvalidFields { message, fields }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> AppError {
+        AppError::Unauthorized {
+            code: ErrorCode::Unauthenticated,
+            message: message.into(),
+        }
+    }
+
+    pub fn forbidden(code: ErrorCode, message: impl Into<String>) -> AppError {
+        AppError::Forbidden {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(code: ErrorCode, message: impl Into<String>) -> AppError {
+        AppError::NotFound {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(code: ErrorCode, message: impl Into<String>) -> AppError {
+        AppError::Conflict {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> AppError {
+        AppError::TooManyRequests {
+            message: message.into(),
+        }
+    }
+
+    /// An internal error caused by another error, such as a failed query.
+    pub fn internal(message: impl Into<String>, source: impl Into<ErrorSource>) -> AppError {
+        AppError::Internal {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// An internal error with no underlying error to record, such as an invariant we expected to hold.
+    pub fn internal_message(message: impl Into<String>) -> AppError {
+        AppError::Internal {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::BadRequest { code, .. }
+            | AppError::Unauthorized { code, .. }
+            | AppError::Forbidden { code, .. }
+            | AppError::NotFound { code, .. }
+            | AppError::Conflict { code, .. } => *code,
+            AppError::InvalidFields { .. } => ErrorCode::InvalidInput,
+            AppError::TooManyRequests { .. } => ErrorCode::RateLimited,
+            AppError::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::BadRequest { message, .. }
+            | AppError::InvalidFields { message, .. }
+            | AppError::Unauthorized { message, .. }
+            | AppError::Forbidden { message, .. }
+            | AppError::NotFound { message, .. }
+            | AppError::Conflict { message, .. }
+            | AppError::TooManyRequests { message }
+            | AppError::Internal { message, .. } => message,
+        }
+    }
+
+    /// Whether the client can retry without changing anything, per the 5XX retry && 4XX terminal rule.
+    /// Rate limits are the exception, and can be retried once `retry-after` has passed.
+    /// So is an `Idempotency-Key` still in use, which can be retried once the first request finishes.
+    pub fn retryable(&self) -> bool {
+        self.status_code().is_server_error()
+            || self.status_code() == StatusCode::TOO_MANY_REQUESTS
+            || self.code() == ErrorCode::IdempotencyKeyInProgress
+    }
+
+    /// Adds the error to the active span, so failures show up in traces without each handler doing it.
+    fn record_span(&self) {
+        get_active_span(|span| {
+            span.set_attribute(KeyValue::new("error.code", self.code().as_str()));
+
+            if self.status_code().is_server_error() {
+                match self {
+                    AppError::Internal {
+                        source: Some(source),
+                        ..
+                    } => span.record_error(source.as_ref()),
+                    _ => span.record_error(self),
+                }
+                span.set_status(Status::error(self.message().to_string()));
+            }
+        });
+    }
+}
+
+/// DB Types are purposefuly not serialized.
+/// We require DTO objects suffixed with 'Pub'
+/// to trim database object appropriately.
+#[derive(Serialize, ToSchema)]
+pub struct AppErrorPub {
+    pub code: &'static str,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Vec<FieldError>>,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Internal {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest { .. } | AppError::InvalidFields { .. } => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            AppError::NotFound { .. } => StatusCode::NOT_FOUND,
+            AppError::Conflict { .. } => StatusCode::CONFLICT,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.record_span();
+
+        HttpResponse::build(self.status_code()).json(AppErrorPub {
+            code: self.code().as_str(),
+            message: self.message().to_string(),
+            retryable: self.retryable(),
+            fields: match self {
+                AppError::InvalidFields { fields, .. } => Some(fields.clone()),
+                _ => None,
+            },
+        })
+    }
+}
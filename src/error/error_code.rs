@@ -0,0 +1,78 @@
+/// Stable, machine-readable reason a request failed.
+/// Clients branch on these, so existing codes must never be renamed or reused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorCode {
+    /// Something in the request failed validation; the message says what.
+    InvalidInput,
+    /// The auth code didn't match any that were sent.
+    InvalidCode,
+    TooManyAuthAttempts,
+    RecoveryEmailMissing,
+    UserDisabled,
+    UserExists,
+    /// The new phone number is the same as the current one.
+    PhoneUnchanged,
+    AlreadyFriends,
+    FriendRequestExists,
+    NotFriends,
+    /// Users can't friend or unfriend themselves.
+    SelfFriend,
+    TooManyPics,
+    /// The target belongs to another user.
+    NotOwner,
+    /// An admin is missing the permission a route needs.
+    MissingPermission,
+    /// No valid JWT was sent.
+    Unauthenticated,
+    /// The client is blocked by an ipblock rule.
+    AccessDenied,
+    UserNotFound,
+    ReviewNotFound,
+    PicNotFound,
+    FriendNotFound,
+    FriendRequestNotFound,
+    IpBlockNotFound,
+    RateLimited,
+    /// The `/sync` token is too old to catch up from, the client has to reload everything.
+    SyncTokenExpired,
+    /// The `Idempotency-Key` was already used for a different request.
+    IdempotencyKeyReused,
+    /// The first request with this `Idempotency-Key` hasn't finished yet.
+    IdempotencyKeyInProgress,
+    /// Something went wrong on our end.
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::InvalidCode => "invalid_code",
+            ErrorCode::TooManyAuthAttempts => "too_many_auth_attempts",
+            ErrorCode::RecoveryEmailMissing => "recovery_email_missing",
+            ErrorCode::UserDisabled => "user_disabled",
+            ErrorCode::UserExists => "user_exists",
+            ErrorCode::PhoneUnchanged => "phone_unchanged",
+            ErrorCode::AlreadyFriends => "already_friends",
+            ErrorCode::FriendRequestExists => "friend_request_exists",
+            ErrorCode::NotFriends => "not_friends",
+            ErrorCode::SelfFriend => "self_friend",
+            ErrorCode::TooManyPics => "too_many_pics",
+            ErrorCode::NotOwner => "not_owner",
+            ErrorCode::MissingPermission => "missing_permission",
+            ErrorCode::Unauthenticated => "unauthenticated",
+            ErrorCode::AccessDenied => "access_denied",
+            ErrorCode::UserNotFound => "user_not_found",
+            ErrorCode::ReviewNotFound => "review_not_found",
+            ErrorCode::PicNotFound => "pic_not_found",
+            ErrorCode::FriendNotFound => "friend_not_found",
+            ErrorCode::FriendRequestNotFound => "friend_request_not_found",
+            ErrorCode::IpBlockNotFound => "ipblock_not_found",
+            ErrorCode::RateLimited => "rate_limited",
+            ErrorCode::SyncTokenExpired => "sync_token_expired",
+            ErrorCode::IdempotencyKeyReused => "idempotency_key_reused",
+            ErrorCode::IdempotencyKeyInProgress => "idempotency_key_in_progress",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
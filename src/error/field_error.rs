@@ -0,0 +1,10 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A single request field that failed validation.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the field as the client sent it.
+    pub field: String,
+    pub message: String,
+}
@@ -0,0 +1,8 @@
+pub mod app_error;
+pub use app_error::*;
+
+pub mod field_error;
+pub use field_error::*;
+
+pub mod error_code;
+pub use error_code::*;
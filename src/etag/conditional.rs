@@ -0,0 +1,60 @@
+use actix_web::{
+    http::header::{CacheControl, CacheDirective, ETag, EntityTag, Header, IfNoneMatch},
+    HttpRequest, HttpResponse,
+};
+use serde::Serialize;
+
+/// Responses only the calling user should see that change as friends post.
+/// Clients may keep them, but check them with `If-None-Match` before each use.
+pub const PRIVATE_REVALIDATE: &[CacheDirective] =
+    &[CacheDirective::Private, CacheDirective::NoCache];
+
+/// Answers a GET whose ETag is known before its response is built.
+pub struct Conditional {
+    etag: EntityTag,
+    cache_control: &'static [CacheDirective],
+    not_modified: bool,
+}
+
+impl Conditional {
+    pub fn new(
+        request: &HttpRequest,
+        etag: EntityTag,
+        cache_control: &'static [CacheDirective],
+    ) -> Conditional {
+        // If-None-Match uses the weak comparison, and a header that doesn't parse matches nothing.
+        let not_modified = match IfNoneMatch::parse(request) {
+            Ok(IfNoneMatch::Any) => true,
+            Ok(IfNoneMatch::Items(etags)) => etags.iter().any(|other| other.weak_eq(&etag)),
+            Err(_) => false,
+        };
+
+        Conditional {
+            etag,
+            cache_control,
+            not_modified,
+        }
+    }
+
+    /// Whether the client already has this version, so `not_modified` can be sent instead of building the response.
+    pub fn is_not_modified(&self) -> bool {
+        self.not_modified
+    }
+
+    pub fn not_modified(&self) -> HttpResponse {
+        HttpResponse::NotModified()
+            .insert_header(ETag(self.etag.clone()))
+            .insert_header(CacheControl(self.cache_control.to_vec()))
+            .finish()
+    }
+
+    pub fn ok<T>(&self, body: &T) -> HttpResponse
+    where
+        T: Serialize,
+    {
+        HttpResponse::Ok()
+            .insert_header(ETag(self.etag.clone()))
+            .insert_header(CacheControl(self.cache_control.to_vec()))
+            .json(body)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod version_tag;
+pub use version_tag::*;
+
+pub mod conditional;
+pub use conditional::*;
@@ -0,0 +1,40 @@
+use actix_web::{http::header::EntityTag, HttpRequest};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+/// Builds a strong ETag from the versions of the data a response is made from,
+/// so a client's copy can be checked before the response itself is built.
+///
+/// Read the versions before the data they cover. A write landing in between then only
+/// makes the next request miss, rather than pairing the old tag with new data.
+pub struct VersionTag {
+    hasher: DefaultHasher,
+}
+
+impl VersionTag {
+    /// Responses differ by user and by query, such as the page and `fields`, so both go into every tag.
+    pub fn new(request: &HttpRequest, user_id: &str) -> VersionTag {
+        let mut hasher = DefaultHasher::new();
+        request.path().hash(&mut hasher);
+        request.query_string().hash(&mut hasher);
+        user_id.hash(&mut hasher);
+
+        VersionTag { hasher }
+    }
+
+    pub fn add<T>(mut self, version: &T) -> VersionTag
+    where
+        T: Hash + ?Sized,
+    {
+        version.hash(&mut self.hasher);
+        self
+    }
+
+    /// `DefaultHasher` is only stable within a build, so tags change across deploys.
+    /// That costs clients one full response each, never a stale one.
+    pub fn finish(self) -> EntityTag {
+        EntityTag::new_strong(format!("{:016x}", self.hasher.finish()))
+    }
+}
@@ -0,0 +1,8 @@
+pub mod validate;
+pub use validate::*;
+
+pub mod valid_json;
+pub use valid_json::*;
+
+pub mod valid_query;
+pub use valid_query::*;
@@ -0,0 +1,46 @@
+use actix_web::{dev::Payload, web::Json, Error, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+
+use super::Validate;
+use crate::error::AppError;
+
+/// A `Json` body that has passed `Validate`. Requests that don't are rejected with field-level errors.
+pub struct ValidJson<T>(pub T);
+
+impl<T> ValidJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for ValidJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = Json::<T>::from_request(request, payload);
+
+        Box::pin(async move {
+            let json = json.await?;
+
+            if let Err(fields) = json.validate() {
+                return Err(AppError::invalid_fields(fields).into());
+            }
+
+            Ok(ValidJson(json.into_inner()))
+        })
+    }
+}
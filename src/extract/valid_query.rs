@@ -0,0 +1,47 @@
+use actix_web::{dev::Payload, web::Query, Error, FromRequest, HttpRequest};
+use serde::de::DeserializeOwned;
+use std::{
+    future::{ready, Ready},
+    ops::Deref,
+};
+
+use super::Validate;
+use crate::error::AppError;
+
+/// A `Query` that has passed `Validate`. Requests that don't are rejected with field-level errors.
+pub struct ValidQuery<T>(pub T);
+
+impl<T> ValidQuery<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let query = match Query::<T>::from_request(request, payload).into_inner() {
+            Ok(query) => query,
+            Err(error) => return ready(Err(error)),
+        };
+
+        if let Err(fields) = query.validate() {
+            return ready(Err(AppError::invalid_fields(fields).into()));
+        }
+
+        ready(Ok(ValidQuery(query.into_inner())))
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::FieldError;
+
+/// Implemented by request types so `ValidJson` and `ValidQuery` can reject them before the handler runs.
+/// Every field is checked, so clients get all the problems with a request at once.
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+/// Collects field errors from the `validation` crate checks.
+#[derive(Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn new() -> FieldErrors {
+        FieldErrors::default()
+    }
+
+    /// Records the check against `field` if it failed.
+    pub fn check<T>(&mut self, field: &str, result: Result<T, String>) {
+        if let Err(message) = result {
+            self.0.push(FieldError {
+                field: field.to_string(),
+                message,
+            });
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        return Err(self.0);
+    }
+}
@@ -1,31 +1,42 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{accept_friend_request, get_acceptable_friend_requests},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AcceptRequest {
     request_id: String,
 }
 
 /// Allows users to accept an incoming friend request.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "request was accepted"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/accept_friend")]
 pub async fn accept_friend(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    friend_repo: Data<dyn FriendRepo>,
     accept_request: Query<AcceptRequest>,
-) -> Result<impl Responder> {
-    let friend_requests_res =
-        get_acceptable_friend_requests(&pool, &authenticated_user.0.clone()).await;
+) -> Result<impl Responder, AppError> {
+    let friend_requests_res = friend_repo
+        .get_acceptable_friend_requests(&authenticated_user.0.clone())
+        .await;
 
     match friend_requests_res {
         Ok(friend_requests) => {
@@ -35,27 +46,29 @@ pub async fn accept_friend(
 
             match request_opt {
                 Some(request) => {
-                    let accept_res = accept_friend_request(
-                        &pool,
-                        &&authenticated_user.0.clone(),
-                        &request.user_id,
-                    )
-                    .await;
+                    let accept_res = friend_repo
+                        .accept_friend_request(&&authenticated_user.0.clone(), &request.user_id)
+                        .await;
 
                     match accept_res {
                         Ok(_) => Ok(HttpResponse::Ok()),
-                        Err(_) => Err(ErrorInternalServerError("failed accepting friend request")),
+                        Err(error) => {
+                            Err(AppError::internal("failed accepting friend request", error))
+                        }
                     }
                 }
                 None => {
-                    return Err(ErrorBadRequest("friend request doesnt exist"));
+                    return Err(AppError::bad_request(
+                        ErrorCode::FriendRequestNotFound,
+                        "friend request doesnt exist",
+                    ));
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch incoming friend requests",
+                error,
             ));
         }
     }
@@ -2,66 +2,85 @@ use std::sync::Mutex;
 
 use crate::{
     authorization::AuthenticatedUser,
-    db::{
-        create_friend_request, does_user_exist, get_current_friends, get_outgoing_friend_requests,
-        get_user,
-    },
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
     notifications_v1::{
         enqueue_notification, NotificationQueue, NotificationQueueItem, NotificationType,
     },
-    tracing::add_error_span,
+    repo::{FriendRepo, UserRepo},
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SendRequest {
     friend_id: String,
 }
 
 /// Allows users to send a friend request to another user.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "request was sent"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/add_friend")]
 pub async fn add_friend(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    friend_repo: Data<dyn FriendRepo>,
     apn_queue: Data<Mutex<NotificationQueue>>,
     send_request: Query<SendRequest>,
-) -> Result<impl Responder> {
+) -> Result<impl Responder, AppError> {
     if &authenticated_user.0 == &send_request.friend_id {
-        return Err(ErrorBadRequest("you cant add yourself"));
+        return Err(AppError::bad_request(
+            ErrorCode::SelfFriend,
+            "you cant add yourself",
+        ));
     }
 
-    let exists_res = does_user_exist(&pool, &send_request.friend_id).await;
+    let exists_res = user_repo.does_user_exist(&send_request.friend_id).await;
     match exists_res {
         Ok(exists) => {
             if !exists {
-                return Err(ErrorBadRequest("no user exists with that id"));
+                return Err(AppError::bad_request(
+                    ErrorCode::UserNotFound,
+                    "no user exists with that id",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorBadRequest("unable to get user"));
+            return Err(AppError::internal("unable to get user", error));
         }
     }
 
-    let existing_requests_res =
-        get_outgoing_friend_requests(&pool, &authenticated_user.0.clone()).await;
+    let existing_requests_res = friend_repo
+        .get_outgoing_friend_requests(&authenticated_user.0.clone())
+        .await;
     match existing_requests_res {
         Ok(existing_requests) => {
             if existing_requests
                 .into_iter()
                 .any(|er| -> bool { &er.friend_id == &send_request.friend_id })
             {
-                return Err(ErrorBadRequest("friend request already sent"));
+                return Err(AppError::bad_request(
+                    ErrorCode::FriendRequestExists,
+                    "friend request already sent",
+                ));
             }
 
-            let friends_res = get_current_friends(&pool, &authenticated_user.0.clone()).await;
+            let friends_res = friend_repo
+                .get_current_friends(&authenticated_user.0.clone())
+                .await;
 
             match friends_res {
                 Ok(friends) => {
@@ -69,26 +88,29 @@ pub async fn add_friend(
                         .into_iter()
                         .any(|f| -> bool { &f.friend_id == &send_request.friend_id })
                     {
-                        return Err(ErrorBadRequest("already friends"));
+                        return Err(AppError::bad_request(
+                            ErrorCode::AlreadyFriends,
+                            "already friends",
+                        ));
                     }
 
-                    let create_res = create_friend_request(
-                        &pool,
-                        &authenticated_user.0.clone().as_str(),
-                        &send_request.friend_id,
-                    )
-                    .await;
+                    let create_res = friend_repo
+                        .create_friend_request(
+                            &authenticated_user.0.clone().as_str(),
+                            &send_request.friend_id,
+                        )
+                        .await;
 
                     match create_res {
                         Ok(_) => {
-                            let user_res = get_user(&pool, &send_request.friend_id).await;
+                            let user_res = user_repo.get_user(&send_request.friend_id).await;
 
                             // Best effort sending the notification through apple sevices.
                             match user_res {
                                 Ok(user_opt) => {
                                     if let Some(user) = user_opt {
                                         let calling_user_res =
-                                            get_user(&pool, &authenticated_user.0).await;
+                                            user_repo.get_user(&authenticated_user.0).await;
 
                                         match calling_user_res {
                                             Ok(calling_user_opt) => {
@@ -120,23 +142,22 @@ pub async fn add_friend(
                             return Ok(HttpResponse::Ok());
                         }
                         Err(error) => {
-                            add_error_span(&error);
-                            return Err(ErrorInternalServerError(
+                            return Err(AppError::internal(
                                 "could not create friend request",
+                                error,
                             ));
                         }
                     }
                 }
                 Err(error) => {
-                    add_error_span(&error);
-                    return Err(ErrorInternalServerError("unable to fetch friends"));
+                    return Err(AppError::internal("unable to fetch friends", error));
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "unable to fetch existing requests",
+                error,
             ));
         }
     }
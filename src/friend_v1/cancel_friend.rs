@@ -1,31 +1,42 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{cancel_friend_request, get_outgoing_friend_requests},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CancelRequest {
     request_id: String,
 }
 
 /// Allows users to cancel their already sent friend request.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "request was cancelled"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/cancel_friend")]
 pub async fn cancel_friend(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    friend_repo: Data<dyn FriendRepo>,
     cancel_request: Query<CancelRequest>,
-) -> Result<impl Responder> {
-    let friend_requests_res =
-        get_outgoing_friend_requests(&pool, &authenticated_user.0.clone()).await;
+) -> Result<impl Responder, AppError> {
+    let friend_requests_res = friend_repo
+        .get_outgoing_friend_requests(&authenticated_user.0.clone())
+        .await;
 
     match friend_requests_res {
         Ok(friend_requests) => {
@@ -34,28 +45,31 @@ pub async fn cancel_friend(
             });
 
             if friend_request_exists {
-                let cancel_res = cancel_friend_request(
-                    &pool,
-                    &cancel_request.request_id,
-                    &&authenticated_user.0.clone(),
-                )
-                .await;
+                let cancel_res = friend_repo
+                    .cancel_friend_request(
+                        &cancel_request.request_id,
+                        &&authenticated_user.0.clone(),
+                    )
+                    .await;
 
                 match cancel_res {
                     Ok(_) => Ok(HttpResponse::Ok()),
-                    Err(error) => {
-                        add_error_span(&error);
-                        Err(ErrorInternalServerError("failed cancelling friend request"))
-                    }
+                    Err(error) => Err(AppError::internal(
+                        "failed cancelling friend request",
+                        error,
+                    )),
                 }
             } else {
-                return Err(ErrorBadRequest("friend request doesnt exist"));
+                return Err(AppError::bad_request(
+                    ErrorCode::FriendRequestNotFound,
+                    "friend request doesnt exist",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch incoming friend requests",
+                error,
             ));
         }
     }
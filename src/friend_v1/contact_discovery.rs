@@ -1,9 +1,8 @@
 use crate::{
-    authorization::AuthenticatedUser, db::phone_number_discovery, tracing::add_error_span,
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::UserRepo,
     user_v1::UserPub,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, Json, ReqData},
     Responder, Result,
@@ -11,21 +10,30 @@ use actix_web::{
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::ToSchema;
 use validation::validate_phone;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct DiscoveryRequest {
     numbers: Vec<String>,
 }
 
 /// Allows users to discover friends using the app from sharing their contacts.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "users matching the given numbers", body = [UserPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/discover_friends")]
 pub async fn discover_friends(
     _: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
     disco_request: Json<DiscoveryRequest>,
-) -> Result<impl Responder> {
+) -> Result<impl Responder, AppError> {
     let input_numbers: Vec<&str> = disco_request
         .numbers
         .iter()
@@ -38,7 +46,7 @@ pub async fn discover_friends(
         return Ok(Json(Vec::<UserPub>::new()));
     }
 
-    let discovery_query_res = phone_number_discovery(&pool, &input_numbers).await;
+    let discovery_query_res = user_repo.phone_number_discovery(&input_numbers).await;
 
     match discovery_query_res {
         Ok(discovery_results) => {
@@ -58,8 +66,7 @@ pub async fn discover_friends(
             return Ok(Json(output));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to discover friends"));
+            return Err(AppError::internal("failed to discover friends", error));
         }
     }
 }
@@ -1,8 +1,9 @@
 use crate::db::{Friend, FriendRequest};
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// All in one return type for fetching all friend information.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FullFriendsPub {
     pub friends: Vec<FriendPub>,
     pub incoming_requests: Vec<FriendRequestPub>,
@@ -13,7 +14,7 @@ pub struct FullFriendsPub {
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FriendPub {
     pub id: String,
     pub created: i64,
@@ -35,7 +36,7 @@ impl From<Friend> for FriendPub {
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FriendRequestPub {
     pub id: String,
     pub created: i64,
@@ -1,28 +1,63 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{
-        get_current_friends, get_incoming_friend_requests, get_incoming_ignored_friend_requests,
-        get_outgoing_friend_requests,
-    },
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::AppError,
+    etag::{Conditional, VersionTag, PRIVATE_REVALIDATE},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, ReqData},
-    Responder, Result,
+    web::{Data, ReqData},
+    HttpRequest, Responder, Result,
 };
-use sqlx::MySqlPool;
 
 use super::{friend_types::FriendPub, FriendRequestPub, FullFriendsPub};
 
 /// Allows a user to get their friends list.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "friends and every pending request", body = FullFriendsPub),
+        (status = 304, description = "unchanged since the version in `If-None-Match`"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/full_friends")]
 pub async fn full_friends(
+    request: HttpRequest,
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let friends_res = get_current_friends(&pool, &authenticated_user.0).await;
+    friend_repo: Data<dyn FriendRepo>,
+) -> Result<impl Responder, AppError> {
+    let version_res = friend_repo.get_friends_version(&authenticated_user.0).await;
+
+    let conditional: Conditional;
+    match version_res {
+        Ok(version) => {
+            let etag = VersionTag::new(&request, &authenticated_user.0)
+                .add(&version)
+                .finish();
+            conditional = Conditional::new(&request, etag, PRIVATE_REVALIDATE);
+        }
+        Err(error) => return Err(AppError::internal("could not get friends version", error)),
+    }
+
+    if conditional.is_not_modified() {
+        return Ok(conditional.not_modified());
+    }
+
+    let full_friends = gather_full_friends(friend_repo.get_ref(), &authenticated_user.0).await?;
+
+    Ok(conditional.ok(&full_friends))
+}
+
+/// Friends and every pending request of the user, as `full_friends` returns them.
+pub async fn gather_full_friends(
+    friend_repo: &dyn FriendRepo,
+    user_id: &str,
+) -> Result<FullFriendsPub, AppError> {
+    let friends_res = friend_repo.get_current_friends(user_id).await;
 
     let friends: Vec<FriendPub>;
     match friends_res {
@@ -32,11 +67,12 @@ pub async fn full_friends(
                 .map(|f| -> FriendPub { f.into() })
                 .collect();
         }
-        Err(_) => return Err(ErrorInternalServerError("could not get friends")),
+        Err(error) => return Err(AppError::internal("could not get friends", error)),
     }
 
-    let ignored_requests_res =
-        get_incoming_ignored_friend_requests(&pool, &authenticated_user.0).await;
+    let ignored_requests_res = friend_repo
+        .get_incoming_ignored_friend_requests(user_id)
+        .await;
 
     let ignored_requests: Vec<FriendRequestPub>;
     match ignored_requests_res {
@@ -47,14 +83,14 @@ pub async fn full_friends(
                 .collect();
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch incoming ignored friend requests",
+                error,
             ));
         }
     }
 
-    let incoming_requests_res = get_incoming_friend_requests(&pool, &authenticated_user.0).await;
+    let incoming_requests_res = friend_repo.get_incoming_friend_requests(user_id).await;
 
     let incoming_requests: Vec<FriendRequestPub>;
     match incoming_requests_res {
@@ -65,14 +101,14 @@ pub async fn full_friends(
                 .collect();
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch incoming friend requests",
+                error,
             ));
         }
     }
 
-    let outgoing_requests_res = get_outgoing_friend_requests(&pool, &authenticated_user.0).await;
+    let outgoing_requests_res = friend_repo.get_outgoing_friend_requests(user_id).await;
 
     let outgoing_requests: Vec<FriendRequestPub>;
     match outgoing_requests_res {
@@ -83,17 +119,17 @@ pub async fn full_friends(
                 .collect();
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch outgoing friend requests",
+                error,
             ));
         }
     }
 
-    Ok(Json(FullFriendsPub {
+    return Ok(FullFriendsPub {
         friends,
         incoming_requests,
         outgoing_requests,
         ignored_requests,
-    }))
+    });
 }
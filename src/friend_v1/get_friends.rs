@@ -1,21 +1,30 @@
-use crate::{authorization::AuthenticatedUser, db::get_current_friends};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::FriendRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, ReqData},
     Responder, Result,
 };
-use sqlx::MySqlPool;
 
 use super::friend_types::FriendPub;
 
 /// Allows a user to get their friends list.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "friends of the calling user", body = [FriendPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("")]
 pub async fn get_friends(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let friends_res = get_current_friends(&pool, &authenticated_user.0).await;
+    friend_repo: Data<dyn FriendRepo>,
+) -> Result<impl Responder, AppError> {
+    let friends_res = friend_repo.get_current_friends(&authenticated_user.0).await;
 
     match friends_res {
         Ok(friends) => {
@@ -25,6 +34,6 @@ pub async fn get_friends(
                 .collect();
             Ok(Json(friends_pub))
         }
-        Err(_) => return Err(ErrorInternalServerError("could not get friends")),
+        Err(error) => return Err(AppError::internal("could not get friends", error)),
     }
 }
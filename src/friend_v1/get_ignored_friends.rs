@@ -1,22 +1,32 @@
-use crate::{authorization::AuthenticatedUser, db::get_incoming_ignored_friend_requests};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::FriendRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, ReqData},
     Responder, Result,
 };
-use sqlx::MySqlPool;
 
 use super::friend_types::FriendRequestPub;
 
 /// Get friend requests that the user has ignored.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "requests ignored by the calling user", body = [FriendRequestPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/ignored_friends")]
 pub async fn get_ignored_friends(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let friend_requests_res =
-        get_incoming_ignored_friend_requests(&pool, &authenticated_user.0).await;
+    friend_repo: Data<dyn FriendRepo>,
+) -> Result<impl Responder, AppError> {
+    let friend_requests_res = friend_repo
+        .get_incoming_ignored_friend_requests(&authenticated_user.0)
+        .await;
 
     match friend_requests_res {
         Ok(friend_requests) => {
@@ -26,9 +36,10 @@ pub async fn get_ignored_friends(
                 .collect();
             Ok(Json(friend_requests_pub))
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
+        Err(error) => {
+            return Err(AppError::internal(
                 "could not fetch incoming ignored friend requests",
+                error,
             ))
         }
     }
@@ -1,21 +1,32 @@
-use crate::{authorization::AuthenticatedUser, db::get_outgoing_friend_requests};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::FriendRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, ReqData},
     Responder, Result,
 };
-use sqlx::MySqlPool;
 
 use super::friend_types::FriendRequestPub;
 
 /// Gets a users outgoing friend request.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "requests sent by the calling user", body = [FriendRequestPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/outgoing_friends")]
 pub async fn get_outgoing_friends(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let friend_requests_res = get_outgoing_friend_requests(&pool, &authenticated_user.0).await;
+    friend_repo: Data<dyn FriendRepo>,
+) -> Result<impl Responder, AppError> {
+    let friend_requests_res = friend_repo
+        .get_outgoing_friend_requests(&authenticated_user.0)
+        .await;
 
     match friend_requests_res {
         Ok(friend_requests) => {
@@ -25,9 +36,10 @@ pub async fn get_outgoing_friends(
                 .collect();
             Ok(Json(friend_requests_pub))
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
+        Err(error) => {
+            return Err(AppError::internal(
                 "could not fetch outgoing friend requests",
+                error,
             ))
         }
     }
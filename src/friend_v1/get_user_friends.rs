@@ -1,36 +1,50 @@
 use super::friend_types::FriendPub;
 use crate::{
     authorization::AuthenticatedUser,
-    db::{are_users_friends, get_current_friends},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetUserFriendRequest {
     user_id: String,
 }
 
 /// Allows a user to get a friends list of a user they are friends with.
+#[utoipa::path(
+    context_path = "/api/v1/friends/",
+    tag = "friends",
+    responses(
+        (status = 200, description = "friends of the given user", body = [FriendPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("user")]
 pub async fn get_user_friends(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    friend_repo: Data<dyn FriendRepo>,
     user_friends_query: Query<GetUserFriendRequest>,
-) -> Result<impl Responder> {
-    let are_friends_res =
-        are_users_friends(&pool, &authenticated_user.0, &user_friends_query.user_id).await;
+) -> Result<impl Responder, AppError> {
+    let are_friends_res = friend_repo
+        .are_users_friends(&authenticated_user.0, &user_friends_query.user_id)
+        .await;
 
     match are_friends_res {
         Ok(are_friends) => {
             if are_friends {
-                let friends_res = get_current_friends(&pool, &user_friends_query.user_id).await;
+                let friends_res = friend_repo
+                    .get_current_friends(&user_friends_query.user_id)
+                    .await;
                 match friends_res {
                     Ok(friends) => {
                         let friends_pub: Vec<FriendPub> = friends
@@ -39,15 +53,19 @@ pub async fn get_user_friends(
                             .collect();
                         Ok(Json(friends_pub))
                     }
-                    Err(_) => return Err(ErrorInternalServerError("could not get friends")),
+                    Err(error) => return Err(AppError::internal("could not get friends", error)),
                 }
             } else {
-                return Err(ErrorBadRequest("you are not friends with this user"));
+                return Err(AppError::bad_request(
+                    ErrorCode::NotFriends,
+                    "you are not friends with this user",
+                ));
             }
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
+        Err(error) => {
+            return Err(AppError::internal(
                 "failed to validate if user is friend",
+                error,
             ))
         }
     }
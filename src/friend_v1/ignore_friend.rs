@@ -1,32 +1,43 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_incoming_friend_requests, ignore_friend_request},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct IgnoreRequest {
     request_id: String,
 }
 
 /// Allows you to ignore a friend request.
 /// The sending user won't be able to send another.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "request was ignored"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/ignore_friend")]
 pub async fn ignore_friend(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    friend_repo: Data<dyn FriendRepo>,
     ignore_request: Query<IgnoreRequest>,
-) -> Result<impl Responder> {
-    let friend_requests_res =
-        get_incoming_friend_requests(&pool, &authenticated_user.0.clone()).await;
+) -> Result<impl Responder, AppError> {
+    let friend_requests_res = friend_repo
+        .get_incoming_friend_requests(&authenticated_user.0.clone())
+        .await;
 
     match friend_requests_res {
         Ok(friend_requests) => {
@@ -35,28 +46,28 @@ pub async fn ignore_friend(
             });
 
             if friend_request_exists {
-                let ignore_res = ignore_friend_request(
-                    &pool,
-                    &ignore_request.request_id,
-                    &&authenticated_user.0.clone(),
-                )
-                .await;
+                let ignore_res = friend_repo
+                    .ignore_friend_request(
+                        &ignore_request.request_id,
+                        &&authenticated_user.0.clone(),
+                    )
+                    .await;
 
                 match ignore_res {
                     Ok(_) => Ok(HttpResponse::Ok()),
-                    Err(error) => {
-                        add_error_span(&error);
-                        Err(ErrorInternalServerError("failed ignoring friend request"))
-                    }
+                    Err(error) => Err(AppError::internal("failed ignoring friend request", error)),
                 }
             } else {
-                return Err(ErrorBadRequest("friend request doesnt exist"));
+                return Err(AppError::bad_request(
+                    ErrorCode::FriendRequestNotFound,
+                    "friend request doesnt exist",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "could not fetch incoming friend requests",
+                error,
             ));
         }
     }
@@ -1,37 +1,52 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_current_friends, remove_current_friend},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::FriendRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RemoveRequest {
     friend_id: String,
 }
 
 /// Removes a friend from a users friendlist.
+#[utoipa::path(
+    context_path = "/api/v1/friends",
+    tag = "friends",
+    responses(
+        (status = 200, description = "friend was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/remove")]
 pub async fn remove_friend(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    friend_repo: Data<dyn FriendRepo>,
     remove_request: Query<RemoveRequest>,
-) -> Result<impl Responder> {
+) -> Result<impl Responder, AppError> {
     if authenticated_user
         .0
         .eq_ignore_ascii_case(&remove_request.friend_id)
     {
-        return Err(ErrorBadRequest("you cannot unfriend yourself"));
+        return Err(AppError::bad_request(
+            ErrorCode::SelfFriend,
+            "you cannot unfriend yourself",
+        ));
     }
 
-    let friends_res = get_current_friends(&pool, &authenticated_user.0.clone()).await;
+    let friends_res = friend_repo
+        .get_current_friends(&authenticated_user.0.clone())
+        .await;
 
     match friends_res {
         Ok(friends) => {
@@ -40,27 +55,26 @@ pub async fn remove_friend(
             });
 
             if friend_exists {
-                let remove_res = remove_current_friend(
-                    &pool,
-                    &&authenticated_user.0.clone(),
-                    &remove_request.friend_id,
-                )
-                .await;
+                let remove_res = friend_repo
+                    .remove_current_friend(
+                        &&authenticated_user.0.clone(),
+                        &remove_request.friend_id,
+                    )
+                    .await;
 
                 match remove_res {
                     Ok(_) => Ok(HttpResponse::Ok()),
-                    Err(error) => {
-                        add_error_span(&error);
-                        Err(ErrorInternalServerError("failed removing friend"))
-                    }
+                    Err(error) => Err(AppError::internal("failed removing friend", error)),
                 }
             } else {
-                return Err(ErrorBadRequest("friend doesnt exist"));
+                return Err(AppError::bad_request(
+                    ErrorCode::FriendNotFound,
+                    "friend doesnt exist",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("could not fetch friends"));
+            return Err(AppError::internal("could not fetch friends", error));
         }
     }
 }
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// The `Idempotency-Key` header taken by the handlers wrapped in `Idempotent`.
+/// Only here to document it, the middleware reads the header itself.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Header)]
+pub struct IdempotencyKeyHeader {
+    /// Any unique value up to 255 characters, a uuid per write is typical.
+    /// Retries with the same key get the first response back, marked `idempotency-replayed: true`.
+    #[serde(rename = "Idempotency-Key")]
+    pub idempotency_key: Option<String>,
+}
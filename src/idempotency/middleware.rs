@@ -0,0 +1,497 @@
+use actix_web::{
+    body::{self, BoxBody, MessageBody},
+    dev::{self, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    error::PayloadError,
+    http::{
+        header::{HeaderMap, CONNECTION, CONTENT_LENGTH, TRANSFER_ENCODING},
+        Method, StatusCode,
+    },
+    web::{Bytes, Data},
+    Error, HttpMessage, HttpResponse, ResponseError,
+};
+use chrono::{Duration, Utc};
+use futures_util::{future::LocalBoxFuture, stream, Stream};
+use sha2::{Digest, Sha256};
+use std::{
+    future::{ready, Ready},
+    pin::Pin,
+    rc::Rc,
+};
+use uuid::Uuid;
+
+use crate::{
+    authorization::AuthenticatedUser,
+    db::IdempotencyKey,
+    error::{AppError, ErrorCode},
+    repo::IdempotencyRepo,
+    tracing::add_error_span,
+};
+
+/// Header a client sets to make a write safe to retry. Any unique value works, a uuid per write is typical.
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Set on a stored response sent back to a retry, rather than one from running the request.
+pub const IDEMPOTENCY_REPLAYED: &str = "idempotency-replayed";
+
+/// How long a key and its response are kept. Retries after this run the request again.
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// How long a key can stay claimed without a response before the next request with it takes it over.
+/// Covers a process that died or a request that was dropped mid-flight, which never complete or release their key.
+/// Well past how long any wrapped handler runs.
+pub const IDEMPOTENCY_KEY_LEASE_SECONDS: i64 = 60;
+
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+pub struct Idempotent;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotent
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IdempotentMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotentMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Makes authenticated POSTs sent with an `Idempotency-Key` run at most once per key.
+/// The first response is stored and sent back to every retry with the same key for a day,
+/// so a client that lost a response can resend the write without making it twice.
+/// Only wraps the handlers that create something, with `wrap = "Idempotent"` on their route.
+///
+/// The stored response keeps its status, body and every header set inside this middleware.
+/// Headers with values that aren't visible ascii are dropped, as are the ones describing the connection.
+///
+/// A key reused for a different request is rejected with a 409, as is a retry arriving while
+/// the first request is still running. 5XX responses aren't stored, leaving the key free to retry with.
+/// A key left claimed for longer than `IDEMPOTENCY_KEY_LEASE_SECONDS` goes to the next request with it.
+pub struct IdempotentMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotentMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, mut request: ServiceRequest) -> Self::Future {
+        let user_id = request
+            .extensions()
+            .get::<AuthenticatedUser>()
+            .map(|user| user.0.clone());
+
+        let user_id = match user_id {
+            Some(user_id)
+                if request.method() == Method::POST
+                    && request.headers().contains_key(IDEMPOTENCY_KEY) =>
+            {
+                user_id
+            }
+            _ => {
+                // reads are already safe to retry, and anything without a key is left alone.
+                let res = self.service.call(request);
+                return Box::pin(
+                    async move { res.await.map(ServiceResponse::map_into_boxed_body) },
+                );
+            }
+        };
+
+        let key = match parse_idempotency_key(&request) {
+            Ok(key) => key,
+            Err(error) => return Box::pin(async { Ok(reject(request, error)) }),
+        };
+
+        let repo = if let Some(repo) = request.app_data::<Data<dyn IdempotencyRepo>>() {
+            repo.clone()
+        } else {
+            // the repo wasn't registered; something broke that isn't caught compile time
+            let error = AppError::internal_message("idempotency repo is not registered");
+            return Box::pin(async { Ok(reject(request, error)) });
+        };
+
+        let service = self.service.clone();
+        return Box::pin(async move {
+            // the body is read here to fingerprint it, then handed back for the handler to read.
+            let body = request.extract::<Bytes>().await?;
+            let fingerprint = get_fingerprint(&request, &body);
+            request.set_payload(bytes_payload(body));
+
+            let now = Utc::now().naive_utc();
+            let expired_before = now - Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+            let abandoned_before = now - Duration::seconds(IDEMPOTENCY_KEY_LEASE_SECONDS);
+
+            match repo
+                .get_idempotency_key(&user_id, &key, &expired_before)
+                .await
+            {
+                Ok(Some(existing))
+                    if existing.status.is_some() || existing.created >= abandoned_before =>
+                {
+                    return Ok(replay(request, existing, &fingerprint))
+                }
+                // unused, or claimed by a request that never finished and is taken over below.
+                Ok(_) => {}
+                Err(error) => {
+                    let error = AppError::internal("unable to get idempotency key", error);
+                    return Ok(reject(request, error));
+                }
+            }
+
+            let claimed = IdempotencyKey {
+                id: Uuid::new_v4().to_string(),
+                created: now,
+                user_id,
+                idempotency_key: key,
+                fingerprint,
+                status: None,
+                headers: None,
+                body: None,
+            };
+
+            match repo
+                .create_idempotency_key(&claimed, &expired_before, &abandoned_before)
+                .await
+            {
+                Ok(true) => {}
+                // a retry with the same key got in first, and is still running or just finished.
+                Ok(false) => return Ok(reject(request, in_progress())),
+                Err(error) => {
+                    let error = AppError::internal("unable to create idempotency key", error);
+                    return Ok(reject(request, error));
+                }
+            }
+
+            let res = match service.call(request).await {
+                Ok(res) => res,
+                Err(error) => {
+                    release(repo.get_ref(), &claimed.id).await;
+                    return Err(error);
+                }
+            };
+
+            if res.status().is_server_error() {
+                // 5XX are retryable, so the retry should run the request again rather than get this back.
+                release(repo.get_ref(), &claimed.id).await;
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (request, response) = res.into_parts();
+            let (response, body) = response.into_parts();
+            let body = match body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(_) => {
+                    release(repo.get_ref(), &claimed.id).await;
+                    let response = AppError::internal_message("unable to read response to store")
+                        .error_response();
+                    return Ok(ServiceResponse::new(request, response));
+                }
+            };
+
+            let headers = encode_headers(response.headers());
+
+            if let Err(error) = repo
+                .complete_idempotency_key(&claimed.id, response.status().as_u16(), &headers, &body)
+                .await
+            {
+                // the write already happened, so the response is still sent.
+                // a retry runs the request again, just as it would have without a key.
+                add_error_span(&error);
+                release(repo.get_ref(), &claimed.id).await;
+            }
+
+            let response = response.set_body(body).map_into_boxed_body();
+            return Ok(ServiceResponse::new(request, response));
+        });
+    }
+}
+
+fn parse_idempotency_key(request: &ServiceRequest) -> Result<String, AppError> {
+    let key = request
+        .headers()
+        .get(IDEMPOTENCY_KEY)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+        return Err(AppError::bad_request(
+            ErrorCode::InvalidInput,
+            format!(
+                "Idempotency-Key must be 1 to {} visible ascii characters",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            ),
+        ));
+    }
+
+    return Ok(key.to_string());
+}
+
+/// Hex sha256 of everything that makes up the request, so a key reused for a different one is caught.
+fn get_fingerprint(request: &ServiceRequest, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.method().as_str());
+    hasher.update(b"\n");
+    hasher.update(request.path());
+    hasher.update(b"\n");
+    hasher.update(request.query_string());
+    hasher.update(b"\n");
+    hasher.update(body);
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Json `[name, value]` pairs, leaving out the framing headers the server sets again on the replay.
+fn encode_headers(headers: &HeaderMap) -> String {
+    let pairs: Vec<(&str, &str)> = headers
+        .iter()
+        .filter(|(name, _)| {
+            *name != CONTENT_LENGTH && *name != TRANSFER_ENCODING && *name != CONNECTION
+        })
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .collect();
+
+    serde_json::to_string(&pairs).unwrap_or_default()
+}
+
+fn decode_headers(headers: Option<&str>) -> Vec<(String, String)> {
+    headers
+        .and_then(|headers| serde_json::from_str(headers).ok())
+        .unwrap_or_default()
+}
+
+fn bytes_payload(body: Bytes) -> Payload {
+    let stream: Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>> =
+        Box::pin(stream::once(ready(Ok(body))));
+
+    Payload::from(stream)
+}
+
+/// Sends back the stored response, as long as the key was first used for this same request.
+fn replay(
+    request: ServiceRequest,
+    key: IdempotencyKey,
+    fingerprint: &str,
+) -> ServiceResponse<BoxBody> {
+    if key.fingerprint != fingerprint {
+        return reject(
+            request,
+            AppError::conflict(
+                ErrorCode::IdempotencyKeyReused,
+                "Idempotency-Key was already used for a different request, send a new key",
+            ),
+        );
+    }
+
+    let status = match key
+        .status
+        .and_then(|status| StatusCode::from_u16(status).ok())
+    {
+        Some(status) => status,
+        None => return reject(request, in_progress()),
+    };
+
+    let mut response = HttpResponse::build(status);
+    for header in decode_headers(key.headers.as_deref()) {
+        response.append_header(header);
+    }
+    response.insert_header((IDEMPOTENCY_REPLAYED, "true"));
+
+    let (request, _pl) = request.into_parts();
+    return ServiceResponse::new(request, response.body(key.body.unwrap_or_default()));
+}
+
+fn in_progress() -> AppError {
+    AppError::conflict(
+        ErrorCode::IdempotencyKeyInProgress,
+        "a request with this Idempotency-Key is still running, retry shortly",
+    )
+}
+
+fn reject(request: ServiceRequest, error: AppError) -> ServiceResponse<BoxBody> {
+    let (request, _pl) = request.into_parts();
+    ServiceResponse::new(request, error.error_response())
+}
+
+/// Frees the key so a retry runs the request again. A failure only means retries see it as in progress until it expires.
+async fn release(repo: &dyn IdempotencyRepo, id: &str) {
+    if let Err(error) = repo.remove_idempotency_key(id).await {
+        add_error_span(&error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{dev::HttpServiceFactory, http::StatusCode, test, web, App, HttpResponse};
+    use chrono::{Duration, Utc};
+    use serde_json::Value;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::{
+        get_fingerprint, Idempotent, IDEMPOTENCY_KEY, IDEMPOTENCY_KEY_LEASE_SECONDS,
+        IDEMPOTENCY_REPLAYED,
+    };
+    use crate::{
+        db::IdempotencyKey,
+        repo::{configure_repos, IdempotencyRepo, MemoryRepo},
+        test_support::as_user,
+    };
+
+    /// A wrapped write that counts how often it really runs.
+    fn write_service(calls: Arc<AtomicUsize>) -> impl HttpServiceFactory {
+        web::resource("/write")
+            .wrap(Idempotent)
+            .route(web::post().to(move |body: web::Bytes| {
+                let calls = calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    HttpResponse::Created()
+                        .insert_header(("x-write", calls.to_string()))
+                        .body(body)
+                }
+            }))
+    }
+
+    fn write(key: &str, body: &'static str) -> test::TestRequest {
+        test::TestRequest::post()
+            .uri("/write")
+            .insert_header((IDEMPOTENCY_KEY, key))
+            .set_payload(body)
+    }
+
+    /// Claims `key` for a `write(key, body)` as if it started `age` ago and is yet to finish.
+    async fn claim(repo: &MemoryRepo, key: &str, body: &'static str, age: Duration) {
+        let request = test::TestRequest::post().uri("/write").to_srv_request();
+        let now = Utc::now().naive_utc();
+        let claimed = IdempotencyKey {
+            id: key.to_string(),
+            created: now - age,
+            user_id: "alice".to_string(),
+            idempotency_key: key.to_string(),
+            fingerprint: get_fingerprint(&request, body.as_bytes()),
+            status: None,
+            headers: None,
+            body: None,
+        };
+
+        assert!(repo
+            .create_idempotency_key(&claimed, &(now - Duration::days(1)), &(now - age))
+            .await
+            .unwrap());
+    }
+
+    #[actix_web::test]
+    async fn retry_replays_the_first_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = test::init_service(
+            App::new()
+                .configure(configure_repos(Arc::new(MemoryRepo::new())))
+                .service(write_service(calls.clone())),
+        )
+        .await;
+
+        let first =
+            test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(IDEMPOTENCY_REPLAYED).is_none());
+
+        let retry =
+            test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers().get(IDEMPOTENCY_REPLAYED).unwrap(), "true");
+        assert_eq!(retry.headers().get("x-write").unwrap(), "0");
+        assert_eq!(test::read_body(retry).await, "hello");
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn key_reused_for_a_different_body_conflicts() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = test::init_service(
+            App::new()
+                .configure(configure_repos(Arc::new(MemoryRepo::new())))
+                .service(write_service(calls.clone())),
+        )
+        .await;
+
+        test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        let reused =
+            test::call_service(&app, as_user(write("key", "goodbye").to_request(), "alice")).await;
+        assert_eq!(reused.status(), StatusCode::CONFLICT);
+
+        let body: Value = test::read_body_json(reused).await;
+        assert_eq!(body["code"], "idempotency_key_reused");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn running_key_conflicts() {
+        let repo = Arc::new(MemoryRepo::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        claim(&repo, "key", "hello", Duration::seconds(1)).await;
+        let app = test::init_service(
+            App::new()
+                .configure(configure_repos(repo))
+                .service(write_service(calls.clone())),
+        )
+        .await;
+
+        let retry =
+            test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        assert_eq!(retry.status(), StatusCode::CONFLICT);
+
+        let body: Value = test::read_body_json(retry).await;
+        assert_eq!(body["code"], "idempotency_key_in_progress");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[actix_web::test]
+    async fn abandoned_key_is_taken_over() {
+        let repo = Arc::new(MemoryRepo::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        claim(
+            &repo,
+            "key",
+            "hello",
+            Duration::seconds(IDEMPOTENCY_KEY_LEASE_SECONDS + 1),
+        )
+        .await;
+        let app = test::init_service(
+            App::new()
+                .configure(configure_repos(repo))
+                .service(write_service(calls.clone())),
+        )
+        .await;
+
+        let retry =
+            test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert!(retry.headers().get(IDEMPOTENCY_REPLAYED).is_none());
+
+        let replayed =
+            test::call_service(&app, as_user(write("key", "hello").to_request(), "alice")).await;
+        assert_eq!(
+            replayed.headers().get(IDEMPOTENCY_REPLAYED).unwrap(),
+            "true"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod idempotency_types;
+pub use idempotency_types::*;
+
+pub mod middleware;
+pub use middleware::*;
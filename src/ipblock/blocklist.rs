@@ -0,0 +1,135 @@
+use crate::{client_ip::parse_ip_net, db::IpBlock};
+use chrono::NaiveDateTime;
+use ipnet::IpNet;
+use std::net::IpAddr;
+
+use super::GeoInfo;
+
+/// What happens to traffic matching a rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IpBlockAction {
+    /// Reject the request.
+    Deny,
+
+    /// Let the request through, even if a deny rule also matches.
+    Allow,
+}
+
+impl IpBlockAction {
+    pub fn parse(value: &str) -> Option<IpBlockAction> {
+        match value {
+            "deny" => Some(IpBlockAction::Deny),
+            "allow" => Some(IpBlockAction::Allow),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpBlockAction::Deny => "deny",
+            IpBlockAction::Allow => "allow",
+        }
+    }
+}
+
+/// What traffic a rule matches.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IpBlockTarget {
+    Network(IpNet),
+    Asn(u32),
+    Country(String),
+}
+
+impl IpBlockTarget {
+    fn matches(&self, ip: &IpAddr, geo: &GeoInfo) -> bool {
+        match self {
+            IpBlockTarget::Network(net) => net.contains(ip),
+            IpBlockTarget::Asn(asn) => geo.asn == Some(*asn),
+            IpBlockTarget::Country(country) => geo.country.as_deref() == Some(country.as_str()),
+        }
+    }
+}
+
+/// A parsed ipblock record, ready to be matched against requests.
+#[derive(Debug, Clone)]
+pub struct IpBlockRule {
+    pub id: String,
+    pub action: IpBlockAction,
+    pub target: IpBlockTarget,
+    pub reason: String,
+    pub expires: Option<NaiveDateTime>,
+}
+
+impl IpBlockRule {
+    /// Parses a db record, returning `None` if the record is malformed.
+    pub fn from_db(ipblock: &IpBlock) -> Option<IpBlockRule> {
+        let target = match (&ipblock.cidr, ipblock.asn, &ipblock.country) {
+            (Some(cidr), None, None) => IpBlockTarget::Network(parse_ip_net(cidr)?),
+            (None, Some(asn), None) => IpBlockTarget::Asn(asn),
+            (None, None, Some(country)) => IpBlockTarget::Country(country.to_uppercase()),
+            _ => return None,
+        };
+
+        Some(IpBlockRule {
+            id: ipblock.id.clone(),
+            action: IpBlockAction::parse(&ipblock.action)?,
+            target,
+            reason: ipblock.reason.clone(),
+            expires: ipblock.expires,
+        })
+    }
+
+    fn is_active(&self, now: &NaiveDateTime) -> bool {
+        match &self.expires {
+            Some(expires) => expires > now,
+            None => true,
+        }
+    }
+}
+
+/// Every active rule, checked in memory on each request.
+#[derive(Default)]
+pub struct IpBlockList {
+    rules: Vec<IpBlockRule>,
+}
+
+impl IpBlockList {
+    pub fn new(rules: Vec<IpBlockRule>) -> Self {
+        IpBlockList { rules }
+    }
+
+    /// Whether any rule needs asn or country data, so lookups can be skipped when not.
+    pub fn needs_geo(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| !matches!(rule.target, IpBlockTarget::Network(_)))
+    }
+
+    /// Finds the deny rule blocking a request, if any.
+    /// Allow rules always win, so a trusted address can be carved out of a blocked network.
+    pub fn find_block(
+        &self,
+        ip: &IpAddr,
+        geo: &GeoInfo,
+        now: &NaiveDateTime,
+    ) -> Option<&IpBlockRule> {
+        let matching = self
+            .rules
+            .iter()
+            .filter(|rule| rule.is_active(now) && rule.target.matches(ip, geo));
+
+        let mut block: Option<&IpBlockRule> = None;
+        for rule in matching {
+            match rule.action {
+                IpBlockAction::Allow => return None,
+                IpBlockAction::Deny => {
+                    if block.is_none() {
+                        block = Some(rule);
+                    }
+                }
+            }
+        }
+
+        return block;
+    }
+}
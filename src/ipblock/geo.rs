@@ -0,0 +1,69 @@
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use std::net::IpAddr;
+
+use crate::config::ConfigError;
+
+/// Network details for an ip address, when a geo database is configured.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GeoInfo {
+    /// Autonomous system number the address is announced from.
+    pub asn: Option<u32>,
+
+    /// ISO country code the address is registered in.
+    pub country: Option<String>,
+}
+
+/// Looks up ip addresses in local MaxMind format databases.
+/// Both databases are optional; asn and country rules never match without them.
+pub struct GeoLookup {
+    asn_reader: Option<Reader<Vec<u8>>>,
+    country_reader: Option<Reader<Vec<u8>>>,
+}
+
+impl GeoLookup {
+    /// Loads the databases at the given paths into memory.
+    /// A missing or unreadable database is reported against the config key that named it.
+    pub fn open(asn_path: Option<&str>, country_path: Option<&str>) -> Result<Self, ConfigError> {
+        let asn_reader = match asn_path {
+            Some(path) => Some(open_reader("geoip.asn_db", path)?),
+            None => None,
+        };
+
+        let country_reader = match country_path {
+            Some(path) => Some(open_reader("geoip.country_db", path)?),
+            None => None,
+        };
+
+        return Ok(GeoLookup {
+            asn_reader,
+            country_reader,
+        });
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> GeoInfo {
+        let asn = self.asn_reader.as_ref().and_then(|reader| {
+            reader
+                .lookup::<geoip2::Asn>(ip)
+                .ok()
+                .and_then(|asn| asn.autonomous_system_number)
+        });
+
+        let country = self.country_reader.as_ref().and_then(|reader| {
+            reader
+                .lookup::<geoip2::Country>(ip)
+                .ok()
+                .and_then(|country| country.country)
+                .and_then(|country| country.iso_code)
+                .map(|iso_code| iso_code.to_uppercase())
+        });
+
+        GeoInfo { asn, country }
+    }
+}
+
+fn open_reader(key: &str, path: &str) -> Result<Reader<Vec<u8>>, ConfigError> {
+    Reader::open_readfile(path).map_err(|error: MaxMindDBError| ConfigError::Invalid {
+        key: key.to_string(),
+        reason: format!("unable to open {}: {}", path, error),
+    })
+}
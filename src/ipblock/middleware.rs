@@ -0,0 +1,87 @@
+use actix_web::{
+    body::EitherBody,
+    dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
+    web::Data,
+    Error, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use opentelemetry_api::{
+    trace::{get_active_span, Status},
+    KeyValue,
+};
+use std::future::{ready, Ready};
+
+use super::IpBlockStore;
+use crate::{
+    client_ip::get_client_ip,
+    error::{AppError, ErrorCode},
+};
+
+pub struct IpBlockFilter;
+
+impl<S, B> Transform<S, ServiceRequest> for IpBlockFilter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IpBlockFilterMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IpBlockFilterMiddleware { service }))
+    }
+}
+
+/// Rejects auth requests from blocked ips, networks, and countries before they reach the handlers.
+/// Lets us shut down SMS pumping from a network without a code change.
+pub struct IpBlockFilterMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for IpBlockFilterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        if request.path().starts_with("/auth") {
+            if let Some(store) = request.app_data::<Data<IpBlockStore>>() {
+                let client_ip = get_client_ip(request.request());
+
+                if let Some(rule) = store.find_block(client_ip.0) {
+                    get_active_span(|span| {
+                        span.set_attribute(KeyValue::new("ipblock.id", rule.id.clone()));
+                        span.set_status(Status::error("blocked by ipblock"));
+                    });
+
+                    let (request, _pl) = request.into_parts();
+                    let response = AppError::forbidden(ErrorCode::AccessDenied, "access denied")
+                        .error_response()
+                        .map_into_right_body();
+                    return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
+                }
+            } else {
+                // this means we are an auth route; but something broke that isn't caught compile time
+                let (request, _pl) = request.into_parts();
+                let response = AppError::internal_message("ipblock store is not registered")
+                    .error_response()
+                    .map_into_right_body();
+                return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
+            }
+        }
+
+        let res = self.service.call(request);
+        return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
+    }
+}
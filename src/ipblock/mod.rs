@@ -0,0 +1,11 @@
+pub mod blocklist;
+pub use blocklist::*;
+
+pub mod geo;
+pub use geo::*;
+
+pub mod middleware;
+pub use middleware::*;
+
+pub mod store;
+pub use store::*;
@@ -0,0 +1,74 @@
+use crate::{repo::IpBlockRepo, tracing::add_error_span};
+use actix_web::web::Data;
+use chrono::Utc;
+use sqlx::Error;
+use std::{
+    net::IpAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{task, time};
+
+use super::{GeoLookup, IpBlockList, IpBlockRule};
+
+/// How often rules are reloaded, so changes from other instances are picked up.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// In memory copy of the active ipblock rules.
+/// Requests are checked against this, never the database directly.
+pub struct IpBlockStore {
+    list: RwLock<Arc<IpBlockList>>,
+    geo: GeoLookup,
+}
+
+impl IpBlockStore {
+    pub fn new(geo: GeoLookup) -> Self {
+        IpBlockStore {
+            list: RwLock::new(Arc::new(IpBlockList::default())),
+            geo,
+        }
+    }
+
+    /// Reloads the active rules from the database.
+    /// Malformed records are skipped rather than failing the whole reload.
+    pub async fn refresh(&self, ipblock_repo: &dyn IpBlockRepo) -> Result<(), Error> {
+        let ipblocks = ipblock_repo.get_active_ipblocks().await?;
+
+        let rules: Vec<IpBlockRule> = ipblocks.iter().filter_map(IpBlockRule::from_db).collect();
+
+        if let Ok(mut list) = self.list.write() {
+            *list = Arc::new(IpBlockList::new(rules));
+        }
+
+        return Ok(());
+    }
+
+    /// Gets the deny rule blocking an ip, if any.
+    pub fn find_block(&self, ip: IpAddr) -> Option<IpBlockRule> {
+        let list = match self.list.read() {
+            Ok(list) => list.clone(),
+            Err(_) => return None,
+        };
+
+        let geo = if list.needs_geo() {
+            self.geo.lookup(ip)
+        } else {
+            Default::default()
+        };
+
+        list.find_block(&ip, &geo, &Utc::now().naive_utc()).cloned()
+    }
+}
+
+/// Periodically reloads the ipblock rules in the background.
+pub fn start_ipblock_refresh(store: Data<IpBlockStore>, ipblock_repo: Data<dyn IpBlockRepo>) {
+    task::spawn(async move {
+        loop {
+            if let Err(error) = store.refresh(ipblock_repo.get_ref()).await {
+                add_error_span(&error);
+            }
+
+            time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,38 @@
+//! Everything the API server and `rwf-admin` share.
+//! The binaries only wire these up.
+
+pub mod admin_v1;
+pub mod auth;
+pub mod authorization;
+pub mod bookmark_v1;
+pub mod client_ip;
+pub mod compound_types;
+pub mod config;
+pub mod db;
+pub mod docs;
+pub mod error;
+pub mod etag;
+pub mod extract;
+pub mod friend_v1;
+pub mod idempotency;
+pub mod ipblock;
+pub mod likes_v1;
+pub mod metrics;
+pub mod notifications_v1;
+#[cfg(feature = "postgres")]
+pub mod pg;
+pub mod pic_v1;
+pub mod ping_routes;
+pub mod ratelimit;
+pub mod replica;
+pub mod reply_v1;
+pub mod repo;
+pub mod report_v1;
+pub mod review_v1;
+pub mod sync_v1;
+#[cfg(test)]
+pub mod test_support;
+pub mod tracing;
+pub mod user_v1;
+
+pub use config::Config;
@@ -1,62 +1,104 @@
 use crate::{
     authorization::AuthenticatedUser,
-    compound_types::CompoundReviewPub,
-    db::get_liked_reviews,
-    review_v1::{gather_compound_review, ReviewPub},
-    tracing::add_error_span,
+    compound_types::{
+        CompoundReviewFieldsRequest, PagedCompoundReviewPub, PagedPub, REVIEW_PAGE_LIMITS,
+    },
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo},
+    review_v1::{gather_compound_reviews, ReviewPub},
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetPagedCurrentLikes {
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for GetPagedCurrentLikes {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Gets all the reviews the user has liked.
+#[utoipa::path(
+    context_path = "/api/v1/like",
+    tag = "like",
+    params(GetPagedCurrentLikes, CompoundReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of reviews liked by the calling user with their components", body = PagedCompoundReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/current_full")]
 pub async fn get_current_liked_reviews_full(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    get_paged_current_likes: Query<GetPagedCurrentLikes>,
-) -> Result<impl Responder> {
-    let reviews_res =
-        get_liked_reviews(&pool, &authenticated_user.0, get_paged_current_likes.page).await;
+    pic_repo: Data<dyn PicRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    get_paged_current_likes: ValidQuery<GetPagedCurrentLikes>,
+    fields_request: ValidQuery<CompoundReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = REVIEW_PAGE_LIMITS.page_request(
+        &get_paged_current_likes.cursor,
+        get_paged_current_likes.limit,
+    );
 
-    let mut compound_reviews: Vec<CompoundReviewPub> = vec![];
+    let reviews_res = like_repo
+        .get_liked_reviews(&authenticated_user.0, &page_request)
+        .await;
 
     match reviews_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
 
-            for review_pub in reviews_pub.into_iter() {
-                let compound_review_res =
-                    gather_compound_review(&pool, &authenticated_user.0, review_pub).await;
-
-                match compound_review_res {
-                    Ok(compound_review) => compound_reviews.push(compound_review),
-                    Err(_) => {
-                        return Err(ErrorInternalServerError(
-                            "failed gathering review contents".to_string(),
-                        ))
-                    }
+            let compound_reviews_res = gather_compound_reviews(
+                like_repo.get_ref(),
+                reply_repo.get_ref(),
+                pic_repo.get_ref(),
+                bookmark_repo.get_ref(),
+                &authenticated_user.0,
+                reviews_pub,
+            )
+            .await;
+
+            match compound_reviews_res {
+                Ok(compound_reviews) => {
+                    return Ok(Json(PagedPub::new(
+                        fields_request.sparse_all(compound_reviews),
+                        reviews.next_cursor,
+                    )));
+                }
+                Err(error) => {
+                    return Err(AppError::internal(
+                        "failed gathering review contents",
+                        error,
+                    ));
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get likes".to_string()));
+            return Err(AppError::internal("unable to get likes", error));
         }
     }
-
-    return Ok(Json(compound_reviews));
 }
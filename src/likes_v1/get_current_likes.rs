@@ -1,36 +1,76 @@
-use crate::{authorization::AuthenticatedUser, db::get_liked_reviews, review_v1::ReviewPub};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{PagedPub, PagedReviewPub, ReviewFieldsRequest, REVIEW_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::LikeRepo,
+    review_v1::ReviewPub,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetPagedCurrentLikes {
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for GetPagedCurrentLikes {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Gets all the reviews the user has liked.
+#[utoipa::path(
+    context_path = "/api/v1/like",
+    tag = "like",
+    params(GetPagedCurrentLikes, ReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of reviews liked by the calling user", body = PagedReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/current")]
 pub async fn get_current_likes(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    get_paged_current_likes: Query<GetPagedCurrentLikes>,
-) -> Result<impl Responder> {
-    let reviews_res =
-        get_liked_reviews(&pool, &authenticated_user.0, get_paged_current_likes.page).await;
+    like_repo: Data<dyn LikeRepo>,
+    get_paged_current_likes: ValidQuery<GetPagedCurrentLikes>,
+    fields_request: ValidQuery<ReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = REVIEW_PAGE_LIMITS.page_request(
+        &get_paged_current_likes.cursor,
+        get_paged_current_likes.limit,
+    );
+
+    let reviews_res = like_repo
+        .get_liked_reviews(&authenticated_user.0, &page_request)
+        .await;
 
     match reviews_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
-            return Ok(Json(reviews_pub));
+            return Ok(Json(PagedPub::new(
+                fields_request.sparse_all(reviews_pub),
+                reviews.next_cursor,
+            )));
         }
-        Err(_) => return Err(ErrorInternalServerError("unable to get likes".to_string())),
+        Err(error) => return Err(AppError::internal("unable to get likes", error)),
     }
 }
@@ -1,37 +1,50 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_all_likes, get_review},
+    docs::ErrorResponses,
+    error::AppError,
+    repo::{LikeRepo, ReviewRepo},
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::like_types::LikePub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetUserByIdRequest {
     review_id: String,
 }
 
 /// Gets all the users who have liked a post.
+#[utoipa::path(
+    context_path = "/api/v1/like",
+    tag = "like",
+    responses(
+        (status = 200, description = "likes on the review", body = [LikePub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("")]
 pub async fn get_likes(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    like_repo: Data<dyn LikeRepo>,
     get_user_request: Query<GetUserByIdRequest>,
-) -> Result<impl Responder> {
-    if let Err(_) = get_review(&pool, &authenticated_user.0, &get_user_request.review_id).await {
-        return Err(ErrorInternalServerError(
-            "unable to find review".to_string(),
-        ));
+) -> Result<impl Responder, AppError> {
+    if let Err(_) = review_repo
+        .get_review(&authenticated_user.0, &get_user_request.review_id)
+        .await
+    {
+        return Err(AppError::internal_message("unable to find review"));
     }
 
-    let likes_res = get_all_likes(&pool, &get_user_request.review_id).await;
+    let likes_res = like_repo.get_all_likes(&get_user_request.review_id).await;
 
     match likes_res {
         Ok(likes) => {
@@ -39,6 +52,6 @@ pub async fn get_likes(
                 likes.into_iter().map(|f| -> LikePub { f.into() }).collect();
             return Ok(Json(likes_pub));
         }
-        Err(_) => return Err(ErrorInternalServerError("unable to get likes".to_string())),
+        Err(error) => return Err(AppError::internal("unable to get likes", error)),
     }
 }
@@ -2,37 +2,55 @@ use std::sync::Mutex;
 
 use crate::{
     authorization::AuthenticatedUser,
-    db::{create_like, create_notification, get_review, get_user, is_already_liked, Review},
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    idempotency::{IdempotencyKeyHeader, Idempotent},
     notifications_v1::{
         enqueue_notification, ActionType, NotificationQueue, NotificationQueueItem,
         NotificationType,
     },
-    tracing::add_error_span,
+    repo::{LikeRepo, NotificationRepo, ReviewRepo, UserRepo},
 };
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LikeReviewRequest {
     pub review_id: String,
     pub like_type: Option<i8>,
 }
 
 /// Allows users to like a review.
-#[post("")]
+#[utoipa::path(
+    context_path = "/api/v1/like",
+    tag = "like",
+    params(IdempotencyKeyHeader),
+    responses(
+        (status = 200, description = "review was liked"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("", wrap = "Idempotent")]
 pub async fn like_review(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    review_repo: Data<dyn ReviewRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    notification_repo: Data<dyn NotificationRepo>,
     apn_queue: Data<Mutex<NotificationQueue>>,
     like_review_request: Query<LikeReviewRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_review(&pool, &authenticated_user.0, &like_review_request.review_id).await;
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &like_review_request.review_id)
+        .await;
 
     let review: Review;
     match review_res {
@@ -40,14 +58,18 @@ pub async fn like_review(
             if let Some(review_tmp) = review_opt {
                 review = review_tmp
             } else {
-                return Err(ErrorNotFound("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
-        Err(_) => return Err(ErrorInternalServerError("failed to get review")),
+        Err(error) => return Err(AppError::internal("failed to get review", error)),
     }
 
-    let already_created_res =
-        is_already_liked(&pool, &authenticated_user.0, &like_review_request.review_id).await;
+    let already_created_res = like_repo
+        .is_already_liked(&authenticated_user.0, &like_review_request.review_id)
+        .await;
 
     match already_created_res {
         Ok(is_liked) => {
@@ -55,39 +77,39 @@ pub async fn like_review(
                 return Ok(HttpResponse::Ok().finish());
             }
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError("failed to fetch existing likes"));
+        Err(error) => {
+            return Err(AppError::internal("failed to fetch existing likes", error));
         }
     }
 
-    let create_res = create_like(
-        &pool,
-        &authenticated_user.0,
-        &like_review_request.review_id,
-        like_review_request.like_type,
-    )
-    .await;
+    let create_res = like_repo
+        .create_like(
+            &authenticated_user.0,
+            &like_review_request.review_id,
+            like_review_request.like_type,
+        )
+        .await;
 
     match create_res {
         Ok(_) => {
             // Creating the notification is best effort. We may look into not awaiting this;
             // though unsure of how the tokio runtime closes out the webrequest.
-            let _ = create_notification(
-                &pool,
-                &authenticated_user.0,
-                &review.user_id,
-                &review.id,
-                ActionType::Like.into(),
-            )
-            .await;
+            let _ = notification_repo
+                .create_notification(
+                    &authenticated_user.0,
+                    &review.user_id,
+                    &review.id,
+                    ActionType::Like.into(),
+                )
+                .await;
 
-            let user_res = get_user(&pool, &review.user_id).await;
+            let user_res = user_repo.get_user(&review.user_id).await;
 
             // Best effort sending the notification through apple sevices.
             match user_res {
                 Ok(user_opt) => {
                     if let Some(user) = user_opt {
-                        let calling_user_res = get_user(&pool, &authenticated_user.0).await;
+                        let calling_user_res = user_repo.get_user(&authenticated_user.0).await;
 
                         match calling_user_res {
                             Ok(calling_user_opt) => {
@@ -118,8 +140,7 @@ pub async fn like_review(
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to like review"));
+            return Err(AppError::internal("failed to like review", error));
         }
     }
 }
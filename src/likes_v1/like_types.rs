@@ -1,10 +1,11 @@
 use crate::db::Like;
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct LikePub {
     pub id: String,
     pub created: i64,
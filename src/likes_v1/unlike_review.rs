@@ -1,53 +1,68 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_review, remove_like},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::{LikeRepo, ReviewRepo},
 };
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LikeReviewRequest {
     pub review_id: String,
 }
 
 /// Allows users to unlike a review.
+#[utoipa::path(
+    context_path = "/api/v1/like",
+    tag = "like",
+    responses(
+        (status = 200, description = "like was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/unlike")]
 pub async fn unlike_review(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    like_repo: Data<dyn LikeRepo>,
     like_review_request: Query<LikeReviewRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_review(&pool, &authenticated_user.0, &like_review_request.review_id).await;
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &like_review_request.review_id)
+        .await;
 
     match review_res {
         Ok(review_opt) => {
             if let None = review_opt {
-                return Err(ErrorNotFound("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to get review"));
+            return Err(AppError::internal("failed to get review", error));
         }
     }
 
-    let remove_res =
-        remove_like(&pool, &authenticated_user.0, &like_review_request.review_id).await;
+    let remove_res = like_repo
+        .remove_like(&authenticated_user.0, &like_review_request.review_id)
+        .await;
 
     match remove_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to remove like"));
+            return Err(AppError::internal("failed to remove like", error));
         }
     }
 }
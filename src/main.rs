@@ -1,99 +1,108 @@
-#[macro_use]
-extern crate lazy_static;
-
-use crate::user_v1::update_user_device_token;
 use actix_cors::Cors;
 use actix_web::{
     http,
-    web::{self, Data, PayloadConfig},
+    web::{self, Data, JsonConfig, PayloadConfig, QueryConfig},
     App, HttpServer,
 };
 use actix_web_opentelemetry::RequestTracing;
-use admin_v1::{get_all_reports, get_user_count};
-use auth::*;
-use authorization::Authentication;
-use bookmark_v1::{add_bookmark, get_all_bookmarks, get_nearby_all_bookmarks, remove_bookmark};
 use chrono::Utc;
-use friend_v1::{
-    accept_friend, add_friend, cancel_friend, decline_friend, discover_friends, full_friends,
-    get_friends, get_ignored_friends, get_incoming_friends, get_outgoing_friends,
-    get_user_friends::get_user_friends, ignore_friend, remove_friend,
-};
 use images::create_s3_client;
-use jwt::{encode_apn_jwt_secret, encode_jwt_secret, mint_apn_jwt, APNSigningKey, SigningKeys};
-use likes_v1::{
-    get_current_liked_reviews_full, get_current_likes, get_likes, like_review, unlike_review,
-};
-use moka::sync::Cache;
-use notifications_v1::{
-    confirm_notifications, get_notifications, start_notification_worker, APNClient,
-    NotificationQueue,
-};
-use opentelemetry::{
-    sdk::{
-        export::trace::stdout,
-        trace::{self, Sampler},
-        Resource,
+use jwt::mint_apn_jwt;
+use reqwest::ClientBuilder;
+#[cfg(feature = "postgres")]
+use reviewwithfriends_backend::repo::PgRepo;
+use reviewwithfriends_backend::{
+    admin_v1::{
+        add_ipblock, clear_user_pic, get_all_reports, get_ipblocks, get_schema, get_user_count,
+        inspect_user, remove_ipblock, reset_user_name,
+    },
+    auth::*,
+    authorization::Authentication,
+    bookmark_v1::{add_bookmark, get_all_bookmarks, get_nearby_all_bookmarks, remove_bookmark},
+    config::load_config,
+    db::set_slow_query_log,
+    docs::ApiDoc,
+    error::{AppError, ErrorCode},
+    friend_v1::{
+        accept_friend, add_friend, cancel_friend, decline_friend, discover_friends, full_friends,
+        get_friends, get_ignored_friends, get_incoming_friends, get_outgoing_friends,
+        get_user_friends::get_user_friends, ignore_friend, remove_friend,
+    },
+    idempotency::{IDEMPOTENCY_KEY, IDEMPOTENCY_REPLAYED},
+    ipblock::{start_ipblock_refresh, GeoLookup, IpBlockFilter, IpBlockStore},
+    likes_v1::{
+        get_current_liked_reviews_full, get_current_likes, get_likes, like_review, unlike_review,
     },
-    KeyValue,
+    metrics::{start_metrics_server, RequestMetrics},
+    notifications_v1::{
+        confirm_notifications, get_notifications, reload_pending_notifications,
+        start_notification_worker, APNClient, NotificationQueue, NotificationWorkerHeartbeat,
+    },
+    pic_v1::{
+        add_profile_pic, add_review_pic, get_profile_pic, remove_review_pic, set_spaces_bucket,
+    },
+    ping_routes::{live, ping, ping_error, ready},
+    ratelimit::{RateLimit, RateLimiter},
+    replica::{RecentWrites, TrackWrites},
+    reply_v1::{add_reply, get_replies, remove_reply},
+    repo::{
+        configure_repos, DatabaseRepo, IpBlockRepo, NotificationRepo, PendingNotificationRepo,
+        UserRepo,
+    },
+    report_v1::{report_bug, report_user, GithubClient},
+    review_v1::{
+        add_review, edit_review, get_full_reviews_from_user, get_latest, get_latest_full,
+        get_recommended_reviews_from_user, get_review_batch, get_review_by_id,
+        get_reviews_from_loc, get_reviews_from_map_bounds,
+        get_reviews_from_map_bounds_with_exclusions, get_reviews_from_user, remove_review,
+        search_latest, update_review_recommended_status,
+    },
+    sync_v1::get_sync,
+    tracing::{add_error_span, setup_tracing, shutdown_tracing},
+    user_v1::{
+        get_me, get_user_batch, get_user_by_id, get_user_by_name, search_user_by_name, update_user,
+        update_user_device_token, update_user_recovery_email,
+    },
+    Config,
 };
-use opentelemetry_otlp::WithExportConfig;
-use pic_v1::{add_profile_pic, add_review_pic, get_profile_pic, remove_review_pic};
-use ping_routes::{ping, ping_error};
-use ratelimit::RateLimit;
-use reply_v1::{add_reply, get_replies, remove_reply};
-use report_v1::{report_bug, report_user, GithubClient};
-use reqwest::ClientBuilder;
-use review_v1::{
-    add_review, edit_review, get_full_reviews_from_user, get_latest, get_latest_full,
-    get_recommended_reviews_from_user, get_review_by_id, get_reviews_from_loc,
-    get_reviews_from_map_bounds, get_reviews_from_map_bounds_with_exclusions,
-    get_reviews_from_user, remove_review, search_latest, update_review_recommended_status,
+#[cfg(not(feature = "postgres"))]
+use reviewwithfriends_backend::{
+    replica::{start_replica_health_check, ReplicaRouting},
+    repo::MySqlRepo,
 };
+#[cfg(not(feature = "postgres"))]
 use sqlx::MySqlPool;
-use std::sync::Mutex;
-use std::{collections::HashMap, env, time::Duration};
-use user_v1::{
-    get_me, get_user_by_id, get_user_by_name, search_user_by_name, update_user,
-    update_user_recovery_email,
-};
-
-mod admin_v1;
-mod auth;
-mod authorization;
-mod bookmark_v1;
-mod compound_types;
-mod db;
-mod friend_v1;
-mod likes_v1;
-mod notifications_v1;
-mod pic_v1;
-mod ping_routes;
-mod ratelimit;
-mod reply_v1;
-mod report_v1;
-mod review_v1;
-mod tracing;
-mod user_v1;
-
-#[derive(Clone)]
-pub struct Config {
-    twilio_key: String,
-    db_connection_string: String,
-    signing_keys: SigningKeys,
-    spaces_key: String,
-    spaces_secret: String,
-    newrelic_key: String,
-    apn_key: APNSigningKey,
-    sendgrid_key: String,
-    github_key: String,
-}
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
 const PIC_CONFIG_LIMIT: usize = 4_262_144;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let config: Config = build_config();
+    // `openapi` prints the spec and exits, so CI can generate it without any config or services.
+    if std::env::args().nth(1).as_deref() == Some("openapi") {
+        let spec = ApiDoc::openapi()
+            .to_pretty_json()
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+        println!("{}", spec);
+
+        return Ok(());
+    }
+
+    let config: Config = load_config().map_err(|error| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+    })?;
+
+    set_spaces_bucket(&config.spaces_bucket);
+    set_slow_query_log(
+        config.slow_query_threshold,
+        config.slow_query_log.as_deref(),
+    )?;
 
     let http_client = ClientBuilder::new()
         .timeout(Duration::new(5, 0))
@@ -109,6 +118,7 @@ async fn main() -> std::io::Result<()> {
             .build()
             .unwrap(),
         key: config.apn_key.clone(),
+        topic: config.apn_topic.clone(),
         token: Mutex::new(apn_token),
         issued_time: Mutex::new(Utc::now().timestamp()),
     });
@@ -121,44 +131,120 @@ async fn main() -> std::io::Result<()> {
         token: config.github_key.clone(),
     });
 
-    let pool: MySqlPool = MySqlPool::connect(&config.db_connection_string)
-        .await
-        .unwrap();
+    let recent_writes = Data::new(RecentWrites::new(config.read_your_writes_window));
+
+    let repo = Arc::new(connect_repo(&config, recent_writes.clone()).await?);
+
+    if config.run_migrations {
+        repo.run_migrations()
+            .await
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+    }
 
     let client = create_s3_client(&config.spaces_key, &config.spaces_secret);
 
-    setup_tracing(&config);
+    setup_tracing(&config.telemetry).map_err(|error| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+    })?;
 
     let queue = Data::new(Mutex::new(NotificationQueue::new()));
 
-    start_notification_worker(queue.clone(), apn_client.clone(), Data::new(pool.clone()));
+    if let Err(error) = reload_pending_notifications(&queue, repo.as_ref()).await {
+        add_error_span(&error);
+    }
+
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
 
-    let ratelimit_cache = setup_moka_cache();
+    let notification_heartbeat = Data::new(NotificationWorkerHeartbeat::new());
 
-    HttpServer::new(move || {
+    let notification_worker = start_notification_worker(
+        queue.clone(),
+        apn_client.clone(),
+        Data::from(repo.clone() as Arc<dyn UserRepo>),
+        Data::from(repo.clone() as Arc<dyn NotificationRepo>),
+        Data::from(repo.clone() as Arc<dyn PendingNotificationRepo>),
+        notification_heartbeat.clone(),
+        shutdown_receiver,
+        config.notification_drain_timeout,
+    );
+
+    let geo_lookup = GeoLookup::open(
+        config.geoip_asn_db.as_deref(),
+        config.geoip_country_db.as_deref(),
+    )
+    .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string()))?;
+
+    let ipblock_store = Data::new(IpBlockStore::new(geo_lookup));
+
+    start_ipblock_refresh(
+        ipblock_store.clone(),
+        Data::from(repo.clone() as Arc<dyn IpBlockRepo>),
+    );
+
+    let rate_limiter = Data::new(RateLimiter::new(config.rate_limit_policies.clone()));
+
+    let metrics_server = start_metrics_server(
+        config.metrics_bind_address,
+        Data::from(repo.clone() as Arc<dyn DatabaseRepo>),
+        queue.clone(),
+    )?;
+    let metrics_handle = metrics_server.handle();
+    actix_web::rt::spawn(metrics_server);
+
+    let openapi = ApiDoc::openapi();
+
+    let bind_address = config.bind_address;
+    let shutdown_timeout = config.shutdown_timeout;
+
+    let server_result = HttpServer::new(move || {
         App::new()
-            .app_data(Data::new(ratelimit_cache.clone()))
+            .app_data(rate_limiter.clone())
+            .app_data(recent_writes.clone())
+            .app_data(ipblock_store.clone())
             .app_data(Data::new(config.clone()))
-            .app_data(Data::new(pool.clone()))
+            .configure(configure_repos(repo.clone()))
             .app_data(Data::new(client.clone()))
             .app_data(Data::new(http_client.clone()))
             .app_data(apn_client.clone())
             .app_data(gh_client.clone())
             .app_data(queue.clone())
+            .app_data(notification_heartbeat.clone())
             .app_data(PayloadConfig::new(PIC_CONFIG_LIMIT))
+            .app_data(JsonConfig::default().error_handler(|error, _| {
+                AppError::bad_request(ErrorCode::InvalidInput, error.to_string()).into()
+            }))
+            .app_data(QueryConfig::default().error_handler(|error, _| {
+                AppError::bad_request(ErrorCode::InvalidInput, error.to_string()).into()
+            }))
+            .wrap(TrackWrites)
             .wrap(RateLimit)
+            .wrap(IpBlockFilter)
             .wrap(Authentication)
+            .wrap(RequestMetrics)
             .wrap(RequestTracing::new())
             .wrap(
-                Cors::default()
-                    .allowed_origin("https://reviewwithfriends.com")
-                    .allowed_origin("http://localhost:8000")
+                build_cors(&config.cors_origins)
                     .allowed_methods(vec!["GET", "POST"])
                     .allowed_headers(vec![http::header::AUTHORIZATION, http::header::ACCEPT])
                     .allowed_header(http::header::CONTENT_TYPE)
+                    .allowed_header(IDEMPOTENCY_KEY)
+                    .expose_headers(vec![
+                        "ratelimit-limit",
+                        "ratelimit-remaining",
+                        "ratelimit-reset",
+                        "retry-after",
+                        IDEMPOTENCY_REPLAYED,
+                    ])
                     .max_age(3600),
             )
-            .service(web::scope("/ping").service(ping).service(ping_error))
+            .service(SwaggerUi::new("/docs/{_:.*}").url("/docs/openapi.json", openapi.clone()))
+            .service(
+                web::scope("/ping")
+                    .service(ping)
+                    .service(ping_error)
+                    .service(live)
+                    .service(ready),
+            )
             .service(
                 web::scope("/auth")
                     .service(request_code)
@@ -170,7 +256,14 @@ async fn main() -> std::io::Result<()> {
             .service(
                 web::scope("/admin")
                     .service(get_user_count)
-                    .service(get_all_reports),
+                    .service(get_all_reports)
+                    .service(inspect_user)
+                    .service(reset_user_name)
+                    .service(clear_user_pic)
+                    .service(get_ipblocks)
+                    .service(add_ipblock)
+                    .service(remove_ipblock)
+                    .service(get_schema),
             )
             .service(
                 web::scope("/api").service(
@@ -210,6 +303,7 @@ async fn main() -> std::io::Result<()> {
                                 .service(add_review)
                                 .service(remove_review)
                                 .service(get_review_by_id)
+                                .service(get_review_batch)
                                 .service(search_latest)
                                 .service(edit_review)
                                 .service(get_recommended_reviews_from_user)
@@ -219,6 +313,7 @@ async fn main() -> std::io::Result<()> {
                             web::scope("/user")
                                 .service(search_user_by_name)
                                 .service(get_user_by_id)
+                                .service(get_user_batch)
                                 .service(get_user_by_name)
                                 .service(update_user)
                                 .service(get_me)
@@ -255,96 +350,76 @@ async fn main() -> std::io::Result<()> {
                                 .service(add_bookmark)
                                 .service(remove_bookmark)
                                 .service(get_nearby_all_bookmarks),
-                        ),
+                        )
+                        .service(web::scope("/sync").service(get_sync)),
                 ),
             )
     })
-    .bind(build_binding())?
+    .bind(bind_address)?
+    .shutdown_timeout(shutdown_timeout.as_secs())
     .run()
-    .await
+    .await;
+
+    // the server has stopped accepting requests and in-flight handlers are done, so nothing else will be queued.
+    let _ = shutdown_sender.send(true);
+    let _ = notification_worker.await;
+
+    metrics_handle.stop(true).await;
+
+    shutdown_tracing().await;
+
+    server_result
 }
 
-/// Fetch build configs from environment variable
-fn build_config() -> Config {
-    if is_dev() {
-        Config {
-            twilio_key: String::from("123"),
-            db_connection_string: env::var("DATABASE_URL").unwrap(),
-            signing_keys: encode_jwt_secret("thisisatestkey"),
-            spaces_key: env::var("MOB_SPACES_KEY").unwrap(),
-            spaces_secret: env::var("MOB_SPACES_SECRET").unwrap(),
-            newrelic_key: String::from("Default"),
-            apn_key: encode_apn_jwt_secret(&env::var("APN_KEY").unwrap()),
-            sendgrid_key: env::var("SENDGRID_KEY").unwrap(),
-            github_key: env::var("GITHUB_KEY").unwrap(),
-        }
-    } else {
-        Config {
-            twilio_key: env::var("TWILIO").unwrap(),
-            db_connection_string: env::var("DB_CONNECTION").unwrap(),
-            signing_keys: encode_jwt_secret(&env::var("JWT_KEY").unwrap()),
-            spaces_key: env::var("SPACES_KEY").unwrap(),
-            spaces_secret: env::var("SPACES_SECRET").unwrap(),
-            newrelic_key: env::var("NR_KEY").unwrap(),
-            apn_key: encode_apn_jwt_secret(&env::var("APN_KEY").unwrap()),
-            sendgrid_key: env::var("SENDGRID_KEY").unwrap(),
-            github_key: env::var("GITHUB_KEY").unwrap(),
-        }
-    }
+/// Allows browser requests from each configured origin.
+fn build_cors(origins: &[String]) -> Cors {
+    origins
+        .iter()
+        .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
 }
 
-pub fn is_dev() -> bool {
-    let is_dev = env::var("MOB_DEV");
+/// Connects to MySQL, the default backend, and the read replica if one is configured.
+#[cfg(not(feature = "postgres"))]
+async fn connect_repo(
+    config: &Config,
+    recent_writes: Data<RecentWrites>,
+) -> std::io::Result<MySqlRepo> {
+    let pool = MySqlPool::connect(&config.db_connection_string)
+        .await
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+    let repo = MySqlRepo::new(pool);
 
-    match is_dev {
-        Ok(_) => true,
-        Err(_) => false,
-    }
-}
+    let replica_connection_string = match &config.db_replica_connection_string {
+        Some(replica_connection_string) => replica_connection_string,
+        None => return Ok(repo),
+    };
 
-/// Set port bindings
-fn build_binding() -> (&'static str, u16) {
-    let is_dev = env::var("MOB_DEV");
+    // connects lazily, so a replica that's down at startup only sends reads to the primary.
+    let replica = MySqlPool::connect_lazy(replica_connection_string).map_err(|error| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+    })?;
+    let routing = Arc::new(ReplicaRouting::new(replica, recent_writes));
+    start_replica_health_check(routing.clone());
 
-    match is_dev {
-        Ok(_) => ("127.0.0.1", 8081),
-        Err(_) => ("0.0.0.0", 80),
-    }
+    return Ok(repo.with_replica(routing));
 }
 
-/// Initialize tracing - New Relic for Prod, stdout for dev
-fn setup_tracing(config: &Config) {
-    if config.newrelic_key != String::from("Default") {
-        let mut nr_otlp_metadata: HashMap<String, String> = HashMap::new();
-        nr_otlp_metadata.insert("api-key".to_string(), config.newrelic_key.clone());
-
-        opentelemetry_otlp::new_pipeline()
-            .tracing()
-            .with_exporter(
-                opentelemetry_otlp::HttpExporterBuilder::default()
-                    .with_endpoint("https://otlp.nr-data.net/v1/traces")
-                    .with_headers(nr_otlp_metadata),
-            )
-            .with_trace_config(
-                trace::config()
-                    .with_sampler(Sampler::AlwaysOn)
-                    .with_resource(Resource::new(vec![KeyValue::new(
-                        "service.name",
-                        "bout-backend",
-                    )])),
-            )
-            .install_batch(opentelemetry::runtime::Tokio)
-            .unwrap();
-    } else {
-        stdout::new_pipeline()
-            .with_trace_config(trace::config().with_sampler(Sampler::AlwaysOn))
-            .install_simple();
+/// Connects to PostgreSQL when built with the `postgres` feature.
+#[cfg(feature = "postgres")]
+async fn connect_repo(
+    config: &Config,
+    _recent_writes: Data<RecentWrites>,
+) -> std::io::Result<PgRepo> {
+    if config.db_replica_connection_string.is_some() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "database.replica_connection_string is only supported with MySQL",
+        ));
     }
-}
 
-fn setup_moka_cache() -> Cache<String, usize> {
-    Cache::builder()
-        .time_to_live(Duration::from_secs(60))
-        .max_capacity(10000)
-        .build()
+    let pool = PgPool::connect(&config.db_connection_string)
+        .await
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error.to_string()))?;
+
+    return Ok(PgRepo::new(pool));
 }
@@ -0,0 +1,109 @@
+use prometheus::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use std::{sync::LazyLock, time::Duration};
+
+// Every metric is registered with the default prometheus registry the first time it is used.
+
+pub static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "http_requests_total",
+        "HTTP requests handled, by route pattern and status.",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_REQUEST_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "http_request_duration_seconds",
+        "HTTP request latency, by route pattern and status.",
+        &["method", "route", "status"]
+    )
+    .unwrap()
+});
+
+pub static DB_POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "db_pool_connections",
+        "MySQL pool connections, by state.",
+        &["state"]
+    )
+    .unwrap()
+});
+
+pub static DB_ROUTED_READS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "db_routed_reads_total",
+        "Reads that could go to the replica, by where they went and why.",
+        &["route"]
+    )
+    .unwrap()
+});
+
+pub static NOTIFICATION_QUEUE_DEPTH: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "notification_queue_depth",
+        "Push notifications waiting to be sent."
+    )
+    .unwrap()
+});
+
+pub static PUSH_NOTIFICATIONS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "push_notifications_total",
+        "Push notifications sent to APNs, by result and APNs status.",
+        &["result", "status"]
+    )
+    .unwrap()
+});
+
+pub static S3_UPLOAD_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "s3_upload_duration_seconds",
+        "Spaces upload latency, by pic kind and result.",
+        &["kind", "result"]
+    )
+    .unwrap()
+});
+
+pub static S3_UPLOAD_BYTES: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "s3_upload_bytes",
+        "Size of pics uploaded to Spaces, by pic kind.",
+        &["kind"],
+        exponential_buckets(16_384.0, 2.0, 9).unwrap()
+    )
+    .unwrap()
+});
+
+pub static SMS_SENT_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "sms_sent_total",
+        "Auth code texts sent through Twilio, by result.",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static RATE_LIMIT_REJECTIONS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "rate_limit_rejections_total",
+        "Requests rejected by the rate limiter, by policy.",
+        &["policy"]
+    )
+    .unwrap()
+});
+
+/// Records a finished pic upload to Spaces.
+pub fn observe_s3_upload(kind: &str, bytes: usize, elapsed: Duration, is_success: bool) {
+    let result = if is_success { "ok" } else { "error" };
+
+    S3_UPLOAD_DURATION_SECONDS
+        .with_label_values(&[kind, result])
+        .observe(elapsed.as_secs_f64());
+    S3_UPLOAD_BYTES
+        .with_label_values(&[kind])
+        .observe(bytes as f64);
+}
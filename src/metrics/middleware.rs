@@ -0,0 +1,74 @@
+use actix_web::{
+    dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    time::Instant,
+};
+
+use super::{HTTP_REQUESTS_TOTAL, HTTP_REQUEST_DURATION_SECONDS};
+
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service }))
+    }
+}
+
+/// Counts and times every request by method, route, and status.
+/// Routes are labeled by their pattern, so ids in paths don't blow up the series count.
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+        let method = request.method().to_string();
+
+        let res = self.service.call(request);
+
+        return Box::pin(async move {
+            let res = res.await?;
+
+            let route = res
+                .request()
+                .match_pattern()
+                .unwrap_or("unmatched".to_string());
+            let status = res.status().as_u16().to_string();
+            let labels = [method.as_str(), route.as_str(), status.as_str()];
+
+            HTTP_REQUESTS_TOTAL.with_label_values(&labels).inc();
+            HTTP_REQUEST_DURATION_SECONDS
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+
+            Ok(res)
+        });
+    }
+}
@@ -0,0 +1,8 @@
+pub mod definitions;
+pub use definitions::*;
+
+pub mod middleware;
+pub use middleware::*;
+
+pub mod server;
+pub use server::*;
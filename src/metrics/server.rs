@@ -0,0 +1,58 @@
+use crate::{error::AppError, notifications_v1::NotificationQueue, repo::DatabaseRepo};
+use actix_web::{dev::Server, get, web::Data, App, HttpResponse, HttpServer, Responder, Result};
+use prometheus::{Encoder, TextEncoder};
+use std::{net::SocketAddr, sync::Mutex};
+
+use super::{DB_POOL_CONNECTIONS, NOTIFICATION_QUEUE_DEPTH};
+
+/// Serves every registered metric in the prometheus text format.
+/// Gauges for state we don't own, like the pool, are sampled at scrape time.
+#[get("/metrics")]
+pub async fn metrics(
+    database_repo: Data<dyn DatabaseRepo>,
+    queue: Data<Mutex<NotificationQueue>>,
+) -> Result<impl Responder, AppError> {
+    let (size, idle) = database_repo.pool_connections();
+    let (size, idle) = (size as i64, idle as i64);
+    DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+    DB_POOL_CONNECTIONS
+        .with_label_values(&["in_use"])
+        .set(size - idle);
+
+    if let Ok(queue) = queue.lock() {
+        NOTIFICATION_QUEUE_DEPTH.set(queue.len() as i64);
+    }
+
+    let mut buffer = vec![];
+    if let Err(error) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        return Err(AppError::internal(
+            "failed to encode metrics",
+            error.to_string(),
+        ));
+    }
+
+    return Ok(HttpResponse::Ok()
+        .content_type(TextEncoder::new().format_type())
+        .body(buffer));
+}
+
+/// Starts the metrics server on its own internal port, so metrics are never exposed through the public ingress.
+/// Signals are left to the main server; the returned server is stopped once the main server finishes.
+pub fn start_metrics_server(
+    bind_address: SocketAddr,
+    database_repo: Data<dyn DatabaseRepo>,
+    queue: Data<Mutex<NotificationQueue>>,
+) -> std::io::Result<Server> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(database_repo.clone())
+            .app_data(queue.clone())
+            .service(metrics)
+    })
+    .workers(1)
+    .disable_signals()
+    .bind(bind_address)?
+    .run();
+
+    return Ok(server);
+}
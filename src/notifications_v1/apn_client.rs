@@ -1,4 +1,5 @@
 use super::NotificationType;
+use crate::{metrics::PUSH_NOTIFICATIONS_TOTAL, tracing::WithTraceContext};
 use chrono::prelude::*;
 use jwt::{mint_apn_jwt, APNSigningKey};
 use reqwest::Client;
@@ -9,6 +10,8 @@ use std::sync::Mutex;
 pub struct APNClient {
     pub client: Client,
     pub key: APNSigningKey,
+    /// Bundle id of the app notifications are sent to.
+    pub topic: String,
     pub token: Mutex<String>,
     pub issued_time: Mutex<i64>,
 }
@@ -18,6 +21,40 @@ impl APNClient {
         mint_apn_jwt(key)
     }
 
+    /// Gets the current provider token, minting a new one when the current one is close to expiring.
+    /// Apple rejects tokens older than an hour, so they are refreshed after 45 minutes.
+    pub fn get_token(&self) -> Result<String, String> {
+        let time_lock_res = self.issued_time.lock();
+        match time_lock_res {
+            Ok(mut issued_time) => {
+                let token_lock_res = self.token.lock();
+                match token_lock_res {
+                    Ok(mut token_g) => {
+                        if Utc::now().timestamp() - *issued_time >= 2700 {
+                            let tmp_token = APNClient::generate_token(&self.key);
+                            *token_g = tmp_token.clone();
+                            *issued_time = Utc::now().timestamp();
+
+                            return Ok(tmp_token);
+                        } else {
+                            return Ok(token_g.clone());
+                        }
+                    }
+                    Err(_) => return Err("unable to get token lock".to_string()),
+                }
+            }
+            Err(_) => return Err("unable to get issued lock".to_string()),
+        }
+    }
+
+    /// Gets how many seconds ago the current provider token was minted.
+    pub fn get_token_age(&self) -> Result<i64, String> {
+        match self.issued_time.lock() {
+            Ok(issued_time) => Ok(Utc::now().timestamp() - *issued_time),
+            Err(_) => Err("unable to get issued lock".to_string()),
+        }
+    }
+
     pub async fn send_notification(
         &self,
         device_token: &str,
@@ -26,31 +63,7 @@ impl APNClient {
         notification_value: Option<String>,
         badge_count: i64,
     ) -> Result<(), String> {
-        let token: String;
-
-        {
-            let time_lock_res = self.issued_time.lock();
-            match time_lock_res {
-                Ok(mut issued_time) => {
-                    let token_lock_res = self.token.lock();
-                    match token_lock_res {
-                        Ok(mut token_g) => {
-                            if Utc::now().timestamp() - *issued_time >= 2700 {
-                                let tmp_token = APNClient::generate_token(&self.key);
-                                *token_g = tmp_token.clone();
-                                *issued_time = Utc::now().timestamp();
-
-                                token = tmp_token;
-                            } else {
-                                token = token_g.clone();
-                            }
-                        }
-                        Err(_) => return Err("unable to get token lock".to_string()),
-                    }
-                }
-                Err(_) => return Err("unable to get issued lock".to_string()),
-            }
-        }
+        let token = self.get_token()?;
 
         let pn = PushNotification {
             aps: Alert {
@@ -80,17 +93,27 @@ impl APNClient {
             .header("apns-push-type", "alert")
             .header("apns-expiration", "0")
             .header("apns-id", "eabeae54-14a8-11e5-b60b-1697f925ec7b")
-            .header("apns-topic", "com.spacedoglabs.spotster")
+            .header("apns-topic", &self.topic)
             .header("apns-priority", "10")
             .body(body)
+            .with_trace_context()
             .send()
             .await;
 
         match result {
             Ok(resp) => {
+                let status = resp.status().as_u16().to_string();
+
                 if resp.status().is_success() {
+                    PUSH_NOTIFICATIONS_TOTAL
+                        .with_label_values(&["sent", &status])
+                        .inc();
                     return Ok(());
                 } else {
+                    PUSH_NOTIFICATIONS_TOTAL
+                        .with_label_values(&["failed", &status])
+                        .inc();
+
                     let mut text = "".to_string();
                     let status_code = resp.status();
 
@@ -105,6 +128,9 @@ impl APNClient {
                 }
             }
             Err(err) => {
+                PUSH_NOTIFICATIONS_TOTAL
+                    .with_label_values(&["failed", "none"])
+                    .inc();
                 return Err(err.to_string());
             }
         }
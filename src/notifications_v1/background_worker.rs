@@ -1,14 +1,26 @@
 use super::APNClient;
-use crate::db::{get_notification_count, get_user};
+use crate::{
+    db::PendingNotification,
+    repo::{NotificationRepo, PendingNotificationRepo, UserRepo},
+    tracing::add_error_span,
+};
 use actix_web::web::Data;
+use chrono::Utc;
 use opentelemetry::global;
-use opentelemetry::trace::{Span, Status, Tracer};
-use sqlx::MySqlPool;
+use opentelemetry::trace::{FutureExt, Status, TraceContextExt, Tracer};
+use opentelemetry::Context;
 use std::collections::VecDeque;
 use std::fmt;
 use std::ops::{Deref, DerefMut};
-use std::{sync::Mutex, time::Duration};
-use tokio::{task, time};
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{sync::watch, task, task::JoinHandle, time};
+use uuid::Uuid;
 
 pub struct NotificationQueue(VecDeque<NotificationQueueItem>);
 
@@ -19,6 +31,12 @@ impl NotificationQueue {
     }
 }
 
+impl Default for NotificationQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deref for NotificationQueue {
     type Target = VecDeque<NotificationQueueItem>;
 
@@ -62,6 +80,19 @@ pub enum NotificationType {
     Post,
 }
 
+impl NotificationType {
+    /// Parses the `Display` form back into a type.
+    pub fn parse(value: &str) -> Option<NotificationType> {
+        match value {
+            "Favorite" => Some(NotificationType::Favorite),
+            "Reply" => Some(NotificationType::Reply),
+            "Add" => Some(NotificationType::Add),
+            "Post" => Some(NotificationType::Post),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for NotificationType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -73,50 +104,220 @@ impl fmt::Display for NotificationType {
     }
 }
 
-/// Starts a background task to process the queue
+/// Last time the notification worker loop ran.
+/// Readiness checks use this to spot a worker that has died or is stuck.
+pub struct NotificationWorkerHeartbeat(AtomicI64);
+
+impl NotificationWorkerHeartbeat {
+    pub fn new() -> Self {
+        NotificationWorkerHeartbeat(AtomicI64::new(Utc::now().timestamp()))
+    }
+
+    pub fn beat(&self) {
+        self.0.store(Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    /// Gets how many seconds ago the worker loop last ran.
+    pub fn seconds_since_beat(&self) -> i64 {
+        Utc::now().timestamp() - self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for NotificationWorkerHeartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<NotificationQueueItem> for PendingNotification {
+    fn from(item: NotificationQueueItem) -> PendingNotification {
+        PendingNotification {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            user_id: item.user_id,
+            notification_value: item.notification_value,
+            message: item.message,
+            notification_type: item.notification_type.to_string(),
+        }
+    }
+}
+
+impl TryFrom<PendingNotification> for NotificationQueueItem {
+    type Error = String;
+
+    fn try_from(pending: PendingNotification) -> Result<NotificationQueueItem, String> {
+        let notification_type = NotificationType::parse(&pending.notification_type).ok_or(
+            format!("unknown notification type {}", pending.notification_type),
+        )?;
+
+        Ok(NotificationQueueItem {
+            user_id: pending.user_id,
+            notification_value: pending.notification_value,
+            message: pending.message,
+            notification_type,
+        })
+    }
+}
+
+/// Starts a background task to process the queue.
+///
+/// Once `shutdown` flips to true the worker keeps sending until the queue is empty or `drain_timeout` passes.
+/// Anything left after that is persisted, and reloaded by `reload_pending_notifications` on the next start.
 pub fn start_notification_worker(
     queue: Data<Mutex<NotificationQueue>>,
     client: Data<APNClient>,
-    pool: Data<MySqlPool>,
-) {
+    user_repo: Data<dyn UserRepo>,
+    notification_repo: Data<dyn NotificationRepo>,
+    pending_notification_repo: Data<dyn PendingNotificationRepo>,
+    heartbeat: Data<NotificationWorkerHeartbeat>,
+    mut shutdown: watch::Receiver<bool>,
+    drain_timeout: Duration,
+) -> JoinHandle<()> {
     task::spawn(async move {
+        let mut drain_deadline: Option<Instant> = None;
+
         loop {
+            heartbeat.beat();
+
+            if drain_deadline.is_none() && *shutdown.borrow() {
+                drain_deadline = Some(Instant::now() + drain_timeout);
+            }
+
+            if let Some(deadline) = drain_deadline {
+                if Instant::now() >= deadline {
+                    persist_remaining_notifications(&queue, pending_notification_repo.get_ref())
+                        .await;
+                    break;
+                }
+            }
+
             let mut dequeued_item: Option<NotificationQueueItem> = None;
 
             if let Ok(mut queue) = queue.lock() {
                 dequeued_item = queue.pop_front();
             }
 
-            if let Some(item) = dequeued_item {
-                let tracer = global::tracer("Apple Push Notification");
-                let mut span = tracer.start("Notification Sent");
-
-                if let Ok(user_opt) = get_user(&pool, &item.user_id).await {
-                    if let Some(user) = user_opt {
-                        if let Some(device_token) = user.device_token {
-                            let badge_count = get_notification_count(&pool, &user.id).await;
-                            let res = client
-                                .send_notification(
-                                    &device_token,
-                                    &item.message,
-                                    item.notification_type,
-                                    item.notification_value,
-                                    badge_count,
-                                )
-                                .await;
-                            if let Err(err) = res {
-                                span.set_status(Status::error(err.clone()));
-                            }
-                        }
+            match dequeued_item {
+                Some(item) => {
+                    send_queued_notification(
+                        &client,
+                        user_repo.get_ref(),
+                        notification_repo.get_ref(),
+                        item,
+                    )
+                    .await
+                }
+                None => {
+                    if drain_deadline.is_some() {
+                        // drained everything before the deadline.
+                        break;
                     }
                 }
+            }
 
-                span.end();
+            if drain_deadline.is_none() {
+                // wakes up early when shutdown starts, rather than waiting out the poll interval.
+                if let Ok(Err(_)) =
+                    time::timeout(Duration::from_millis(100), shutdown.changed()).await
+                {
+                    // nothing can signal shutdown anymore, so start draining now.
+                    drain_deadline = Some(Instant::now() + drain_timeout);
+                }
             }
+        }
+    })
+}
 
-            time::sleep(Duration::from_millis(100)).await;
+async fn send_queued_notification(
+    client: &APNClient,
+    user_repo: &dyn UserRepo,
+    notification_repo: &dyn NotificationRepo,
+    item: NotificationQueueItem,
+) {
+    let tracer = global::tracer("Apple Push Notification");
+    // made current so the apns request is exported as a child of this span.
+    let context = Context::current_with_span(tracer.start("Notification Sent"));
+
+    if let Err(err) = deliver_notification(client, user_repo, notification_repo, item)
+        .with_context(context.clone())
+        .await
+    {
+        context.span().set_status(Status::error(err));
+    }
+
+    context.span().end();
+}
+
+async fn deliver_notification(
+    client: &APNClient,
+    user_repo: &dyn UserRepo,
+    notification_repo: &dyn NotificationRepo,
+    item: NotificationQueueItem,
+) -> Result<(), String> {
+    if let Ok(user_opt) = user_repo.get_user(&item.user_id).await {
+        if let Some(user) = user_opt {
+            if let Some(device_token) = user.device_token {
+                let badge_count = notification_repo.get_notification_count(&user.id).await;
+                client
+                    .send_notification(
+                        &device_token,
+                        &item.message,
+                        item.notification_type,
+                        item.notification_value,
+                        badge_count,
+                    )
+                    .await?;
+            }
         }
-    });
+    }
+
+    return Ok(());
+}
+
+/// Saves whatever is still queued so it can be sent after a restart.
+async fn persist_remaining_notifications(
+    queue: &Mutex<NotificationQueue>,
+    pending_notification_repo: &dyn PendingNotificationRepo,
+) {
+    let mut remaining: Vec<PendingNotification> = vec![];
+
+    if let Ok(mut queue) = queue.lock() {
+        remaining = queue
+            .drain(..)
+            .map(|f| -> PendingNotification { f.into() })
+            .collect();
+    }
+
+    if remaining.is_empty() {
+        return;
+    }
+
+    if let Err(error) = pending_notification_repo
+        .create_pending_notifications(&remaining)
+        .await
+    {
+        add_error_span(&error);
+    }
+}
+
+/// Loads notifications persisted by a previous shutdown back onto the queue.
+pub async fn reload_pending_notifications(
+    queue: &Mutex<NotificationQueue>,
+    pending_notification_repo: &dyn PendingNotificationRepo,
+) -> Result<(), sqlx::Error> {
+    let pending = pending_notification_repo
+        .take_pending_notifications()
+        .await?;
+
+    if let Ok(mut queue) = queue.lock() {
+        queue.extend(
+            pending
+                .into_iter()
+                .filter_map(|f| -> Option<NotificationQueueItem> { f.try_into().ok() }),
+        );
+    }
+
+    return Ok(());
 }
 
 /// Add a NotificationQueueItem to the queue
@@ -1,29 +1,37 @@
-use crate::{authorization::AuthenticatedUser, db, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::NotificationRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
-use sqlx::MySqlPool;
 
 /// Acknowledges all notifications have been received by the client.
+#[utoipa::path(
+    context_path = "/api/v1/notification",
+    tag = "notification",
+    responses(
+        (status = 200, description = "notifications were confirmed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("")]
 pub async fn confirm_notifications(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let reply_res = db::confirm_notifications(&pool, &authenticated_user.0).await;
+    notification_repo: Data<dyn NotificationRepo>,
+) -> Result<impl Responder, AppError> {
+    let reply_res = notification_repo
+        .confirm_notifications(&authenticated_user.0)
+        .await;
 
     match reply_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable confirm notifications".to_string(),
-            ));
+            return Err(AppError::internal("unable confirm notifications", error));
         }
     }
 }
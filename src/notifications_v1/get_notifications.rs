@@ -1,20 +1,61 @@
 use super::notification_types::NotificationPub;
-use crate::{authorization::AuthenticatedUser, db, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    docs::ErrorResponses,
+    error::AppError,
+    etag::{Conditional, VersionTag, PRIVATE_REVALIDATE},
+    repo::NotificationRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, ReqData},
-    Responder, Result,
+    web::{Data, ReqData},
+    HttpRequest, Responder, Result,
 };
-use sqlx::MySqlPool;
 
 /// Gets the top 50 latest notifications for a user.
+#[utoipa::path(
+    context_path = "/api/v1/notification",
+    tag = "notification",
+    responses(
+        (status = 200, description = "notifications for the calling user", body = [NotificationPub]),
+        (status = 304, description = "unchanged since the version in `If-None-Match`"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("")]
 pub async fn get_notifications(
+    request: HttpRequest,
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let notifications_res = db::get_notifications(&pool, &authenticated_user.0).await;
+    notification_repo: Data<dyn NotificationRepo>,
+) -> Result<impl Responder, AppError> {
+    let version_res = notification_repo
+        .get_notifications_version(&authenticated_user.0)
+        .await;
+
+    let conditional: Conditional;
+    match version_res {
+        Ok(version) => {
+            let etag = VersionTag::new(&request, &authenticated_user.0)
+                .add(&version)
+                .finish();
+            conditional = Conditional::new(&request, etag, PRIVATE_REVALIDATE);
+        }
+        Err(error) => {
+            return Err(AppError::internal(
+                "unable to get notifications version",
+                error,
+            ));
+        }
+    }
+
+    if conditional.is_not_modified() {
+        return Ok(conditional.not_modified());
+    }
+
+    let notifications_res = notification_repo
+        .get_notifications(&authenticated_user.0)
+        .await;
 
     match notifications_res {
         Ok(notifications) => {
@@ -22,13 +63,10 @@ pub async fn get_notifications(
                 .into_iter()
                 .map(|f| -> NotificationPub { f.into() })
                 .collect();
-            return Ok(Json(notifications_pub));
+            return Ok(conditional.ok(&notifications_pub));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get notifications".to_string(),
-            ));
+            return Err(AppError::internal("unable to get notifications", error));
         }
     }
 }
@@ -0,0 +1,301 @@
+use sqlx::{Error, PgPool, Row};
+
+use super::{traced, NO_SUCH_TABLE};
+use crate::db::{
+    AuthAttempt, IpBlock, PhoneAuth, Pic, Report, Review, SchemaMigration, User, UserActivityCounts,
+};
+
+/// Gets the total number of active users.
+pub async fn get_total_user_count(client: &PgPool) -> Result<i64, Error> {
+    traced("get_total_user_count", async move {
+        let row = sqlx::query("SELECT count(*) FROM \"user\"")
+            .fetch_one(client)
+            .await?;
+
+        return Ok(row.get(0));
+    })
+    .await
+}
+
+/// Simply gets all report records.
+pub async fn get_all_reports(client: &PgPool) -> Result<Vec<Report>, Error> {
+    traced("get_all_reports", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM reports",
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Report::from).collect());
+    })
+    .await
+}
+
+/// Tries to get a user by `user.email`, and will return `None` if not found.
+pub async fn get_user_by_email(client: &PgPool, email: &str) -> Result<Option<User>, Error> {
+    traced("get_user_by_email", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   \"user\"
+        WHERE  email = $1 ",
+        )
+        .bind(email)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.last().map(User::from));
+    })
+    .await
+}
+
+/// Gets the most recent phoneauths for a phone, regardless of `phoneauth.used`.
+pub async fn get_recent_phoneauths(
+    client: &PgPool,
+    phone: &str,
+    limit: u32,
+) -> Result<Vec<PhoneAuth>, Error> {
+    traced("get_recent_phoneauths", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   phoneauth
+        WHERE  phone = $1
+        ORDER  BY created DESC
+        LIMIT  $2 ",
+        )
+        .bind(phone)
+        .bind(limit as i64)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(PhoneAuth::from).collect());
+    })
+    .await
+}
+
+/// Gets the most recent authattempts for a phone.
+pub async fn get_recent_authattempts(
+    client: &PgPool,
+    phone: &str,
+    limit: u32,
+) -> Result<Vec<AuthAttempt>, Error> {
+    traced("get_recent_authattempts", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   authattempt
+        WHERE  phone = $1
+        ORDER  BY created DESC
+        LIMIT  $2 ",
+        )
+        .bind(phone)
+        .bind(limit as i64)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(AuthAttempt::from).collect());
+    })
+    .await
+}
+
+/// Gets the number of records a user owns across the main tables.
+/// The friend count excludes the friend record every user has with themselves.
+pub async fn get_user_activity_counts(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<UserActivityCounts, Error> {
+    traced("get_user_activity_counts", async move {
+        let row = sqlx::query(
+            "SELECT (SELECT count(*) FROM review WHERE user_id = $1)                      AS reviews,
+                (SELECT count(*) FROM friend WHERE user_id = $1 AND friend_id != $1)  AS friends,
+                (SELECT count(*) FROM friendrequest WHERE friend_id = $1)             AS incoming_requests,
+                (SELECT count(*) FROM friendrequest WHERE user_id = $1)               AS outgoing_requests,
+                (SELECT count(*) FROM likes WHERE user_id = $1)                       AS likes,
+                (SELECT count(*) FROM reply WHERE user_id = $1)                       AS replies,
+                (SELECT count(*) FROM bookmark WHERE user_id = $1)                    AS bookmarks,
+                (SELECT count(*) FROM notification WHERE review_user_id = $1)         AS notifications ",
+        )
+        .bind(user_id)
+        .fetch_one(client)
+        .await?;
+
+        return Ok((&row).into());
+    })
+    .await
+}
+
+/// Gets all reports a user has filed against other users.
+pub async fn get_reports_filed(client: &PgPool, reporter_id: &str) -> Result<Vec<Report>, Error> {
+    traced("get_reports_filed", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   reports
+        WHERE  reporter_id = $1
+        ORDER  BY created DESC",
+        )
+        .bind(reporter_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Report::from).collect());
+    })
+    .await
+}
+
+/// Gets all reports other users have filed against a user.
+pub async fn get_reports_received(client: &PgPool, user_id: &str) -> Result<Vec<Report>, Error> {
+    traced("get_reports_received", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   reports
+        WHERE  user_id = $1
+        ORDER  BY created DESC",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Report::from).collect());
+    })
+    .await
+}
+
+/// Gets the latest reviews made by a user.
+/// ## Does not account for friends, this is only for admin use.
+pub async fn get_recent_reviews_by_user(
+    client: &PgPool,
+    user_id: &str,
+    limit: u32,
+) -> Result<Vec<Review>, Error> {
+    traced("get_recent_reviews_by_user", async move {
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) AS longitude,
+        ST_Y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+ WHERE  r.user_id = $1
+ ORDER  BY r.created DESC
+ LIMIT  $2 ",
+        )
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Review::from).collect());
+    })
+    .await
+}
+
+/// Gets every ipblock rule, including expired ones.
+pub async fn get_all_ipblocks(client: &PgPool) -> Result<Vec<IpBlock>, Error> {
+    traced("get_all_ipblocks", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   ipblock
+        ORDER  BY created DESC",
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(IpBlock::from).collect());
+    })
+    .await
+}
+
+/// Gets every migration recorded in the database, oldest first.
+/// A database that was never migrated has no history table, which reads as nothing applied.
+pub async fn get_applied_migrations(client: &PgPool) -> Result<Vec<SchemaMigration>, Error> {
+    traced("get_applied_migrations", async move {
+        let rows_res = sqlx::query(
+            "SELECT version, description, installed_on, success, checksum, execution_time
+        FROM   _sqlx_migrations
+        ORDER  BY version",
+        )
+        .fetch_all(client)
+        .await;
+
+        let rows = match rows_res {
+            Ok(rows) => rows,
+            Err(Error::Database(error)) if error.code().as_deref() == Some(NO_SUCH_TABLE) => {
+                return Ok(vec![]);
+            }
+            Err(error) => return Err(error),
+        };
+
+        return Ok(rows.iter().map(SchemaMigration::from).collect());
+    })
+    .await
+}
+
+/// Gets pics nothing points at anymore.
+/// Review pics whose review is gone, and profile pics no user has set.
+pub async fn get_orphaned_pics(client: &PgPool) -> Result<Vec<Pic>, Error> {
+    traced("get_orphaned_pics", async move {
+        let rows = sqlx::query(
+            "SELECT p.*
+        FROM   pic AS p
+        WHERE  ( p.review_id IS NOT NULL
+                 AND NOT EXISTS (SELECT 1
+                                 FROM   review AS r
+                                 WHERE  r.id = p.review_id) )
+                OR ( p.review_id IS NULL
+                     AND NOT EXISTS (SELECT 1
+                                     FROM   \"user\" AS u
+                                     WHERE  u.pic_id = p.id) )
+        ORDER  BY p.created ASC",
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Pic::from).collect());
+    })
+    .await
+}
+
+/// Gets the pics attached to any review made by a user.
+pub async fn get_review_pics_by_user(client: &PgPool, user_id: &str) -> Result<Vec<Pic>, Error> {
+    traced("get_review_pics_by_user", async move {
+        let rows = sqlx::query(
+            "SELECT p.*
+        FROM   pic AS p
+               INNER JOIN review AS r
+                       ON p.review_id = r.id
+        WHERE  r.user_id = $1 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Pic::from).collect());
+    })
+    .await
+}
+
+/// Gets users without a friend record for themselves.
+/// `create_user` adds one, but accounts created before it did are missing it.
+pub async fn get_users_missing_self_friend(client: &PgPool) -> Result<Vec<User>, Error> {
+    traced("get_users_missing_self_friend", async move {
+        let rows = sqlx::query(
+            "SELECT u.*
+        FROM   \"user\" AS u
+        WHERE  NOT EXISTS (SELECT 1
+                           FROM   friend AS f
+                           WHERE  f.user_id = u.id
+                                  AND f.friend_id = u.id)",
+        )
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(User::from).collect());
+    })
+    .await
+}
@@ -0,0 +1,1223 @@
+use images::DEFAULT_PIC_ID;
+use sqlx::{
+    types::chrono::{Duration, NaiveDateTime, Utc},
+    Error, PgPool, Postgres, Transaction,
+};
+use uuid::Uuid;
+
+use super::traced;
+use crate::db::{
+    Bookmark, IdempotencyKey, IpBlock, PendingNotification, Pic, Review, User, TOMBSTONE_BOOKMARK,
+    TOMBSTONE_LIKE, TOMBSTONE_REPLY, TOMBSTONE_REVIEW, USER_ACTION_TYPE,
+};
+use crate::sync_v1::SYNC_TOKEN_MAX_AGE_DAYS;
+
+/// Records deleted rows so `/sync` can tell clients to drop them.
+/// Each item is the deleted row's id and the user whose friends could see it.
+/// Also prunes tombstones older than any sync token still accepted, as nothing can ask for them.
+async fn create_tombstones(
+    trans: &mut Transaction<'_, Postgres>,
+    item_type: &str,
+    items: Vec<(String, String)>,
+) -> Result<(), Error> {
+    let created = Utc::now().naive_utc();
+    let expired_before = created - Duration::days(SYNC_TOKEN_MAX_AGE_DAYS);
+
+    sqlx::query("DELETE FROM tombstone WHERE created < $1")
+        .bind(expired_before)
+        .execute(&mut *trans)
+        .await?;
+
+    for (item_id, owner_id) in items {
+        sqlx::query(
+            "INSERT INTO tombstone (id, created, item_type, item_id, owner_id) VALUES ($1,$2,$3,$4,$5)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(created)
+        .bind(item_type)
+        .bind(item_id)
+        .bind(owner_id)
+        .execute(&mut *trans)
+        .await?;
+    }
+
+    return Ok(());
+}
+
+/// Creates a user from the passed User struct.
+/// Sets the pic_id to `DEFAULT_PIC_ID`
+/// Does not generate a guid for `user.id`
+/// Does not set a date for `user.created`
+pub async fn create_user(client: &PgPool, user: &User) -> Result<(), Error> {
+    traced("create_user", async move {
+        let mut trans = client.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO \"user\" (id, name, display_name, phone, created, pic_id) VALUES ($1,$2,$3,$4,$5,$6)",
+        )
+        .bind(&user.id)
+        .bind(&user.name)
+        .bind(&user.display_name)
+        .bind(&user.phone)
+        .bind(&user.created)
+        .bind(DEFAULT_PIC_ID)
+        .execute(&mut trans)
+        .await?;
+
+        // This insert is important. We assume you are friends with yourself, otherwise you can't see your own posts.
+        sqlx::query("INSERT INTO friend (id, created, user_id, friend_id) VALUES ($1,$2,$3,$4)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(Utc::now().naive_utc())
+            .bind(&user.id)
+            .bind(&user.id)
+            .execute(&mut trans)
+            .await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a phoneauth record for tracking and validating user auth attempts.
+/// ## Sets the `phoneauth.created` to `Utc::now().naive_utc()`
+/// ## Sets the `phoneauth.id` to `Uuid::new_v4().to_string()`
+/// ## Sets the `phoneauth.used` to `false`
+pub async fn create_phoneauth(
+    client: &PgPool,
+    phone: &str,
+    code: &str,
+    ip: &str,
+) -> Result<(), Error> {
+    traced("create_phoneauth", async move {
+        sqlx::query(
+            "INSERT INTO phoneauth (id, phone, created, ip, code, used) VALUES ($1,$2,$3,$4,$5,0)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(phone)
+        .bind(Utc::now().naive_utc())
+        .bind(ip)
+        .bind(code)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets an `phoneauth.used` to `true` signalling it can no longer be used to generate a token.
+pub async fn update_authattempt_used(client: &PgPool, id: &str) -> Result<(), Error> {
+    traced("update_authattempt_used", async move {
+        sqlx::query("UPDATE phoneauth SET used = 1 WHERE id = $1")
+            .bind(id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates an authattempt record for tracking attempts to auth as a user.
+/// ## Sets the `authattempt.created` to `Utc::now().naive_utc()`
+/// ## Sets the `authattempt.id` to `Uuid::new_v4().to_string()`
+pub async fn create_authattempt(client: &PgPool, phone: &str) -> Result<(), Error> {
+    traced("create_authattempt", async move {
+        sqlx::query("INSERT INTO authattempt (id, phone, created) VALUES ($1,$2,$3)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(phone)
+            .bind(Utc::now().naive_utc())
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets a phone for a given user account.
+pub async fn update_user_phone(client: &PgPool, user_id: &str, phone: &str) -> Result<(), Error> {
+    traced("update_user_phone", async move {
+        sqlx::query("UPDATE \"user\" SET phone = $1 WHERE id = $2")
+            .bind(phone)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a friend request as a user to another user.
+/// ## Sets the `friendrequest.created` to `Utc::now().naive_utc()`
+/// ## Sets the `friendrequest.id` to `Uuid::new_v4().to_string()`
+pub async fn create_friend_request(
+    client: &PgPool,
+    user_id: &str,
+    friend_id: &str,
+) -> Result<(), Error> {
+    traced("create_friend_request", async move {
+        sqlx::query(
+            "INSERT INTO friendrequest (id, created, user_id, friend_id, ignored) VALUES ($1,$2,$3,$4,0)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(friend_id)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets an incoming friend request for a user to ignored.
+/// ## Sets the `friendrequest.ignored` to `true`
+pub async fn ignore_friend_request(
+    client: &PgPool,
+    request_id: &str,
+    friend_id: &str,
+) -> Result<(), Error> {
+    traced("ignore_friend_request", async move {
+        sqlx::query("UPDATE friendrequest SET ignored = 1 WHERE id = $1 and friend_id = $2")
+            .bind(request_id)
+            .bind(friend_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Deletes an incoming friend request for a user.
+pub async fn decline_friend_request(
+    client: &PgPool,
+    request_id: &str,
+    friend_id: &str,
+) -> Result<(), Error> {
+    traced("decline_friend_request", async move {
+        sqlx::query("DELETE FROM friendrequest WHERE id = $1 and friend_id = $2")
+            .bind(request_id)
+            .bind(friend_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Deletes an outgoing friend request.
+pub async fn cancel_friend_request(
+    client: &PgPool,
+    request_id: &str,
+    user_id: &str,
+) -> Result<(), Error> {
+    traced("cancel_friend_request", async move {
+        sqlx::query("DELETE FROM friendrequest WHERE id = $1 and user_id = $2")
+            .bind(request_id)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Accepts an incoming friend request from another user.
+/// ## Transaction based.
+/// Deletes both directions that could exist.
+/// Creates both directions of the friend relationship.
+pub async fn accept_friend_request(
+    client: &PgPool,
+    user_id: &str,
+    friend_id: &str,
+) -> Result<(), Error> {
+    traced("accept_friend_request", async move {
+        let mut trans = client.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM friendrequest
+        WHERE  ( user_id = $1 AND friend_id = $2 )
+                OR ( user_id = $2 AND friend_id = $1 )",
+        )
+        .bind(user_id)
+        .bind(friend_id)
+        .execute(&mut trans)
+        .await?;
+
+        for (from_id, to_id) in [(user_id, friend_id), (friend_id, user_id)] {
+            sqlx::query(
+                "INSERT INTO friend (id, created, user_id, friend_id)
+            SELECT $1, $2, $3, $4
+            WHERE  NOT EXISTS (SELECT 1
+                               FROM   friend
+                               WHERE  user_id = $3
+                                      AND friend_id = $4)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(Utc::now().naive_utc())
+            .bind(from_id)
+            .bind(to_id)
+            .execute(&mut trans)
+            .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes a friend.
+/// ## Transaction based.
+/// Attempts to remove both directiong of the friendship.
+pub async fn remove_current_friend(
+    client: &PgPool,
+    user_id: &str,
+    friend_id: &str,
+) -> Result<(), Error> {
+    traced("remove_current_friend", async move {
+        sqlx::query(
+            "DELETE FROM friend
+        WHERE  ( user_id = $1 AND friend_id = $2 )
+                OR ( user_id = $2 AND friend_id = $1 )",
+        )
+        .bind(user_id)
+        .bind(friend_id)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a pic record in the database to be related to a user or review.
+/// ## Sets the `pic.created` to `Utc::now().naive_utc()`
+/// ## Sets the `pic.id` to `Uuid::new_v4().to_string()`
+/// ## Sets the `pic.pic_handler` to `1` for now.
+/// ## Marks the review as updated, so `/sync` resends its pics.
+pub async fn create_pic(
+    client: &PgPool,
+    review_id: Option<String>,
+    width: u16,
+    height: u16,
+) -> Result<Pic, Error> {
+    traced("create_pic", async move {
+        let pic = Pic {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            created: Utc::now().naive_utc(),
+            pic_handler: 1,
+            width,
+            height,
+        };
+
+        let mut trans = client.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO pic (id, created, pic_handler, review_id, width, height) VALUES ($1,$2,$3,$4,$5,$6)",
+        )
+        .bind(&pic.id)
+        .bind(&pic.created)
+        .bind(pic.pic_handler as i16)
+        .bind(&pic.review_id)
+        .bind(pic.width as i32)
+        .bind(pic.height as i32)
+        .execute(&mut trans)
+        .await?;
+
+        if let Some(review_id) = &pic.review_id {
+            sqlx::query("UPDATE review SET updated = $1 WHERE id = $2")
+                .bind(&pic.created)
+                .bind(review_id)
+                .execute(&mut trans)
+                .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(pic);
+    })
+    .await
+}
+
+/// Updates a `user.pic_id` for a given user.
+/// The pic_id has no Foreign Key Constraints to the pic table.
+pub async fn update_user_pic_id(client: &PgPool, pic_id: &str, user_id: &str) -> Result<(), Error> {
+    traced("update_user_pic_id", async move {
+        sqlx::query("UPDATE \"user\" SET pic_id = $1 WHERE id = $2")
+            .bind(pic_id)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Deletes a pic record.
+/// Has no foreign key restrictions or harsh bindings to actual pic storage.
+pub async fn delete_pic(client: &PgPool, pic_id: &str) -> Result<(), Error> {
+    traced("delete_pic", async move {
+        sqlx::query("DELETE FROM pic WHERE id = $1")
+            .bind(pic_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a review from the given `Review`.
+/// Requires all fields to be set on incoming review. Validates nothing explicitly other than column constraints.
+pub async fn create_review(client: &PgPool, review: &Review) -> Result<(), Error> {
+    traced("create_review", async move {
+        sqlx::query(
+            "INSERT INTO review
+        (id, user_id, created, updated, text, stars, location_name, is_custom, category, location, delivered)
+        VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,ST_MakePoint($10,$11),$12)",
+        )
+        .bind(&review.id)
+        .bind(&review.user_id)
+        .bind(&review.created)
+        .bind(&review.created)
+        .bind(&review.text)
+        .bind(review.stars as i16)
+        .bind(&review.location_name)
+        .bind(review.is_custom as i16)
+        .bind(&review.category)
+        .bind(review.longitude)
+        .bind(review.latitude)
+        .bind(review.delivered as i16)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets a `review.pic_id` to NULL.
+/// Marks the review as updated, so `/sync` resends its pics.
+/// ## Transaction Based
+pub async fn remove_review_pic_id(
+    client: &PgPool,
+    pic_id: &str,
+    review_id: &str,
+) -> Result<(), Error> {
+    traced("remove_review_pic_id", async move {
+        let mut trans = client.begin().await?;
+
+        sqlx::query("DELETE FROM pic where id = $1 and review_id = $2")
+            .bind(pic_id)
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query("UPDATE review SET updated = $1 WHERE id = $2")
+            .bind(Utc::now().naive_utc())
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a like record associated with a given review.
+/// ## Sets the `likes.created` to `Utc::now().naive_utc()`
+/// ## Sets the `likes.id` to `Uuid::new_v4().to_string()`
+pub async fn create_like(
+    client: &PgPool,
+    user_id: &str,
+    review_id: &str,
+    like_type: Option<i8>,
+) -> Result<(), Error> {
+    traced("create_like", async move {
+        sqlx::query(
+            "INSERT INTO likes
+        (id, created, user_id, review_id, like_type)
+        VALUES ($1,$2,$3,$4,$5)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(review_id)
+        .bind(like_type.unwrap_or(0) as i16)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes a like record, leaving a tombstone for it.
+/// ## Transaction Based
+pub async fn remove_like(client: &PgPool, user_id: &str, review_id: &str) -> Result<(), Error> {
+    traced("remove_like", async move {
+        let mut trans = client.begin().await?;
+
+        let items = sqlx::query_as::<_, (String, String)>(
+            "SELECT l.id, r.user_id
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+        WHERE  l.user_id = $1
+               AND l.review_id = $2",
+        )
+        .bind(user_id)
+        .bind(review_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query("DELETE FROM likes where user_id = $1 and review_id = $2")
+            .bind(user_id)
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        create_tombstones(&mut trans, TOMBSTONE_LIKE, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes a review and all likes/replies.
+/// Only the review gets a tombstone, clients drop its likes and replies with it.
+/// ## Transaction Based
+pub async fn remove_review_and_children(client: &PgPool, review_id: &str) -> Result<(), Error> {
+    traced("remove_review_and_children", async move {
+        let mut trans = client.begin().await?;
+
+        let items =
+            sqlx::query_as::<_, (String, String)>("SELECT id, user_id FROM review WHERE id = $1")
+                .bind(review_id)
+                .fetch_all(&mut trans)
+                .await?;
+
+        sqlx::query("DELETE FROM reply WHERE review_id = $1")
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query("DELETE FROM likes WHERE review_id = $1")
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query("DELETE FROM review WHERE id = $1")
+            .bind(review_id)
+            .execute(&mut trans)
+            .await?;
+
+        create_tombstones(&mut trans, TOMBSTONE_REVIEW, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a reply record against a given review.
+/// ## Sets the `reply.created` to `Utc::now().naive_utc()`
+/// ## Sets the `reply.id` to `Uuid::new_v4().to_string()`
+pub async fn create_reply(
+    client: &PgPool,
+    user_id: &str,
+    review_id: &str,
+    text: &str,
+    reply_to_id: Option<&String>,
+) -> Result<(), Error> {
+    traced("create_reply", async move {
+        sqlx::query(
+            "INSERT INTO reply
+        (id, created, user_id, review_id, text, reply_to_id)
+        VALUES ($1,$2,$3,$4,$5,$6)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(review_id)
+        .bind(text)
+        .bind(reply_to_id)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Deletes a given reply by id, leaving a tombstone for it.
+/// ## Transaction Based
+pub async fn delete_reply(
+    client: &PgPool,
+    reply_id: &str,
+    review_id: &str,
+    user_id: &str,
+) -> Result<(), Error> {
+    traced("delete_reply", async move {
+        let mut trans = client.begin().await?;
+
+        let items = sqlx::query_as::<_, (String, String)>(
+            "SELECT rp.id, r.user_id
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+        WHERE  rp.id = $1
+               AND rp.review_id = $2
+               AND rp.user_id = $3",
+        )
+        .bind(reply_id)
+        .bind(review_id)
+        .bind(user_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query("DELETE FROM reply WHERE id = $1 AND review_id = $2 and user_id = $3")
+            .bind(reply_id)
+            .bind(review_id)
+            .bind(user_id)
+            .execute(&mut trans)
+            .await?;
+
+        create_tombstones(&mut trans, TOMBSTONE_REPLY, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Updates a review by setting the passed values.
+/// Will always set the passed values.
+pub async fn update_review(
+    client: &PgPool,
+    review_id: &str,
+    stars: u8,
+    text: &str,
+) -> Result<(), Error> {
+    traced("update_review", async move {
+        sqlx::query("UPDATE review SET stars = $1, text = $2, updated = $3 WHERE id = $4")
+            .bind(stars as i16)
+            .bind(text)
+            .bind(Utc::now().naive_utc())
+            .bind(review_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Updates the delivered status for the review.
+pub async fn update_review_delivered(
+    client: &PgPool,
+    review_id: &str,
+    delivered: u8,
+) -> Result<(), Error> {
+    traced("update_review_delivered", async move {
+        sqlx::query("UPDATE review SET delivered = $1, updated = $2 WHERE id = $3")
+            .bind(delivered as i16)
+            .bind(Utc::now().naive_utc())
+            .bind(review_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Updates a users names.
+/// Will always try to set the passed values.
+pub async fn update_usernames(
+    client: &PgPool,
+    user_id: &str,
+    display_name: &str,
+    name: &str,
+) -> Result<(), Error> {
+    traced("update_usernames", async move {
+        sqlx::query("UPDATE \"user\" SET display_name = $1, name = $2 WHERE id = $3")
+            .bind(display_name)
+            .bind(name)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Updates a users device token for push notifications.
+pub async fn update_device_token(
+    client: &PgPool,
+    user_id: &str,
+    device_token: &str,
+) -> Result<(), Error> {
+    traced("update_device_token", async move {
+        sqlx::query("UPDATE \"user\" SET device_token = $1 WHERE id = $2")
+            .bind(device_token)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Updates a users recovery for updating account phone numbers.
+pub async fn update_recovery_email(
+    client: &PgPool,
+    user_id: &str,
+    email: &str,
+) -> Result<(), Error> {
+    traced("update_recovery_email", async move {
+        sqlx::query("UPDATE \"user\" SET email = $1 WHERE id = $2")
+            .bind(email)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Deletes all notifications, which is essentially confirming them.
+pub async fn confirm_notifications(client: &PgPool, user_id: &str) -> Result<(), Error> {
+    traced("confirm_notifications", async move {
+        sqlx::query("DELETE FROM notification WHERE review_user_id = $1")
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a notification for a user as a response for the given action.
+pub async fn create_notification(
+    client: &PgPool,
+    user_id: &str,
+    review_user_id: &str,
+    review_id: &str,
+    action_type: u8,
+) -> Result<(), Error> {
+    traced("create_notification", async move {
+        // No need to notify yourself of your own action.
+        if review_user_id.eq_ignore_ascii_case(user_id) {
+            return Ok(());
+        }
+
+        let notifications = sqlx::query(
+            "SELECT id
+            FROM  notification AS n
+        WHERE n.review_id = $1
+            AND n.user_id = $2
+            AND n.action_type = $3",
+        )
+        .bind(review_id)
+        .bind(user_id)
+        .bind(action_type as i16)
+        .fetch_all(client)
+        .await?;
+
+        // If this type from this user on this review already exists, we can just early return.
+        if notifications.len() == 1 {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO notification
+        (id, created, review_user_id, user_id, review_id, action_type)
+        VALUES ($1,$2,$3,$4,$5,$6)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(Utc::now().naive_utc())
+        .bind(review_user_id)
+        .bind(user_id)
+        .bind(review_id)
+        .bind(action_type as i16)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a report record against a user, with a request id also.
+pub async fn report_user(client: &PgPool, user_id: &str, reporter_id: &str) -> Result<(), Error> {
+    traced("report_user", async move {
+        // No need to report yourself.
+        if user_id.eq_ignore_ascii_case(reporter_id) {
+            return Ok(());
+        }
+
+        let reports = sqlx::query(
+            "SELECT id
+            FROM  reports AS n
+        WHERE n.user_id = $1
+            AND n.reporter_id = $2
+            AND n.report_type = $3",
+        )
+        .bind(user_id)
+        .bind(reporter_id)
+        .bind(USER_ACTION_TYPE as i16)
+        .fetch_all(client)
+        .await?;
+
+        // If this report already exists, just ignore this.
+        if reports.len() == 1 {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO reports
+        (id, created, user_id, reporter_id, report_type)
+        VALUES ($1,$2,$3,$4,$5)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(Utc::now().naive_utc())
+        .bind(user_id)
+        .bind(reporter_id)
+        .bind(USER_ACTION_TYPE as i16)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Update whether a review is recommended or not.
+pub async fn update_review_recommended(
+    client: &PgPool,
+    review_id: &str,
+    user_id: &str,
+    recommended: bool,
+) -> Result<(), Error> {
+    traced("update_review_recommended", async move {
+        sqlx::query(
+            "UPDATE review SET recommended = $1, updated = $2 WHERE id = $3 and user_id = $4",
+        )
+        .bind(recommended as i16)
+        .bind(Utc::now().naive_utc())
+        .bind(review_id)
+        .bind(user_id)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates a bookmark from the given `Bookmark`.
+/// Requires all fields to be set on incoming bookmark. Validates nothing explicitly other than column constraints.
+pub async fn create_bookmark(client: &PgPool, bookmark: &Bookmark) -> Result<(), Error> {
+    traced("create_bookmark", async move {
+        sqlx::query(
+            "INSERT INTO bookmark
+        (id, user_id, created, location_name, category, location)
+        VALUES ($1,$2,$3,$4,$5,ST_MakePoint($6,$7))",
+        )
+        .bind(&bookmark.id)
+        .bind(&bookmark.user_id)
+        .bind(&bookmark.created)
+        .bind(&bookmark.location_name)
+        .bind(&bookmark.category)
+        .bind(bookmark.longitude)
+        .bind(bookmark.latitude)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes the user's bookmarks at a location, leaving a tombstone for each.
+/// ## Transaction Based
+pub async fn remove_bookmark(
+    client: &PgPool,
+    user_id: &str,
+    location_name: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<(), Error> {
+    traced("remove_bookmark", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let mut trans = client.begin().await?;
+
+        let items = sqlx::query_as::<_, (String, String)>(
+            "SELECT bm.id, bm.user_id
+        FROM   bookmark AS bm
+        WHERE  bm.user_id = $1
+               AND bm.location_name = $2
+               AND ST_DWithin(bm.location, ST_MakePoint($3, $4), $5)",
+        )
+        .bind(user_id)
+        .bind(location_name)
+        .bind(longitude)
+        .bind(latitude)
+        .bind(ACCURACY_SIZE)
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM bookmark as bm
+            WHERE  bm.user_id = $1
+               AND bm.location_name = $2
+               AND ST_DWithin(bm.location, ST_MakePoint($3, $4), $5)",
+        )
+        .bind(user_id)
+        .bind(location_name)
+        .bind(longitude)
+        .bind(latitude)
+        .bind(ACCURACY_SIZE)
+        .execute(&mut trans)
+        .await?;
+
+        create_tombstones(&mut trans, TOMBSTONE_BOOKMARK, items).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates an ipblock rule from the passed IpBlock struct.
+pub async fn create_ipblock(client: &PgPool, ipblock: &IpBlock) -> Result<(), Error> {
+    traced("create_ipblock", async move {
+        sqlx::query(
+            "INSERT INTO ipblock (id, created, created_by, action, cidr, asn, country, reason, expires) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)",
+        )
+        .bind(&ipblock.id)
+        .bind(&ipblock.created)
+        .bind(&ipblock.created_by)
+        .bind(&ipblock.action)
+        .bind(&ipblock.cidr)
+        .bind(ipblock.asn.map(|asn| asn as i64))
+        .bind(&ipblock.country)
+        .bind(&ipblock.reason)
+        .bind(&ipblock.expires)
+        .execute(client)
+        .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes an ipblock rule, returning false when none has the id.
+pub async fn remove_ipblock(client: &PgPool, id: &str) -> Result<bool, Error> {
+    traced("remove_ipblock", async move {
+        let result = sqlx::query("DELETE FROM ipblock WHERE id = $1")
+            .bind(id)
+            .execute(client)
+            .await?;
+
+        return Ok(result.rows_affected() > 0);
+    })
+    .await
+}
+
+/// Persists queued notifications so they survive a restart.
+pub async fn create_pending_notifications(
+    client: &PgPool,
+    pending_notifications: &[PendingNotification],
+) -> Result<(), Error> {
+    traced("create_pending_notifications", async move {
+        let mut trans = client.begin().await?;
+
+        for pending in pending_notifications {
+            sqlx::query(
+                "INSERT INTO pendingnotification (id, created, user_id, notification_value, message, notification_type) VALUES ($1,$2,$3,$4,$5,$6)",
+            )
+            .bind(&pending.id)
+            .bind(&pending.created)
+            .bind(&pending.user_id)
+            .bind(&pending.notification_value)
+            .bind(&pending.message)
+            .bind(&pending.notification_type)
+            .execute(&mut trans)
+            .await?;
+        }
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Gets and removes every persisted notification.
+/// Rows are deleted as they're read, so instances starting together won't both send them.
+pub async fn take_pending_notifications(
+    client: &PgPool,
+) -> Result<Vec<PendingNotification>, Error> {
+    traced("take_pending_notifications", async move {
+        let rows = sqlx::query("DELETE FROM pendingnotification RETURNING *")
+            .fetch_all(client)
+            .await?;
+
+        let mut pending_notifications: Vec<PendingNotification> =
+            rows.iter().map(PendingNotification::from).collect();
+        pending_notifications.sort_by_key(|pending| pending.created);
+
+        return Ok(pending_notifications);
+    })
+    .await
+}
+
+/// Claims an idempotency key for the request about to run, first dropping every key used before `expired_before`,
+/// and the user's key if it was claimed before `abandoned_before` and never completed.
+/// Returns false when the user already holds the key, so another request got there first.
+pub async fn create_idempotency_key(
+    client: &PgPool,
+    key: &IdempotencyKey,
+    expired_before: &NaiveDateTime,
+    abandoned_before: &NaiveDateTime,
+) -> Result<bool, Error> {
+    traced("create_idempotency_key", async move {
+        sqlx::query(
+            "DELETE FROM idempotencykey WHERE created < $1 OR (user_id = $2 AND idempotency_key = $3 AND status IS NULL AND created < $4)",
+        )
+        .bind(expired_before)
+        .bind(&key.user_id)
+        .bind(&key.idempotency_key)
+        .bind(abandoned_before)
+        .execute(client)
+        .await?;
+
+        let result = sqlx::query(
+            "INSERT INTO idempotencykey (id, created, user_id, idempotency_key, fingerprint) VALUES ($1,$2,$3,$4,$5)
+        ON CONFLICT (user_id, idempotency_key) DO NOTHING",
+        )
+        .bind(&key.id)
+        .bind(key.created)
+        .bind(&key.user_id)
+        .bind(&key.idempotency_key)
+        .bind(&key.fingerprint)
+        .execute(client)
+        .await?;
+
+        return Ok(result.rows_affected() == 1);
+    })
+    .await
+}
+
+/// Stores the response to replay for a claimed idempotency key.
+pub async fn complete_idempotency_key(
+    client: &PgPool,
+    id: &str,
+    status: u16,
+    headers: &str,
+    body: &[u8],
+) -> Result<(), Error> {
+    traced("complete_idempotency_key", async move {
+        sqlx::query("UPDATE idempotencykey SET status = $1, headers = $2, body = $3 WHERE id = $4")
+            .bind(status as i32)
+            .bind(headers)
+            .bind(body)
+            .bind(id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Releases an idempotency key, so the request can be retried with it.
+pub async fn remove_idempotency_key(client: &PgPool, id: &str) -> Result<(), Error> {
+    traced("remove_idempotency_key", async move {
+        sqlx::query("DELETE FROM idempotencykey WHERE id = $1")
+            .bind(id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Sets `user.disabled`, which stops the user from requesting sign in codes.
+pub async fn update_user_disabled(
+    client: &PgPool,
+    user_id: &str,
+    disabled: bool,
+) -> Result<(), Error> {
+    traced("update_user_disabled", async move {
+        sqlx::query("UPDATE \"user\" SET disabled = $1 WHERE id = $2")
+            .bind(disabled as i16)
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Creates the friend record a user needs to see their own posts.
+/// ## Sets the `friend.created` to `Utc::now().naive_utc()`
+/// ## Sets the `friend.id` to `Uuid::new_v4().to_string()`
+pub async fn create_self_friend(client: &PgPool, user_id: &str) -> Result<(), Error> {
+    traced("create_self_friend", async move {
+        sqlx::query("INSERT INTO friend (id, created, user_id, friend_id) VALUES ($1,$2,$3,$3)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(Utc::now().naive_utc())
+            .bind(user_id)
+            .execute(client)
+            .await?;
+
+        return Ok(());
+    })
+    .await
+}
+
+/// Removes everything a user has made or received, leaving just the account and its self friend record.
+/// Leaves tombstones for the user's reviews, bookmarks, and likes and replies on other users' reviews.
+/// Pic records are left alone, their storage has to be cleaned up first.
+/// ## Transaction Based
+pub async fn remove_user_activity(client: &PgPool, user_id: &str) -> Result<(), Error> {
+    traced("remove_user_activity", async move {
+        let mut trans = client.begin().await?;
+
+        let reviews = sqlx::query_as::<_, (String, String)>(
+            "SELECT id, user_id FROM review WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        let replies = sqlx::query_as::<_, (String, String)>(
+            "SELECT rp.id, r.user_id
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+        WHERE  rp.user_id = $1
+               AND r.user_id != $1",
+        )
+        .bind(user_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        let likes = sqlx::query_as::<_, (String, String)>(
+            "SELECT l.id, r.user_id
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+        WHERE  l.user_id = $1
+               AND r.user_id != $1",
+        )
+        .bind(user_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        let bookmarks = sqlx::query_as::<_, (String, String)>(
+            "SELECT id, user_id FROM bookmark WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&mut trans)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM reply
+        WHERE  user_id = $1
+                OR review_id IN (SELECT id
+                                 FROM   review
+                                 WHERE  user_id = $1)",
+        )
+        .bind(user_id)
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM likes
+        WHERE  user_id = $1
+                OR review_id IN (SELECT id
+                                 FROM   review
+                                 WHERE  user_id = $1)",
+        )
+        .bind(user_id)
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query("DELETE FROM review WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query("DELETE FROM notification WHERE review_user_id = $1 OR user_id = $1")
+            .bind(user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query("DELETE FROM friendrequest WHERE user_id = $1 OR friend_id = $1")
+            .bind(user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query(
+            "DELETE FROM friend
+        WHERE  ( user_id = $1
+                  OR friend_id = $1 )
+               AND user_id != friend_id",
+        )
+        .bind(user_id)
+        .execute(&mut trans)
+        .await?;
+
+        sqlx::query("DELETE FROM bookmark WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut trans)
+            .await?;
+
+        sqlx::query(
+            "UPDATE \"user\" SET pic_id = $1, device_token = NULL, email = NULL WHERE id = $2",
+        )
+        .bind(DEFAULT_PIC_ID)
+        .bind(user_id)
+        .execute(&mut trans)
+        .await?;
+
+        create_tombstones(&mut trans, TOMBSTONE_REVIEW, reviews).await?;
+        create_tombstones(&mut trans, TOMBSTONE_REPLY, replies).await?;
+        create_tombstones(&mut trans, TOMBSTONE_LIKE, likes).await?;
+        create_tombstones(&mut trans, TOMBSTONE_BOOKMARK, bookmarks).await?;
+
+        trans.commit().await?;
+
+        return Ok(());
+    })
+    .await
+}
@@ -0,0 +1,14 @@
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    PgPool,
+};
+
+/// Every migration in `migrations_postgres/`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations_postgres");
+
+/// Applies any embedded migrations the database is missing, in version order.
+/// sqlx holds a `pg_advisory_lock` for the whole run,
+/// so replicas starting together wait on each other instead of racing.
+pub async fn run_migrations(client: &PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(client).await
+}
@@ -0,0 +1,36 @@
+//! PostgreSQL + PostGIS versions of the `db` functions, built with the `postgres` feature.
+//! Signatures and results match `db`, so `PgRepo` can stand in for `MySqlRepo`.
+
+use sqlx::Error;
+use std::future::Future;
+
+use crate::db::{traced_system, RowCount};
+
+pub mod types;
+
+pub mod query;
+pub use query::*;
+
+pub mod sync_query;
+pub use sync_query::*;
+
+pub mod admin_query;
+pub use admin_query::*;
+
+pub mod execute;
+pub use execute::*;
+
+pub mod migrate;
+pub use migrate::*;
+
+/// SQLSTATE Postgres reports when a query references a table that doesn't exist.
+pub const NO_SUCH_TABLE: &str = "42P01";
+
+/// `db::traced`, reporting `postgresql` as the database system.
+pub async fn traced<T, F>(name: &'static str, query: F) -> Result<T, Error>
+where
+    T: RowCount,
+    F: Future<Output = Result<T, Error>>,
+{
+    traced_system("postgresql", name, query).await
+}
@@ -0,0 +1,1345 @@
+use chrono::Duration;
+use sqlx::{
+    types::chrono::{NaiveDateTime, Utc},
+    Error, PgPool, Row,
+};
+
+use super::traced;
+use crate::db::{
+    AuthAttempt, Bookmark, Cursor, DataVersion, ExpandedNotification, Friend, FriendRequest,
+    IdempotencyKey, IpBlock, Like, Page, PageRequest, PhoneAuth, Pic, Reply, Review,
+    ReviewAnnotation, User,
+};
+
+/// Simply gets a ping record.
+pub async fn get_ping(client: &PgPool, id: &str) -> Result<String, Error> {
+    traced("get_ping", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   ping
+        WHERE  id = $1 ",
+        )
+        .bind(id)
+        .fetch_one(client)
+        .await?;
+
+        return Ok(row.get("id"));
+    })
+    .await
+}
+
+/// Tries to get a user by `user.id`, and will return `None` if not found.
+pub async fn get_user(client: &PgPool, id: &str) -> Result<Option<User>, Error> {
+    traced("get_user", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   \"user\"
+        WHERE  id = $1 ",
+        )
+        .bind(id)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(User::from));
+    })
+    .await
+}
+
+/// Gets every user with one of the given ids, in no particular order.
+pub async fn get_users_by_ids(client: &PgPool, ids: &[String]) -> Result<Vec<User>, Error> {
+    traced("get_users_by_ids", async move {
+        let rows = sqlx::query("SELECT * FROM \"user\" WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(User::from).collect());
+    })
+    .await
+}
+
+/// Tries to get a user by exact `user.name`, and will return `None` if not found.
+pub async fn get_user_from_name(client: &PgPool, name: &str) -> Result<Option<User>, Error> {
+    traced("get_user_from_name", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   \"user\"
+        WHERE  name = $1 ",
+        )
+        .bind(name)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(User::from));
+    })
+    .await
+}
+
+/// Gets a list of users from the given `user.name`.
+/// This search is a trailing wildcard and limits to top 50 results.
+/// ILIKE keeps the search case insensitive, like the MySQL collation does.
+pub async fn search_user_from_name(client: &PgPool, name: &str) -> Result<Vec<User>, Error> {
+    traced("search_user_from_name", async move {
+        let search_term = format!("{}%", name.replace("%", ""));
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   \"user\"
+        WHERE  name ILIKE $1
+        LIMIT  50 ",
+        )
+        .bind(search_term)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(User::from).collect());
+    })
+    .await
+}
+
+/// Gets the existence of a user by `user.id`. True if exists, false if not.
+pub async fn does_user_exist(client: &PgPool, id: &str) -> Result<bool, Error> {
+    traced("does_user_exist", async move {
+        let rows = sqlx::query(
+            "SELECT id
+        FROM   \"user\"
+        WHERE  id = $1 ",
+        )
+        .bind(id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.len() == 1);
+    })
+    .await
+}
+
+/// Gets the existence of a user by `user.name`. True if exists, false if not.
+pub async fn does_user_exist_by_name(client: &PgPool, name: &str) -> Result<bool, Error> {
+    traced("does_user_exist_by_name", async move {
+        let rows = sqlx::query(
+            "SELECT id
+        FROM   \"user\"
+        WHERE  name = $1 ",
+        )
+        .bind(name)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.len() == 1);
+    })
+    .await
+}
+
+/// Gets a given user by `user.phone`, and will return `None` if not found.
+pub async fn get_user_by_phone(client: &PgPool, phone: &str) -> Result<Option<User>, Error> {
+    traced("get_user_by_phone", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM \"user\"
+        WHERE phone = $1 ",
+        )
+        .bind(phone)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.last().map(User::from));
+    })
+    .await
+}
+
+/// Gets the current phoneauths.
+/// Results are within the last 1 hour of `phoneauth.created`, and `phoneauth.used` is `false`
+pub async fn get_current_phoneauths(client: &PgPool, phone: &str) -> Result<Vec<PhoneAuth>, Error> {
+    traced("get_current_phoneauths", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   phoneauth
+        WHERE  created > $1
+               AND phone = $2
+               AND used = 0",
+        )
+        .bind(Utc::now().naive_utc() - Duration::hours(1))
+        .bind(phone)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(PhoneAuth::from).collect());
+    })
+    .await
+}
+
+/// Gets the current authattempts for a given `authattempt.phone` in the last 1 hour of `authattempt.created`.
+pub async fn get_phoneauth_attempts(
+    client: &PgPool,
+    phone: &str,
+) -> Result<Vec<AuthAttempt>, Error> {
+    traced("get_phoneauth_attempts", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   authattempt
+        WHERE  created > $1
+            AND phone = $2 ",
+        )
+        .bind(Utc::now().naive_utc() - Duration::hours(1))
+        .bind(phone)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(AuthAttempt::from).collect());
+    })
+    .await
+}
+
+/// Gets the users current incoming friend requests that `friendrequest.ignored` is false.
+pub async fn get_incoming_friend_requests(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<FriendRequest>, Error> {
+    traced("get_incoming_friend_requests", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   friendrequest
+        WHERE  friend_id = $1
+            AND ignored = 0 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(FriendRequest::from).collect());
+    })
+    .await
+}
+
+/// Gets the users current incoming friend requests that can be accepted. This included requests that have been ignored.
+pub async fn get_acceptable_friend_requests(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<FriendRequest>, Error> {
+    traced("get_acceptable_friend_requests", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   friendrequest
+        WHERE  friend_id = $1 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(FriendRequest::from).collect());
+    })
+    .await
+}
+
+/// Gets the users current incoming friend requests that `friendrequest.ignored` is true.
+pub async fn get_incoming_ignored_friend_requests(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<FriendRequest>, Error> {
+    traced("get_incoming_ignored_friend_requests", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   friendrequest
+        WHERE  friend_id = $1
+            AND ignored = 1 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(FriendRequest::from).collect());
+    })
+    .await
+}
+
+/// Gets the users outgoing friend requests.
+pub async fn get_outgoing_friend_requests(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<FriendRequest>, Error> {
+    traced("get_outgoing_friend_requests", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   friendrequest
+        WHERE  user_id = $1 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(FriendRequest::from).collect());
+    })
+    .await
+}
+
+/// Versions of everything `full_friends` returns.
+/// Friends, then incoming, ignored and outgoing requests.
+/// Ignoring a request moves it between the incoming and ignored counts.
+pub async fn get_friends_version(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_friends_version", async move {
+        let rows = sqlx::query(
+            "SELECT 1 AS part, COUNT(*) AS count, MAX(created) AS latest
+        FROM   friend
+        WHERE  user_id = $1
+        UNION ALL
+        SELECT 2, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  friend_id = $1
+            AND ignored = 0
+        UNION ALL
+        SELECT 3, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  friend_id = $1
+            AND ignored = 1
+        UNION ALL
+        SELECT 4, COUNT(*), MAX(created)
+        FROM   friendrequest
+        WHERE  user_id = $1
+        ORDER BY part",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
+}
+
+/// Gets the users current friends list.
+/// Not paged.
+pub async fn get_current_friends(client: &PgPool, user_id: &str) -> Result<Vec<Friend>, Error> {
+    traced("get_current_friends", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   friend
+        WHERE  user_id = $1 ",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Friend::from).collect());
+    })
+    .await
+}
+
+/// Gets a pic record for a specific id, will return `None` if it doesn't exists.
+pub async fn get_pic(client: &PgPool, id: &str) -> Result<Option<Pic>, Error> {
+    traced("get_pic", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   pic
+        WHERE  id = $1 ",
+        )
+        .bind(id)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(Pic::from));
+    })
+    .await
+}
+
+/// Gets a single review for the given review_id.
+/// Accounts for the passed user_id's fiends and own reviews.
+pub async fn get_review(
+    client: &PgPool,
+    user_id: &str,
+    review_id: &str,
+) -> Result<Option<Review>, Error> {
+    traced("get_review", async move {
+        let row = sqlx::query(
+            "SELECT r1.id,
+        r1.user_id,
+        r1.created,
+        r1.category,
+        r1.text,
+        r1.stars,
+        r1.location_name,
+        ST_X(r1.location) as longitude,
+        ST_Y(r1.location) as latitude,
+        r1.is_custom,
+        r1.delivered,
+        r1.recommended
+        FROM   review AS r1
+               INNER JOIN friend AS f1
+                       ON f1.friend_id = r1.user_id
+        WHERE  f1.user_id = $1
+               AND r1.id = $2
+        UNION
+        SELECT r2.id,
+        r2.user_id,
+        r2.created,
+        r2.category,
+        r2.text,
+        r2.stars,
+        r2.location_name,
+        ST_X(r2.location) as longitude,
+        ST_Y(r2.location) as latitude,
+        r2.is_custom,
+        r2.delivered,
+        r2.recommended
+        FROM   review AS r2
+        WHERE  r2.user_id = $1
+               AND r2.id = $2 ",
+        )
+        .bind(user_id)
+        .bind(review_id)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(Review::from));
+    })
+    .await
+}
+
+/// Gets every review with one of the given ids that the user can see, in no particular order.
+/// Same visibility as `get_review`: their own reviews and their friends'.
+pub async fn get_reviews_by_ids(
+    client: &PgPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<Review>, Error> {
+    traced("get_reviews_by_ids", async move {
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+        WHERE  r.id = ANY($1)
+               AND ( r.user_id = $2
+                      OR EXISTS (SELECT 1
+                                 FROM   friend AS f
+                                 WHERE  f.user_id = $2
+                                        AND f.friend_id = r.user_id) )",
+        )
+        .bind(review_ids)
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Review::from).collect());
+    })
+    .await
+}
+
+/// Gets all reviews from a given name, latitude, and longitude combination.
+/// Accounts for the passed user_id's fiends and own reviews.
+/// ## Results are NOT paged.
+pub async fn get_reviews_from_location(
+    client: &PgPool,
+    user_id: &str,
+    name: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<Vec<Review>, Error> {
+    traced("get_reviews_from_location", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND r.location_name = $2
+               AND ST_DWithin(r.location, ST_MakePoint($3, $4), $5)",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(longitude)
+        .bind(latitude)
+        .bind(ACCURACY_SIZE)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Review::from).collect());
+    })
+    .await
+}
+
+/// Gets all reviews from a user.
+/// Accounts for the passed user_id's fiends and own reviews.
+pub async fn get_reviews_from_user(
+    client: &PgPool,
+    user_id: &str,
+    target_user_id: &str,
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_reviews_from_user", async move {
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+            AND r.user_id = $2
+            AND ( r.created, r.id ) < ( $3, $4 )
+        ORDER BY r.created DESC, r.id DESC
+        LIMIT  $5 ",
+        )
+        .bind(user_id)
+        .bind(target_user_id)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let reviews = rows.iter().map(Review::from).collect();
+
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
+}
+
+/// Gets all recommended reviews from a user.
+/// Accounts for the passed user_id's fiends and own reviews.
+pub async fn get_recommended_reviews_from_user(
+    client: &PgPool,
+    user_id: &str,
+    target_user_id: &str,
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_recommended_reviews_from_user", async move {
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+            AND r.user_id = $2
+            AND r.recommended = 1
+            AND ( r.created, r.id ) < ( $3, $4 )
+        ORDER BY r.created DESC, r.id DESC
+        LIMIT  $5 ",
+        )
+        .bind(user_id)
+        .bind(target_user_id)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let reviews = rows.iter().map(Review::from).collect();
+
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
+}
+
+/// Gets reviews from a given bounding box.
+/// Accounts for the passed user_id's friends and own reviews.
+/// ## Results are paged.
+pub async fn get_reviews_from_bounds(
+    client: &PgPool,
+    user_id: &str,
+    latitude_north: f64,
+    latitude_south: f64,
+    longitude_west: f64,
+    longitude_east: f64,
+    page: &PageRequest,
+) -> Result<Page<ReviewAnnotation>, Error> {
+    traced("get_reviews_from_bounds", async move {
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        p.id             AS pic_id,
+        p.pic_handler   AS pic_handler,
+        r.category,
+        r.location_name,
+        ST_X(r.location) AS longitude,
+        ST_Y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+        INNER JOIN friend AS f
+                ON r.user_id = f.friend_id
+        LEFT JOIN pic AS p
+               ON p.id = (SELECT id
+                          FROM   pic pp
+                          WHERE  pp.review_id = r.id
+                          LIMIT  1)
+ WHERE  f.user_id = $1
+        AND ST_Contains(ST_MakeEnvelope($2, $3, $4, $5), r.location)
+        AND ( r.created, r.id ) < ( $6, $7 )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  $8 ",
+        )
+        .bind(user_id)
+        .bind(longitude_west)
+        .bind(latitude_south)
+        .bind(longitude_east)
+        .bind(latitude_north)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let annotations = rows.iter().map(ReviewAnnotation::from).collect();
+
+        return Ok(Page::from_rows(annotations, page, ReviewAnnotation::cursor));
+    })
+    .await
+}
+
+/// Gets reviews from a given bounding box.
+/// Accounts for the passed user_id's friends and own reviews.
+/// ## Results are paged.
+pub async fn get_reviews_from_bounds_with_exclusions(
+    client: &PgPool,
+    user_id: &str,
+    latitude_north: f64,
+    latitude_south: f64,
+    longitude_west: f64,
+    longitude_east: f64,
+    latitude_north_e: f64,
+    latitude_south_e: f64,
+    longitude_west_e: f64,
+    longitude_east_e: f64,
+    page: &PageRequest,
+) -> Result<Page<ReviewAnnotation>, Error> {
+    traced("get_reviews_from_bounds_with_exclusions", async move {
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        p.id             AS pic_id,
+        p.pic_handler   AS pic_handler,
+        r.category,
+        r.location_name,
+        ST_X(r.location) AS longitude,
+        ST_Y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+        INNER JOIN friend AS f
+                ON r.user_id = f.friend_id
+        LEFT JOIN pic AS p
+               ON p.id = (SELECT id
+                          FROM   pic pp
+                          WHERE  pp.review_id = r.id
+                          LIMIT  1)
+ WHERE  f.user_id = $1
+        AND ST_Contains(ST_MakeEnvelope($2, $3, $4, $5), r.location)
+        AND NOT ST_Contains(ST_MakeEnvelope($6, $7, $8, $9), r.location)
+        AND ( r.created, r.id ) < ( $10, $11 )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  $12 ",
+        )
+        .bind(user_id)
+        .bind(longitude_west)
+        .bind(latitude_south)
+        .bind(longitude_east)
+        .bind(latitude_north)
+        .bind(longitude_west_e)
+        .bind(latitude_south_e)
+        .bind(longitude_east_e)
+        .bind(latitude_north_e)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let annotations = rows.iter().map(ReviewAnnotation::from).collect();
+
+        return Ok(Page::from_rows(annotations, page, ReviewAnnotation::cursor));
+    })
+    .await
+}
+
+/// Fetches latest reviews from a given page.
+/// Accounts for the passed user_id's fiends and own reviews.
+/// ## Results are paged.
+pub async fn get_latest_reviews(
+    client: &PgPool,
+    user_id: &str,
+    page: &PageRequest,
+    category: &Option<String>,
+) -> Result<Page<Review>, Error> {
+    traced("get_latest_reviews", async move {
+        let (before_created, before_id) = page.before();
+
+        // a NULL category matches every review.
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) AS longitude,
+        ST_Y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+        INNER JOIN friend AS f
+                ON r.user_id = f.friend_id
+ WHERE  f.user_id = $1
+ AND ( $2::varchar IS NULL OR r.category = $2 )
+ AND ( r.created, r.id ) < ( $3, $4 )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  $5 ",
+        )
+        .bind(user_id)
+        .bind(category)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let reviews = rows.iter().map(Review::from).collect();
+
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
+}
+
+/// Fetches latest reviews from a given page.
+/// Accounts for the passed user_id's fiends and own reviews.
+/// ## Results are paged.
+pub async fn search_latest_reviews(
+    client: &PgPool,
+    user_id: &str,
+    search_prefix: &str,
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("search_latest_reviews", async move {
+        let (before_created, before_id) = page.before();
+        let search_term = format!("{}%", search_prefix.replace("%", ""));
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) AS longitude,
+        ST_Y(r.location) AS latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+ FROM   review AS r
+        INNER JOIN friend AS f
+                ON r.user_id = f.friend_id
+ WHERE  f.user_id = $1
+ AND r.location_name ILIKE $2
+ AND ( r.created, r.id ) < ( $3, $4 )
+ ORDER  BY r.created DESC, r.id DESC
+ LIMIT  $5 ",
+        )
+        .bind(user_id)
+        .bind(search_term)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let reviews = rows.iter().map(Review::from).collect();
+
+        return Ok(Page::from_rows(reviews, page, Review::cursor));
+    })
+    .await
+}
+
+/// Gets all likes for a given review.
+/// ## Does not validate the review is able to be viewed by calling user.
+pub async fn get_all_likes(client: &PgPool, review_id: &str) -> Result<Vec<Like>, Error> {
+    traced("get_all_likes", async move {
+        let rows = sqlx::query("SELECT * FROM likes WHERE  review_id = $1 ")
+            .bind(review_id)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(Like::from).collect());
+    })
+    .await
+}
+
+/// Versions of what `gather_compound_reviews` adds to the given reviews.
+/// Likes, replies and pics on the reviews, then the user's bookmarks.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_compound_review_version(
+    client: &PgPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_compound_review_version", async move {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let rows = sqlx::query(
+            "SELECT 1 AS part, COUNT(*) AS count, MAX(created) AS latest
+        FROM   likes
+        WHERE  review_id = ANY($1)
+        UNION ALL
+        SELECT 2, COUNT(*), MAX(created)
+        FROM   reply
+        WHERE  review_id = ANY($1)
+        UNION ALL
+        SELECT 3, COUNT(*), MAX(created)
+        FROM   pic
+        WHERE  review_id = ANY($1)
+        UNION ALL
+        SELECT 4, COUNT(*), MAX(created)
+        FROM   bookmark
+        WHERE  user_id = $2
+        ORDER BY part",
+        )
+        .bind(review_ids)
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
+}
+
+/// Gets all likes for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_likes_for_reviews(
+    client: &PgPool,
+    review_ids: &[String],
+) -> Result<Vec<Like>, Error> {
+    traced("get_likes_for_reviews", async move {
+        let rows = sqlx::query("SELECT * FROM likes WHERE review_id = ANY($1)")
+            .bind(review_ids)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(Like::from).collect());
+    })
+    .await
+}
+
+/// Gets all likes that a user has made, most recently liked first.
+/// Paged on the like rather than the review, so the cursor is the like's `(created, id)`.
+pub async fn get_liked_reviews(
+    client: &PgPool,
+    user_id: &str,
+    page: &PageRequest,
+) -> Result<Page<Review>, Error> {
+    traced("get_liked_reviews", async move {
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT r.id,
+            r.user_id,
+            r.created,
+            r.category,
+            r.text,
+            r.stars,
+            r.location_name,
+            ST_X(r.location) AS longitude,
+            ST_Y(r.location) AS latitude,
+            r.is_custom,
+            r.delivered,
+            r.recommended,
+            l.id AS like_id,
+            l.created AS like_created
+            FROM   review as r
+        INNER JOIN likes as l on r.id = l.review_id
+            WHERE  l.user_id = $1
+            AND ( l.created, l.id ) < ( $2, $3 )
+        ORDER  BY l.created DESC, l.id DESC
+        LIMIT  $4 ",
+        )
+        .bind(user_id)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let liked = rows
+            .iter()
+            .map(|row| {
+                let cursor = Cursor {
+                    created: row.get("like_created"),
+                    id: row.get("like_id"),
+                };
+
+                (cursor, Review::from(row))
+            })
+            .collect();
+
+        let liked_page = Page::from_rows(liked, page, |(cursor, _)| cursor.clone());
+
+        return Ok(liked_page.map(|(_, review)| review));
+    })
+    .await
+}
+
+/// Gets whether a specific review is already liked by a user.
+pub async fn is_already_liked(
+    client: &PgPool,
+    user_id: &str,
+    review_id: &str,
+) -> Result<bool, Error> {
+    traced("is_already_liked", async move {
+        let rows = sqlx::query(
+            "SELECT id
+        FROM   likes
+        WHERE  review_id = $1
+            AND user_id = $2 ",
+        )
+        .bind(review_id)
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.len() > 0);
+    })
+    .await
+}
+
+/// Gets all the replies for a given review.
+/// ## Does not validate the review is able to be viewed by calling user.
+pub async fn get_all_replies(client: &PgPool, review_id: &str) -> Result<Vec<Reply>, Error> {
+    traced("get_all_replies", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   reply
+        WHERE  review_id = $1 ",
+        )
+        .bind(review_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Reply::from).collect());
+    })
+    .await
+}
+
+/// Gets all the pics for a given review.
+/// ## Does not validate the review is able to be viewed by calling user.
+pub async fn get_all_pics(client: &PgPool, review_id: &str) -> Result<Vec<Pic>, Error> {
+    traced("get_all_pics", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   pic
+        WHERE  review_id = $1 ",
+        )
+        .bind(review_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Pic::from).collect());
+    })
+    .await
+}
+
+/// Gets all the replies for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_replies_for_reviews(
+    client: &PgPool,
+    review_ids: &[String],
+) -> Result<Vec<Reply>, Error> {
+    traced("get_replies_for_reviews", async move {
+        let rows = sqlx::query("SELECT * FROM reply WHERE review_id = ANY($1)")
+            .bind(review_ids)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(Reply::from).collect());
+    })
+    .await
+}
+
+/// Gets all the pics for any of the given reviews.
+/// ## Does not validate the reviews are able to be viewed by calling user.
+pub async fn get_pics_for_reviews(
+    client: &PgPool,
+    review_ids: &[String],
+) -> Result<Vec<Pic>, Error> {
+    traced("get_pics_for_reviews", async move {
+        let rows = sqlx::query("SELECT * FROM pic WHERE review_id = ANY($1)")
+            .bind(review_ids)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(Pic::from).collect());
+    })
+    .await
+}
+
+/// Version of the notifications `get_notifications` reads from.
+pub async fn get_notifications_version(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<DataVersion>, Error> {
+    traced("get_notifications_version", async move {
+        let rows = sqlx::query(
+            "SELECT COUNT(*) AS count, MAX(n.created) AS latest
+        FROM   notification AS n
+        INNER JOIN review AS r ON r.id = n.review_id
+        WHERE  n.review_user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(DataVersion::from).collect());
+    })
+    .await
+}
+
+/// Gets the top 50 latest notifications for the user.
+pub async fn get_notifications(
+    client: &PgPool,
+    user_id: &str,
+) -> Result<Vec<ExpandedNotification>, Error> {
+    traced("get_notifications", async move {
+        let rows = sqlx::query(
+            "SELECT n.id, n.created, n.review_user_id, n.user_id, n.review_id, n.action_type, r.location_name AS review_location
+        FROM   notification AS n
+        INNER JOIN review as r on r.id = n.review_id
+    WHERE  n.review_user_id = $1
+        ORDER BY n.created DESC
+    LIMIT 50",
+        )
+        .bind(user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(ExpandedNotification::from).collect());
+    })
+    .await
+}
+
+/// Gets the count of notifications the user has pending.
+pub async fn get_notification_count(client: &PgPool, user_id: &str) -> i64 {
+    let count_res = traced("get_notification_count", async move {
+        sqlx::query("SELECT count(*) as count FROM notification WHERE review_user_id = $1")
+            .bind(user_id)
+            .fetch_one(client)
+            .await
+            .map(|row| -> i64 { row.get("count") })
+    })
+    .await;
+
+    match count_res {
+        Ok(count) => return count,
+        Err(_) => return 0,
+    }
+}
+
+/// Searches for users that match any of the given phone numbers.
+/// It's expected the caller randomize the ordering to ensure
+/// reverse lookups are not as efficient.
+pub async fn phone_number_discovery(
+    client: &PgPool,
+    numbers: &Vec<&str>,
+) -> Result<Vec<User>, Error> {
+    traced("phone_number_discovery", async move {
+        let rows = sqlx::query("SELECT * FROM \"user\" WHERE phone = ANY($1)")
+            .bind(numbers)
+            .fetch_all(client)
+            .await?;
+
+        return Ok(rows.iter().map(User::from).collect());
+    })
+    .await
+}
+
+/// Gets whether users are friends with eachother.
+/// Order of user_id params doesn't really matter.
+pub async fn are_users_friends(
+    client: &PgPool,
+    user_id: &str,
+    other_user_id: &str,
+) -> Result<bool, Error> {
+    traced("are_users_friends", async move {
+        let rows = sqlx::query(
+            "SELECT id
+        FROM   friend
+        WHERE  user_id = $1
+            AND friend_id = $2 ",
+        )
+        .bind(user_id)
+        .bind(other_user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.len() > 0);
+    })
+    .await
+}
+
+/// Gets a reply with a specific Id from a specific review.
+pub async fn get_reply(
+    client: &PgPool,
+    review_id: &str,
+    reply_id: &str,
+) -> Result<Option<Reply>, Error> {
+    traced("get_reply", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   reply
+        WHERE  id = $1
+            AND review_id = $2",
+        )
+        .bind(reply_id)
+        .bind(review_id)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(Reply::from));
+    })
+    .await
+}
+
+/// Gets all bookmarks fromm a friend of this user.
+/// Accounts for the passed user_id's friends and own bookmarks.
+pub async fn get_all_bookmarks(
+    client: &PgPool,
+    user_id: &str,
+    target_user_id: &str,
+) -> Result<Vec<Bookmark>, Error> {
+    traced("get_all_bookmarks", async move {
+        let rows = sqlx::query(
+            "SELECT bm.id,
+        bm.user_id,
+        bm.created,
+        bm.category,
+        bm.location_name,
+        ST_X(bm.location) as longitude,
+        ST_Y(bm.location) as latitude
+        FROM   bookmark AS bm
+               INNER JOIN friend AS f
+                       ON bm.user_id = f.friend_id
+        WHERE  f.user_id = $1
+            AND bm.user_id = $2
+        ORDER BY bm.created DESC",
+        )
+        .bind(user_id)
+        .bind(target_user_id)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Bookmark::from).collect());
+    })
+    .await
+}
+
+/// Gets the existence of a bookmark by `bookmark.user_id` and `bookmark.id`. True if exists, false if not.
+pub async fn does_bookmark_exist(
+    client: &PgPool,
+    user_id: &str,
+    location_name: &str,
+    latitude: f64,
+    longitude: f64,
+) -> Result<bool, Error> {
+    traced("does_bookmark_exist", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let rows = sqlx::query(
+            "SELECT r.id
+        FROM   bookmark AS r
+        WHERE  r.user_id = $1
+               AND r.location_name = $2
+               AND ST_DWithin(r.location, ST_MakePoint($3, $4), $5)",
+        )
+        .bind(user_id)
+        .bind(location_name)
+        .bind(longitude)
+        .bind(latitude)
+        .bind(ACCURACY_SIZE)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.len() >= 1);
+    })
+    .await
+}
+
+/// Of the given reviews, the ids of those the user has bookmarked the location of.
+/// Matches the same way as `does_bookmark_exist`.
+pub async fn get_bookmarked_review_ids(
+    client: &PgPool,
+    user_id: &str,
+    review_ids: &[String],
+) -> Result<Vec<String>, Error> {
+    traced("get_bookmarked_review_ids", async move {
+        const ACCURACY_SIZE: f64 = 0.001;
+        let rows = sqlx::query(
+            "SELECT r.id
+        FROM   review AS r
+        WHERE  r.id = ANY($1)
+               AND EXISTS (SELECT 1
+                           FROM   bookmark AS bm
+                           WHERE  bm.user_id = $2
+                                  AND bm.location_name = r.location_name
+                                  AND ST_DWithin(bm.location, r.location, $3))",
+        )
+        .bind(review_ids)
+        .bind(user_id)
+        .bind(ACCURACY_SIZE)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(|row| row.get("id")).collect());
+    })
+    .await
+}
+
+/// Gets all bookmarks from a nearby radius
+/// Accounts for the passed user_id's fiends and own bookmarks.
+pub async fn get_nearby_bookmarks(
+    client: &PgPool,
+    calling_user_id: &str,
+    user_id: &str,
+    page: &PageRequest,
+    latitude: f64,
+    longitude: f64,
+) -> Result<Page<Bookmark>, Error> {
+    traced("get_nearby_bookmarks", async move {
+        const ACCURACY_SIZE: f64 = 0.025;
+
+        let (before_created, before_id) = page.before();
+
+        let rows = sqlx::query(
+            "SELECT bm.id,
+        bm.user_id,
+        bm.created,
+        bm.category,
+        bm.location_name,
+        ST_X(bm.location) as longitude,
+        ST_Y(bm.location) as latitude
+        FROM   bookmark AS bm
+               INNER JOIN friend AS f
+                       ON bm.user_id = f.friend_id
+        WHERE  f.user_id = $1
+            AND bm.user_id = $2
+               AND ST_DWithin(bm.location, ST_MakePoint($3, $4), $5)
+               AND ( bm.created, bm.id ) < ( $6, $7 )
+            ORDER BY bm.created DESC, bm.id DESC
+               LIMIT  $8 ",
+        )
+        .bind(calling_user_id)
+        .bind(user_id)
+        .bind(longitude)
+        .bind(latitude)
+        .bind(ACCURACY_SIZE)
+        .bind(before_created)
+        .bind(before_id)
+        .bind(page.fetch_limit() as i64)
+        .fetch_all(client)
+        .await?;
+
+        let bookmarks = rows.iter().map(Bookmark::from).collect();
+
+        return Ok(Page::from_rows(bookmarks, page, Bookmark::cursor));
+    })
+    .await
+}
+
+/// Gets all ipblock rules that have not expired yet.
+pub async fn get_active_ipblocks(client: &PgPool) -> Result<Vec<IpBlock>, Error> {
+    traced("get_active_ipblocks", async move {
+        let rows = sqlx::query(
+            "SELECT *
+        FROM   ipblock
+        WHERE  expires IS NULL
+                OR expires > $1 ",
+        )
+        .bind(Utc::now().naive_utc())
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(IpBlock::from).collect());
+    })
+    .await
+}
+
+/// Gets the user's idempotency key, unless it was first used before `since`.
+pub async fn get_idempotency_key(
+    client: &PgPool,
+    user_id: &str,
+    idempotency_key: &str,
+    since: &NaiveDateTime,
+) -> Result<Option<IdempotencyKey>, Error> {
+    traced("get_idempotency_key", async move {
+        let row = sqlx::query(
+            "SELECT *
+        FROM   idempotencykey
+        WHERE  user_id = $1
+               AND idempotency_key = $2
+               AND created >= $3 ",
+        )
+        .bind(user_id)
+        .bind(idempotency_key)
+        .bind(since)
+        .fetch_optional(client)
+        .await?;
+
+        return Ok(row.as_ref().map(IdempotencyKey::from));
+    })
+    .await
+}
@@ -0,0 +1,162 @@
+//! Queries for `/sync`. Each returns what changed at or after `since` among the rows the user can see.
+//! Everything from a friendship made at or after `since` is included whatever its age, the client has never seen it.
+
+use sqlx::{types::chrono::NaiveDateTime, Error, PgPool};
+
+use super::traced;
+use crate::db::{Bookmark, Like, Reply, Review, Tombstone};
+
+/// Reviews from the user's friends created or edited since `since`.
+pub async fn get_reviews_changed_since(
+    client: &PgPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Review>, Error> {
+    traced("get_reviews_changed_since", async move {
+        let rows = sqlx::query(
+            "SELECT r.id,
+        r.user_id,
+        r.created,
+        r.category,
+        r.text,
+        r.stars,
+        r.location_name,
+        ST_X(r.location) as longitude,
+        ST_Y(r.location) as latitude,
+        r.is_custom,
+        r.delivered,
+        r.recommended
+        FROM   review AS r
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND ( r.updated >= $2
+                      OR f.created >= $2 )
+        ORDER  BY r.updated ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Review::from).collect());
+    })
+    .await
+}
+
+/// Likes made since `since` on reviews from the user's friends.
+pub async fn get_likes_since(
+    client: &PgPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Like>, Error> {
+    traced("get_likes_since", async move {
+        let rows = sqlx::query(
+            "SELECT l.*
+        FROM   likes AS l
+               INNER JOIN review AS r
+                       ON r.id = l.review_id
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND ( l.created >= $2
+                      OR f.created >= $2 )
+        ORDER  BY l.created ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Like::from).collect());
+    })
+    .await
+}
+
+/// Replies made since `since` on reviews from the user's friends.
+pub async fn get_replies_since(
+    client: &PgPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Reply>, Error> {
+    traced("get_replies_since", async move {
+        let rows = sqlx::query(
+            "SELECT rp.*
+        FROM   reply AS rp
+               INNER JOIN review AS r
+                       ON r.id = rp.review_id
+               INNER JOIN friend AS f
+                       ON r.user_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND ( rp.created >= $2
+                      OR f.created >= $2 )
+        ORDER  BY rp.created ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Reply::from).collect());
+    })
+    .await
+}
+
+/// Bookmarks the user's friends made since `since`.
+pub async fn get_bookmarks_since(
+    client: &PgPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Bookmark>, Error> {
+    traced("get_bookmarks_since", async move {
+        let rows = sqlx::query(
+            "SELECT bm.id,
+        bm.user_id,
+        bm.created,
+        bm.category,
+        bm.location_name,
+        ST_X(bm.location) as longitude,
+        ST_Y(bm.location) as latitude
+        FROM   bookmark AS bm
+               INNER JOIN friend AS f
+                       ON bm.user_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND ( bm.created >= $2
+                      OR f.created >= $2 )
+        ORDER  BY bm.created ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Bookmark::from).collect());
+    })
+    .await
+}
+
+/// Reviews, replies, likes and bookmarks deleted since `since` that the user's friends owned.
+pub async fn get_tombstones_since(
+    client: &PgPool,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Result<Vec<Tombstone>, Error> {
+    traced("get_tombstones_since", async move {
+        let rows = sqlx::query(
+            "SELECT t.*
+        FROM   tombstone AS t
+               INNER JOIN friend AS f
+                       ON t.owner_id = f.friend_id
+        WHERE  f.user_id = $1
+               AND t.created >= $2
+        ORDER  BY t.created ASC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(client)
+        .await?;
+
+        return Ok(rows.iter().map(Tombstone::from).collect());
+    })
+    .await
+}
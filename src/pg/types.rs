@@ -0,0 +1,298 @@
+use sqlx::{postgres::PgRow, Row};
+
+use crate::db::{
+    AuthAttempt, Bookmark, DataVersion, ExpandedNotification, Friend, FriendRequest,
+    IdempotencyKey, IpBlock, Like, Notification, PendingNotification, PhoneAuth, Pic, Reply,
+    Report, Review, ReviewAnnotation, SchemaMigration, Tombstone, User, UserActivityCounts,
+};
+
+// Postgres has no unsigned or single byte integers, so flags and small numbers are stored
+// one size up and narrowed back into the types the MySQL backend returns.
+
+impl From<&PgRow> for User {
+    fn from(row: &PgRow) -> User {
+        User {
+            id: row.get("id"),
+            name: row.get("name"),
+            display_name: row.get("display_name"),
+            phone: row.get("phone"),
+            created: row.get("created"),
+            pic_id: row.get("pic_id"),
+            device_token: row.get("device_token"),
+            email: row.get("email"),
+            disabled: row.get::<i16, _>("disabled") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for PhoneAuth {
+    fn from(row: &PgRow) -> PhoneAuth {
+        PhoneAuth {
+            id: row.get("id"),
+            phone: row.get("phone"),
+            created: row.get("created"),
+            ip: row.get("ip"),
+            code: row.get("code"),
+            used: row.get::<i16, _>("used") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for AuthAttempt {
+    fn from(row: &PgRow) -> AuthAttempt {
+        AuthAttempt {
+            id: row.get("id"),
+            phone: row.get("phone"),
+            created: row.get("created"),
+        }
+    }
+}
+
+impl From<&PgRow> for Friend {
+    fn from(row: &PgRow) -> Friend {
+        Friend {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            friend_id: row.get("friend_id"),
+        }
+    }
+}
+
+impl From<&PgRow> for FriendRequest {
+    fn from(row: &PgRow) -> FriendRequest {
+        FriendRequest {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            friend_id: row.get("friend_id"),
+            ignored: row.get::<i16, _>("ignored") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for Pic {
+    fn from(row: &PgRow) -> Pic {
+        Pic {
+            id: row.get("id"),
+            review_id: row.get("review_id"),
+            created: row.get("created"),
+            pic_handler: row.get::<i16, _>("pic_handler") as u8,
+            width: row.get::<i32, _>("width") as u16,
+            height: row.get::<i32, _>("height") as u16,
+        }
+    }
+}
+
+impl From<&PgRow> for Review {
+    fn from(row: &PgRow) -> Review {
+        Review {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            created: row.get("created"),
+            category: row.get("category"),
+            text: row.get("text"),
+            stars: row.get::<i16, _>("stars") as u8,
+            location_name: row.get("location_name"),
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+            is_custom: row.get::<i16, _>("is_custom") as i8,
+            delivered: row.get::<i16, _>("delivered") as i8,
+            recommended: row.get::<i16, _>("recommended") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for ReviewAnnotation {
+    fn from(row: &PgRow) -> ReviewAnnotation {
+        ReviewAnnotation {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            created: row.get("created"),
+            pic_id: row.get("pic_id"),
+            pic_handler: row
+                .get::<Option<i16>, _>("pic_handler")
+                .map(|pic_handler| pic_handler as u8),
+            category: row.get("category"),
+            location_name: row.get("location_name"),
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+            is_custom: row.get::<i16, _>("is_custom") as i8,
+            delivered: row.get::<i16, _>("delivered") as i8,
+            recommended: row.get::<i16, _>("recommended") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for Like {
+    fn from(row: &PgRow) -> Like {
+        Like {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            review_id: row.get("review_id"),
+            like_type: row.get::<i16, _>("like_type") as i8,
+        }
+    }
+}
+
+impl From<&PgRow> for Reply {
+    fn from(row: &PgRow) -> Reply {
+        Reply {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            review_id: row.get("review_id"),
+            text: row.get("text"),
+            reply_to_id: row.get("reply_to_id"),
+        }
+    }
+}
+
+impl From<&PgRow> for Notification {
+    fn from(row: &PgRow) -> Notification {
+        Notification {
+            id: row.get("id"),
+            created: row.get("created"),
+            review_user_id: row.get("review_user_id"),
+            user_id: row.get("user_id"),
+            review_id: row.get("review_id"),
+            action_type: row.get::<i16, _>("action_type") as u8,
+        }
+    }
+}
+
+impl From<&PgRow> for ExpandedNotification {
+    fn from(row: &PgRow) -> ExpandedNotification {
+        ExpandedNotification {
+            id: row.get("id"),
+            created: row.get("created"),
+            review_user_id: row.get("review_user_id"),
+            user_id: row.get("user_id"),
+            review_id: row.get("review_id"),
+            action_type: row.get::<i16, _>("action_type") as u8,
+            review_location: row.get("review_location"),
+        }
+    }
+}
+
+impl From<&PgRow> for Report {
+    fn from(row: &PgRow) -> Report {
+        Report {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            reporter_id: row.get("reporter_id"),
+            report_type: row.get::<i16, _>("report_type") as u8,
+        }
+    }
+}
+
+impl From<&PgRow> for Bookmark {
+    fn from(row: &PgRow) -> Bookmark {
+        Bookmark {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            created: row.get("created"),
+            category: row.get("category"),
+            location_name: row.get("location_name"),
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+        }
+    }
+}
+
+impl From<&PgRow> for Tombstone {
+    fn from(row: &PgRow) -> Tombstone {
+        Tombstone {
+            id: row.get("id"),
+            created: row.get("created"),
+            item_type: row.get("item_type"),
+            item_id: row.get("item_id"),
+            owner_id: row.get("owner_id"),
+        }
+    }
+}
+
+impl From<&PgRow> for IdempotencyKey {
+    fn from(row: &PgRow) -> IdempotencyKey {
+        IdempotencyKey {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            idempotency_key: row.get("idempotency_key"),
+            fingerprint: row.get("fingerprint"),
+            status: row
+                .get::<Option<i32>, _>("status")
+                .map(|status| status as u16),
+            headers: row.get("headers"),
+            body: row.get("body"),
+        }
+    }
+}
+
+impl From<&PgRow> for UserActivityCounts {
+    fn from(row: &PgRow) -> UserActivityCounts {
+        UserActivityCounts {
+            reviews: row.get("reviews"),
+            friends: row.get("friends"),
+            incoming_requests: row.get("incoming_requests"),
+            outgoing_requests: row.get("outgoing_requests"),
+            likes: row.get("likes"),
+            replies: row.get("replies"),
+            bookmarks: row.get("bookmarks"),
+            notifications: row.get("notifications"),
+        }
+    }
+}
+
+impl From<&PgRow> for IpBlock {
+    fn from(row: &PgRow) -> IpBlock {
+        IpBlock {
+            id: row.get("id"),
+            created: row.get("created"),
+            created_by: row.get("created_by"),
+            action: row.get("action"),
+            cidr: row.get("cidr"),
+            asn: row.get::<Option<i64>, _>("asn").map(|asn| asn as u32),
+            country: row.get("country"),
+            reason: row.get("reason"),
+            expires: row.get("expires"),
+        }
+    }
+}
+
+impl From<&PgRow> for PendingNotification {
+    fn from(row: &PgRow) -> PendingNotification {
+        PendingNotification {
+            id: row.get("id"),
+            created: row.get("created"),
+            user_id: row.get("user_id"),
+            notification_value: row.get("notification_value"),
+            message: row.get("message"),
+            notification_type: row.get("notification_type"),
+        }
+    }
+}
+
+impl From<&PgRow> for SchemaMigration {
+    fn from(row: &PgRow) -> SchemaMigration {
+        SchemaMigration {
+            version: row.get("version"),
+            description: row.get("description"),
+            installed_on: row.get("installed_on"),
+            success: row.get("success"),
+            checksum: row.get("checksum"),
+            execution_time: row.get("execution_time"),
+        }
+    }
+}
+
+impl From<&PgRow> for DataVersion {
+    fn from(row: &PgRow) -> DataVersion {
+        DataVersion {
+            count: row.get("count"),
+            latest: row.get("latest"),
+        }
+    }
+}
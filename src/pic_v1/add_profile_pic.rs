@@ -1,28 +1,37 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{create_pic, get_user, update_user_pic_id},
-    pic_v1::TARGET_DO_BUCKET,
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::{PicRepo, UserRepo},
 };
 use actix_web::{
     post,
     web::{Bytes, Data, ReqData},
     HttpResponse, Result,
 };
-use images::{ByteStream, PutObjectRequest, S3Client, S3};
-use sqlx::MySqlPool;
+use images::S3Client;
 use validation::validate_profile_pic;
 
-use super::shared_utils::best_effort_delete_pic;
+use super::shared_utils::{best_effort_delete_pic, upload_pic};
 
 /// Allows users to update their profile pic.
+#[utoipa::path(
+    context_path = "/api/v1/pic",
+    tag = "pic",
+    responses(
+        (status = 200, description = "pic was stored"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/profile_pic")]
 pub async fn add_profile_pic(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     pic_bytes: Bytes,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let validation_result = validate_profile_pic(&pic_bytes);
 
     let width: u16;
@@ -34,59 +43,54 @@ pub async fn add_profile_pic(
             height = size.1;
         }
         Err(err) => {
-            return Ok(HttpResponse::BadRequest().body(err));
+            return Err(AppError::bad_request(ErrorCode::InvalidInput, err));
         }
     }
 
     let previous_pic_id: String;
-    let user_res = get_user(&pool, &authenticated_user.0).await;
+    let user_res = user_repo.get_user(&authenticated_user.0).await;
 
     match user_res {
         Ok(user_opt) => {
             if let Some(user) = user_opt {
                 previous_pic_id = user.pic_id;
             } else {
-                return Ok(HttpResponse::NotFound().body("could not find user"));
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("unable to get user"));
+            return Err(AppError::internal("unable to get user", error));
         }
     }
 
-    let pic_res = create_pic(&pool, None, width, height).await;
+    let pic_res = pic_repo.create_pic(None, width, height).await;
 
     match pic_res {
         Ok(pic) => {
             // We'll always put profile pics here into DO.
             // This saves a join on querying users.
-            if let Err(error) = s3_client
-                .put_object(PutObjectRequest {
-                    body: Some(ByteStream::from(<Vec<u8>>::from(pic_bytes))),
-                    bucket: TARGET_DO_BUCKET.to_string(),
-                    key: pic.id.clone(),
-                    acl: Some("public-read".to_string()),
-                    ..Default::default()
-                })
-                .await
+            if let Err(error) =
+                upload_pic(&s3_client, &pic.id, <Vec<u8>>::from(pic_bytes), "profile").await
             {
-                add_error_span(&error);
-                return Ok(HttpResponse::InternalServerError().body("unable to store profile pic"));
+                return Err(AppError::internal("unable to store profile pic", error));
             }
 
-            if let Err(error) = update_user_pic_id(&pool, &pic.id, &authenticated_user.0).await {
-                add_error_span(&error);
-                return Ok(HttpResponse::InternalServerError().body("unable to save profile pic"));
+            if let Err(error) = user_repo
+                .update_user_pic_id(&pic.id, &authenticated_user.0)
+                .await
+            {
+                return Err(AppError::internal("unable to save profile pic", error));
             }
 
-            best_effort_delete_pic(&s3_client, &pool, &previous_pic_id).await; // best effort - we can clean up stored images later
+            best_effort_delete_pic(&s3_client, pic_repo.get_ref(), &previous_pic_id).await; // best effort - we can clean up stored images later
 
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("unable to create profile pic"));
+            return Err(AppError::internal("unable to create profile pic", error));
         }
     }
 }
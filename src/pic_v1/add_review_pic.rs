@@ -1,33 +1,48 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{create_pic, get_all_pics, get_review, remove_review_pic_id, Review},
-    pic_v1::TARGET_DO_BUCKET,
-    tracing::add_error_span,
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    idempotency::{IdempotencyKeyHeader, Idempotent},
+    pic_v1::shared_utils::upload_pic,
+    repo::{PicRepo, ReviewRepo},
 };
 use actix_web::{
     post,
     web::{Bytes, Data, Query, ReqData},
     HttpResponse, Result,
 };
-use images::{ByteStream, PutObjectRequest, S3Client, S3};
+use images::S3Client;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 use validation::validate_review_pic;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AddReviewPicRequest {
     review_id: String,
 }
 
 /// Allows users to add a pic to their review.
-#[post("/review_pic")]
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "pic",
+    params(IdempotencyKeyHeader),
+    responses(
+        (status = 200, description = "pic was stored"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/review_pic", wrap = "Idempotent")]
 pub async fn add_review_pic(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     add_review_pic_request: Query<AddReviewPicRequest>,
     pic_bytes: Bytes,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let validation_result = validate_review_pic(&pic_bytes);
 
     let width: u16;
@@ -39,16 +54,13 @@ pub async fn add_review_pic(
             height = size.1;
         }
         Err(err) => {
-            return Ok(HttpResponse::BadRequest().body(err));
+            return Err(AppError::bad_request(ErrorCode::InvalidInput, err));
         }
     }
 
-    let review_res = get_review(
-        &pool,
-        &authenticated_user.0,
-        &add_review_pic_request.review_id,
-    )
-    .await;
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &add_review_pic_request.review_id)
+        .await;
 
     let review: Review;
     match review_res {
@@ -56,64 +68,62 @@ pub async fn add_review_pic(
             if let Some(review_tmp) = review_opt {
                 review = review_tmp;
             } else {
-                return Ok(HttpResponse::NotFound().body("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("failed to get review"));
+            return Err(AppError::internal("failed to get review", error));
         }
     }
 
     if review.user_id != authenticated_user.0 {
-        return Ok(HttpResponse::InternalServerError().body("unable to edit this review"));
+        return Err(AppError::forbidden(
+            ErrorCode::NotOwner,
+            "unable to edit this review",
+        ));
     }
 
-    let pics_res = get_all_pics(&pool, &review.id).await;
+    let pics_res = pic_repo.get_all_pics(&review.id).await;
 
     match pics_res {
         Ok(pics) => {
             if pics.len() >= 7 {
-                return Ok(HttpResponse::BadRequest().body("too many pics already"));
+                return Err(AppError::bad_request(
+                    ErrorCode::TooManyPics,
+                    "too many pics already",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("unable to get pics"));
+            return Err(AppError::internal("unable to get pics", error));
         }
     }
 
-    let pic_res = create_pic(
-        &pool,
-        Some(add_review_pic_request.review_id.clone()),
-        width,
-        height,
-    )
-    .await;
+    let pic_res = pic_repo
+        .create_pic(
+            Some(add_review_pic_request.review_id.clone()),
+            width,
+            height,
+        )
+        .await;
 
     match pic_res {
         Ok(pic) => {
-            if let Err(error) = s3_client
-                .put_object(PutObjectRequest {
-                    body: Some(ByteStream::from(<Vec<u8>>::from(pic_bytes))),
-                    bucket: TARGET_DO_BUCKET.to_string(),
-                    key: pic.id.clone(),
-                    acl: Some("public-read".to_string()),
-                    ..Default::default()
-                })
-                .await
+            if let Err(error) =
+                upload_pic(&s3_client, &pic.id, <Vec<u8>>::from(pic_bytes), "review").await
             {
-                add_error_span(&error);
-                let _ = remove_review_pic_id(&pool, &pic.id, &review.id).await;
+                let _ = pic_repo.remove_review_pic_id(&pic.id, &review.id).await;
 
-                return Ok(HttpResponse::InternalServerError().body("unable to store review pic"));
+                return Err(AppError::internal("unable to store review pic", error));
             }
 
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("unable to create review pic"));
+            return Err(AppError::internal("unable to create review pic", error));
         }
     }
 }
@@ -1,6 +1,10 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_pic, get_user, Pic},
+    db::Pic,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    pic_v1::get_spaces_bucket,
+    repo::{PicRepo, UserRepo},
 };
 use actix_web::{
     get,
@@ -10,48 +14,62 @@ use actix_web::{
 };
 use images::{GetObjectOutput, GetObjectRequest, S3Client, S3};
 use serde::Deserialize;
-use sqlx::MySqlPool;
 use tokio::io::AsyncReadExt;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ProfilePicRequest {
     user_id: String,
 }
 
 /// Fetches a profile pic set for a user.
+#[utoipa::path(
+    context_path = "/api/v1/pic",
+    tag = "pic",
+    responses(
+        (status = 200, description = "profile pic as a jpeg", content_type = "image/jpeg"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/profile_pic")]
 pub async fn get_profile_pic(
     _authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     avatar_request: Query<ProfilePicRequest>,
-) -> Result<HttpResponse> {
+) -> Result<HttpResponse, AppError> {
     let pic_id: String;
-    if let Ok(user_opt) = get_user(&pool, &avatar_request.user_id).await {
+    if let Ok(user_opt) = user_repo.get_user(&avatar_request.user_id).await {
         if let Some(user) = user_opt {
             pic_id = user.pic_id;
         } else {
-            return Ok(HttpResponse::NotFound().body("could not find user"));
+            return Err(AppError::not_found(
+                ErrorCode::UserNotFound,
+                "could not find user",
+            ));
         }
     } else {
-        return Ok(HttpResponse::InternalServerError().body("failed to get user"));
+        return Err(AppError::internal_message("failed to get user"));
     }
 
     let pic: Pic;
-    if let Ok(pic_opt) = get_pic(&pool, &pic_id).await {
+    if let Ok(pic_opt) = pic_repo.get_pic(&pic_id).await {
         if let Some(pic_tmp) = pic_opt {
             pic = pic_tmp;
         } else {
-            return Ok(HttpResponse::NotFound().body("pic not found"));
+            return Err(AppError::not_found(ErrorCode::PicNotFound, "pic not found"));
         }
     } else {
-        return Ok(HttpResponse::InternalServerError().body("failed to fetch pic"));
+        return Err(AppError::internal_message("failed to fetch pic"));
     }
 
     let pic_obj: GetObjectOutput;
     if let Ok(pic_obj_) = s3_client
         .get_object(GetObjectRequest {
-            bucket: "bout".to_string(),
+            bucket: get_spaces_bucket().to_string(),
             key: pic.id,
             ..Default::default()
         })
@@ -59,7 +77,7 @@ pub async fn get_profile_pic(
     {
         pic_obj = pic_obj_;
     } else {
-        return Ok(HttpResponse::InternalServerError().body("failed to fetch pic from db"));
+        return Err(AppError::internal_message("failed to fetch pic from db"));
     }
 
     let mut buf: Vec<u8> = Vec::new();
@@ -71,7 +89,9 @@ pub async fn get_profile_pic(
         .read_to_end(&mut buf)
         .await
     {
-        return Ok(HttpResponse::InternalServerError().body("failed to fetch pic from storage"));
+        return Err(AppError::internal_message(
+            "failed to fetch pic from storage",
+        ));
     }
 
     Ok(HttpResponse::Ok()
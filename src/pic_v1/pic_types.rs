@@ -1,27 +1,35 @@
-use crate::{db::Pic, is_dev};
+use crate::db::Pic;
 use serde::Serialize;
+use std::sync::OnceLock;
+use utoipa::ToSchema;
 
-lazy_static! {
-    pub static ref TARGET_DO_BUCKET: &'static str = {
-        if is_dev() {
-            "review-with-friends-dev"
-        } else {
-            "bout"
-        }
-    };
+/// Spaces bucket pics are stored in, set once from config at startup.
+static SPACES_BUCKET: OnceLock<String> = OnceLock::new();
+
+/// Sets the bucket pics are stored in. Only the first call has any effect.
+pub fn set_spaces_bucket(bucket: &str) {
+    let _ = SPACES_BUCKET.set(bucket.to_string());
+}
+
+/// Gets the bucket pics are stored in.
+pub fn get_spaces_bucket() -> &'static str {
+    SPACES_BUCKET
+        .get()
+        .expect("spaces bucket must be set from config at startup")
 }
 
 pub fn get_digital_ocean_url(pic_id: &str) -> String {
     format!(
         "https://{}.sfo3.cdn.digitaloceanspaces.com/{}",
-        *TARGET_DO_BUCKET, pic_id
+        get_spaces_bucket(),
+        pic_id
     )
 }
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PicPub {
     pub id: String,
     pub review_id: Option<String>,
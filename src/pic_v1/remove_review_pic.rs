@@ -1,8 +1,10 @@
 use super::shared_utils::best_effort_delete_pic;
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_all_pics, get_review, remove_review_pic_id, Pic, Review},
-    tracing::add_error_span,
+    db::{Pic, Review},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::{PicRepo, ReviewRepo},
 };
 use actix_web::{
     post,
@@ -11,27 +13,35 @@ use actix_web::{
 };
 use images::S3Client;
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RemoveReviewPicRequest {
     review_id: String,
     pic_id: String,
 }
 
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "pic",
+    responses(
+        (status = 200, description = "pic was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/remove_review_pic")]
 pub async fn remove_review_pic(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     remove_review_pic_request: Query<RemoveReviewPicRequest>,
-) -> Result<HttpResponse> {
-    let review_res = get_review(
-        &pool,
-        &authenticated_user.0,
-        &remove_review_pic_request.review_id,
-    )
-    .await;
+) -> Result<HttpResponse, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &remove_review_pic_request.review_id)
+        .await;
 
     let review: Review;
     match review_res {
@@ -39,27 +49,31 @@ pub async fn remove_review_pic(
             if let Some(review_tmp) = review_opt {
                 review = review_tmp;
             } else {
-                return Ok(HttpResponse::NotFound().body("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("failed to get review"));
+            return Err(AppError::internal("failed to get review", error));
         }
     }
 
     if review.user_id != authenticated_user.0 {
-        return Ok(HttpResponse::BadRequest().body("unable to edit this review"));
+        return Err(AppError::forbidden(
+            ErrorCode::NotOwner,
+            "unable to edit this review",
+        ));
     }
 
-    let pics_res = get_all_pics(&pool, &review.id).await;
+    let pics_res = pic_repo.get_all_pics(&review.id).await;
 
     let pics: Vec<Pic>;
     match pics_res {
         Ok(pics_tmp) => pics = pics_tmp,
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("unable to get pics"));
+            return Err(AppError::internal("unable to get pics", error));
         }
     }
 
@@ -67,17 +81,25 @@ pub async fn remove_review_pic(
         .iter()
         .any(|x| x.id == remove_review_pic_request.pic_id)
     {
-        return Ok(HttpResponse::BadRequest().body("pic doesnt exist"));
+        return Err(AppError::bad_request(
+            ErrorCode::PicNotFound,
+            "pic doesnt exist",
+        ));
     }
 
-    if let Err(error) =
-        remove_review_pic_id(&pool, &remove_review_pic_request.pic_id, &review.id).await
+    if let Err(error) = pic_repo
+        .remove_review_pic_id(&remove_review_pic_request.pic_id, &review.id)
+        .await
     {
-        add_error_span(&error);
-        return Ok(HttpResponse::InternalServerError().body("unable to remove pic"));
+        return Err(AppError::internal("unable to remove pic", error));
     }
 
-    best_effort_delete_pic(&s3_client, &pool, &remove_review_pic_request.pic_id).await;
+    best_effort_delete_pic(
+        &s3_client,
+        pic_repo.get_ref(),
+        &remove_review_pic_request.pic_id,
+    )
+    .await;
 
     return Ok(HttpResponse::Ok().finish());
 }
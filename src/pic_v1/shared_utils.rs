@@ -1,23 +1,51 @@
-use images::{DeleteObjectRequest, S3Client, DEFAULT_PIC_ID, S3};
-use sqlx::MySqlPool;
+use images::{
+    ByteStream, DeleteObjectRequest, PutObjectError, PutObjectOutput, PutObjectRequest,
+    RusotoError, S3Client, DEFAULT_PIC_ID, S3,
+};
+use std::time::Instant;
 
-use crate::db::delete_pic;
+use crate::{metrics::observe_s3_upload, repo::PicRepo};
 
-use super::TARGET_DO_BUCKET;
+use super::get_spaces_bucket;
 
-pub async fn best_effort_delete_pic(s3_client: &S3Client, pool: &MySqlPool, pic_id: &str) {
+pub async fn best_effort_delete_pic(s3_client: &S3Client, pic_repo: &dyn PicRepo, pic_id: &str) {
     if pic_id == DEFAULT_PIC_ID {
         return; // dont cleanup our default image :D
     }
 
     if let Ok(_) = s3_client
         .delete_object(DeleteObjectRequest {
-            bucket: TARGET_DO_BUCKET.to_string(),
+            bucket: get_spaces_bucket().to_string(),
             key: pic_id.to_string(),
             ..Default::default()
         })
         .await
     {
-        let _delete_pic_res = delete_pic(pool, pic_id).await;
+        let _delete_pic_res = pic_repo.delete_pic(pic_id).await;
     }
 }
+
+/// Uploads a pic to Spaces as publicly readable, recording upload metrics under `kind`.
+pub async fn upload_pic(
+    s3_client: &S3Client,
+    pic_id: &str,
+    bytes: Vec<u8>,
+    kind: &str,
+) -> Result<PutObjectOutput, RusotoError<PutObjectError>> {
+    let size = bytes.len();
+    let start = Instant::now();
+
+    let put_res = s3_client
+        .put_object(PutObjectRequest {
+            body: Some(ByteStream::from(bytes)),
+            bucket: get_spaces_bucket().to_string(),
+            key: pic_id.to_string(),
+            acl: Some("public-read".to_string()),
+            ..Default::default()
+        })
+        .await;
+
+    observe_s3_upload(kind, size, start.elapsed(), put_res.is_ok());
+
+    return put_res;
+}
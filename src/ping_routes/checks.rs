@@ -0,0 +1,101 @@
+use crate::{
+    notifications_v1::{APNClient, NotificationWorkerHeartbeat},
+    pic_v1::get_spaces_bucket,
+    repo::DatabaseRepo,
+};
+use images::{HeadBucketRequest, S3Client, S3};
+use std::{future::Future, time::Duration};
+use tokio::time::{self, Instant};
+
+use super::{DependencyCheckPub, PING_ID};
+
+/// Longest a single check can take before the dependency is considered down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The notification worker loops every 100ms, or after each send. Sends time out after 5s.
+const MAX_HEARTBEAT_AGE_SECONDS: i64 = 30;
+
+/// Runs a check with a timeout, timing how long it takes.
+/// The check returns optional detail when up, or the reason it's down.
+async fn run_check<F>(name: &'static str, check: F) -> DependencyCheckPub
+where
+    F: Future<Output = Result<Option<String>, String>>,
+{
+    let start = Instant::now();
+
+    let result = match time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(result) => result,
+        Err(_) => Err("timed out".to_string()),
+    };
+
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(detail) => DependencyCheckPub {
+            name,
+            status: "up",
+            latency_ms,
+            detail,
+        },
+        Err(error) => DependencyCheckPub {
+            name,
+            status: "down",
+            latency_ms,
+            detail: Some(error),
+        },
+    }
+}
+
+/// Checks a connection can be taken from the pool and used.
+pub async fn check_database(database_repo: &dyn DatabaseRepo) -> DependencyCheckPub {
+    run_check("database", async {
+        database_repo
+            .get_ping(PING_ID)
+            .await
+            .map(|_| None)
+            .map_err(|error| error.to_string())
+    })
+    .await
+}
+
+/// Checks the pic bucket is reachable with our credentials.
+pub async fn check_spaces(s3_client: &S3Client) -> DependencyCheckPub {
+    run_check("spaces", async {
+        s3_client
+            .head_bucket(HeadBucketRequest {
+                bucket: get_spaces_bucket().to_string(),
+                ..Default::default()
+            })
+            .await
+            .map(|_| None)
+            .map_err(|error| error.to_string())
+    })
+    .await
+}
+
+/// Checks a valid APNs provider token is available, minting a new one if it's due.
+pub async fn check_apns(apn_client: &APNClient) -> DependencyCheckPub {
+    run_check("apns", async {
+        apn_client.get_token()?;
+        let token_age = apn_client.get_token_age()?;
+
+        Ok(Some(format!("token age {}s", token_age)))
+    })
+    .await
+}
+
+/// Checks the notification worker is still looping.
+pub async fn check_notification_worker(
+    heartbeat: &NotificationWorkerHeartbeat,
+) -> DependencyCheckPub {
+    run_check("notification_worker", async {
+        let seconds_since_beat = heartbeat.seconds_since_beat();
+
+        if seconds_since_beat > MAX_HEARTBEAT_AGE_SECONDS {
+            return Err(format!("no heartbeat for {}s", seconds_since_beat));
+        }
+
+        Ok(Some(format!("last heartbeat {}s ago", seconds_since_beat)))
+    })
+    .await
+}
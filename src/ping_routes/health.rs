@@ -0,0 +1,67 @@
+use crate::{
+    notifications_v1::{APNClient, NotificationWorkerHeartbeat},
+    repo::DatabaseRepo,
+};
+use actix_web::{get, web::Data, HttpResponse, Responder, Result};
+use images::S3Client;
+
+use super::{check_apns, check_database, check_notification_worker, check_spaces, HealthPub};
+use crate::docs::ErrorResponses;
+
+/// Liveness probe. Only shows the process is up and serving requests.
+/// Dependencies are left out on purpose, a database outage shouldn't get every pod restarted.
+#[utoipa::path(
+    context_path = "/ping",
+    tag = "ping",
+    responses(
+        (status = 200, description = "process is running", body = HealthPub),
+        ErrorResponses
+    )
+)]
+#[get("/live")]
+pub async fn live() -> Result<impl Responder> {
+    return Ok(HttpResponse::Ok().json(HealthPub {
+        status: "up",
+        checks: vec![],
+    }));
+}
+
+/// Readiness probe. Checks every dependency we need to serve traffic.
+/// Returns a 503 when any of them are down, so the pod is taken out of rotation.
+#[utoipa::path(
+    context_path = "/ping",
+    tag = "ping",
+    responses(
+        (status = 200, description = "every dependency is healthy", body = HealthPub),
+        (status = 503, description = "a dependency is unhealthy", body = HealthPub),
+        ErrorResponses
+    )
+)]
+#[get("/ready")]
+pub async fn ready(
+    database_repo: Data<dyn DatabaseRepo>,
+    s3_client: Data<S3Client>,
+    apn_client: Data<APNClient>,
+    heartbeat: Data<NotificationWorkerHeartbeat>,
+) -> Result<impl Responder> {
+    let (database, spaces, apns, notification_worker) = futures::join!(
+        check_database(database_repo.get_ref()),
+        check_spaces(&s3_client),
+        check_apns(&apn_client),
+        check_notification_worker(&heartbeat),
+    );
+
+    let checks = vec![database, spaces, apns, notification_worker];
+    let is_up = checks.iter().all(|check| check.status == "up");
+
+    let health = HealthPub {
+        status: if is_up { "up" } else { "down" },
+        checks,
+    };
+
+    if is_up {
+        return Ok(HttpResponse::Ok().json(health));
+    } else {
+        return Ok(HttpResponse::ServiceUnavailable().json(health));
+    }
+}
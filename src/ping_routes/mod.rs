@@ -0,0 +1,11 @@
+pub mod ping;
+pub use ping::*;
+
+pub mod health;
+pub use health::*;
+
+pub mod checks;
+pub use checks::*;
+
+pub mod types;
+pub use types::*;
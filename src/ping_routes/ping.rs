@@ -0,0 +1,45 @@
+use crate::{docs::ErrorResponses, error::AppError, repo::DatabaseRepo};
+use actix_web::{get, web::Data, HttpResponse, Responder, Result};
+
+pub const PING_ID: &str = "123";
+
+/// Simple API for validating db connectivity.
+#[utoipa::path(
+    context_path = "/ping",
+    tag = "ping",
+    responses(
+        (status = 200, description = "database is reachable"),
+        ErrorResponses
+    )
+)]
+#[get("")]
+pub async fn ping(database_repo: Data<dyn DatabaseRepo>) -> Result<impl Responder, AppError> {
+    let ping_res = database_repo.get_ping(PING_ID).await;
+
+    match ping_res {
+        Ok(_) => Ok(HttpResponse::Ok()),
+        Err(error) => {
+            return Err(AppError::internal("failed to ping", error));
+        }
+    }
+}
+
+#[utoipa::path(
+    context_path = "/ping",
+    tag = "ping",
+    responses(
+        (status = 200, description = "database is reachable"),
+        ErrorResponses
+    )
+)]
+#[get("/error")]
+pub async fn ping_error(database_repo: Data<dyn DatabaseRepo>) -> Result<impl Responder, AppError> {
+    let ping_res = database_repo.get_ping("1234").await;
+
+    match ping_res {
+        Ok(_) => Ok(HttpResponse::Ok()),
+        Err(error) => {
+            return Err(AppError::internal("failed to ping", error));
+        }
+    }
+}
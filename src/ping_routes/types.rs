@@ -0,0 +1,23 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Result of checking a single dependency.
+#[derive(Serialize, ToSchema)]
+pub struct DependencyCheckPub {
+    /// Name of the dependency, i.e. `database`.
+    pub name: &'static str,
+    /// Either `up` or `down`.
+    pub status: &'static str,
+    /// How long the check took.
+    pub latency_ms: u64,
+    /// Extra context, such as the error when the dependency is down.
+    pub detail: Option<String>,
+}
+
+/// Overall health, with a breakdown per dependency.
+#[derive(Serialize, ToSchema)]
+pub struct HealthPub {
+    /// `up` only when every check is up.
+    pub status: &'static str,
+    pub checks: Vec<DependencyCheckPub>,
+}
@@ -0,0 +1,64 @@
+use moka::sync::Cache;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::{find_policy, RateLimitDecision, RateLimitKey, RateLimitPolicy, TokenBucket};
+
+/// Holds the configured policies, and a token bucket for every policy and subject pair seen recently.
+pub struct RateLimiter {
+    policies: Vec<RateLimitPolicy>,
+    buckets: Cache<String, Arc<Mutex<TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(policies: Vec<RateLimitPolicy>) -> Self {
+        // a bucket idle for a full period has refilled, so it is safe to forget about it.
+        let longest_period = policies
+            .iter()
+            .map(|policy| policy.period_seconds)
+            .max()
+            .unwrap_or(60);
+
+        RateLimiter {
+            policies,
+            buckets: Cache::builder()
+                .time_to_idle(Duration::from_secs(longest_period.max(1)))
+                .max_capacity(100_000)
+                .build(),
+        }
+    }
+
+    /// Finds the policy for a request, if the request is rate limited at all.
+    pub fn policy_for(&self, method: &str, path: &str) -> Option<&RateLimitPolicy> {
+        find_policy(&self.policies, method, path)
+    }
+
+    /// Takes a token from the bucket for the policy and subject, creating a full bucket if needed.
+    pub fn check(&self, policy: &RateLimitPolicy, subject: &str) -> RateLimitDecision {
+        let key = format!("{}:{}", policy.name, subject);
+
+        let bucket = self.buckets.get_with(key, || {
+            Arc::new(Mutex::new(TokenBucket::new(
+                policy.capacity,
+                Duration::from_secs(policy.period_seconds),
+            )))
+        });
+
+        let mut bucket = bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        bucket.try_take(Instant::now())
+    }
+}
+
+/// Picks the subject a request is grouped by for a policy.
+/// Unauthenticated requests have no user_id, so they are always grouped by ip.
+pub fn get_subject(key: &RateLimitKey, user_id: Option<&str>, ip: &str) -> String {
+    match (key, user_id) {
+        (RateLimitKey::User, Some(user_id)) => format!("user:{}", user_id),
+        _ => format!("ip:{}", ip),
+    }
+}
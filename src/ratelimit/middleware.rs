@@ -0,0 +1,108 @@
+use actix_web::{
+    body::EitherBody,
+    dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
+    web::Data,
+    Error, HttpMessage, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+use super::{get_subject, RateLimiter};
+use crate::{
+    authorization::AuthenticatedUser, client_ip::get_client_ip, error::AppError,
+    metrics::RATE_LIMIT_REJECTIONS_TOTAL,
+};
+
+pub struct RateLimit;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware { service }))
+    }
+}
+
+/// Rate limit middleware that applies the most specific configured policy to each request.
+/// Auth routes are limited by ip to stop auth spammers sending messages to tons of users, and to prevent brute forcing.
+/// Everything else is limited per user, so expensive routes can't be hammered.
+pub struct RateLimitMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let limiter = if let Some(limiter) = request.app_data::<Data<RateLimiter>>() {
+            limiter.clone()
+        } else {
+            // the limiter wasn't registered; something broke that isn't caught compile time
+            let (request, _pl) = request.into_parts();
+            let response = AppError::internal_message("rate limiter is not registered")
+                .error_response()
+                .map_into_right_body();
+            return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
+        };
+
+        let policy =
+            if let Some(policy) = limiter.policy_for(request.method().as_str(), request.path()) {
+                policy
+            } else {
+                // we are not a route subject to any rate limiting.
+                let res = self.service.call(request);
+                return Box::pin(async move { res.await.map(ServiceResponse::map_into_left_body) });
+            };
+
+        let ip = get_client_ip(request.request()).to_string();
+
+        let user_id = request
+            .extensions()
+            .get::<AuthenticatedUser>()
+            .map(|user| user.0.clone());
+
+        let subject = get_subject(&policy.key, user_id.as_deref(), &ip);
+        let decision = limiter.check(policy, &subject);
+
+        if !decision.allowed {
+            RATE_LIMIT_REJECTIONS_TOTAL
+                .with_label_values(&[&policy.name])
+                .inc();
+
+            // this request is rate limited, bop it.
+            let (request, _pl) = request.into_parts();
+            let mut response = AppError::too_many_requests("rate limit exceeded, try again later")
+                .error_response();
+            decision.apply_headers(response.headers_mut());
+
+            let response = response.map_into_right_body();
+            return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
+        }
+
+        let res = self.service.call(request);
+        return Box::pin(async move {
+            let mut res = res.await?;
+            decision.apply_headers(res.headers_mut());
+
+            Ok(res.map_into_left_body())
+        });
+    }
+}
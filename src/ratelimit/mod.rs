@@ -0,0 +1,11 @@
+pub mod limiter;
+pub use limiter::*;
+
+pub mod middleware;
+pub use middleware::*;
+
+pub mod policy;
+pub use policy::*;
+
+pub mod token_bucket;
+pub use token_bucket::*;
@@ -0,0 +1,146 @@
+use serde::Deserialize;
+
+/// What a rate limit bucket is keyed on.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    /// The authenticated user_id. Falls back to the ip for unauthenticated requests.
+    User,
+
+    /// The ip address the request came from.
+    Ip,
+}
+
+/// A rate limit applied to one route, or a group of routes.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitPolicy {
+    /// Unique name for the policy. Buckets are namespaced by this.
+    pub name: String,
+
+    /// Path the policy applies to.
+    /// A trailing `*` matches any path starting with the rest, otherwise the path must match exactly.
+    pub path: String,
+
+    /// Optional HTTP method the policy is restricted to, i.e. `POST`.
+    pub method: Option<String>,
+
+    /// The most requests allowed in a burst.
+    pub capacity: u32,
+
+    /// Seconds it takes an empty bucket to completely refill.
+    pub period_seconds: u64,
+
+    /// What requests are grouped by.
+    pub key: RateLimitKey,
+}
+
+impl RateLimitPolicy {
+    /// Gets whether the given request method and path are subject to this policy.
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        if let Some(policy_method) = &self.method {
+            if !policy_method.eq_ignore_ascii_case(method) {
+                return false;
+            }
+        }
+
+        match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.path,
+        }
+    }
+
+    /// Ranks how specific the policy is, used to choose between multiple matching policies.
+    /// Exact paths beat prefixes, longer prefixes beat shorter ones, and method restrictions break ties.
+    fn specificity(&self) -> (bool, usize, bool) {
+        (
+            !self.path.ends_with('*'),
+            self.path.len(),
+            self.method.is_some(),
+        )
+    }
+}
+
+/// Finds the most specific policy for the given request method and path.
+pub fn find_policy<'a>(
+    policies: &'a [RateLimitPolicy],
+    method: &str,
+    path: &str,
+) -> Option<&'a RateLimitPolicy> {
+    policies
+        .iter()
+        .filter(|policy| policy.matches(method, path))
+        .max_by_key(|policy| policy.specificity())
+}
+
+/// The policies used when none are configured.
+///
+/// Auth routes send SMS which is expensive when abused, so they are limited harshly by ip.
+/// Everything else is limited per user, with tighter limits on expensive routes.
+pub fn default_policies() -> Vec<RateLimitPolicy> {
+    vec![
+        RateLimitPolicy {
+            name: "auth".to_string(),
+            path: "/auth*".to_string(),
+            method: None,
+            capacity: 5,
+            period_seconds: 60,
+            key: RateLimitKey::Ip,
+        },
+        RateLimitPolicy {
+            name: "api".to_string(),
+            path: "/api/*".to_string(),
+            method: None,
+            capacity: 300,
+            period_seconds: 60,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "discover_friends".to_string(),
+            path: "/api/v1/friends/discover_friends".to_string(),
+            method: None,
+            capacity: 10,
+            period_seconds: 3600,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "search_user_by_name".to_string(),
+            path: "/api/v1/user/search_by_name".to_string(),
+            method: None,
+            capacity: 30,
+            period_seconds: 60,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "sync".to_string(),
+            path: "/api/v1/sync".to_string(),
+            method: None,
+            capacity: 30,
+            period_seconds: 60,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "add_review".to_string(),
+            path: "/api/v1/review/".to_string(),
+            method: Some("POST".to_string()),
+            capacity: 20,
+            period_seconds: 3600,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "add_review_pic".to_string(),
+            path: "/api/v1/review/review_pic".to_string(),
+            method: Some("POST".to_string()),
+            capacity: 30,
+            period_seconds: 3600,
+            key: RateLimitKey::User,
+        },
+        RateLimitPolicy {
+            name: "add_profile_pic".to_string(),
+            path: "/api/v1/pic/profile_pic".to_string(),
+            method: Some("POST".to_string()),
+            capacity: 10,
+            period_seconds: 3600,
+            key: RateLimitKey::User,
+        },
+    ]
+}
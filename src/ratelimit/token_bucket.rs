@@ -0,0 +1,96 @@
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use std::time::{Duration, Instant};
+
+/// Classic token bucket. Each request takes a token, and tokens refill continuously
+/// so a full bucket is restored after the policy period.
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let period_seconds = period.as_secs_f64().max(1.0);
+
+        TokenBucket {
+            capacity,
+            tokens: capacity,
+            refill_per_second: capacity / period_seconds,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills the bucket for the time passed and tries to take a single token.
+    pub fn try_take(&mut self, now: Instant) -> RateLimitDecision {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+
+        let retry_after_seconds = if allowed {
+            0
+        } else {
+            ((1.0 - self.tokens) / self.refill_per_second).ceil() as u64
+        };
+
+        RateLimitDecision {
+            allowed,
+            limit: self.capacity as u32,
+            remaining: self.tokens.floor() as u32,
+            reset_seconds: ((self.capacity - self.tokens) / self.refill_per_second).ceil() as u64,
+            retry_after_seconds,
+        }
+    }
+}
+
+/// Outcome of checking a request against its rate limit policy.
+pub struct RateLimitDecision {
+    /// Whether the request can continue.
+    pub allowed: bool,
+
+    /// Bucket capacity for the policy.
+    pub limit: u32,
+
+    /// Requests left before being limited.
+    pub remaining: u32,
+
+    /// Seconds until the bucket is completely refilled.
+    pub reset_seconds: u64,
+
+    /// Seconds until another request will be allowed. Only set when the request is limited.
+    pub retry_after_seconds: u64,
+}
+
+impl RateLimitDecision {
+    /// Writes the `RateLimit-*` headers, and `Retry-After` when limited.
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            HeaderName::from_static("ratelimit-limit"),
+            HeaderValue::from(self.limit),
+        );
+        headers.insert(
+            HeaderName::from_static("ratelimit-remaining"),
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            HeaderName::from_static("ratelimit-reset"),
+            HeaderValue::from(self.reset_seconds),
+        );
+
+        if !self.allowed {
+            headers.insert(
+                RETRY_AFTER,
+                HeaderValue::from(self.retry_after_seconds.max(1)),
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use actix_web::{
+    dev::{self, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    web::Data,
+    Error, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+use super::RecentWrites;
+use crate::authorization::AuthenticatedUser;
+
+/// POSTs that only read, taking a POST to fit a list of ids in the body.
+/// Counting them as writes would pin their callers to the primary for nothing.
+const READ_ONLY_POSTS: [&str; 2] = ["/api/v1/review/batch", "/api/v1/user/batch"];
+
+pub struct TrackWrites;
+
+impl<S, B> Transform<S, ServiceRequest> for TrackWrites
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = TrackWritesMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TrackWritesMiddleware { service }))
+    }
+}
+
+/// Records authenticated POST requests in `RecentWrites`, so that user's next reads come from the primary.
+/// Every write goes through a POST, so this catches them without each handler having to.
+/// The few POSTs in `READ_ONLY_POSTS` that only read are left out.
+/// Does nothing when `RecentWrites` isn't registered.
+pub struct TrackWritesMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TrackWritesMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        if request.method() == Method::POST && !READ_ONLY_POSTS.contains(&request.path()) {
+            if let Some(recent_writes) = request.app_data::<Data<RecentWrites>>() {
+                if let Some(user) = request.extensions().get::<AuthenticatedUser>() {
+                    // recorded before the handler runs, so reads racing the write also skip the replica.
+                    recent_writes.record(&user.0);
+                }
+            }
+        }
+
+        let res = self.service.call(request);
+        return Box::pin(async move { res.await });
+    }
+}
@@ -0,0 +1,8 @@
+pub mod recent_writes;
+pub use recent_writes::*;
+
+pub mod middleware;
+pub use middleware::*;
+
+pub mod routing;
+pub use routing::*;
@@ -0,0 +1,43 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Users who have written recently, so their reads can skip a lagging replica.
+pub struct RecentWrites {
+    window: Duration,
+    writes: Mutex<HashMap<String, Instant>>,
+}
+
+impl RecentWrites {
+    /// Reads stay on the primary for `window` after each write.
+    pub fn new(window: Duration) -> Self {
+        RecentWrites {
+            window,
+            writes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a write by the user, dropping any writes that have aged out of the window.
+    pub fn record(&self, user_id: &str) {
+        let now = Instant::now();
+
+        if let Ok(mut writes) = self.writes.lock() {
+            writes.retain(|_, written| now.duration_since(*written) < self.window);
+            writes.insert(user_id.to_string(), now);
+        }
+    }
+
+    /// Gets whether the user wrote within the window.
+    pub fn wrote_recently(&self, user_id: &str) -> bool {
+        match self.writes.lock() {
+            Ok(writes) => writes
+                .get(user_id)
+                .map(|written| written.elapsed() < self.window)
+                .unwrap_or(false),
+            // can't tell, so play it safe and read from the primary.
+            Err(_) => true,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use actix_web::web::Data;
+use sqlx::{Error, MySqlPool};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{task, time};
+
+use super::RecentWrites;
+use crate::{db::get_ping, metrics::DB_ROUTED_READS_TOTAL, ping_routes::PING_ID};
+
+/// How often the replica is pinged to decide whether reads can go back to it.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A read replica that heavy reads are sent to when it's safe to.
+pub struct ReplicaRouting {
+    replica: MySqlPool,
+    healthy: AtomicBool,
+    recent_writes: Data<RecentWrites>,
+}
+
+impl ReplicaRouting {
+    pub fn new(replica: MySqlPool, recent_writes: Data<RecentWrites>) -> Self {
+        ReplicaRouting {
+            replica,
+            healthy: AtomicBool::new(true),
+            recent_writes,
+        }
+    }
+
+    /// Gets the replica if the user's read can go there.
+    /// `None` means read from the primary, because the user just wrote or the replica is down.
+    pub fn read_pool(&self, user_id: &str) -> Option<&MySqlPool> {
+        if self.recent_writes.wrote_recently(user_id) {
+            DB_ROUTED_READS_TOTAL
+                .with_label_values(&["recent_write"])
+                .inc();
+            return None;
+        }
+
+        if !self.healthy.load(Ordering::Relaxed) {
+            DB_ROUTED_READS_TOTAL
+                .with_label_values(&["unhealthy"])
+                .inc();
+            return None;
+        }
+
+        DB_ROUTED_READS_TOTAL.with_label_values(&["replica"]).inc();
+        return Some(&self.replica);
+    }
+
+    /// Stops sending reads to the replica until the next health check passes.
+    pub fn mark_unhealthy(&self) {
+        DB_ROUTED_READS_TOTAL
+            .with_label_values(&["replica_error"])
+            .inc();
+        self.healthy.store(false, Ordering::Relaxed);
+    }
+
+    /// Pings the replica and records whether it answered.
+    pub async fn check_health(&self) {
+        let healthy = get_ping(&self.replica, PING_ID).await.is_ok();
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+}
+
+/// Whether an error means the replica couldn't be reached, rather than the query itself failing.
+/// Only these are retried on the primary.
+pub fn is_connection_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Io(_)
+            | Error::Tls(_)
+            | Error::PoolTimedOut
+            | Error::PoolClosed
+            | Error::WorkerCrashed
+    )
+}
+
+/// Periodically checks the replica in the background, so reads return to it once it recovers.
+pub fn start_replica_health_check(routing: Arc<ReplicaRouting>) {
+    task::spawn(async move {
+        loop {
+            time::sleep(HEALTH_CHECK_INTERVAL).await;
+
+            routing.check_health().await;
+        }
+    });
+}
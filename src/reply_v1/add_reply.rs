@@ -1,43 +1,68 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{create_notification, create_reply, get_reply, get_review, get_user, Review},
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidJson, Validate},
+    idempotency::{IdempotencyKeyHeader, Idempotent},
     notifications_v1::{
         enqueue_notification, ActionType, NotificationQueue, NotificationQueueItem,
         NotificationType,
     },
-    tracing::add_error_span,
+    repo::{NotificationRepo, ReplyRepo, ReviewRepo, UserRepo},
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
-    web::{Data, Json, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
 use std::sync::Mutex;
+use utoipa::ToSchema;
 use validation::validate_reply_text;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AddReplyRequest {
     text: String,
     review_id: String,
     reply_to_id: Option<String>,
 }
 
+impl Validate for AddReplyRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("text", validate_reply_text(&self.text));
+
+        errors.finish()
+    }
+}
+
 /// Allows users to add a reply linked to a review.
-#[post("")]
+#[utoipa::path(
+    context_path = "/api/v1/reply",
+    tag = "reply",
+    request_body = AddReplyRequest,
+    params(IdempotencyKeyHeader),
+    responses(
+        (status = 200, description = "reply was created"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("", wrap = "Idempotent")]
 pub async fn add_reply(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    review_repo: Data<dyn ReviewRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    notification_repo: Data<dyn NotificationRepo>,
     apn_queue: Data<Mutex<NotificationQueue>>,
-    add_reply_request: Json<AddReplyRequest>,
-) -> Result<impl Responder> {
-    if let Err(err) = validate_reply_text(&add_reply_request.text) {
-        return Err(ErrorBadRequest(err));
-    }
-
-    let review_res = get_review(&pool, &authenticated_user.0, &add_reply_request.review_id).await;
+    add_reply_request: ValidJson<AddReplyRequest>,
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &add_reply_request.review_id)
+        .await;
 
     let review: Review;
 
@@ -46,32 +71,42 @@ pub async fn add_reply(
             if let Some(review_tmp) = review_opt {
                 review = review_tmp;
             } else {
-                return Err(ErrorBadRequest("unable to find review".to_string()));
+                return Err(AppError::bad_request(
+                    ErrorCode::ReviewNotFound,
+                    "unable to find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get review".to_string()));
+            return Err(AppError::internal("unable to get review", error));
         }
     }
 
-    let reply_res = create_reply(
-        &pool,
-        &authenticated_user.0,
-        &add_reply_request.review_id,
-        &add_reply_request.text,
-        add_reply_request.reply_to_id.as_ref(),
-    )
-    .await;
+    let reply_res = reply_repo
+        .create_reply(
+            &authenticated_user.0,
+            &add_reply_request.review_id,
+            &add_reply_request.text,
+            add_reply_request.reply_to_id.as_ref(),
+        )
+        .await;
 
     match reply_res {
         Ok(_) => {
-            enqueue_reply_notification_to_author(&pool, &apn_queue, &review, &authenticated_user.0)
-                .await;
+            enqueue_reply_notification_to_author(
+                &user_repo,
+                &notification_repo,
+                &apn_queue,
+                &review,
+                &authenticated_user.0,
+            )
+            .await;
 
             if let Some(reply_to_id) = &add_reply_request.reply_to_id {
                 enqueue_reply_notification_to_reply_id(
-                    &pool,
+                    &user_repo,
+                    &reply_repo,
+                    &notification_repo,
                     &apn_queue,
                     &review,
                     &authenticated_user.0,
@@ -83,35 +118,35 @@ pub async fn add_reply(
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable create reply".to_string()));
+            return Err(AppError::internal("unable create reply", error));
         }
     }
 }
 
 /// Generally a best-effort async function to get notifications out.
 async fn enqueue_reply_notification_to_author(
-    pool: &Data<MySqlPool>,
+    user_repo: &Data<dyn UserRepo>,
+    notification_repo: &Data<dyn NotificationRepo>,
     apn_queue: &Data<Mutex<NotificationQueue>>,
     review: &Review,
     authenticated_user_id: &str,
 ) {
-    let _ = create_notification(
-        &pool,
-        authenticated_user_id,
-        &review.user_id,
-        &review.id,
-        ActionType::Reply.into(),
-    )
-    .await;
+    let _ = notification_repo
+        .create_notification(
+            authenticated_user_id,
+            &review.user_id,
+            &review.id,
+            ActionType::Reply.into(),
+        )
+        .await;
 
-    let user_res = get_user(&pool, &review.user_id).await;
+    let user_res = user_repo.get_user(&review.user_id).await;
 
     // Best effort sending the notification through apple sevices.
     match user_res {
         Ok(user_opt) => {
             if let Some(user) = user_opt {
-                let calling_user_res = get_user(&pool, authenticated_user_id).await;
+                let calling_user_res = user_repo.get_user(authenticated_user_id).await;
 
                 match calling_user_res {
                     Ok(calling_user_opt) => {
@@ -142,29 +177,31 @@ async fn enqueue_reply_notification_to_author(
 
 /// Generally a best-effort async function to get notifications out.
 async fn enqueue_reply_notification_to_reply_id(
-    pool: &Data<MySqlPool>,
+    user_repo: &Data<dyn UserRepo>,
+    reply_repo: &Data<dyn ReplyRepo>,
+    notification_repo: &Data<dyn NotificationRepo>,
     apn_queue: &Data<Mutex<NotificationQueue>>,
     review: &Review,
     authenticated_user_id: &str,
     reply_to_id: &str,
 ) {
-    let reply_res = get_reply(pool, &review.id, reply_to_id).await;
+    let reply_res = reply_repo.get_reply(&review.id, reply_to_id).await;
 
     match reply_res {
         Ok(reply_opt) => {
             // We found valid reply this reply is replying to.
             if let Some(reply) = reply_opt {
-                let _ = create_notification(
-                    &pool,
-                    authenticated_user_id,
-                    &reply.user_id,
-                    &review.id,
-                    ActionType::ReplyTo.into(),
-                )
-                .await;
+                let _ = notification_repo
+                    .create_notification(
+                        authenticated_user_id,
+                        &reply.user_id,
+                        &review.id,
+                        ActionType::ReplyTo.into(),
+                    )
+                    .await;
 
                 // get the user who owns the reply we are replying to
-                let user_res = get_user(&pool, &reply.user_id).await;
+                let user_res = user_repo.get_user(&reply.user_id).await;
 
                 // Best effort sending the notification through apple sevices.
                 match user_res {
@@ -172,7 +209,7 @@ async fn enqueue_reply_notification_to_reply_id(
                         // we actually have the user now, big chill
                         // user is the person who should be getting the notification
                         if let Some(user) = user_opt {
-                            let calling_user_res = get_user(&pool, authenticated_user_id).await;
+                            let calling_user_res = user_repo.get_user(authenticated_user_id).await;
 
                             match calling_user_res {
                                 Ok(calling_user_opt) => {
@@ -1,42 +1,61 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_all_replies, get_review},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::{ReplyRepo, ReviewRepo},
 };
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::reply_types::ReplyPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetRepliesRequest {
     review_id: String,
 }
 
 /// Gets all the replies for a given review.
+#[utoipa::path(
+    context_path = "/api/v1/reply",
+    tag = "reply",
+    responses(
+        (status = 200, description = "replies on the review", body = [ReplyPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("")]
 pub async fn get_replies(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
     get_replies_request: Query<GetRepliesRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_review(&pool, &authenticated_user.0, &get_replies_request.review_id).await;
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &get_replies_request.review_id)
+        .await;
 
     match review_res {
         Ok(review_opt) => {
             if let None = review_opt {
-                return Err(ErrorNotFound("could not find review".to_string()));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
-        Err(_) => return Err(ErrorInternalServerError("failed to get review".to_string())),
+        Err(error) => return Err(AppError::internal("failed to get review", error)),
     }
 
-    let reply_res = get_all_replies(&pool, &get_replies_request.review_id).await;
+    let reply_res = reply_repo
+        .get_all_replies(&get_replies_request.review_id)
+        .await;
 
     match reply_res {
         Ok(replies) => {
@@ -46,6 +65,6 @@ pub async fn get_replies(
                 .collect();
             return Ok(Json(reply_pub));
         }
-        Err(_) => return Err(ErrorInternalServerError("unable to get likes".to_string())),
+        Err(error) => return Err(AppError::internal("unable to get likes", error)),
     }
 }
@@ -1,53 +1,62 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{delete_reply, get_review},
-    tracing::add_error_span,
+    docs::ErrorResponses,
+    error::AppError,
+    repo::{ReplyRepo, ReviewRepo},
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RemoveReplyRequest {
     reply_id: String,
     review_id: String,
 }
 
 /// Allows users to remove replies.
+#[utoipa::path(
+    context_path = "/api/v1/reply",
+    tag = "reply",
+    responses(
+        (status = 200, description = "reply was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/remove")]
 pub async fn remove_reply(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
     add_reply_request: Query<RemoveReplyRequest>,
-) -> Result<impl Responder> {
-    if let Err(error) = get_review(&pool, &authenticated_user.0, &add_reply_request.review_id).await
+) -> Result<impl Responder, AppError> {
+    if let Err(error) = review_repo
+        .get_review(&authenticated_user.0, &add_reply_request.review_id)
+        .await
     {
-        add_error_span(&error);
-        return Err(ErrorInternalServerError(
-            "unable to find review".to_string(),
-        ));
+        return Err(AppError::internal("unable to find review", error));
     }
 
-    let delete_res = delete_reply(
-        &pool,
-        &add_reply_request.reply_id,
-        &add_reply_request.review_id,
-        &authenticated_user.0,
-    )
-    .await;
+    let delete_res = reply_repo
+        .delete_reply(
+            &add_reply_request.reply_id,
+            &add_reply_request.review_id,
+            &authenticated_user.0,
+        )
+        .await;
 
     match delete_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable delete reply".to_string()));
+            return Err(AppError::internal("unable delete reply", error));
         }
     }
 }
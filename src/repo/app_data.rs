@@ -0,0 +1,52 @@
+use actix_web::web::{Data, ServiceConfig};
+use std::sync::Arc;
+
+use super::{
+    AuthRepo, BookmarkRepo, DatabaseRepo, FriendRepo, IdempotencyRepo, IpBlockRepo, LikeRepo,
+    NotificationRepo, PendingNotificationRepo, PicRepo, ReplyRepo, ReportRepo, ReviewRepo,
+    SyncRepo, UserRepo,
+};
+
+/// Registers `repo` as every `Data<dyn ...Repo>` the handlers extract.
+/// Use with `App::configure`, passing a `MySqlRepo` or `PgRepo` in the server, or a `MemoryRepo` in tests.
+pub fn configure_repos<R>(repo: Arc<R>) -> impl Fn(&mut ServiceConfig) + Clone
+where
+    R: UserRepo
+        + AuthRepo
+        + FriendRepo
+        + ReviewRepo
+        + PicRepo
+        + LikeRepo
+        + ReplyRepo
+        + NotificationRepo
+        + ReportRepo
+        + BookmarkRepo
+        + IpBlockRepo
+        + PendingNotificationRepo
+        + SyncRepo
+        + IdempotencyRepo
+        + DatabaseRepo
+        + 'static,
+{
+    move |cfg: &mut ServiceConfig| {
+        cfg.app_data(repo_data::<dyn UserRepo>(repo.clone()))
+            .app_data(repo_data::<dyn AuthRepo>(repo.clone()))
+            .app_data(repo_data::<dyn FriendRepo>(repo.clone()))
+            .app_data(repo_data::<dyn ReviewRepo>(repo.clone()))
+            .app_data(repo_data::<dyn PicRepo>(repo.clone()))
+            .app_data(repo_data::<dyn LikeRepo>(repo.clone()))
+            .app_data(repo_data::<dyn ReplyRepo>(repo.clone()))
+            .app_data(repo_data::<dyn NotificationRepo>(repo.clone()))
+            .app_data(repo_data::<dyn ReportRepo>(repo.clone()))
+            .app_data(repo_data::<dyn BookmarkRepo>(repo.clone()))
+            .app_data(repo_data::<dyn IpBlockRepo>(repo.clone()))
+            .app_data(repo_data::<dyn PendingNotificationRepo>(repo.clone()))
+            .app_data(repo_data::<dyn SyncRepo>(repo.clone()))
+            .app_data(repo_data::<dyn IdempotencyRepo>(repo.clone()))
+            .app_data(repo_data::<dyn DatabaseRepo>(repo.clone()));
+    }
+}
+
+fn repo_data<T: ?Sized>(repo: Arc<T>) -> Data<T> {
+    Data::from(repo)
+}
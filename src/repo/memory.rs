@@ -0,0 +1,2014 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use images::DEFAULT_PIC_ID;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    Error,
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+};
+use uuid::Uuid;
+
+use crate::db::{
+    AuthAttempt, Bookmark, Cursor, DataVersion, ExpandedNotification, Friend, FriendRequest,
+    IdempotencyKey, IpBlock, Like, Notification, Page, PageRequest, PendingNotification, PhoneAuth,
+    Pic, Reply, Report, Review, ReviewAnnotation, SchemaMigration, Tombstone, User,
+    UserActivityCounts, MIGRATOR, TOMBSTONE_BOOKMARK, TOMBSTONE_LIKE, TOMBSTONE_REPLY,
+    TOMBSTONE_REVIEW, USER_ACTION_TYPE,
+};
+use crate::sync_v1::SYNC_TOKEN_MAX_AGE_DAYS;
+
+use super::{
+    AuthRepo, BookmarkRepo, DatabaseRepo, FriendRepo, IdempotencyRepo, IpBlockRepo, LikeRepo,
+    NotificationRepo, PendingNotificationRepo, PicRepo, ReplyRepo, ReportRepo, ReviewRepo,
+    SyncRepo, UserRepo,
+};
+
+// Distances match the MySQL queries in `db`.
+const SAME_LOCATION_DISTANCE: f64 = 0.001;
+const NEARBY_BOOKMARK_DISTANCE: f64 = 0.025;
+
+/// Repositories kept in memory, for tests that shouldn't need MySQL.
+/// Applies the same friend-scoped visibility rules as the MySQL queries.
+///
+/// ```
+/// use chrono::Utc;
+/// use reviewwithfriends_backend::{
+///     db::{PageRequest, Review, User},
+///     repo::{FriendRepo, MemoryRepo, ReviewRepo, UserRepo},
+/// };
+///
+/// fn user(id: &str) -> User {
+///     User {
+///         id: id.to_string(),
+///         name: id.to_string(),
+///         display_name: id.to_string(),
+///         phone: format!("+1555000{}", id.len()),
+///         created: Utc::now().naive_utc(),
+///         pic_id: "default".to_string(),
+///         device_token: None,
+///         email: None,
+///         disabled: 0,
+///     }
+/// }
+///
+/// # actix_web::rt::System::new().block_on(async {
+/// let repo = MemoryRepo::new();
+/// repo.create_user(&user("alice")).await.unwrap();
+/// repo.create_user(&user("bob")).await.unwrap();
+///
+/// let review = Review {
+///     id: "review".to_string(),
+///     user_id: "bob".to_string(),
+///     created: Utc::now().naive_utc(),
+///     category: "food".to_string(),
+///     text: "good".to_string(),
+///     stars: 5,
+///     location_name: "Cafe".to_string(),
+///     latitude: 47.6,
+///     longitude: -122.3,
+///     is_custom: 0,
+///     delivered: 0,
+///     recommended: 0,
+/// };
+/// repo.create_review(&review).await.unwrap();
+///
+/// // bob always sees his own review, alice only once they're friends.
+/// assert!(repo.get_review("bob", "review").await.unwrap().is_some());
+/// assert!(repo.get_review("alice", "review").await.unwrap().is_none());
+///
+/// repo.create_friend_request("alice", "bob").await.unwrap();
+/// repo.accept_friend_request("bob", "alice").await.unwrap();
+///
+/// assert!(repo.get_review("alice", "review").await.unwrap().is_some());
+/// let latest = repo
+///     .get_latest_reviews("alice", &PageRequest::first(5), &None)
+///     .await
+///     .unwrap();
+/// assert_eq!(latest.items.len(), 1);
+/// # });
+/// ```
+#[derive(Default)]
+pub struct MemoryRepo {
+    tables: Mutex<MemoryTables>,
+}
+
+#[derive(Default)]
+struct MemoryTables {
+    users: Vec<User>,
+    phoneauths: Vec<PhoneAuth>,
+    authattempts: Vec<AuthAttempt>,
+    friends: Vec<Friend>,
+    friend_requests: Vec<FriendRequest>,
+    reviews: Vec<Review>,
+    /// `review.updated`, which `Review` doesn't carry.
+    review_updated: HashMap<String, NaiveDateTime>,
+    pics: Vec<Pic>,
+    likes: Vec<Like>,
+    replies: Vec<Reply>,
+    notifications: Vec<Notification>,
+    reports: Vec<Report>,
+    bookmarks: Vec<Bookmark>,
+    ipblocks: Vec<IpBlock>,
+    pending_notifications: Vec<PendingNotification>,
+    tombstones: Vec<Tombstone>,
+    idempotency_keys: Vec<IdempotencyKey>,
+}
+
+impl MemoryRepo {
+    pub fn new() -> MemoryRepo {
+        MemoryRepo::default()
+    }
+
+    fn tables(&self) -> MutexGuard<MemoryTables> {
+        // nothing panics while holding the lock, but a failed test shouldn't take every other one down with it.
+        self.tables
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl MemoryTables {
+    /// Whether `user_id` has a friend record for `other_user_id`, which is what every friend-scoped query joins on.
+    fn is_friend(&self, user_id: &str, other_user_id: &str) -> bool {
+        self.friends
+            .iter()
+            .any(|friend| friend.user_id == user_id && friend.friend_id == other_user_id)
+    }
+
+    fn visible_reviews(&self, user_id: &str) -> Vec<Review> {
+        self.reviews
+            .iter()
+            .filter(|review| self.is_friend(user_id, &review.user_id))
+            .cloned()
+            .collect()
+    }
+
+    fn annotate(&self, review: &Review) -> ReviewAnnotation {
+        let pic = self
+            .pics
+            .iter()
+            .find(|pic| pic.review_id.as_deref() == Some(review.id.as_str()));
+
+        ReviewAnnotation {
+            id: review.id.clone(),
+            user_id: review.user_id.clone(),
+            created: review.created,
+            pic_id: pic.map(|pic| pic.id.clone()),
+            pic_handler: pic.map(|pic| pic.pic_handler),
+            category: review.category.clone(),
+            location_name: review.location_name.clone(),
+            latitude: review.latitude,
+            longitude: review.longitude,
+            is_custom: review.is_custom,
+            delivered: review.delivered,
+            recommended: review.recommended,
+        }
+    }
+
+    /// Whether a sync since `since` sends `user_id` a row of `owner_id`'s that changed at `changed`.
+    /// Matches the `sync_query` joins, a friendship made since then sends everything.
+    fn is_synced(
+        &self,
+        user_id: &str,
+        owner_id: &str,
+        changed: NaiveDateTime,
+        since: &NaiveDateTime,
+    ) -> bool {
+        self.friends.iter().any(|friend| {
+            friend.user_id == user_id
+                && friend.friend_id == owner_id
+                && (changed >= *since || friend.created >= *since)
+        })
+    }
+
+    fn review_author(&self, review_id: &str) -> Option<String> {
+        self.reviews
+            .iter()
+            .find(|review| review.id == review_id)
+            .map(|review| review.user_id.clone())
+    }
+
+    fn touch_review(&mut self, review_id: &str) {
+        if let Some(updated) = self.review_updated.get_mut(review_id) {
+            *updated = Utc::now().naive_utc();
+        }
+    }
+
+    fn add_tombstone(&mut self, item_type: &str, item_id: &str, owner_id: &str) {
+        let created = Utc::now().naive_utc();
+        let expired_before = created - Duration::days(SYNC_TOKEN_MAX_AGE_DAYS);
+        self.tombstones
+            .retain(|tombstone| tombstone.created >= expired_before);
+
+        self.tombstones.push(Tombstone {
+            id: Uuid::new_v4().to_string(),
+            created,
+            item_type: item_type.to_string(),
+            item_id: item_id.to_string(),
+            owner_id: owner_id.to_string(),
+        });
+    }
+
+    fn add_friend(&mut self, user_id: &str, friend_id: &str) {
+        if !self.is_friend(user_id, friend_id) {
+            self.friends.push(Friend {
+                id: Uuid::new_v4().to_string(),
+                created: Utc::now().naive_utc(),
+                user_id: user_id.to_string(),
+                friend_id: friend_id.to_string(),
+            });
+        }
+    }
+}
+
+fn page<T>(items: Vec<T>, page: u32, page_size: usize) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(page as usize * page_size)
+        .take(page_size)
+        .collect()
+}
+
+/// Newest first by `(created, id)`, starting after the cursor.
+fn keyset_page<T, F>(mut items: Vec<T>, page_request: &PageRequest, key: F) -> Page<T>
+where
+    F: Fn(&T) -> Cursor,
+{
+    items.retain(|item| page_request.includes(&key(item)));
+    items.sort_by_key(|item| std::cmp::Reverse(key(item)));
+    items.truncate(page_request.fetch_limit() as usize);
+
+    return Page::from_rows(items, page_request, key);
+}
+
+fn newest_first<T, F>(mut items: Vec<T>, created: F) -> Vec<T>
+where
+    F: Fn(&T) -> chrono::NaiveDateTime,
+{
+    items.sort_by_key(|item| std::cmp::Reverse(created(item)));
+    items
+}
+
+/// Stands in for `ST_Contains(ST_Buffer(point, distance), location)`.
+fn is_within(
+    latitude: f64,
+    longitude: f64,
+    other_latitude: f64,
+    other_longitude: f64,
+    distance: f64,
+) -> bool {
+    (latitude - other_latitude).hypot(longitude - other_longitude) < distance
+}
+
+/// Stands in for `ST_Contains(ST_MakeEnvelope(...), location)`, which excludes the edges.
+fn is_in_bounds(
+    latitude: f64,
+    longitude: f64,
+    latitude_north: f64,
+    latitude_south: f64,
+    longitude_west: f64,
+    longitude_east: f64,
+) -> bool {
+    latitude > latitude_south.min(latitude_north)
+        && latitude < latitude_south.max(latitude_north)
+        && longitude > longitude_west.min(longitude_east)
+        && longitude < longitude_west.max(longitude_east)
+}
+
+/// Stands in for `LIKE 'prefix%'` under a case insensitive collation.
+fn starts_with_ignore_case(value: &str, prefix: &str) -> bool {
+    value
+        .to_lowercase()
+        .starts_with(&prefix.replace('%', "").to_lowercase())
+}
+
+#[async_trait]
+impl UserRepo for MemoryRepo {
+    async fn get_user(&self, id: &str) -> Result<Option<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables.users.iter().find(|user| user.id == id).cloned());
+    }
+
+    async fn get_users_by_ids(&self, ids: &[String]) -> Result<Vec<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| ids.contains(&user.id))
+            .cloned()
+            .collect());
+    }
+
+    async fn get_user_from_name(&self, name: &str) -> Result<Option<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables.users.iter().find(|user| user.name == name).cloned());
+    }
+
+    async fn search_user_from_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| starts_with_ignore_case(&user.name, name))
+            .take(50)
+            .cloned()
+            .collect());
+    }
+
+    async fn does_user_exist(&self, id: &str) -> Result<bool, Error> {
+        let tables = self.tables();
+        return Ok(tables.users.iter().filter(|user| user.id == id).count() == 1);
+    }
+
+    async fn does_user_exist_by_name(&self, name: &str) -> Result<bool, Error> {
+        let tables = self.tables();
+        return Ok(tables.users.iter().filter(|user| user.name == name).count() == 1);
+    }
+
+    async fn get_user_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| user.phone == phone)
+            .last()
+            .cloned());
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| user.email.as_deref() == Some(email))
+            .last()
+            .cloned());
+    }
+
+    async fn phone_number_discovery(&self, numbers: &Vec<&str>) -> Result<Vec<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| numbers.contains(&user.phone.as_str()))
+            .cloned()
+            .collect());
+    }
+
+    async fn create_user(&self, user: &User) -> Result<(), Error> {
+        let mut tables = self.tables();
+
+        // only what the insert sets, everything else starts at the column default.
+        let mut user = user.clone();
+        user.pic_id = DEFAULT_PIC_ID.to_string();
+        user.device_token = None;
+        user.email = None;
+        user.disabled = 0;
+        tables.users.push(user.clone());
+
+        tables.add_friend(&user.id, &user.id);
+
+        return Ok(());
+    }
+
+    async fn update_user_phone(&self, user_id: &str, phone: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.phone = phone.to_string();
+        }
+
+        return Ok(());
+    }
+
+    async fn update_usernames(
+        &self,
+        user_id: &str,
+        display_name: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.display_name = display_name.to_string();
+            user.name = name.to_string();
+        }
+
+        return Ok(());
+    }
+
+    async fn update_device_token(&self, user_id: &str, device_token: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.device_token = Some(device_token.to_string());
+        }
+
+        return Ok(());
+    }
+
+    async fn update_recovery_email(&self, user_id: &str, email: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.email = Some(email.to_string());
+        }
+
+        return Ok(());
+    }
+
+    async fn update_user_pic_id(&self, pic_id: &str, user_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.pic_id = pic_id.to_string();
+        }
+
+        return Ok(());
+    }
+
+    async fn get_total_user_count(&self) -> Result<i64, Error> {
+        let tables = self.tables();
+        return Ok(tables.users.len() as i64);
+    }
+
+    async fn get_user_activity_counts(&self, user_id: &str) -> Result<UserActivityCounts, Error> {
+        let tables = self.tables();
+
+        return Ok(UserActivityCounts {
+            reviews: tables
+                .reviews
+                .iter()
+                .filter(|review| review.user_id == user_id)
+                .count() as i64,
+            friends: tables
+                .friends
+                .iter()
+                .filter(|friend| friend.user_id == user_id && friend.friend_id != user_id)
+                .count() as i64,
+            incoming_requests: tables
+                .friend_requests
+                .iter()
+                .filter(|request| request.friend_id == user_id)
+                .count() as i64,
+            outgoing_requests: tables
+                .friend_requests
+                .iter()
+                .filter(|request| request.user_id == user_id)
+                .count() as i64,
+            likes: tables
+                .likes
+                .iter()
+                .filter(|like| like.user_id == user_id)
+                .count() as i64,
+            replies: tables
+                .replies
+                .iter()
+                .filter(|reply| reply.user_id == user_id)
+                .count() as i64,
+            bookmarks: tables
+                .bookmarks
+                .iter()
+                .filter(|bookmark| bookmark.user_id == user_id)
+                .count() as i64,
+            notifications: tables
+                .notifications
+                .iter()
+                .filter(|notification| notification.review_user_id == user_id)
+                .count() as i64,
+        });
+    }
+
+    async fn remove_user_activity(&self, user_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        let review_ids: Vec<String> = tables
+            .reviews
+            .iter()
+            .filter(|review| review.user_id == user_id)
+            .map(|review| review.id.clone())
+            .collect();
+
+        let mut tombstones: Vec<(&str, String, String)> = review_ids
+            .iter()
+            .map(|review_id| (TOMBSTONE_REVIEW, review_id.clone(), user_id.to_string()))
+            .collect();
+        for reply in tables
+            .replies
+            .iter()
+            .filter(|reply| reply.user_id == user_id)
+        {
+            match tables.review_author(&reply.review_id) {
+                Some(author) if author != user_id => {
+                    tombstones.push((TOMBSTONE_REPLY, reply.id.clone(), author))
+                }
+                _ => {}
+            }
+        }
+        for like in tables.likes.iter().filter(|like| like.user_id == user_id) {
+            match tables.review_author(&like.review_id) {
+                Some(author) if author != user_id => {
+                    tombstones.push((TOMBSTONE_LIKE, like.id.clone(), author))
+                }
+                _ => {}
+            }
+        }
+        for bookmark in tables
+            .bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.user_id == user_id)
+        {
+            tombstones.push((TOMBSTONE_BOOKMARK, bookmark.id.clone(), user_id.to_string()));
+        }
+
+        tables
+            .replies
+            .retain(|reply| reply.user_id != user_id && !review_ids.contains(&reply.review_id));
+        tables
+            .likes
+            .retain(|like| like.user_id != user_id && !review_ids.contains(&like.review_id));
+        tables.reviews.retain(|review| review.user_id != user_id);
+        for review_id in &review_ids {
+            tables.review_updated.remove(review_id);
+        }
+        tables.notifications.retain(|notification| {
+            notification.review_user_id != user_id && notification.user_id != user_id
+        });
+        tables
+            .friend_requests
+            .retain(|request| request.user_id != user_id && request.friend_id != user_id);
+        tables.friends.retain(|friend| {
+            (friend.user_id != user_id && friend.friend_id != user_id)
+                || friend.user_id == friend.friend_id
+        });
+        tables
+            .bookmarks
+            .retain(|bookmark| bookmark.user_id != user_id);
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.pic_id = DEFAULT_PIC_ID.to_string();
+            user.device_token = None;
+            user.email = None;
+        }
+
+        for (item_type, item_id, owner_id) in tombstones {
+            tables.add_tombstone(item_type, &item_id, &owner_id);
+        }
+
+        return Ok(());
+    }
+
+    async fn update_user_disabled(&self, user_id: &str, disabled: bool) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for user in tables.users.iter_mut().filter(|user| user.id == user_id) {
+            user.disabled = disabled as i8;
+        }
+
+        return Ok(());
+    }
+
+    async fn get_users_missing_self_friend(&self) -> Result<Vec<User>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .users
+            .iter()
+            .filter(|user| !tables.is_friend(&user.id, &user.id))
+            .cloned()
+            .collect());
+    }
+}
+
+#[async_trait]
+impl AuthRepo for MemoryRepo {
+    async fn get_current_phoneauths(&self, phone: &str) -> Result<Vec<PhoneAuth>, Error> {
+        let tables = self.tables();
+        let since = Utc::now().naive_utc() - Duration::hours(1);
+
+        return Ok(tables
+            .phoneauths
+            .iter()
+            .filter(|auth| auth.created > since && auth.phone == phone && auth.used == 0)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_phoneauth_attempts(&self, phone: &str) -> Result<Vec<AuthAttempt>, Error> {
+        let tables = self.tables();
+        let since = Utc::now().naive_utc() - Duration::hours(1);
+
+        return Ok(tables
+            .authattempts
+            .iter()
+            .filter(|attempt| attempt.created > since && attempt.phone == phone)
+            .cloned()
+            .collect());
+    }
+
+    async fn create_phoneauth(&self, phone: &str, code: &str, ip: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.phoneauths.push(PhoneAuth {
+            id: Uuid::new_v4().to_string(),
+            phone: phone.to_string(),
+            created: Utc::now().naive_utc(),
+            ip: ip.to_string(),
+            code: code.to_string(),
+            used: 0,
+        });
+
+        return Ok(());
+    }
+
+    async fn update_authattempt_used(&self, id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for auth in tables.phoneauths.iter_mut().filter(|auth| auth.id == id) {
+            auth.used = 1;
+        }
+
+        return Ok(());
+    }
+
+    async fn create_authattempt(&self, phone: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.authattempts.push(AuthAttempt {
+            id: Uuid::new_v4().to_string(),
+            phone: phone.to_string(),
+            created: Utc::now().naive_utc(),
+        });
+
+        return Ok(());
+    }
+
+    async fn get_recent_phoneauths(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<PhoneAuth>, Error> {
+        let tables = self.tables();
+        let auths = tables
+            .phoneauths
+            .iter()
+            .filter(|auth| auth.phone == phone)
+            .cloned()
+            .collect();
+
+        return Ok(page(
+            newest_first(auths, |auth| auth.created),
+            0,
+            limit as usize,
+        ));
+    }
+
+    async fn get_recent_authattempts(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<AuthAttempt>, Error> {
+        let tables = self.tables();
+        let attempts = tables
+            .authattempts
+            .iter()
+            .filter(|attempt| attempt.phone == phone)
+            .cloned()
+            .collect();
+
+        return Ok(page(
+            newest_first(attempts, |attempt| attempt.created),
+            0,
+            limit as usize,
+        ));
+    }
+}
+
+#[async_trait]
+impl FriendRepo for MemoryRepo {
+    async fn get_incoming_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .friend_requests
+            .iter()
+            .filter(|request| request.friend_id == user_id && request.ignored == 0)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_acceptable_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .friend_requests
+            .iter()
+            .filter(|request| request.friend_id == user_id)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_incoming_ignored_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .friend_requests
+            .iter()
+            .filter(|request| request.friend_id == user_id && request.ignored != 0)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_outgoing_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .friend_requests
+            .iter()
+            .filter(|request| request.user_id == user_id)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_current_friends(&self, user_id: &str) -> Result<Vec<Friend>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .friends
+            .iter()
+            .filter(|friend| friend.user_id == user_id)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_friends_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        let tables = self.tables();
+        let requests = |include: fn(&FriendRequest, &str) -> bool| {
+            DataVersion::of(
+                tables
+                    .friend_requests
+                    .iter()
+                    .filter(|request| include(request, user_id))
+                    .map(|request| request.created),
+            )
+        };
+
+        return Ok(vec![
+            DataVersion::of(
+                tables
+                    .friends
+                    .iter()
+                    .filter(|friend| friend.user_id == user_id)
+                    .map(|friend| friend.created),
+            ),
+            requests(|request, user_id| request.friend_id == user_id && request.ignored == 0),
+            requests(|request, user_id| request.friend_id == user_id && request.ignored != 0),
+            requests(|request, user_id| request.user_id == user_id),
+        ]);
+    }
+
+    async fn are_users_friends(&self, user_id: &str, other_user_id: &str) -> Result<bool, Error> {
+        let tables = self.tables();
+        return Ok(tables.is_friend(user_id, other_user_id));
+    }
+
+    async fn create_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.friend_requests.push(FriendRequest {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            user_id: user_id.to_string(),
+            friend_id: friend_id.to_string(),
+            ignored: 0,
+        });
+
+        return Ok(());
+    }
+
+    async fn ignore_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for request in tables
+            .friend_requests
+            .iter_mut()
+            .filter(|request| request.id == request_id && request.friend_id == friend_id)
+        {
+            request.ignored = 1;
+        }
+
+        return Ok(());
+    }
+
+    async fn decline_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables
+            .friend_requests
+            .retain(|request| !(request.id == request_id && request.friend_id == friend_id));
+
+        return Ok(());
+    }
+
+    async fn cancel_friend_request(&self, request_id: &str, user_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables
+            .friend_requests
+            .retain(|request| !(request.id == request_id && request.user_id == user_id));
+
+        return Ok(());
+    }
+
+    async fn accept_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+
+        tables.friend_requests.retain(|request| {
+            !((request.user_id == user_id && request.friend_id == friend_id)
+                || (request.user_id == friend_id && request.friend_id == user_id))
+        });
+
+        tables.add_friend(user_id, friend_id);
+        tables.add_friend(friend_id, user_id);
+
+        return Ok(());
+    }
+
+    async fn remove_current_friend(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.friends.retain(|friend| {
+            !((friend.user_id == user_id && friend.friend_id == friend_id)
+                || (friend.user_id == friend_id && friend.friend_id == user_id))
+        });
+
+        return Ok(());
+    }
+
+    async fn create_self_friend(&self, user_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.add_friend(user_id, user_id);
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl ReviewRepo for MemoryRepo {
+    async fn get_review(&self, user_id: &str, review_id: &str) -> Result<Option<Review>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .reviews
+            .iter()
+            .find(|review| {
+                review.id == review_id
+                    && (review.user_id == user_id || tables.is_friend(user_id, &review.user_id))
+            })
+            .cloned());
+    }
+
+    async fn get_reviews_by_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<Review>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .reviews
+            .iter()
+            .filter(|review| {
+                review_ids.contains(&review.id)
+                    && (review.user_id == user_id || tables.is_friend(user_id, &review.user_id))
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_compound_review_version(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<DataVersion>, Error> {
+        if review_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let tables = self.tables();
+        return Ok(vec![
+            DataVersion::of(
+                tables
+                    .likes
+                    .iter()
+                    .filter(|like| review_ids.contains(&like.review_id))
+                    .map(|like| like.created),
+            ),
+            DataVersion::of(
+                tables
+                    .replies
+                    .iter()
+                    .filter(|reply| review_ids.contains(&reply.review_id))
+                    .map(|reply| reply.created),
+            ),
+            DataVersion::of(
+                tables
+                    .pics
+                    .iter()
+                    .filter(|pic| {
+                        pic.review_id
+                            .as_ref()
+                            .is_some_and(|review_id| review_ids.contains(review_id))
+                    })
+                    .map(|pic| pic.created),
+            ),
+            DataVersion::of(
+                tables
+                    .bookmarks
+                    .iter()
+                    .filter(|bookmark| bookmark.user_id == user_id)
+                    .map(|bookmark| bookmark.created),
+            ),
+        ]);
+    }
+
+    async fn get_reviews_from_location(
+        &self,
+        user_id: &str,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<Review>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .visible_reviews(user_id)
+            .into_iter()
+            .filter(|review| {
+                review.location_name == name
+                    && is_within(
+                        review.latitude,
+                        review.longitude,
+                        latitude,
+                        longitude,
+                        SAME_LOCATION_DISTANCE,
+                    )
+            })
+            .collect());
+    }
+
+    async fn get_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page_request: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        let tables = self.tables();
+        let reviews = tables
+            .visible_reviews(user_id)
+            .into_iter()
+            .filter(|review| review.user_id == target_user_id)
+            .collect();
+
+        return Ok(keyset_page(reviews, page_request, Review::cursor));
+    }
+
+    async fn get_recommended_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page_request: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        let tables = self.tables();
+        let reviews = tables
+            .visible_reviews(user_id)
+            .into_iter()
+            .filter(|review| review.user_id == target_user_id && review.recommended != 0)
+            .collect();
+
+        return Ok(keyset_page(reviews, page_request, Review::cursor));
+    }
+
+    async fn get_reviews_from_bounds(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        page_request: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        let tables = self.tables();
+        let annotations = tables
+            .visible_reviews(user_id)
+            .iter()
+            .filter(|review| {
+                is_in_bounds(
+                    review.latitude,
+                    review.longitude,
+                    latitude_north,
+                    latitude_south,
+                    longitude_west,
+                    longitude_east,
+                )
+            })
+            .map(|review| tables.annotate(review))
+            .collect();
+
+        return Ok(keyset_page(
+            annotations,
+            page_request,
+            ReviewAnnotation::cursor,
+        ));
+    }
+
+    async fn get_reviews_from_bounds_with_exclusions(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        latitude_north_e: f64,
+        latitude_south_e: f64,
+        longitude_west_e: f64,
+        longitude_east_e: f64,
+        page_request: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        let tables = self.tables();
+        let annotations = tables
+            .visible_reviews(user_id)
+            .iter()
+            .filter(|review| {
+                is_in_bounds(
+                    review.latitude,
+                    review.longitude,
+                    latitude_north,
+                    latitude_south,
+                    longitude_west,
+                    longitude_east,
+                ) && !is_in_bounds(
+                    review.latitude,
+                    review.longitude,
+                    latitude_north_e,
+                    latitude_south_e,
+                    longitude_west_e,
+                    longitude_east_e,
+                )
+            })
+            .map(|review| tables.annotate(review))
+            .collect();
+
+        return Ok(keyset_page(
+            annotations,
+            page_request,
+            ReviewAnnotation::cursor,
+        ));
+    }
+
+    async fn get_latest_reviews(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+        category: &Option<String>,
+    ) -> Result<Page<Review>, Error> {
+        let tables = self.tables();
+        let reviews = tables
+            .visible_reviews(user_id)
+            .into_iter()
+            .filter(|review| match category {
+                Some(category) => &review.category == category,
+                None => true,
+            })
+            .collect();
+
+        return Ok(keyset_page(reviews, page_request, Review::cursor));
+    }
+
+    async fn search_latest_reviews(
+        &self,
+        user_id: &str,
+        search_prefix: &str,
+        page_request: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        let tables = self.tables();
+        let reviews = tables
+            .visible_reviews(user_id)
+            .into_iter()
+            .filter(|review| starts_with_ignore_case(&review.location_name, search_prefix))
+            .collect();
+
+        return Ok(keyset_page(reviews, page_request, Review::cursor));
+    }
+
+    async fn get_recent_reviews_by_user(
+        &self,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<Review>, Error> {
+        let tables = self.tables();
+        let reviews = tables
+            .reviews
+            .iter()
+            .filter(|review| review.user_id == user_id)
+            .cloned()
+            .collect();
+
+        return Ok(page(
+            newest_first(reviews, |review| review.created),
+            0,
+            limit as usize,
+        ));
+    }
+
+    async fn create_review(&self, review: &Review) -> Result<(), Error> {
+        let mut tables = self.tables();
+
+        // recommended isn't part of the insert, so it starts at the column default.
+        let mut review = review.clone();
+        review.recommended = 0;
+        tables
+            .review_updated
+            .insert(review.id.clone(), review.created);
+        tables.reviews.push(review);
+
+        return Ok(());
+    }
+
+    async fn update_review(&self, review_id: &str, stars: u8, text: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for review in tables
+            .reviews
+            .iter_mut()
+            .filter(|review| review.id == review_id)
+        {
+            review.stars = stars;
+            review.text = text.to_string();
+        }
+        tables.touch_review(review_id);
+
+        return Ok(());
+    }
+
+    async fn update_review_delivered(&self, review_id: &str, delivered: u8) -> Result<(), Error> {
+        let mut tables = self.tables();
+        for review in tables
+            .reviews
+            .iter_mut()
+            .filter(|review| review.id == review_id)
+        {
+            review.delivered = delivered as i8;
+        }
+        tables.touch_review(review_id);
+
+        return Ok(());
+    }
+
+    async fn update_review_recommended(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        recommended: bool,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        let mut changed = false;
+        for review in tables
+            .reviews
+            .iter_mut()
+            .filter(|review| review.id == review_id && review.user_id == user_id)
+        {
+            review.recommended = recommended as i8;
+            changed = true;
+        }
+        if changed {
+            tables.touch_review(review_id);
+        }
+
+        return Ok(());
+    }
+
+    async fn remove_review_and_children(&self, review_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.replies.retain(|reply| reply.review_id != review_id);
+        tables.likes.retain(|like| like.review_id != review_id);
+        if let Some(author) = tables.review_author(review_id) {
+            tables.add_tombstone(TOMBSTONE_REVIEW, review_id, &author);
+        }
+        tables.reviews.retain(|review| review.id != review_id);
+        tables.review_updated.remove(review_id);
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl PicRepo for MemoryRepo {
+    async fn get_pic(&self, id: &str) -> Result<Option<Pic>, Error> {
+        let tables = self.tables();
+        return Ok(tables.pics.iter().find(|pic| pic.id == id).cloned());
+    }
+
+    async fn get_all_pics(&self, review_id: &str) -> Result<Vec<Pic>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .pics
+            .iter()
+            .filter(|pic| pic.review_id.as_deref() == Some(review_id))
+            .cloned()
+            .collect());
+    }
+
+    async fn get_pics_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Pic>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .pics
+            .iter()
+            .filter(|pic| {
+                pic.review_id
+                    .as_ref()
+                    .is_some_and(|review_id| review_ids.contains(review_id))
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn create_pic(
+        &self,
+        review_id: Option<String>,
+        width: u16,
+        height: u16,
+    ) -> Result<Pic, Error> {
+        let mut tables = self.tables();
+        let pic = Pic {
+            id: Uuid::new_v4().to_string(),
+            review_id,
+            created: Utc::now().naive_utc(),
+            pic_handler: 1,
+            width,
+            height,
+        };
+        tables.pics.push(pic.clone());
+        if let Some(review_id) = &pic.review_id {
+            tables.touch_review(review_id);
+        }
+
+        return Ok(pic);
+    }
+
+    async fn delete_pic(&self, pic_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.pics.retain(|pic| pic.id != pic_id);
+
+        return Ok(());
+    }
+
+    async fn remove_review_pic_id(&self, pic_id: &str, review_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables
+            .pics
+            .retain(|pic| !(pic.id == pic_id && pic.review_id.as_deref() == Some(review_id)));
+        tables.touch_review(review_id);
+
+        return Ok(());
+    }
+
+    async fn get_orphaned_pics(&self) -> Result<Vec<Pic>, Error> {
+        let tables = self.tables();
+        let mut pics: Vec<Pic> = tables
+            .pics
+            .iter()
+            .filter(|pic| match &pic.review_id {
+                Some(review_id) => tables.review_author(review_id).is_none(),
+                None => !tables.users.iter().any(|user| user.pic_id == pic.id),
+            })
+            .cloned()
+            .collect();
+        pics.sort_by_key(|pic| pic.created);
+
+        return Ok(pics);
+    }
+
+    async fn get_review_pics_by_user(&self, user_id: &str) -> Result<Vec<Pic>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .pics
+            .iter()
+            .filter(|pic| {
+                pic.review_id
+                    .as_deref()
+                    .and_then(|review_id| tables.review_author(review_id))
+                    .as_deref()
+                    == Some(user_id)
+            })
+            .cloned()
+            .collect());
+    }
+}
+
+#[async_trait]
+impl LikeRepo for MemoryRepo {
+    async fn get_all_likes(&self, review_id: &str) -> Result<Vec<Like>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .likes
+            .iter()
+            .filter(|like| like.review_id == review_id)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_likes_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Like>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .likes
+            .iter()
+            .filter(|like| review_ids.contains(&like.review_id))
+            .cloned()
+            .collect());
+    }
+
+    async fn get_liked_reviews(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        let tables = self.tables();
+        let liked = tables
+            .likes
+            .iter()
+            .filter(|like| like.user_id == user_id)
+            .filter_map(|like| {
+                let review = tables
+                    .reviews
+                    .iter()
+                    .find(|review| review.id == like.review_id)?;
+                let cursor = Cursor {
+                    created: like.created,
+                    id: like.id.clone(),
+                };
+
+                Some((cursor, review.clone()))
+            })
+            .collect();
+
+        let liked_page = keyset_page(liked, page_request, |(cursor, _)| cursor.clone());
+
+        return Ok(liked_page.map(|(_, review)| review));
+    }
+
+    async fn is_already_liked(&self, user_id: &str, review_id: &str) -> Result<bool, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .likes
+            .iter()
+            .any(|like| like.user_id == user_id && like.review_id == review_id));
+    }
+
+    async fn create_like(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        like_type: Option<i8>,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.likes.push(Like {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            user_id: user_id.to_string(),
+            review_id: review_id.to_string(),
+            like_type: like_type.unwrap_or(0),
+        });
+
+        return Ok(());
+    }
+
+    async fn remove_like(&self, user_id: &str, review_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        let removed: Vec<String> = tables
+            .likes
+            .iter()
+            .filter(|like| like.user_id == user_id && like.review_id == review_id)
+            .map(|like| like.id.clone())
+            .collect();
+        tables
+            .likes
+            .retain(|like| !(like.user_id == user_id && like.review_id == review_id));
+
+        if let Some(author) = tables.review_author(review_id) {
+            for like_id in removed {
+                tables.add_tombstone(TOMBSTONE_LIKE, &like_id, &author);
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl ReplyRepo for MemoryRepo {
+    async fn get_all_replies(&self, review_id: &str) -> Result<Vec<Reply>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .replies
+            .iter()
+            .filter(|reply| reply.review_id == review_id)
+            .cloned()
+            .collect());
+    }
+
+    async fn get_replies_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Reply>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .replies
+            .iter()
+            .filter(|reply| review_ids.contains(&reply.review_id))
+            .cloned()
+            .collect());
+    }
+
+    async fn get_reply(&self, review_id: &str, reply_id: &str) -> Result<Option<Reply>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .replies
+            .iter()
+            .find(|reply| reply.id == reply_id && reply.review_id == review_id)
+            .cloned());
+    }
+
+    async fn create_reply(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        text: &str,
+        reply_to_id: Option<&String>,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.replies.push(Reply {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            user_id: user_id.to_string(),
+            review_id: review_id.to_string(),
+            text: text.to_string(),
+            reply_to_id: reply_to_id.cloned(),
+        });
+
+        return Ok(());
+    }
+
+    async fn delete_reply(
+        &self,
+        reply_id: &str,
+        review_id: &str,
+        user_id: &str,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        let removed = tables.replies.iter().any(|reply| {
+            reply.id == reply_id && reply.review_id == review_id && reply.user_id == user_id
+        });
+        tables.replies.retain(|reply| {
+            !(reply.id == reply_id && reply.review_id == review_id && reply.user_id == user_id)
+        });
+
+        if let (true, Some(author)) = (removed, tables.review_author(review_id)) {
+            tables.add_tombstone(TOMBSTONE_REPLY, reply_id, &author);
+        }
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl NotificationRepo for MemoryRepo {
+    async fn get_notifications(&self, user_id: &str) -> Result<Vec<ExpandedNotification>, Error> {
+        let tables = self.tables();
+        let notifications = tables
+            .notifications
+            .iter()
+            .filter(|notification| notification.review_user_id == user_id)
+            .cloned()
+            .collect();
+
+        let expanded = newest_first(notifications, |notification| notification.created)
+            .into_iter()
+            .filter_map(|notification| {
+                let review = tables
+                    .reviews
+                    .iter()
+                    .find(|review| review.id == notification.review_id)?;
+
+                Some(ExpandedNotification {
+                    id: notification.id,
+                    created: notification.created,
+                    review_user_id: notification.review_user_id,
+                    review_id: notification.review_id,
+                    user_id: notification.user_id,
+                    action_type: notification.action_type,
+                    review_location: review.location_name.clone(),
+                })
+            })
+            .take(50)
+            .collect();
+
+        return Ok(expanded);
+    }
+
+    async fn get_notifications_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        let tables = self.tables();
+        return Ok(vec![DataVersion::of(
+            tables
+                .notifications
+                .iter()
+                .filter(|notification| {
+                    notification.review_user_id == user_id
+                        && tables
+                            .reviews
+                            .iter()
+                            .any(|review| review.id == notification.review_id)
+                })
+                .map(|notification| notification.created),
+        )]);
+    }
+
+    async fn get_notification_count(&self, user_id: &str) -> i64 {
+        let tables = self.tables();
+        return tables
+            .notifications
+            .iter()
+            .filter(|notification| notification.review_user_id == user_id)
+            .count() as i64;
+    }
+
+    async fn confirm_notifications(&self, user_id: &str) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables
+            .notifications
+            .retain(|notification| notification.review_user_id != user_id);
+
+        return Ok(());
+    }
+
+    async fn create_notification(
+        &self,
+        user_id: &str,
+        review_user_id: &str,
+        review_id: &str,
+        action_type: u8,
+    ) -> Result<(), Error> {
+        if review_user_id.eq_ignore_ascii_case(user_id) {
+            return Ok(());
+        }
+
+        let mut tables = self.tables();
+
+        if tables.notifications.iter().any(|notification| {
+            notification.review_id == review_id
+                && notification.user_id == user_id
+                && notification.action_type == action_type
+        }) {
+            return Ok(());
+        }
+
+        tables.notifications.push(Notification {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            review_user_id: review_user_id.to_string(),
+            review_id: review_id.to_string(),
+            user_id: user_id.to_string(),
+            action_type,
+        });
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl ReportRepo for MemoryRepo {
+    async fn report_user(&self, user_id: &str, reporter_id: &str) -> Result<(), Error> {
+        if user_id.eq_ignore_ascii_case(reporter_id) {
+            return Ok(());
+        }
+
+        let mut tables = self.tables();
+        let report_type = USER_ACTION_TYPE as u8;
+
+        if tables.reports.iter().any(|report| {
+            report.user_id == user_id
+                && report.reporter_id == reporter_id
+                && report.report_type == report_type
+        }) {
+            return Ok(());
+        }
+
+        tables.reports.push(Report {
+            id: Uuid::new_v4().to_string(),
+            created: Utc::now().naive_utc(),
+            user_id: user_id.to_string(),
+            reporter_id: reporter_id.to_string(),
+            report_type,
+        });
+
+        return Ok(());
+    }
+
+    async fn get_all_reports(&self) -> Result<Vec<Report>, Error> {
+        let tables = self.tables();
+        return Ok(tables.reports.clone());
+    }
+
+    async fn get_reports_filed(&self, reporter_id: &str) -> Result<Vec<Report>, Error> {
+        let tables = self.tables();
+        let reports = tables
+            .reports
+            .iter()
+            .filter(|report| report.reporter_id == reporter_id)
+            .cloned()
+            .collect();
+
+        return Ok(newest_first(reports, |report| report.created));
+    }
+
+    async fn get_reports_received(&self, user_id: &str) -> Result<Vec<Report>, Error> {
+        let tables = self.tables();
+        let reports = tables
+            .reports
+            .iter()
+            .filter(|report| report.user_id == user_id)
+            .cloned()
+            .collect();
+
+        return Ok(newest_first(reports, |report| report.created));
+    }
+}
+
+#[async_trait]
+impl BookmarkRepo for MemoryRepo {
+    async fn get_all_bookmarks(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+    ) -> Result<Vec<Bookmark>, Error> {
+        let tables = self.tables();
+        let bookmarks = tables
+            .bookmarks
+            .iter()
+            .filter(|bookmark| {
+                bookmark.user_id == target_user_id && tables.is_friend(user_id, &bookmark.user_id)
+            })
+            .cloned()
+            .collect();
+
+        return Ok(newest_first(bookmarks, |bookmark| bookmark.created));
+    }
+
+    async fn does_bookmark_exist(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<bool, Error> {
+        let tables = self.tables();
+        return Ok(tables.bookmarks.iter().any(|bookmark| {
+            bookmark.user_id == user_id
+                && bookmark.location_name == location_name
+                && is_within(
+                    bookmark.latitude,
+                    bookmark.longitude,
+                    latitude,
+                    longitude,
+                    SAME_LOCATION_DISTANCE,
+                )
+        }));
+    }
+
+    async fn get_bookmarked_review_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<String>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .reviews
+            .iter()
+            .filter(|review| review_ids.contains(&review.id))
+            .filter(|review| {
+                tables.bookmarks.iter().any(|bookmark| {
+                    bookmark.user_id == user_id
+                        && bookmark.location_name == review.location_name
+                        && is_within(
+                            bookmark.latitude,
+                            bookmark.longitude,
+                            review.latitude,
+                            review.longitude,
+                            SAME_LOCATION_DISTANCE,
+                        )
+                })
+            })
+            .map(|review| review.id.clone())
+            .collect());
+    }
+
+    async fn get_nearby_bookmarks(
+        &self,
+        calling_user_id: &str,
+        user_id: &str,
+        page_request: &PageRequest,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Page<Bookmark>, Error> {
+        let tables = self.tables();
+        let bookmarks = tables
+            .bookmarks
+            .iter()
+            .filter(|bookmark| {
+                bookmark.user_id == user_id
+                    && tables.is_friend(calling_user_id, &bookmark.user_id)
+                    && is_within(
+                        bookmark.latitude,
+                        bookmark.longitude,
+                        latitude,
+                        longitude,
+                        NEARBY_BOOKMARK_DISTANCE,
+                    )
+            })
+            .cloned()
+            .collect();
+
+        return Ok(keyset_page(bookmarks, page_request, Bookmark::cursor));
+    }
+
+    async fn create_bookmark(&self, bookmark: &Bookmark) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.bookmarks.push(bookmark.clone());
+
+        return Ok(());
+    }
+
+    async fn remove_bookmark(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        let (removed, kept) =
+            std::mem::take(&mut tables.bookmarks)
+                .into_iter()
+                .partition(|bookmark: &Bookmark| {
+                    bookmark.user_id == user_id
+                        && bookmark.location_name == location_name
+                        && is_within(
+                            bookmark.latitude,
+                            bookmark.longitude,
+                            latitude,
+                            longitude,
+                            SAME_LOCATION_DISTANCE,
+                        )
+                });
+        tables.bookmarks = kept;
+
+        for bookmark in removed {
+            tables.add_tombstone(TOMBSTONE_BOOKMARK, &bookmark.id, &bookmark.user_id);
+        }
+
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl IpBlockRepo for MemoryRepo {
+    async fn get_active_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        let tables = self.tables();
+        let now = Utc::now().naive_utc();
+
+        return Ok(tables
+            .ipblocks
+            .iter()
+            .filter(|ipblock| match ipblock.expires {
+                Some(expires) => expires > now,
+                None => true,
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_all_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        let tables = self.tables();
+        return Ok(newest_first(tables.ipblocks.clone(), |ipblock| {
+            ipblock.created
+        }));
+    }
+
+    async fn create_ipblock(&self, ipblock: &IpBlock) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables.ipblocks.push(ipblock.clone());
+
+        return Ok(());
+    }
+
+    async fn remove_ipblock(&self, id: &str) -> Result<bool, Error> {
+        let mut tables = self.tables();
+        let count = tables.ipblocks.len();
+        tables.ipblocks.retain(|ipblock| ipblock.id != id);
+
+        return Ok(tables.ipblocks.len() < count);
+    }
+}
+
+#[async_trait]
+impl PendingNotificationRepo for MemoryRepo {
+    async fn create_pending_notifications(
+        &self,
+        pending_notifications: &[PendingNotification],
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        tables
+            .pending_notifications
+            .extend(pending_notifications.iter().cloned());
+
+        return Ok(());
+    }
+
+    async fn take_pending_notifications(&self) -> Result<Vec<PendingNotification>, Error> {
+        let mut tables = self.tables();
+        let mut pending_notifications: Vec<PendingNotification> =
+            tables.pending_notifications.drain(..).collect();
+        pending_notifications.sort_by_key(|pending| pending.created);
+
+        return Ok(pending_notifications);
+    }
+}
+
+/// There's no schema to migrate, so this reports nothing applied and pings always succeed.
+#[async_trait]
+impl SyncRepo for MemoryRepo {
+    async fn get_reviews_changed_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Review>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .reviews
+            .iter()
+            .filter(|review| {
+                let updated = tables
+                    .review_updated
+                    .get(&review.id)
+                    .copied()
+                    .unwrap_or(review.created);
+                tables.is_synced(user_id, &review.user_id, updated, since)
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_likes_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Like>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .likes
+            .iter()
+            .filter(|like| match tables.review_author(&like.review_id) {
+                Some(author) => tables.is_synced(user_id, &author, like.created, since),
+                None => false,
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_replies_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Reply>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .replies
+            .iter()
+            .filter(|reply| match tables.review_author(&reply.review_id) {
+                Some(author) => tables.is_synced(user_id, &author, reply.created, since),
+                None => false,
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_bookmarks_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Bookmark>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .bookmarks
+            .iter()
+            .filter(|bookmark| {
+                tables.is_synced(user_id, &bookmark.user_id, bookmark.created, since)
+            })
+            .cloned()
+            .collect());
+    }
+
+    async fn get_tombstones_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Tombstone>, Error> {
+        let tables = self.tables();
+        return Ok(tables
+            .tombstones
+            .iter()
+            .filter(|tombstone| {
+                tombstone.created >= *since && tables.is_friend(user_id, &tombstone.owner_id)
+            })
+            .cloned()
+            .collect());
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepo for MemoryRepo {
+    async fn get_idempotency_key(
+        &self,
+        user_id: &str,
+        idempotency_key: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Option<IdempotencyKey>, Error> {
+        return Ok(self
+            .tables()
+            .idempotency_keys
+            .iter()
+            .find(|key| {
+                key.user_id == user_id
+                    && key.idempotency_key == idempotency_key
+                    && key.created >= *since
+            })
+            .cloned());
+    }
+
+    async fn create_idempotency_key(
+        &self,
+        key: &IdempotencyKey,
+        expired_before: &NaiveDateTime,
+        abandoned_before: &NaiveDateTime,
+    ) -> Result<bool, Error> {
+        let mut tables = self.tables();
+        tables.idempotency_keys.retain(|existing| {
+            let abandoned = existing.user_id == key.user_id
+                && existing.idempotency_key == key.idempotency_key
+                && existing.status.is_none()
+                && existing.created < *abandoned_before;
+
+            existing.created >= *expired_before && !abandoned
+        });
+
+        if tables.idempotency_keys.iter().any(|existing| {
+            existing.user_id == key.user_id && existing.idempotency_key == key.idempotency_key
+        }) {
+            return Ok(false);
+        }
+
+        tables.idempotency_keys.push(key.clone());
+        return Ok(true);
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        id: &str,
+        status: u16,
+        headers: &str,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let mut tables = self.tables();
+        if let Some(key) = tables.idempotency_keys.iter_mut().find(|key| key.id == id) {
+            key.status = Some(status);
+            key.headers = Some(headers.to_string());
+            key.body = Some(body.to_vec());
+        }
+
+        return Ok(());
+    }
+
+    async fn remove_idempotency_key(&self, id: &str) -> Result<(), Error> {
+        self.tables().idempotency_keys.retain(|key| key.id != id);
+        return Ok(());
+    }
+}
+
+#[async_trait]
+impl DatabaseRepo for MemoryRepo {
+    async fn get_ping(&self, id: &str) -> Result<String, Error> {
+        return Ok(id.to_string());
+    }
+
+    async fn get_applied_migrations(&self) -> Result<Vec<SchemaMigration>, Error> {
+        return Ok(vec![]);
+    }
+
+    async fn run_migrations(&self) -> Result<(), MigrateError> {
+        return Ok(());
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MIGRATOR
+    }
+
+    fn pool_connections(&self) -> (u32, usize) {
+        (0, 0)
+    }
+}
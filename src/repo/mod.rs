@@ -0,0 +1,19 @@
+pub mod traits;
+pub use traits::*;
+
+pub mod mysql;
+pub use mysql::*;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "postgres")]
+pub use postgres::*;
+
+pub mod memory;
+pub use memory::*;
+
+pub mod app_data;
+pub use app_data::*;
+
+#[cfg(test)]
+mod suite;
@@ -0,0 +1,797 @@
+use async_trait::async_trait;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    types::chrono::NaiveDateTime,
+    Error, MySqlPool,
+};
+use std::{future::Future, sync::Arc};
+
+use crate::db::{
+    self, AuthAttempt, Bookmark, DataVersion, ExpandedNotification, Friend, FriendRequest,
+    IdempotencyKey, IpBlock, Like, Page, PageRequest, PendingNotification, PhoneAuth, Pic, Reply,
+    Report, Review, ReviewAnnotation, SchemaMigration, Tombstone, User, UserActivityCounts,
+};
+
+use crate::{
+    replica::{is_connection_error, ReplicaRouting},
+    tracing::add_error_span,
+};
+
+use super::{
+    AuthRepo, BookmarkRepo, DatabaseRepo, FriendRepo, IdempotencyRepo, IpBlockRepo, LikeRepo,
+    NotificationRepo, PendingNotificationRepo, PicRepo, ReplyRepo, ReportRepo, ReviewRepo,
+    SyncRepo, UserRepo,
+};
+
+/// Repositories backed by MySQL, using the functions in `db`.
+/// Heavy feed and map reads go to the replica when one is set.
+pub struct MySqlRepo {
+    pool: MySqlPool,
+    replica: Option<Arc<ReplicaRouting>>,
+}
+
+impl MySqlRepo {
+    pub fn new(pool: MySqlPool) -> MySqlRepo {
+        MySqlRepo {
+            pool,
+            replica: None,
+        }
+    }
+
+    /// Sends heavy reads to the replica, keeping the primary for writes and everything else.
+    pub fn with_replica(mut self, replica: Arc<ReplicaRouting>) -> MySqlRepo {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Runs a read on the replica when `user_id` can safely read from it, otherwise on the primary.
+    /// A replica that can't be reached is marked unhealthy and the read is retried on the primary.
+    async fn read<T, F, Fut>(&self, user_id: &str, query: F) -> Result<T, Error>
+    where
+        T: Send,
+        F: Fn(MySqlPool) -> Fut + Send,
+        Fut: Future<Output = Result<T, Error>> + Send,
+    {
+        if let Some(replica) = &self.replica {
+            if let Some(pool) = replica.read_pool(user_id) {
+                match query(pool.clone()).await {
+                    Err(error) if is_connection_error(&error) => {
+                        add_error_span(&error);
+                        replica.mark_unhealthy();
+                    }
+                    result => return result,
+                }
+            }
+        }
+
+        query(self.pool.clone()).await
+    }
+}
+
+#[async_trait]
+impl UserRepo for MySqlRepo {
+    async fn get_user(&self, id: &str) -> Result<Option<User>, Error> {
+        db::get_user(&self.pool, id).await
+    }
+
+    async fn get_users_by_ids(&self, ids: &[String]) -> Result<Vec<User>, Error> {
+        db::get_users_by_ids(&self.pool, ids).await
+    }
+
+    async fn get_user_from_name(&self, name: &str) -> Result<Option<User>, Error> {
+        db::get_user_from_name(&self.pool, name).await
+    }
+
+    async fn search_user_from_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        db::search_user_from_name(&self.pool, name).await
+    }
+
+    async fn does_user_exist(&self, id: &str) -> Result<bool, Error> {
+        db::does_user_exist(&self.pool, id).await
+    }
+
+    async fn does_user_exist_by_name(&self, name: &str) -> Result<bool, Error> {
+        db::does_user_exist_by_name(&self.pool, name).await
+    }
+
+    async fn get_user_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        db::get_user_by_phone(&self.pool, phone).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        db::get_user_by_email(&self.pool, email).await
+    }
+
+    async fn phone_number_discovery(&self, numbers: &Vec<&str>) -> Result<Vec<User>, Error> {
+        db::phone_number_discovery(&self.pool, numbers).await
+    }
+
+    async fn create_user(&self, user: &User) -> Result<(), Error> {
+        db::create_user(&self.pool, user).await
+    }
+
+    async fn update_user_phone(&self, user_id: &str, phone: &str) -> Result<(), Error> {
+        db::update_user_phone(&self.pool, user_id, phone).await
+    }
+
+    async fn update_usernames(
+        &self,
+        user_id: &str,
+        display_name: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        db::update_usernames(&self.pool, user_id, display_name, name).await
+    }
+
+    async fn update_device_token(&self, user_id: &str, device_token: &str) -> Result<(), Error> {
+        db::update_device_token(&self.pool, user_id, device_token).await
+    }
+
+    async fn update_recovery_email(&self, user_id: &str, email: &str) -> Result<(), Error> {
+        db::update_recovery_email(&self.pool, user_id, email).await
+    }
+
+    async fn update_user_pic_id(&self, pic_id: &str, user_id: &str) -> Result<(), Error> {
+        db::update_user_pic_id(&self.pool, pic_id, user_id).await
+    }
+
+    async fn get_total_user_count(&self) -> Result<i64, Error> {
+        db::get_total_user_count(&self.pool).await
+    }
+
+    async fn get_user_activity_counts(&self, user_id: &str) -> Result<UserActivityCounts, Error> {
+        db::get_user_activity_counts(&self.pool, user_id).await
+    }
+
+    async fn remove_user_activity(&self, user_id: &str) -> Result<(), Error> {
+        db::remove_user_activity(&self.pool, user_id).await
+    }
+
+    async fn update_user_disabled(&self, user_id: &str, disabled: bool) -> Result<(), Error> {
+        db::update_user_disabled(&self.pool, user_id, disabled).await
+    }
+
+    async fn get_users_missing_self_friend(&self) -> Result<Vec<User>, Error> {
+        db::get_users_missing_self_friend(&self.pool).await
+    }
+}
+
+#[async_trait]
+impl AuthRepo for MySqlRepo {
+    async fn get_current_phoneauths(&self, phone: &str) -> Result<Vec<PhoneAuth>, Error> {
+        db::get_current_phoneauths(&self.pool, phone).await
+    }
+
+    async fn get_phoneauth_attempts(&self, phone: &str) -> Result<Vec<AuthAttempt>, Error> {
+        db::get_phoneauth_attempts(&self.pool, phone).await
+    }
+
+    async fn create_phoneauth(&self, phone: &str, code: &str, ip: &str) -> Result<(), Error> {
+        db::create_phoneauth(&self.pool, phone, code, ip).await
+    }
+
+    async fn update_authattempt_used(&self, id: &str) -> Result<(), Error> {
+        db::update_authattempt_used(&self.pool, id).await
+    }
+
+    async fn create_authattempt(&self, phone: &str) -> Result<(), Error> {
+        db::create_authattempt(&self.pool, phone).await
+    }
+
+    async fn get_recent_phoneauths(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<PhoneAuth>, Error> {
+        db::get_recent_phoneauths(&self.pool, phone, limit).await
+    }
+
+    async fn get_recent_authattempts(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<AuthAttempt>, Error> {
+        db::get_recent_authattempts(&self.pool, phone, limit).await
+    }
+}
+
+#[async_trait]
+impl FriendRepo for MySqlRepo {
+    async fn get_incoming_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        db::get_incoming_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_acceptable_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        db::get_acceptable_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_incoming_ignored_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        db::get_incoming_ignored_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_outgoing_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        db::get_outgoing_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_current_friends(&self, user_id: &str) -> Result<Vec<Friend>, Error> {
+        db::get_current_friends(&self.pool, user_id).await
+    }
+
+    async fn get_friends_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        db::get_friends_version(&self.pool, user_id).await
+    }
+
+    async fn are_users_friends(&self, user_id: &str, other_user_id: &str) -> Result<bool, Error> {
+        db::are_users_friends(&self.pool, user_id, other_user_id).await
+    }
+
+    async fn create_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        db::create_friend_request(&self.pool, user_id, friend_id).await
+    }
+
+    async fn ignore_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        db::ignore_friend_request(&self.pool, request_id, friend_id).await
+    }
+
+    async fn decline_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        db::decline_friend_request(&self.pool, request_id, friend_id).await
+    }
+
+    async fn cancel_friend_request(&self, request_id: &str, user_id: &str) -> Result<(), Error> {
+        db::cancel_friend_request(&self.pool, request_id, user_id).await
+    }
+
+    async fn accept_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        db::accept_friend_request(&self.pool, user_id, friend_id).await
+    }
+
+    async fn remove_current_friend(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        db::remove_current_friend(&self.pool, user_id, friend_id).await
+    }
+
+    async fn create_self_friend(&self, user_id: &str) -> Result<(), Error> {
+        db::create_self_friend(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl ReviewRepo for MySqlRepo {
+    async fn get_review(&self, user_id: &str, review_id: &str) -> Result<Option<Review>, Error> {
+        db::get_review(&self.pool, user_id, review_id).await
+    }
+
+    async fn get_reviews_by_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<Review>, Error> {
+        db::get_reviews_by_ids(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_compound_review_version(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<DataVersion>, Error> {
+        db::get_compound_review_version(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_reviews_from_location(
+        &self,
+        user_id: &str,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_reviews_from_location(&pool, user_id, name, latitude, longitude).await
+        })
+        .await
+    }
+
+    async fn get_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_reviews_from_user(&pool, user_id, target_user_id, page).await
+        })
+        .await
+    }
+
+    async fn get_recommended_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_recommended_reviews_from_user(&pool, user_id, target_user_id, page).await
+        })
+        .await
+    }
+
+    async fn get_reviews_from_bounds(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_reviews_from_bounds(
+                &pool,
+                user_id,
+                latitude_north,
+                latitude_south,
+                longitude_west,
+                longitude_east,
+                page,
+            )
+            .await
+        })
+        .await
+    }
+
+    async fn get_reviews_from_bounds_with_exclusions(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        latitude_north_e: f64,
+        latitude_south_e: f64,
+        longitude_west_e: f64,
+        longitude_east_e: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_reviews_from_bounds_with_exclusions(
+                &pool,
+                user_id,
+                latitude_north,
+                latitude_south,
+                longitude_west,
+                longitude_east,
+                latitude_north_e,
+                latitude_south_e,
+                longitude_west_e,
+                longitude_east_e,
+                page,
+            )
+            .await
+        })
+        .await
+    }
+
+    async fn get_latest_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+        category: &Option<String>,
+    ) -> Result<Page<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_latest_reviews(&pool, user_id, page, category).await
+        })
+        .await
+    }
+
+    async fn search_latest_reviews(
+        &self,
+        user_id: &str,
+        search_prefix: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::search_latest_reviews(&pool, user_id, search_prefix, page).await
+        })
+        .await
+    }
+
+    async fn get_recent_reviews_by_user(
+        &self,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<Review>, Error> {
+        db::get_recent_reviews_by_user(&self.pool, user_id, limit).await
+    }
+
+    async fn create_review(&self, review: &Review) -> Result<(), Error> {
+        db::create_review(&self.pool, review).await
+    }
+
+    async fn update_review(&self, review_id: &str, stars: u8, text: &str) -> Result<(), Error> {
+        db::update_review(&self.pool, review_id, stars, text).await
+    }
+
+    async fn update_review_delivered(&self, review_id: &str, delivered: u8) -> Result<(), Error> {
+        db::update_review_delivered(&self.pool, review_id, delivered).await
+    }
+
+    async fn update_review_recommended(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        recommended: bool,
+    ) -> Result<(), Error> {
+        db::update_review_recommended(&self.pool, review_id, user_id, recommended).await
+    }
+
+    async fn remove_review_and_children(&self, review_id: &str) -> Result<(), Error> {
+        db::remove_review_and_children(&self.pool, review_id).await
+    }
+}
+
+#[async_trait]
+impl PicRepo for MySqlRepo {
+    async fn get_pic(&self, id: &str) -> Result<Option<Pic>, Error> {
+        db::get_pic(&self.pool, id).await
+    }
+
+    async fn get_all_pics(&self, review_id: &str) -> Result<Vec<Pic>, Error> {
+        db::get_all_pics(&self.pool, review_id).await
+    }
+
+    async fn get_pics_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Pic>, Error> {
+        db::get_pics_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn create_pic(
+        &self,
+        review_id: Option<String>,
+        width: u16,
+        height: u16,
+    ) -> Result<Pic, Error> {
+        db::create_pic(&self.pool, review_id, width, height).await
+    }
+
+    async fn delete_pic(&self, pic_id: &str) -> Result<(), Error> {
+        db::delete_pic(&self.pool, pic_id).await
+    }
+
+    async fn remove_review_pic_id(&self, pic_id: &str, review_id: &str) -> Result<(), Error> {
+        db::remove_review_pic_id(&self.pool, pic_id, review_id).await
+    }
+
+    async fn get_orphaned_pics(&self) -> Result<Vec<Pic>, Error> {
+        db::get_orphaned_pics(&self.pool).await
+    }
+
+    async fn get_review_pics_by_user(&self, user_id: &str) -> Result<Vec<Pic>, Error> {
+        db::get_review_pics_by_user(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl LikeRepo for MySqlRepo {
+    async fn get_all_likes(&self, review_id: &str) -> Result<Vec<Like>, Error> {
+        db::get_all_likes(&self.pool, review_id).await
+    }
+
+    async fn get_likes_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Like>, Error> {
+        db::get_likes_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn get_liked_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_liked_reviews(&pool, user_id, page).await
+        })
+        .await
+    }
+
+    async fn is_already_liked(&self, user_id: &str, review_id: &str) -> Result<bool, Error> {
+        db::is_already_liked(&self.pool, user_id, review_id).await
+    }
+
+    async fn create_like(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        like_type: Option<i8>,
+    ) -> Result<(), Error> {
+        db::create_like(&self.pool, user_id, review_id, like_type).await
+    }
+
+    async fn remove_like(&self, user_id: &str, review_id: &str) -> Result<(), Error> {
+        db::remove_like(&self.pool, user_id, review_id).await
+    }
+}
+
+#[async_trait]
+impl ReplyRepo for MySqlRepo {
+    async fn get_all_replies(&self, review_id: &str) -> Result<Vec<Reply>, Error> {
+        db::get_all_replies(&self.pool, review_id).await
+    }
+
+    async fn get_replies_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Reply>, Error> {
+        db::get_replies_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn get_reply(&self, review_id: &str, reply_id: &str) -> Result<Option<Reply>, Error> {
+        db::get_reply(&self.pool, review_id, reply_id).await
+    }
+
+    async fn create_reply(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        text: &str,
+        reply_to_id: Option<&String>,
+    ) -> Result<(), Error> {
+        db::create_reply(&self.pool, user_id, review_id, text, reply_to_id).await
+    }
+
+    async fn delete_reply(
+        &self,
+        reply_id: &str,
+        review_id: &str,
+        user_id: &str,
+    ) -> Result<(), Error> {
+        db::delete_reply(&self.pool, reply_id, review_id, user_id).await
+    }
+}
+
+#[async_trait]
+impl NotificationRepo for MySqlRepo {
+    async fn get_notifications(&self, user_id: &str) -> Result<Vec<ExpandedNotification>, Error> {
+        db::get_notifications(&self.pool, user_id).await
+    }
+
+    async fn get_notifications_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        db::get_notifications_version(&self.pool, user_id).await
+    }
+
+    async fn get_notification_count(&self, user_id: &str) -> i64 {
+        db::get_notification_count(&self.pool, user_id).await
+    }
+
+    async fn confirm_notifications(&self, user_id: &str) -> Result<(), Error> {
+        db::confirm_notifications(&self.pool, user_id).await
+    }
+
+    async fn create_notification(
+        &self,
+        user_id: &str,
+        review_user_id: &str,
+        review_id: &str,
+        action_type: u8,
+    ) -> Result<(), Error> {
+        db::create_notification(&self.pool, user_id, review_user_id, review_id, action_type).await
+    }
+}
+
+#[async_trait]
+impl ReportRepo for MySqlRepo {
+    async fn report_user(&self, user_id: &str, reporter_id: &str) -> Result<(), Error> {
+        db::report_user(&self.pool, user_id, reporter_id).await
+    }
+
+    async fn get_all_reports(&self) -> Result<Vec<Report>, Error> {
+        db::get_all_reports(&self.pool).await
+    }
+
+    async fn get_reports_filed(&self, reporter_id: &str) -> Result<Vec<Report>, Error> {
+        db::get_reports_filed(&self.pool, reporter_id).await
+    }
+
+    async fn get_reports_received(&self, user_id: &str) -> Result<Vec<Report>, Error> {
+        db::get_reports_received(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl BookmarkRepo for MySqlRepo {
+    async fn get_all_bookmarks(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+    ) -> Result<Vec<Bookmark>, Error> {
+        self.read(user_id, |pool| async move {
+            db::get_all_bookmarks(&pool, user_id, target_user_id).await
+        })
+        .await
+    }
+
+    async fn does_bookmark_exist(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<bool, Error> {
+        db::does_bookmark_exist(&self.pool, user_id, location_name, latitude, longitude).await
+    }
+
+    async fn get_bookmarked_review_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<String>, Error> {
+        db::get_bookmarked_review_ids(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_nearby_bookmarks(
+        &self,
+        calling_user_id: &str,
+        user_id: &str,
+        page: &PageRequest,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Page<Bookmark>, Error> {
+        self.read(calling_user_id, |pool| async move {
+            db::get_nearby_bookmarks(&pool, calling_user_id, user_id, page, latitude, longitude)
+                .await
+        })
+        .await
+    }
+
+    async fn create_bookmark(&self, bookmark: &Bookmark) -> Result<(), Error> {
+        db::create_bookmark(&self.pool, bookmark).await
+    }
+
+    async fn remove_bookmark(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), Error> {
+        db::remove_bookmark(&self.pool, user_id, location_name, latitude, longitude).await
+    }
+}
+
+#[async_trait]
+impl IpBlockRepo for MySqlRepo {
+    async fn get_active_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        db::get_active_ipblocks(&self.pool).await
+    }
+
+    async fn get_all_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        db::get_all_ipblocks(&self.pool).await
+    }
+
+    async fn create_ipblock(&self, ipblock: &IpBlock) -> Result<(), Error> {
+        db::create_ipblock(&self.pool, ipblock).await
+    }
+
+    async fn remove_ipblock(&self, id: &str) -> Result<bool, Error> {
+        db::remove_ipblock(&self.pool, id).await
+    }
+}
+
+#[async_trait]
+impl PendingNotificationRepo for MySqlRepo {
+    async fn create_pending_notifications(
+        &self,
+        pending_notifications: &[PendingNotification],
+    ) -> Result<(), Error> {
+        db::create_pending_notifications(&self.pool, pending_notifications).await
+    }
+
+    async fn take_pending_notifications(&self) -> Result<Vec<PendingNotification>, Error> {
+        db::take_pending_notifications(&self.pool).await
+    }
+}
+
+// Read from the primary, a lagging replica would let changes slip behind the token.
+#[async_trait]
+impl SyncRepo for MySqlRepo {
+    async fn get_reviews_changed_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Review>, Error> {
+        db::get_reviews_changed_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_likes_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Like>, Error> {
+        db::get_likes_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_replies_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Reply>, Error> {
+        db::get_replies_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_bookmarks_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Bookmark>, Error> {
+        db::get_bookmarks_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_tombstones_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Tombstone>, Error> {
+        db::get_tombstones_since(&self.pool, user_id, since).await
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepo for MySqlRepo {
+    async fn get_idempotency_key(
+        &self,
+        user_id: &str,
+        idempotency_key: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Option<IdempotencyKey>, Error> {
+        db::get_idempotency_key(&self.pool, user_id, idempotency_key, since).await
+    }
+
+    async fn create_idempotency_key(
+        &self,
+        key: &IdempotencyKey,
+        expired_before: &NaiveDateTime,
+        abandoned_before: &NaiveDateTime,
+    ) -> Result<bool, Error> {
+        db::create_idempotency_key(&self.pool, key, expired_before, abandoned_before).await
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        id: &str,
+        status: u16,
+        headers: &str,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        db::complete_idempotency_key(&self.pool, id, status, headers, body).await
+    }
+
+    async fn remove_idempotency_key(&self, id: &str) -> Result<(), Error> {
+        db::remove_idempotency_key(&self.pool, id).await
+    }
+}
+
+#[async_trait]
+impl DatabaseRepo for MySqlRepo {
+    async fn get_ping(&self, id: &str) -> Result<String, Error> {
+        db::get_ping(&self.pool, id).await
+    }
+
+    async fn get_applied_migrations(&self) -> Result<Vec<SchemaMigration>, Error> {
+        db::get_applied_migrations(&self.pool).await
+    }
+
+    async fn run_migrations(&self) -> Result<(), MigrateError> {
+        db::run_migrations(&self.pool).await
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &db::MIGRATOR
+    }
+
+    fn pool_connections(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+}
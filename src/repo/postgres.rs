@@ -0,0 +1,737 @@
+use async_trait::async_trait;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    types::chrono::NaiveDateTime,
+    Error, PgPool,
+};
+
+use crate::{
+    db::{
+        AuthAttempt, Bookmark, DataVersion, ExpandedNotification, Friend, FriendRequest,
+        IdempotencyKey, IpBlock, Like, Page, PageRequest, PendingNotification, PhoneAuth, Pic,
+        Reply, Report, Review, ReviewAnnotation, SchemaMigration, Tombstone, User,
+        UserActivityCounts,
+    },
+    pg,
+};
+
+use super::{
+    AuthRepo, BookmarkRepo, DatabaseRepo, FriendRepo, IdempotencyRepo, IpBlockRepo, LikeRepo,
+    NotificationRepo, PendingNotificationRepo, PicRepo, ReplyRepo, ReportRepo, ReviewRepo,
+    SyncRepo, UserRepo,
+};
+
+/// Repositories backed by PostgreSQL with PostGIS, using the functions in `pg`.
+pub struct PgRepo {
+    pool: PgPool,
+}
+
+impl PgRepo {
+    pub fn new(pool: PgPool) -> PgRepo {
+        PgRepo { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepo for PgRepo {
+    async fn get_user(&self, id: &str) -> Result<Option<User>, Error> {
+        pg::get_user(&self.pool, id).await
+    }
+
+    async fn get_users_by_ids(&self, ids: &[String]) -> Result<Vec<User>, Error> {
+        pg::get_users_by_ids(&self.pool, ids).await
+    }
+
+    async fn get_user_from_name(&self, name: &str) -> Result<Option<User>, Error> {
+        pg::get_user_from_name(&self.pool, name).await
+    }
+
+    async fn search_user_from_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        pg::search_user_from_name(&self.pool, name).await
+    }
+
+    async fn does_user_exist(&self, id: &str) -> Result<bool, Error> {
+        pg::does_user_exist(&self.pool, id).await
+    }
+
+    async fn does_user_exist_by_name(&self, name: &str) -> Result<bool, Error> {
+        pg::does_user_exist_by_name(&self.pool, name).await
+    }
+
+    async fn get_user_by_phone(&self, phone: &str) -> Result<Option<User>, Error> {
+        pg::get_user_by_phone(&self.pool, phone).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, Error> {
+        pg::get_user_by_email(&self.pool, email).await
+    }
+
+    async fn phone_number_discovery(&self, numbers: &Vec<&str>) -> Result<Vec<User>, Error> {
+        pg::phone_number_discovery(&self.pool, numbers).await
+    }
+
+    async fn create_user(&self, user: &User) -> Result<(), Error> {
+        pg::create_user(&self.pool, user).await
+    }
+
+    async fn update_user_phone(&self, user_id: &str, phone: &str) -> Result<(), Error> {
+        pg::update_user_phone(&self.pool, user_id, phone).await
+    }
+
+    async fn update_usernames(
+        &self,
+        user_id: &str,
+        display_name: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        pg::update_usernames(&self.pool, user_id, display_name, name).await
+    }
+
+    async fn update_device_token(&self, user_id: &str, device_token: &str) -> Result<(), Error> {
+        pg::update_device_token(&self.pool, user_id, device_token).await
+    }
+
+    async fn update_recovery_email(&self, user_id: &str, email: &str) -> Result<(), Error> {
+        pg::update_recovery_email(&self.pool, user_id, email).await
+    }
+
+    async fn update_user_pic_id(&self, pic_id: &str, user_id: &str) -> Result<(), Error> {
+        pg::update_user_pic_id(&self.pool, pic_id, user_id).await
+    }
+
+    async fn get_total_user_count(&self) -> Result<i64, Error> {
+        pg::get_total_user_count(&self.pool).await
+    }
+
+    async fn get_user_activity_counts(&self, user_id: &str) -> Result<UserActivityCounts, Error> {
+        pg::get_user_activity_counts(&self.pool, user_id).await
+    }
+
+    async fn remove_user_activity(&self, user_id: &str) -> Result<(), Error> {
+        pg::remove_user_activity(&self.pool, user_id).await
+    }
+
+    async fn update_user_disabled(&self, user_id: &str, disabled: bool) -> Result<(), Error> {
+        pg::update_user_disabled(&self.pool, user_id, disabled).await
+    }
+
+    async fn get_users_missing_self_friend(&self) -> Result<Vec<User>, Error> {
+        pg::get_users_missing_self_friend(&self.pool).await
+    }
+}
+
+#[async_trait]
+impl AuthRepo for PgRepo {
+    async fn get_current_phoneauths(&self, phone: &str) -> Result<Vec<PhoneAuth>, Error> {
+        pg::get_current_phoneauths(&self.pool, phone).await
+    }
+
+    async fn get_phoneauth_attempts(&self, phone: &str) -> Result<Vec<AuthAttempt>, Error> {
+        pg::get_phoneauth_attempts(&self.pool, phone).await
+    }
+
+    async fn create_phoneauth(&self, phone: &str, code: &str, ip: &str) -> Result<(), Error> {
+        pg::create_phoneauth(&self.pool, phone, code, ip).await
+    }
+
+    async fn update_authattempt_used(&self, id: &str) -> Result<(), Error> {
+        pg::update_authattempt_used(&self.pool, id).await
+    }
+
+    async fn create_authattempt(&self, phone: &str) -> Result<(), Error> {
+        pg::create_authattempt(&self.pool, phone).await
+    }
+
+    async fn get_recent_phoneauths(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<PhoneAuth>, Error> {
+        pg::get_recent_phoneauths(&self.pool, phone, limit).await
+    }
+
+    async fn get_recent_authattempts(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<AuthAttempt>, Error> {
+        pg::get_recent_authattempts(&self.pool, phone, limit).await
+    }
+}
+
+#[async_trait]
+impl FriendRepo for PgRepo {
+    async fn get_incoming_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        pg::get_incoming_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_acceptable_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        pg::get_acceptable_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_incoming_ignored_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        pg::get_incoming_ignored_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_outgoing_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error> {
+        pg::get_outgoing_friend_requests(&self.pool, user_id).await
+    }
+
+    async fn get_current_friends(&self, user_id: &str) -> Result<Vec<Friend>, Error> {
+        pg::get_current_friends(&self.pool, user_id).await
+    }
+
+    async fn get_friends_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        pg::get_friends_version(&self.pool, user_id).await
+    }
+
+    async fn are_users_friends(&self, user_id: &str, other_user_id: &str) -> Result<bool, Error> {
+        pg::are_users_friends(&self.pool, user_id, other_user_id).await
+    }
+
+    async fn create_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        pg::create_friend_request(&self.pool, user_id, friend_id).await
+    }
+
+    async fn ignore_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        pg::ignore_friend_request(&self.pool, request_id, friend_id).await
+    }
+
+    async fn decline_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error> {
+        pg::decline_friend_request(&self.pool, request_id, friend_id).await
+    }
+
+    async fn cancel_friend_request(&self, request_id: &str, user_id: &str) -> Result<(), Error> {
+        pg::cancel_friend_request(&self.pool, request_id, user_id).await
+    }
+
+    async fn accept_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        pg::accept_friend_request(&self.pool, user_id, friend_id).await
+    }
+
+    async fn remove_current_friend(&self, user_id: &str, friend_id: &str) -> Result<(), Error> {
+        pg::remove_current_friend(&self.pool, user_id, friend_id).await
+    }
+
+    async fn create_self_friend(&self, user_id: &str) -> Result<(), Error> {
+        pg::create_self_friend(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl ReviewRepo for PgRepo {
+    async fn get_review(&self, user_id: &str, review_id: &str) -> Result<Option<Review>, Error> {
+        pg::get_review(&self.pool, user_id, review_id).await
+    }
+
+    async fn get_reviews_by_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<Review>, Error> {
+        pg::get_reviews_by_ids(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_compound_review_version(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<DataVersion>, Error> {
+        pg::get_compound_review_version(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_reviews_from_location(
+        &self,
+        user_id: &str,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<Review>, Error> {
+        pg::get_reviews_from_location(&self.pool, user_id, name, latitude, longitude).await
+    }
+
+    async fn get_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        pg::get_reviews_from_user(&self.pool, user_id, target_user_id, page).await
+    }
+
+    async fn get_recommended_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        pg::get_recommended_reviews_from_user(&self.pool, user_id, target_user_id, page).await
+    }
+
+    async fn get_reviews_from_bounds(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        pg::get_reviews_from_bounds(
+            &self.pool,
+            user_id,
+            latitude_north,
+            latitude_south,
+            longitude_west,
+            longitude_east,
+            page,
+        )
+        .await
+    }
+
+    async fn get_reviews_from_bounds_with_exclusions(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        latitude_north_e: f64,
+        latitude_south_e: f64,
+        longitude_west_e: f64,
+        longitude_east_e: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error> {
+        pg::get_reviews_from_bounds_with_exclusions(
+            &self.pool,
+            user_id,
+            latitude_north,
+            latitude_south,
+            longitude_west,
+            longitude_east,
+            latitude_north_e,
+            latitude_south_e,
+            longitude_west_e,
+            longitude_east_e,
+            page,
+        )
+        .await
+    }
+
+    async fn get_latest_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+        category: &Option<String>,
+    ) -> Result<Page<Review>, Error> {
+        pg::get_latest_reviews(&self.pool, user_id, page, category).await
+    }
+
+    async fn search_latest_reviews(
+        &self,
+        user_id: &str,
+        search_prefix: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        pg::search_latest_reviews(&self.pool, user_id, search_prefix, page).await
+    }
+
+    async fn get_recent_reviews_by_user(
+        &self,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<Review>, Error> {
+        pg::get_recent_reviews_by_user(&self.pool, user_id, limit).await
+    }
+
+    async fn create_review(&self, review: &Review) -> Result<(), Error> {
+        pg::create_review(&self.pool, review).await
+    }
+
+    async fn update_review(&self, review_id: &str, stars: u8, text: &str) -> Result<(), Error> {
+        pg::update_review(&self.pool, review_id, stars, text).await
+    }
+
+    async fn update_review_delivered(&self, review_id: &str, delivered: u8) -> Result<(), Error> {
+        pg::update_review_delivered(&self.pool, review_id, delivered).await
+    }
+
+    async fn update_review_recommended(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        recommended: bool,
+    ) -> Result<(), Error> {
+        pg::update_review_recommended(&self.pool, review_id, user_id, recommended).await
+    }
+
+    async fn remove_review_and_children(&self, review_id: &str) -> Result<(), Error> {
+        pg::remove_review_and_children(&self.pool, review_id).await
+    }
+}
+
+#[async_trait]
+impl PicRepo for PgRepo {
+    async fn get_pic(&self, id: &str) -> Result<Option<Pic>, Error> {
+        pg::get_pic(&self.pool, id).await
+    }
+
+    async fn get_all_pics(&self, review_id: &str) -> Result<Vec<Pic>, Error> {
+        pg::get_all_pics(&self.pool, review_id).await
+    }
+
+    async fn get_pics_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Pic>, Error> {
+        pg::get_pics_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn create_pic(
+        &self,
+        review_id: Option<String>,
+        width: u16,
+        height: u16,
+    ) -> Result<Pic, Error> {
+        pg::create_pic(&self.pool, review_id, width, height).await
+    }
+
+    async fn delete_pic(&self, pic_id: &str) -> Result<(), Error> {
+        pg::delete_pic(&self.pool, pic_id).await
+    }
+
+    async fn remove_review_pic_id(&self, pic_id: &str, review_id: &str) -> Result<(), Error> {
+        pg::remove_review_pic_id(&self.pool, pic_id, review_id).await
+    }
+
+    async fn get_orphaned_pics(&self) -> Result<Vec<Pic>, Error> {
+        pg::get_orphaned_pics(&self.pool).await
+    }
+
+    async fn get_review_pics_by_user(&self, user_id: &str) -> Result<Vec<Pic>, Error> {
+        pg::get_review_pics_by_user(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl LikeRepo for PgRepo {
+    async fn get_all_likes(&self, review_id: &str) -> Result<Vec<Like>, Error> {
+        pg::get_all_likes(&self.pool, review_id).await
+    }
+
+    async fn get_likes_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Like>, Error> {
+        pg::get_likes_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn get_liked_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error> {
+        pg::get_liked_reviews(&self.pool, user_id, page).await
+    }
+
+    async fn is_already_liked(&self, user_id: &str, review_id: &str) -> Result<bool, Error> {
+        pg::is_already_liked(&self.pool, user_id, review_id).await
+    }
+
+    async fn create_like(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        like_type: Option<i8>,
+    ) -> Result<(), Error> {
+        pg::create_like(&self.pool, user_id, review_id, like_type).await
+    }
+
+    async fn remove_like(&self, user_id: &str, review_id: &str) -> Result<(), Error> {
+        pg::remove_like(&self.pool, user_id, review_id).await
+    }
+}
+
+#[async_trait]
+impl ReplyRepo for PgRepo {
+    async fn get_all_replies(&self, review_id: &str) -> Result<Vec<Reply>, Error> {
+        pg::get_all_replies(&self.pool, review_id).await
+    }
+
+    async fn get_replies_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Reply>, Error> {
+        pg::get_replies_for_reviews(&self.pool, review_ids).await
+    }
+
+    async fn get_reply(&self, review_id: &str, reply_id: &str) -> Result<Option<Reply>, Error> {
+        pg::get_reply(&self.pool, review_id, reply_id).await
+    }
+
+    async fn create_reply(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        text: &str,
+        reply_to_id: Option<&String>,
+    ) -> Result<(), Error> {
+        pg::create_reply(&self.pool, user_id, review_id, text, reply_to_id).await
+    }
+
+    async fn delete_reply(
+        &self,
+        reply_id: &str,
+        review_id: &str,
+        user_id: &str,
+    ) -> Result<(), Error> {
+        pg::delete_reply(&self.pool, reply_id, review_id, user_id).await
+    }
+}
+
+#[async_trait]
+impl NotificationRepo for PgRepo {
+    async fn get_notifications(&self, user_id: &str) -> Result<Vec<ExpandedNotification>, Error> {
+        pg::get_notifications(&self.pool, user_id).await
+    }
+
+    async fn get_notifications_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error> {
+        pg::get_notifications_version(&self.pool, user_id).await
+    }
+
+    async fn get_notification_count(&self, user_id: &str) -> i64 {
+        pg::get_notification_count(&self.pool, user_id).await
+    }
+
+    async fn confirm_notifications(&self, user_id: &str) -> Result<(), Error> {
+        pg::confirm_notifications(&self.pool, user_id).await
+    }
+
+    async fn create_notification(
+        &self,
+        user_id: &str,
+        review_user_id: &str,
+        review_id: &str,
+        action_type: u8,
+    ) -> Result<(), Error> {
+        pg::create_notification(&self.pool, user_id, review_user_id, review_id, action_type).await
+    }
+}
+
+#[async_trait]
+impl ReportRepo for PgRepo {
+    async fn report_user(&self, user_id: &str, reporter_id: &str) -> Result<(), Error> {
+        pg::report_user(&self.pool, user_id, reporter_id).await
+    }
+
+    async fn get_all_reports(&self) -> Result<Vec<Report>, Error> {
+        pg::get_all_reports(&self.pool).await
+    }
+
+    async fn get_reports_filed(&self, reporter_id: &str) -> Result<Vec<Report>, Error> {
+        pg::get_reports_filed(&self.pool, reporter_id).await
+    }
+
+    async fn get_reports_received(&self, user_id: &str) -> Result<Vec<Report>, Error> {
+        pg::get_reports_received(&self.pool, user_id).await
+    }
+}
+
+#[async_trait]
+impl BookmarkRepo for PgRepo {
+    async fn get_all_bookmarks(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+    ) -> Result<Vec<Bookmark>, Error> {
+        pg::get_all_bookmarks(&self.pool, user_id, target_user_id).await
+    }
+
+    async fn does_bookmark_exist(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<bool, Error> {
+        pg::does_bookmark_exist(&self.pool, user_id, location_name, latitude, longitude).await
+    }
+
+    async fn get_bookmarked_review_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<String>, Error> {
+        pg::get_bookmarked_review_ids(&self.pool, user_id, review_ids).await
+    }
+
+    async fn get_nearby_bookmarks(
+        &self,
+        calling_user_id: &str,
+        user_id: &str,
+        page: &PageRequest,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Page<Bookmark>, Error> {
+        pg::get_nearby_bookmarks(
+            &self.pool,
+            calling_user_id,
+            user_id,
+            page,
+            latitude,
+            longitude,
+        )
+        .await
+    }
+
+    async fn create_bookmark(&self, bookmark: &Bookmark) -> Result<(), Error> {
+        pg::create_bookmark(&self.pool, bookmark).await
+    }
+
+    async fn remove_bookmark(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), Error> {
+        pg::remove_bookmark(&self.pool, user_id, location_name, latitude, longitude).await
+    }
+}
+
+#[async_trait]
+impl IpBlockRepo for PgRepo {
+    async fn get_active_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        pg::get_active_ipblocks(&self.pool).await
+    }
+
+    async fn get_all_ipblocks(&self) -> Result<Vec<IpBlock>, Error> {
+        pg::get_all_ipblocks(&self.pool).await
+    }
+
+    async fn create_ipblock(&self, ipblock: &IpBlock) -> Result<(), Error> {
+        pg::create_ipblock(&self.pool, ipblock).await
+    }
+
+    async fn remove_ipblock(&self, id: &str) -> Result<bool, Error> {
+        pg::remove_ipblock(&self.pool, id).await
+    }
+}
+
+#[async_trait]
+impl PendingNotificationRepo for PgRepo {
+    async fn create_pending_notifications(
+        &self,
+        pending_notifications: &[PendingNotification],
+    ) -> Result<(), Error> {
+        pg::create_pending_notifications(&self.pool, pending_notifications).await
+    }
+
+    async fn take_pending_notifications(&self) -> Result<Vec<PendingNotification>, Error> {
+        pg::take_pending_notifications(&self.pool).await
+    }
+}
+
+#[async_trait]
+impl SyncRepo for PgRepo {
+    async fn get_reviews_changed_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Review>, Error> {
+        pg::get_reviews_changed_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_likes_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Like>, Error> {
+        pg::get_likes_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_replies_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Reply>, Error> {
+        pg::get_replies_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_bookmarks_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Bookmark>, Error> {
+        pg::get_bookmarks_since(&self.pool, user_id, since).await
+    }
+
+    async fn get_tombstones_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Tombstone>, Error> {
+        pg::get_tombstones_since(&self.pool, user_id, since).await
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepo for PgRepo {
+    async fn get_idempotency_key(
+        &self,
+        user_id: &str,
+        idempotency_key: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Option<IdempotencyKey>, Error> {
+        pg::get_idempotency_key(&self.pool, user_id, idempotency_key, since).await
+    }
+
+    async fn create_idempotency_key(
+        &self,
+        key: &IdempotencyKey,
+        expired_before: &NaiveDateTime,
+        abandoned_before: &NaiveDateTime,
+    ) -> Result<bool, Error> {
+        pg::create_idempotency_key(&self.pool, key, expired_before, abandoned_before).await
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        id: &str,
+        status: u16,
+        headers: &str,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        pg::complete_idempotency_key(&self.pool, id, status, headers, body).await
+    }
+
+    async fn remove_idempotency_key(&self, id: &str) -> Result<(), Error> {
+        pg::remove_idempotency_key(&self.pool, id).await
+    }
+}
+
+#[async_trait]
+impl DatabaseRepo for PgRepo {
+    async fn get_ping(&self, id: &str) -> Result<String, Error> {
+        pg::get_ping(&self.pool, id).await
+    }
+
+    async fn get_applied_migrations(&self) -> Result<Vec<SchemaMigration>, Error> {
+        pg::get_applied_migrations(&self.pool).await
+    }
+
+    async fn run_migrations(&self) -> Result<(), MigrateError> {
+        pg::run_migrations(&self.pool).await
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &pg::MIGRATOR
+    }
+
+    fn pool_connections(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+}
@@ -0,0 +1,448 @@
+//! Behaviour every backend has to share, written against the repo traits so the same checks run
+//! against `MemoryRepo` here and against a live database where one is available.
+//!
+//! Every run makes its own users, and reviews are only visible to friends, so it can be pointed at a
+//! database that already has data in it.
+
+use chrono::{Duration, NaiveDateTime, Timelike, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        Bookmark, PageRequest, Review, User, TOMBSTONE_BOOKMARK, TOMBSTONE_LIKE, TOMBSTONE_REPLY,
+        TOMBSTONE_REVIEW,
+    },
+    repo::{
+        BookmarkRepo, FriendRepo, LikeRepo, MemoryRepo, ReplyRepo, ReviewRepo, SyncRepo, UserRepo,
+    },
+};
+
+pub async fn run<R>(repo: &R)
+where
+    R: UserRepo + FriendRepo + ReviewRepo + LikeRepo + ReplyRepo + BookmarkRepo + SyncRepo,
+{
+    users(repo).await;
+    friend_requests(repo).await;
+    review_visibility(repo).await;
+    latest_paging(repo).await;
+    spatial_bounds(repo).await;
+    spatial_radius(repo).await;
+    user_activity_removal(repo).await;
+}
+
+const LATITUDE: f64 = 47.6;
+const LONGITUDE: f64 = -122.3;
+
+/// Whole seconds, so the value survives a round trip through any backend's datetime column.
+fn now() -> NaiveDateTime {
+    let now = Utc::now().naive_utc();
+    now.with_nanosecond(0).unwrap_or(now)
+}
+
+async fn new_user<R: UserRepo>(repo: &R) -> User {
+    let id = Uuid::new_v4().to_string();
+    let user = User {
+        name: format!("suite{}", &id[..8]),
+        display_name: "Suite".to_string(),
+        phone: format!("+1555{}", &id[..8]),
+        created: now(),
+        pic_id: "default".to_string(),
+        device_token: None,
+        email: None,
+        disabled: 0,
+        id,
+    };
+    repo.create_user(&user).await.unwrap();
+    user
+}
+
+async fn new_review<R: ReviewRepo>(
+    repo: &R,
+    user_id: &str,
+    created: NaiveDateTime,
+    latitude: f64,
+    longitude: f64,
+) -> Review {
+    let review = Review {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        created,
+        category: "food".to_string(),
+        text: "good".to_string(),
+        stars: 5,
+        location_name: "Cafe".to_string(),
+        latitude,
+        longitude,
+        is_custom: 0,
+        delivered: 0,
+        recommended: 0,
+    };
+    repo.create_review(&review).await.unwrap();
+    review
+}
+
+async fn befriend<R: FriendRepo>(repo: &R, user_id: &str, friend_id: &str) {
+    repo.create_friend_request(user_id, friend_id)
+        .await
+        .unwrap();
+    repo.accept_friend_request(friend_id, user_id)
+        .await
+        .unwrap();
+}
+
+async fn users<R: UserRepo>(repo: &R) {
+    let user = new_user(repo).await;
+
+    let found = repo.get_user(&user.id).await.unwrap().unwrap();
+    assert_eq!(found.name, user.name);
+    assert_eq!(found.created, user.created);
+
+    let by_name = repo.get_user_from_name(&user.name).await.unwrap().unwrap();
+    assert_eq!(by_name.id, user.id);
+
+    assert!(repo.does_user_exist(&user.id).await.unwrap());
+    assert!(!repo
+        .does_user_exist(&Uuid::new_v4().to_string())
+        .await
+        .unwrap());
+
+    let ids = vec![user.id.clone(), Uuid::new_v4().to_string()];
+    let by_ids = repo.get_users_by_ids(&ids).await.unwrap();
+    assert_eq!(by_ids.len(), 1);
+    assert_eq!(by_ids[0].id, user.id);
+}
+
+async fn friend_requests<R: UserRepo + FriendRepo>(repo: &R) {
+    let alice = new_user(repo).await;
+    let bob = new_user(repo).await;
+
+    assert!(repo.are_users_friends(&alice.id, &alice.id).await.unwrap());
+    assert!(!repo.are_users_friends(&alice.id, &bob.id).await.unwrap());
+
+    repo.create_friend_request(&alice.id, &bob.id)
+        .await
+        .unwrap();
+    let outgoing = repo.get_outgoing_friend_requests(&alice.id).await.unwrap();
+    assert_eq!(outgoing.len(), 1);
+    let incoming = repo.get_incoming_friend_requests(&bob.id).await.unwrap();
+    assert_eq!(incoming.len(), 1);
+
+    repo.accept_friend_request(&bob.id, &alice.id)
+        .await
+        .unwrap();
+    assert!(repo.are_users_friends(&alice.id, &bob.id).await.unwrap());
+    assert!(repo.are_users_friends(&bob.id, &alice.id).await.unwrap());
+    assert!(repo
+        .get_incoming_friend_requests(&bob.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    repo.remove_current_friend(&alice.id, &bob.id)
+        .await
+        .unwrap();
+    assert!(!repo.are_users_friends(&alice.id, &bob.id).await.unwrap());
+    assert!(!repo.are_users_friends(&bob.id, &alice.id).await.unwrap());
+}
+
+async fn review_visibility<R: UserRepo + FriendRepo + ReviewRepo>(repo: &R) {
+    let alice = new_user(repo).await;
+    let bob = new_user(repo).await;
+    let (latitude, longitude) = (LATITUDE, LONGITUDE);
+    let review = new_review(repo, &bob.id, now(), latitude, longitude).await;
+    let ids = vec![review.id.clone()];
+
+    assert!(repo
+        .get_review(&bob.id, &review.id)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(repo
+        .get_review(&alice.id, &review.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(repo
+        .get_reviews_by_ids(&alice.id, &ids)
+        .await
+        .unwrap()
+        .is_empty());
+
+    befriend(repo, &alice.id, &bob.id).await;
+
+    let found = repo
+        .get_review(&alice.id, &review.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.user_id, bob.id);
+    assert_eq!(found.latitude, latitude);
+    assert_eq!(found.longitude, longitude);
+    assert_eq!(
+        repo.get_reviews_by_ids(&alice.id, &ids)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+async fn latest_paging<R: UserRepo + ReviewRepo>(repo: &R) {
+    let user = new_user(repo).await;
+    let (latitude, longitude) = (LATITUDE, LONGITUDE);
+    let created = now();
+    let newest = new_review(repo, &user.id, created, latitude, longitude).await;
+    let middle = new_review(
+        repo,
+        &user.id,
+        created - Duration::minutes(1),
+        latitude,
+        longitude,
+    )
+    .await;
+    let oldest = new_review(
+        repo,
+        &user.id,
+        created - Duration::minutes(2),
+        latitude,
+        longitude,
+    )
+    .await;
+
+    let first = repo
+        .get_latest_reviews(&user.id, &PageRequest::first(2), &None)
+        .await
+        .unwrap();
+    let ids: Vec<&str> = first
+        .items
+        .iter()
+        .map(|review| review.id.as_str())
+        .collect();
+    assert_eq!(ids, vec![newest.id.as_str(), middle.id.as_str()]);
+    assert!(first.next_cursor.is_some());
+
+    let next = PageRequest {
+        after: first.next_cursor,
+        limit: 2,
+    };
+    let second = repo
+        .get_latest_reviews(&user.id, &next, &None)
+        .await
+        .unwrap();
+    assert_eq!(second.items.len(), 1);
+    assert_eq!(second.items[0].id, oldest.id);
+    assert!(second.next_cursor.is_none());
+
+    let other_category = Some("drinks".to_string());
+    let filtered = repo
+        .get_latest_reviews(&user.id, &PageRequest::first(5), &other_category)
+        .await
+        .unwrap();
+    assert!(filtered.items.is_empty());
+}
+
+async fn spatial_bounds<R: UserRepo + ReviewRepo>(repo: &R) {
+    let user = new_user(repo).await;
+    let (latitude, longitude) = (LATITUDE, LONGITUDE);
+    let inside = new_review(repo, &user.id, now(), latitude, longitude).await;
+    let excluded = new_review(repo, &user.id, now(), latitude + 0.5, longitude + 0.5).await;
+    new_review(repo, &user.id, now(), latitude + 5.0, longitude).await;
+
+    let found = repo
+        .get_reviews_from_bounds(
+            &user.id,
+            latitude + 1.0,
+            latitude - 1.0,
+            longitude - 1.0,
+            longitude + 1.0,
+            &PageRequest::first(10),
+        )
+        .await
+        .unwrap();
+    let mut ids: Vec<&str> = found
+        .items
+        .iter()
+        .map(|review| review.id.as_str())
+        .collect();
+    ids.sort();
+    let mut expected = vec![inside.id.as_str(), excluded.id.as_str()];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let found = repo
+        .get_reviews_from_bounds_with_exclusions(
+            &user.id,
+            latitude + 1.0,
+            latitude - 1.0,
+            longitude - 1.0,
+            longitude + 1.0,
+            latitude + 0.75,
+            latitude + 0.25,
+            longitude + 0.25,
+            longitude + 0.75,
+            &PageRequest::first(10),
+        )
+        .await
+        .unwrap();
+    assert_eq!(found.items.len(), 1);
+    assert_eq!(found.items[0].id, inside.id);
+}
+
+async fn spatial_radius<R: UserRepo + ReviewRepo>(repo: &R) {
+    let user = new_user(repo).await;
+    let (latitude, longitude) = (LATITUDE, LONGITUDE);
+    let review = new_review(repo, &user.id, now(), latitude, longitude).await;
+
+    // about 20m north, well within the same location.
+    let found = repo
+        .get_reviews_from_location(&user.id, "Cafe", latitude + 0.0002, longitude)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, review.id);
+
+    // about 1km north is somewhere else.
+    let found = repo
+        .get_reviews_from_location(&user.id, "Cafe", latitude + 0.01, longitude)
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+
+    let found = repo
+        .get_reviews_from_location(&user.id, "Bakery", latitude, longitude)
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+}
+
+/// `(item_type, item_id)` of every tombstone `user_id` would sync since `since`, sorted.
+async fn synced_tombstones<R: SyncRepo>(
+    repo: &R,
+    user_id: &str,
+    since: &NaiveDateTime,
+) -> Vec<(String, String)> {
+    let mut tombstones: Vec<(String, String)> = repo
+        .get_tombstones_since(user_id, since)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tombstone| (tombstone.item_type, tombstone.item_id))
+        .collect();
+    tombstones.sort();
+    tombstones
+}
+
+async fn user_activity_removal<R>(repo: &R)
+where
+    R: UserRepo + FriendRepo + ReviewRepo + LikeRepo + ReplyRepo + BookmarkRepo + SyncRepo,
+{
+    let alice = new_user(repo).await;
+    let bob = new_user(repo).await;
+    befriend(repo, &alice.id, &bob.id).await;
+    let (latitude, longitude) = (LATITUDE, LONGITUDE);
+    let own_review = new_review(repo, &alice.id, now(), latitude, longitude).await;
+    let bob_review = new_review(repo, &bob.id, now(), latitude, longitude).await;
+
+    // bob's like and reply go with alice's review, hers on bob's review need their own tombstones.
+    repo.create_like(&bob.id, &own_review.id, None)
+        .await
+        .unwrap();
+    repo.create_reply(&bob.id, &own_review.id, "nice", None)
+        .await
+        .unwrap();
+    repo.create_like(&alice.id, &bob_review.id, None)
+        .await
+        .unwrap();
+    repo.create_reply(&alice.id, &bob_review.id, "agreed", None)
+        .await
+        .unwrap();
+    let bookmark = Bookmark {
+        id: Uuid::new_v4().to_string(),
+        user_id: alice.id.clone(),
+        created: now(),
+        category: "food".to_string(),
+        location_name: "Cafe".to_string(),
+        latitude,
+        longitude,
+    };
+    repo.create_bookmark(&bookmark).await.unwrap();
+
+    let like = repo.get_all_likes(&bob_review.id).await.unwrap().remove(0);
+    let reply = repo
+        .get_all_replies(&bob_review.id)
+        .await
+        .unwrap()
+        .remove(0);
+
+    let since = now();
+    repo.remove_user_activity(&alice.id).await.unwrap();
+
+    let counts = repo.get_user_activity_counts(&alice.id).await.unwrap();
+    assert_eq!(
+        (
+            counts.reviews,
+            counts.friends,
+            counts.likes,
+            counts.replies,
+            counts.bookmarks
+        ),
+        (0, 0, 0, 0, 0)
+    );
+    assert!(repo.get_all_likes(&bob_review.id).await.unwrap().is_empty());
+    assert!(repo
+        .get_all_replies(&bob_review.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // alice keeps her self friend record, so her other devices drop what she made.
+    let mut expected = vec![
+        (TOMBSTONE_BOOKMARK.to_string(), bookmark.id.clone()),
+        (TOMBSTONE_REVIEW.to_string(), own_review.id.clone()),
+    ];
+    expected.sort();
+    assert_eq!(synced_tombstones(repo, &alice.id, &since).await, expected);
+
+    let mut expected = vec![
+        (TOMBSTONE_LIKE.to_string(), like.id),
+        (TOMBSTONE_REPLY.to_string(), reply.id),
+    ];
+    expected.sort();
+    assert_eq!(synced_tombstones(repo, &bob.id, &since).await, expected);
+}
+
+#[actix_web::test]
+async fn memory_repo() {
+    run(&MemoryRepo::new()).await;
+}
+
+/// Needs a PostGIS database to migrate and write to, e.g.
+/// `TEST_POSTGRES_URL=postgres://... cargo test --features postgres -- --ignored`.
+#[cfg(feature = "postgres")]
+#[actix_web::test]
+#[ignore]
+async fn postgres_repo() {
+    use crate::repo::{DatabaseRepo, PgRepo};
+    use sqlx::PgPool;
+
+    let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL is not set");
+    let repo = PgRepo::new(PgPool::connect(&url).await.unwrap());
+    repo.run_migrations().await.unwrap();
+
+    run(&repo).await;
+}
+
+/// Like `postgres_repo`, against a MySQL database from `TEST_MYSQL_URL`.
+/// The database has to be called `mob`, early migrations name it directly.
+#[actix_web::test]
+#[ignore]
+async fn mysql_repo() {
+    use crate::repo::{DatabaseRepo, MySqlRepo};
+    use sqlx::MySqlPool;
+
+    let url = std::env::var("TEST_MYSQL_URL").expect("TEST_MYSQL_URL is not set");
+    let repo = MySqlRepo::new(MySqlPool::connect(&url).await.unwrap());
+    repo.run_migrations().await.unwrap();
+
+    run(&repo).await;
+}
@@ -0,0 +1,534 @@
+use async_trait::async_trait;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    types::chrono::NaiveDateTime,
+    Error,
+};
+
+use crate::db::{
+    AuthAttempt, Bookmark, DataVersion, ExpandedNotification, Friend, FriendRequest,
+    IdempotencyKey, IpBlock, Like, Page, PageRequest, PendingNotification, PhoneAuth, Pic, Reply,
+    Report, Review, ReviewAnnotation, SchemaMigration, Tombstone, User, UserActivityCounts,
+};
+
+/// Users and their profile fields.
+#[async_trait]
+pub trait UserRepo: Send + Sync {
+    async fn get_user(&self, id: &str) -> Result<Option<User>, Error>;
+
+    /// Users with any of `ids`, in no particular order. Unknown ids are left out.
+    async fn get_users_by_ids(&self, ids: &[String]) -> Result<Vec<User>, Error>;
+
+    /// Exact match on `user.name`.
+    async fn get_user_from_name(&self, name: &str) -> Result<Option<User>, Error>;
+
+    /// Prefix match on `user.name`, limited to 50 users.
+    async fn search_user_from_name(&self, name: &str) -> Result<Vec<User>, Error>;
+
+    async fn does_user_exist(&self, id: &str) -> Result<bool, Error>;
+
+    async fn does_user_exist_by_name(&self, name: &str) -> Result<bool, Error>;
+
+    async fn get_user_by_phone(&self, phone: &str) -> Result<Option<User>, Error>;
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>, Error>;
+
+    /// Users with any of the given phone numbers.
+    async fn phone_number_discovery(&self, numbers: &Vec<&str>) -> Result<Vec<User>, Error>;
+
+    /// Also makes the user friends with themselves, so they can see their own posts.
+    async fn create_user(&self, user: &User) -> Result<(), Error>;
+
+    async fn update_user_phone(&self, user_id: &str, phone: &str) -> Result<(), Error>;
+
+    async fn update_usernames(
+        &self,
+        user_id: &str,
+        display_name: &str,
+        name: &str,
+    ) -> Result<(), Error>;
+
+    async fn update_device_token(&self, user_id: &str, device_token: &str) -> Result<(), Error>;
+
+    async fn update_recovery_email(&self, user_id: &str, email: &str) -> Result<(), Error>;
+
+    async fn update_user_pic_id(&self, pic_id: &str, user_id: &str) -> Result<(), Error>;
+
+    async fn get_total_user_count(&self) -> Result<i64, Error>;
+
+    async fn get_user_activity_counts(&self, user_id: &str) -> Result<UserActivityCounts, Error>;
+
+    /// Removes everything the user has made or received, leaving tombstones for what friends had synced.
+    /// Keeps the account, its self friend record and its pics.
+    async fn remove_user_activity(&self, user_id: &str) -> Result<(), Error>;
+
+    /// Disabled users can't request sign in codes.
+    async fn update_user_disabled(&self, user_id: &str, disabled: bool) -> Result<(), Error>;
+
+    /// Users created before `create_user` added their self friend record.
+    async fn get_users_missing_self_friend(&self) -> Result<Vec<User>, Error>;
+}
+
+/// Phone sign in codes and the attempts to use them.
+#[async_trait]
+pub trait AuthRepo: Send + Sync {
+    /// Unused phoneauths from the last hour.
+    async fn get_current_phoneauths(&self, phone: &str) -> Result<Vec<PhoneAuth>, Error>;
+
+    /// Authattempts from the last hour.
+    async fn get_phoneauth_attempts(&self, phone: &str) -> Result<Vec<AuthAttempt>, Error>;
+
+    async fn create_phoneauth(&self, phone: &str, code: &str, ip: &str) -> Result<(), Error>;
+
+    async fn update_authattempt_used(&self, id: &str) -> Result<(), Error>;
+
+    async fn create_authattempt(&self, phone: &str) -> Result<(), Error>;
+
+    async fn get_recent_phoneauths(&self, phone: &str, limit: u32)
+        -> Result<Vec<PhoneAuth>, Error>;
+
+    async fn get_recent_authattempts(
+        &self,
+        phone: &str,
+        limit: u32,
+    ) -> Result<Vec<AuthAttempt>, Error>;
+}
+
+/// Friendships and friend requests.
+/// A friendship is a friend record in each direction, and every user has one with themselves.
+#[async_trait]
+pub trait FriendRepo: Send + Sync {
+    async fn get_incoming_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error>;
+
+    /// Incoming requests, including ignored ones.
+    async fn get_acceptable_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error>;
+
+    async fn get_incoming_ignored_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error>;
+
+    async fn get_outgoing_friend_requests(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<FriendRequest>, Error>;
+
+    async fn get_current_friends(&self, user_id: &str) -> Result<Vec<Friend>, Error>;
+
+    /// Changes whenever friends or any incoming, ignored or outgoing request does.
+    async fn get_friends_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error>;
+
+    async fn are_users_friends(&self, user_id: &str, other_user_id: &str) -> Result<bool, Error>;
+
+    async fn create_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error>;
+
+    async fn ignore_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error>;
+
+    async fn decline_friend_request(&self, request_id: &str, friend_id: &str) -> Result<(), Error>;
+
+    async fn cancel_friend_request(&self, request_id: &str, user_id: &str) -> Result<(), Error>;
+
+    /// Removes requests in both directions and creates both friend records.
+    async fn accept_friend_request(&self, user_id: &str, friend_id: &str) -> Result<(), Error>;
+
+    /// Removes both friend records.
+    async fn remove_current_friend(&self, user_id: &str, friend_id: &str) -> Result<(), Error>;
+
+    /// The friend record a user needs to see their own posts.
+    async fn create_self_friend(&self, user_id: &str) -> Result<(), Error>;
+}
+
+/// Reviews.
+/// Unless noted, reads only return reviews by users the `user_id` is friends with, which includes themselves.
+#[async_trait]
+pub trait ReviewRepo: Send + Sync {
+    async fn get_review(&self, user_id: &str, review_id: &str) -> Result<Option<Review>, Error>;
+
+    /// Reviews with any of `review_ids`, in no particular order.
+    /// Unknown ids and reviews the user can't see are left out.
+    async fn get_reviews_by_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<Review>, Error>;
+
+    /// Changes whenever the likes, replies or pics on any of `review_ids` do, or the user's bookmarks.
+    async fn get_compound_review_version(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<DataVersion>, Error>;
+
+    /// Reviews with `name` within roughly 100m of the point. Not paged.
+    async fn get_reviews_from_location(
+        &self,
+        user_id: &str,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<Review>, Error>;
+
+    async fn get_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error>;
+
+    async fn get_recommended_reviews_from_user(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error>;
+
+    async fn get_reviews_from_bounds(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error>;
+
+    /// Like `get_reviews_from_bounds`, leaving out anything inside the second box.
+    async fn get_reviews_from_bounds_with_exclusions(
+        &self,
+        user_id: &str,
+        latitude_north: f64,
+        latitude_south: f64,
+        longitude_west: f64,
+        longitude_east: f64,
+        latitude_north_e: f64,
+        latitude_south_e: f64,
+        longitude_west_e: f64,
+        longitude_east_e: f64,
+        page: &PageRequest,
+    ) -> Result<Page<ReviewAnnotation>, Error>;
+
+    async fn get_latest_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+        category: &Option<String>,
+    ) -> Result<Page<Review>, Error>;
+
+    /// Latest reviews whose location name starts with `search_prefix`.
+    async fn search_latest_reviews(
+        &self,
+        user_id: &str,
+        search_prefix: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error>;
+
+    /// Ignores friends, this is only for admin use.
+    async fn get_recent_reviews_by_user(
+        &self,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<Review>, Error>;
+
+    async fn create_review(&self, review: &Review) -> Result<(), Error>;
+
+    async fn update_review(&self, review_id: &str, stars: u8, text: &str) -> Result<(), Error>;
+
+    async fn update_review_delivered(&self, review_id: &str, delivered: u8) -> Result<(), Error>;
+
+    async fn update_review_recommended(
+        &self,
+        review_id: &str,
+        user_id: &str,
+        recommended: bool,
+    ) -> Result<(), Error>;
+
+    /// Removes the review along with its likes and replies.
+    async fn remove_review_and_children(&self, review_id: &str) -> Result<(), Error>;
+}
+
+/// Pic records for profile and review pics. Storage is handled separately.
+#[async_trait]
+pub trait PicRepo: Send + Sync {
+    async fn get_pic(&self, id: &str) -> Result<Option<Pic>, Error>;
+
+    async fn get_all_pics(&self, review_id: &str) -> Result<Vec<Pic>, Error>;
+
+    /// `get_all_pics` for a whole set of reviews in one query.
+    async fn get_pics_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Pic>, Error>;
+
+    async fn create_pic(
+        &self,
+        review_id: Option<String>,
+        width: u16,
+        height: u16,
+    ) -> Result<Pic, Error>;
+
+    async fn delete_pic(&self, pic_id: &str) -> Result<(), Error>;
+
+    async fn remove_review_pic_id(&self, pic_id: &str, review_id: &str) -> Result<(), Error>;
+
+    /// Review pics whose review is gone, and profile pics no user has set, oldest first.
+    async fn get_orphaned_pics(&self) -> Result<Vec<Pic>, Error>;
+
+    /// Pics attached to any of the user's reviews.
+    async fn get_review_pics_by_user(&self, user_id: &str) -> Result<Vec<Pic>, Error>;
+}
+
+/// Likes on reviews.
+#[async_trait]
+pub trait LikeRepo: Send + Sync {
+    async fn get_all_likes(&self, review_id: &str) -> Result<Vec<Like>, Error>;
+
+    /// `get_all_likes` for a whole set of reviews in one query.
+    async fn get_likes_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Like>, Error>;
+
+    /// Reviews the user has liked, most recently liked first.
+    async fn get_liked_reviews(
+        &self,
+        user_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<Review>, Error>;
+
+    async fn is_already_liked(&self, user_id: &str, review_id: &str) -> Result<bool, Error>;
+
+    async fn create_like(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        like_type: Option<i8>,
+    ) -> Result<(), Error>;
+
+    async fn remove_like(&self, user_id: &str, review_id: &str) -> Result<(), Error>;
+}
+
+/// Replies on reviews.
+#[async_trait]
+pub trait ReplyRepo: Send + Sync {
+    async fn get_all_replies(&self, review_id: &str) -> Result<Vec<Reply>, Error>;
+
+    /// `get_all_replies` for a whole set of reviews in one query.
+    async fn get_replies_for_reviews(&self, review_ids: &[String]) -> Result<Vec<Reply>, Error>;
+
+    async fn get_reply(&self, review_id: &str, reply_id: &str) -> Result<Option<Reply>, Error>;
+
+    async fn create_reply(
+        &self,
+        user_id: &str,
+        review_id: &str,
+        text: &str,
+        reply_to_id: Option<&String>,
+    ) -> Result<(), Error>;
+
+    async fn delete_reply(
+        &self,
+        reply_id: &str,
+        review_id: &str,
+        user_id: &str,
+    ) -> Result<(), Error>;
+}
+
+/// In-app notifications about activity on a user's reviews.
+#[async_trait]
+pub trait NotificationRepo: Send + Sync {
+    /// The 50 latest notifications for the user.
+    async fn get_notifications(&self, user_id: &str) -> Result<Vec<ExpandedNotification>, Error>;
+
+    /// Changes whenever the user's notifications do.
+    async fn get_notifications_version(&self, user_id: &str) -> Result<Vec<DataVersion>, Error>;
+
+    /// Zero when the count can't be read.
+    async fn get_notification_count(&self, user_id: &str) -> i64;
+
+    async fn confirm_notifications(&self, user_id: &str) -> Result<(), Error>;
+
+    /// Does nothing for a user's own actions, or when the same notification already exists.
+    async fn create_notification(
+        &self,
+        user_id: &str,
+        review_user_id: &str,
+        review_id: &str,
+        action_type: u8,
+    ) -> Result<(), Error>;
+}
+
+/// Reports against users.
+#[async_trait]
+pub trait ReportRepo: Send + Sync {
+    /// Does nothing for self reports, or when the reporter already reported the user.
+    async fn report_user(&self, user_id: &str, reporter_id: &str) -> Result<(), Error>;
+
+    async fn get_all_reports(&self) -> Result<Vec<Report>, Error>;
+
+    async fn get_reports_filed(&self, reporter_id: &str) -> Result<Vec<Report>, Error>;
+
+    async fn get_reports_received(&self, user_id: &str) -> Result<Vec<Report>, Error>;
+}
+
+/// Bookmarked locations.
+/// Like reviews, reads only return bookmarks by users the caller is friends with.
+#[async_trait]
+pub trait BookmarkRepo: Send + Sync {
+    async fn get_all_bookmarks(
+        &self,
+        user_id: &str,
+        target_user_id: &str,
+    ) -> Result<Vec<Bookmark>, Error>;
+
+    /// Whether the user has bookmarked `location_name` within roughly 100m of the point.
+    async fn does_bookmark_exist(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<bool, Error>;
+
+    /// Of `review_ids`, those whose location the user has bookmarked, matched like `does_bookmark_exist`.
+    async fn get_bookmarked_review_ids(
+        &self,
+        user_id: &str,
+        review_ids: &[String],
+    ) -> Result<Vec<String>, Error>;
+
+    async fn get_nearby_bookmarks(
+        &self,
+        calling_user_id: &str,
+        user_id: &str,
+        page: &PageRequest,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Page<Bookmark>, Error>;
+
+    async fn create_bookmark(&self, bookmark: &Bookmark) -> Result<(), Error>;
+
+    async fn remove_bookmark(
+        &self,
+        user_id: &str,
+        location_name: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<(), Error>;
+}
+
+/// Ipblock rules.
+#[async_trait]
+pub trait IpBlockRepo: Send + Sync {
+    /// Rules that haven't expired.
+    async fn get_active_ipblocks(&self) -> Result<Vec<IpBlock>, Error>;
+
+    async fn get_all_ipblocks(&self) -> Result<Vec<IpBlock>, Error>;
+
+    async fn create_ipblock(&self, ipblock: &IpBlock) -> Result<(), Error>;
+
+    /// False when no rule has the id.
+    async fn remove_ipblock(&self, id: &str) -> Result<bool, Error>;
+}
+
+/// Push notifications persisted across restarts.
+#[async_trait]
+pub trait PendingNotificationRepo: Send + Sync {
+    async fn create_pending_notifications(
+        &self,
+        pending_notifications: &[PendingNotification],
+    ) -> Result<(), Error>;
+
+    /// Gets and removes every persisted notification, oldest first.
+    async fn take_pending_notifications(&self) -> Result<Vec<PendingNotification>, Error>;
+}
+
+/// What changed since a point in time, for clients catching up with `/sync`.
+/// Everything from a friendship made since then is included, however old.
+#[async_trait]
+pub trait SyncRepo: Send + Sync {
+    /// Reviews from the user's friends created or edited since `since`.
+    async fn get_reviews_changed_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Review>, Error>;
+
+    /// Likes made since `since` on reviews from the user's friends.
+    async fn get_likes_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Like>, Error>;
+
+    /// Replies made since `since` on reviews from the user's friends.
+    async fn get_replies_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Reply>, Error>;
+
+    /// Bookmarks the user's friends made since `since`.
+    async fn get_bookmarks_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Bookmark>, Error>;
+
+    /// Reviews, replies, likes and bookmarks of the user's friends deleted since `since`.
+    async fn get_tombstones_since(
+        &self,
+        user_id: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Vec<Tombstone>, Error>;
+}
+
+/// Responses stored for `Idempotency-Key`s, so retried writes aren't made twice.
+#[async_trait]
+pub trait IdempotencyRepo: Send + Sync {
+    /// The user's key, unless it was first used before `since`.
+    async fn get_idempotency_key(
+        &self,
+        user_id: &str,
+        idempotency_key: &str,
+        since: &NaiveDateTime,
+    ) -> Result<Option<IdempotencyKey>, Error>;
+
+    /// Claims the key after dropping every key used before `expired_before`,
+    /// and the user's key if it was claimed before `abandoned_before` and never completed.
+    /// False when the user already holds it.
+    async fn create_idempotency_key(
+        &self,
+        key: &IdempotencyKey,
+        expired_before: &NaiveDateTime,
+        abandoned_before: &NaiveDateTime,
+    ) -> Result<bool, Error>;
+
+    /// Stores the response to replay for the key. `headers` is a json array of `[name, value]` pairs.
+    async fn complete_idempotency_key(
+        &self,
+        id: &str,
+        status: u16,
+        headers: &str,
+        body: &[u8],
+    ) -> Result<(), Error>;
+
+    /// Releases the key so the request can be retried with it.
+    async fn remove_idempotency_key(&self, id: &str) -> Result<(), Error>;
+}
+
+/// The database itself, for health checks, metrics, and migrations.
+#[async_trait]
+pub trait DatabaseRepo: Send + Sync {
+    async fn get_ping(&self, id: &str) -> Result<String, Error>;
+
+    /// Migrations recorded in the database, oldest first.
+    async fn get_applied_migrations(&self) -> Result<Vec<SchemaMigration>, Error>;
+
+    async fn run_migrations(&self) -> Result<(), MigrateError>;
+
+    /// Migrations embedded in this build for this backend.
+    fn migrator(&self) -> &'static Migrator;
+
+    /// Open and idle connections.
+    fn pool_connections(&self) -> (u32, usize);
+}
@@ -1,3 +1,4 @@
+use crate::tracing::WithTraceContext;
 use reqwest::Client;
 use serde::Serialize;
 
@@ -32,6 +33,7 @@ impl GithubClient {
             .header("accept", "application/vnd.github+json")
             .header("user-agent", "RWF Backend")
             .body(body)
+            .with_trace_context()
             .send()
             .await;
 
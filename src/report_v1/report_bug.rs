@@ -1,33 +1,56 @@
-use crate::{authorization::AuthenticatedUser, report_v1::GithubClient};
+use crate::{
+    authorization::AuthenticatedUser,
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidJson, Validate},
+    report_v1::GithubClient,
+};
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
-    web::{Data, Json, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
+use utoipa::ToSchema;
+use validation::{validate_report_description, validate_report_title};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct BugReportRequest {
     title: String,
     description: String,
 }
 
+impl Validate for BugReportRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("title", validate_report_title(&self.title));
+        errors.check(
+            "description",
+            validate_report_description(&self.description),
+        );
+
+        errors.finish()
+    }
+}
+
 /// Report a bug.
+#[utoipa::path(
+    context_path = "/api/v1/report",
+    tag = "report",
+    request_body = BugReportRequest,
+    responses(
+        (status = 200, description = "bug was filed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/bug")]
 pub async fn report_bug(
     _authenticated_user: ReqData<AuthenticatedUser>,
     gh_client: Data<GithubClient>,
-    bug_report_request: Json<BugReportRequest>,
-) -> Result<impl Responder> {
-    if bug_report_request.title.chars().count() > 64 {
-        return Err(ErrorBadRequest("title too long".to_string()));
-    }
-
-    if bug_report_request.title.chars().count() > 1024 {
-        return Err(ErrorBadRequest("description too long".to_string()));
-    }
-
+    bug_report_request: ValidJson<BugReportRequest>,
+) -> Result<impl Responder, AppError> {
     let report_res = gh_client
         .submit_bug(&bug_report_request.title, &bug_report_request.description)
         .await;
@@ -36,10 +59,8 @@ pub async fn report_bug(
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
-        Err(_) => {
-            return Err(ErrorInternalServerError(
-                "unable to create bug report".to_string(),
-            ));
+        Err(error) => {
+            return Err(AppError::internal("unable to create bug report", error));
         }
     }
 }
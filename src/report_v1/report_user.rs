@@ -1,52 +1,65 @@
-use crate::{authorization::AuthenticatedUser, db, db::get_user, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    repo::{ReportRepo, UserRepo},
+};
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserReportRequest {
     user_id: String,
 }
 
 /// Report a user.
+#[utoipa::path(
+    context_path = "/api/v1/report",
+    tag = "report",
+    responses(
+        (status = 200, description = "report was filed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/user")]
 pub async fn report_user(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    report_repo: Data<dyn ReportRepo>,
     user_report_request: Query<UserReportRequest>,
-) -> Result<impl Responder> {
-    let user_res = get_user(&pool, &user_report_request.user_id).await;
+) -> Result<impl Responder, AppError> {
+    let user_res = user_repo.get_user(&user_report_request.user_id).await;
 
     match user_res {
         Ok(target_user_opt) => {
             if target_user_opt.is_none() {
-                return Err(ErrorBadRequest("target user doesn't exist".to_string()));
+                return Err(AppError::bad_request(
+                    ErrorCode::UserNotFound,
+                    "target user doesn't exist",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get target user".to_string(),
-            ));
+            return Err(AppError::internal("unable to get target user", error));
         }
     }
 
-    let report_res =
-        db::report_user(&pool, &user_report_request.user_id, &authenticated_user.0).await;
+    let report_res = report_repo
+        .report_user(&user_report_request.user_id, &authenticated_user.0)
+        .await;
     match report_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to create report".to_string(),
-            ));
+            return Err(AppError::internal("unable to create report", error));
         }
     }
 }
@@ -2,27 +2,27 @@ use std::sync::Mutex;
 
 use crate::{
     authorization::AuthenticatedUser,
-    db::{
-        create_pic, create_review, get_current_friends, get_user, remove_review_and_children,
-        Review,
-    },
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidJson, Validate},
+    idempotency::{IdempotencyKeyHeader, Idempotent},
     notifications_v1::{
         enqueue_notification, NotificationQueue, NotificationQueueItem, NotificationType,
     },
-    pic_v1::TARGET_DO_BUCKET,
-    tracing::add_error_span,
+    pic_v1::shared_utils::upload_pic,
+    repo::{FriendRepo, PicRepo, ReviewRepo, UserRepo},
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
-    web::{Data, Json, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use base64::{engine::general_purpose, Engine};
 use chrono::{NaiveDateTime, Utc};
-use images::{ByteStream, PutObjectRequest, S3Client, S3};
+use images::S3Client;
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validation::{
     validate_latitude, validate_location_name, validate_longitude, validate_review_category,
@@ -31,7 +31,7 @@ use validation::{
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct AddReviewRequest {
     pub text: String,
     pub stars: u8,
@@ -47,39 +47,44 @@ pub struct AddReviewRequest {
     pub delivered: Option<bool>,
 }
 
+impl Validate for AddReviewRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check("text", validate_review_text(&self.text));
+        errors.check("stars", validate_stars(self.stars));
+        errors.check("category", validate_review_category(&self.category));
+        errors.check("location_name", validate_location_name(&self.location_name));
+        errors.check("latitude", validate_latitude(self.latitude));
+        errors.check("longitude", validate_longitude(self.longitude));
+
+        errors.finish()
+    }
+}
+
 /// Allows the user to create a review for a specific place.
-#[post("/")]
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    request_body = AddReviewRequest,
+    params(IdempotencyKeyHeader),
+    responses(
+        (status = 200, description = "created review", body = ReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/", wrap = "Idempotent")]
 pub async fn add_review(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
+    friend_repo: Data<dyn FriendRepo>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     apn_queue: Data<Mutex<NotificationQueue>>,
-    add_review_request: Json<AddReviewRequest>,
-) -> Result<impl Responder> {
-    if let Err(err) = validate_review_text(&add_review_request.text) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_longitude(add_review_request.longitude) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_latitude(add_review_request.latitude) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_location_name(&add_review_request.location_name) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_stars(add_review_request.stars) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_review_category(&add_review_request.category) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
+    add_review_request: ValidJson<AddReviewRequest>,
+) -> Result<impl Responder, AppError> {
     let validation_result = validate_pics(&add_review_request);
 
     let pics: Vec<PicToUpload>;
@@ -89,24 +94,31 @@ pub async fn add_review(
             pics = pics_tmp;
         }
         Err(err) => {
-            return Ok(HttpResponse::BadRequest().body(err));
+            return Err(AppError::bad_request(ErrorCode::InvalidInput, err));
         }
     }
 
     let review = map_review_to_db(&add_review_request, &authenticated_user.0);
 
-    let create_res = create_review(&pool, &review).await;
+    let create_res = review_repo.create_review(&review).await;
 
     match create_res {
         Ok(_) => {
-            let pic_upload_res = upload_and_store_pics(s3_client, &pool, &review, pics).await;
+            let pic_upload_res = upload_and_store_pics(
+                s3_client,
+                review_repo.get_ref(),
+                pic_repo.get_ref(),
+                &review,
+                pics,
+            )
+            .await;
 
             match pic_upload_res {
                 Ok(_) => {
-                    let friends_res = get_current_friends(&pool, &authenticated_user.0).await;
+                    let friends_res = friend_repo.get_current_friends(&authenticated_user.0).await;
 
                     if let Ok(friends) = friends_res {
-                        let calling_user_res = get_user(&pool, &authenticated_user.0).await;
+                        let calling_user_res = user_repo.get_user(&authenticated_user.0).await;
                         if let Ok(calling_user_opt) = calling_user_res {
                             if let Some(calling_user) = calling_user_opt {
                                 for friend in friends {
@@ -132,13 +144,12 @@ pub async fn add_review(
                     return Ok(HttpResponse::Ok().json(ReviewPub::from(review)));
                 }
                 Err(error) => {
-                    return Err(ErrorInternalServerError(error));
+                    return Err(AppError::internal_message(error));
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to create review"));
+            return Err(AppError::internal("failed to create review", error));
         }
     }
 }
@@ -200,38 +211,26 @@ struct PicToUpload<'a> {
 
 async fn upload_and_store_pics(
     s3_client: Data<S3Client>,
-    pool: &MySqlPool,
+    review_repo: &dyn ReviewRepo,
+    pic_repo: &dyn PicRepo,
     review: &Review,
     pics: Vec<PicToUpload<'_>>,
 ) -> Result<(), String> {
     for pic_data in pics {
-        let pic_res = create_pic(
-            &pool,
-            Some(review.id.clone()),
-            pic_data.width,
-            pic_data.height,
-        )
-        .await;
+        let pic_res = pic_repo
+            .create_pic(Some(review.id.clone()), pic_data.width, pic_data.height)
+            .await;
 
         match pic_res {
             Ok(pic) => {
                 if let Ok(bytes) = general_purpose::STANDARD.decode(pic_data.data) {
-                    if let Err(_) = s3_client
-                        .put_object(PutObjectRequest {
-                            body: Some(ByteStream::from(bytes)),
-                            bucket: TARGET_DO_BUCKET.to_string(),
-                            key: pic.id.clone(),
-                            acl: Some("public-read".to_string()),
-                            ..Default::default()
-                        })
-                        .await
-                    {
-                        let _ = remove_review_and_children(&pool, &review.id).await;
+                    if let Err(_) = upload_pic(&s3_client, &pic.id, bytes, "review").await {
+                        let _ = review_repo.remove_review_and_children(&review.id).await;
 
                         return Err("unable to store review pic".to_string());
                     }
                 } else {
-                    let _ = remove_review_and_children(&pool, &review.id).await;
+                    let _ = review_repo.remove_review_and_children(&review.id).await;
 
                     return Err("failed to read pic buffer".to_string());
                 }
@@ -1,19 +1,21 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_review, update_review, update_review_delivered, Review},
-    tracing::add_error_span,
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidJson, Validate},
+    repo::ReviewRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
     post,
-    web::{Data, Json, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
-use validation::{validate_reply_text, validate_stars};
+use utoipa::ToSchema;
+use validation::{validate_review_text, validate_stars};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct EditReviewRequest {
     pub review_id: String,
     pub text: Option<String>,
@@ -21,35 +23,68 @@ pub struct EditReviewRequest {
     pub delivered: Option<bool>,
 }
 
+impl Validate for EditReviewRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(text) = &self.text {
+            errors.check("text", validate_review_text(text));
+        }
+
+        if let Some(stars) = self.stars {
+            errors.check("stars", validate_stars(stars));
+        }
+
+        errors.finish()
+    }
+}
+
 // Allows owner to edit specific fields in a review.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    request_body = EditReviewRequest,
+    responses(
+        (status = 200, description = "review was updated"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/edit")]
 pub async fn edit_review(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    edit_review_request: Json<EditReviewRequest>,
-) -> Result<impl Responder> {
+    review_repo: Data<dyn ReviewRepo>,
+    edit_review_request: ValidJson<EditReviewRequest>,
+) -> Result<impl Responder, AppError> {
     let new_stars: u8;
     let new_text: String;
 
     let review: Review;
-    let review_res = get_review(&pool, &authenticated_user.0, &edit_review_request.review_id).await;
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &edit_review_request.review_id)
+        .await;
 
     match review_res {
         Ok(review_opt) => {
             if let Some(review_tmp) = review_opt {
                 review = review_tmp;
             } else {
-                return Err(ErrorNotFound("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to get review"));
+            return Err(AppError::internal("failed to get review", error));
         }
     }
 
     if review.user_id != authenticated_user.0 {
-        return Ok(HttpResponse::BadRequest().body("unable to edit this review"));
+        return Err(AppError::forbidden(
+            ErrorCode::NotOwner,
+            "unable to edit this review",
+        ));
     }
 
     if edit_review_request.stars.is_none() && edit_review_request.text.is_none() {
@@ -66,34 +101,26 @@ pub async fn edit_review(
         None => new_text = review.text,
     }
 
-    if let Err(err) = validate_reply_text(&new_text) {
-        return Err(ErrorBadRequest(err));
-    }
-
-    if let Err(err) = validate_stars(new_stars) {
-        return Err(ErrorBadRequest(err));
-    }
-
     if let Some(delivered) = &edit_review_request.delivered {
-        let update_delivered_res =
-            update_review_delivered(&pool, &edit_review_request.review_id, *delivered as u8).await;
+        let update_delivered_res = review_repo
+            .update_review_delivered(&edit_review_request.review_id, *delivered as u8)
+            .await;
 
         if let Err(error) = update_delivered_res {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to edit review"));
+            return Err(AppError::internal("failed to edit review", error));
         }
     }
 
-    let update_res =
-        update_review(&pool, &edit_review_request.review_id, new_stars, &new_text).await;
+    let update_res = review_repo
+        .update_review(&edit_review_request.review_id, new_stars, &new_text)
+        .await;
 
     match update_res {
         Ok(_) => {
             return Ok(HttpResponse::Ok().finish());
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to edit review"));
+            return Err(AppError::internal("failed to edit review", error));
         }
     }
 }
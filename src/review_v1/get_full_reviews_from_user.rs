@@ -1,69 +1,109 @@
 use crate::{
-    authorization::AuthenticatedUser, compound_types::CompoundReviewPub, db,
-    review_v1::gather_compound_review, tracing::add_error_span,
+    authorization::AuthenticatedUser,
+    compound_types::{
+        CompoundReviewFieldsRequest, PagedCompoundReviewPub, PagedPub, REVIEW_PAGE_LIMITS,
+    },
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo, ReviewRepo},
+    review_v1::gather_compound_reviews,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserReviewRequest {
     user_id: String,
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
 }
 
+impl Validate for UserReviewRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(UserReviewRequest, CompoundReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of reviews by the user with their components", body = PagedCompoundReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/full_reviews_from_user")]
 pub async fn get_full_reviews_from_user(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    user_review_request: Query<UserReviewRequest>,
-) -> Result<impl Responder> {
-    let review_res = db::get_reviews_from_user(
-        &pool,
-        &authenticated_user.0,
-        &user_review_request.user_id,
-        user_review_request.page,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    user_review_request: ValidQuery<UserReviewRequest>,
+    fields_request: ValidQuery<CompoundReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request =
+        REVIEW_PAGE_LIMITS.page_request(&user_review_request.cursor, user_review_request.limit);
 
-    let mut compound_reviews: Vec<CompoundReviewPub> = vec![];
+    let review_res = review_repo
+        .get_reviews_from_user(
+            &authenticated_user.0,
+            &user_review_request.user_id,
+            &page_request,
+        )
+        .await;
 
     match review_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
 
-            for review_pub in reviews_pub.into_iter() {
-                let compound_review_res =
-                    gather_compound_review(&pool, &authenticated_user.0, review_pub).await;
+            let compound_reviews_res = gather_compound_reviews(
+                like_repo.get_ref(),
+                reply_repo.get_ref(),
+                pic_repo.get_ref(),
+                bookmark_repo.get_ref(),
+                &authenticated_user.0,
+                reviews_pub,
+            )
+            .await;
 
-                match compound_review_res {
-                    Ok(compound_review) => compound_reviews.push(compound_review),
-                    Err(error) => {
-                        add_error_span(&error);
-                        return Err(ErrorInternalServerError(
-                            "failed gathering review contents".to_string(),
-                        ));
-                    }
+            match compound_reviews_res {
+                Ok(compound_reviews) => {
+                    return Ok(Json(PagedPub::new(
+                        fields_request.sparse_all(compound_reviews),
+                        reviews.next_cursor,
+                    )));
+                }
+                Err(error) => {
+                    return Err(AppError::internal(
+                        "failed gathering review contents",
+                        error,
+                    ));
                 }
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get reviews for user".to_string(),
-            ));
+            return Err(AppError::internal("unable to get reviews for user", error));
         }
     }
-
-    return Ok(Json(compound_reviews));
 }
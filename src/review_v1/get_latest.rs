@@ -1,49 +1,80 @@
-use crate::{authorization::AuthenticatedUser, db::get_latest_reviews, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{PagedPub, PagedReviewPub, ReviewFieldsRequest, REVIEW_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::ReviewRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ReviewLocationRequest {
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for ReviewLocationRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Simple activity tracking.
 /// Paged for infinite scroll support.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(ReviewLocationRequest, ReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of latest reviews", body = PagedReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/latest")]
 pub async fn get_latest(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    review_location_request: Query<ReviewLocationRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_latest_reviews(
-        &pool,
-        &authenticated_user.0,
-        review_location_request.page,
-        &None,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    review_location_request: ValidQuery<ReviewLocationRequest>,
+    fields_request: ValidQuery<ReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = REVIEW_PAGE_LIMITS.page_request(
+        &review_location_request.cursor,
+        review_location_request.limit,
+    );
+
+    let review_res = review_repo
+        .get_latest_reviews(&authenticated_user.0, &page_request, &None)
+        .await;
 
     match review_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
-            Ok(Json(reviews_pub))
+            return Ok(Json(PagedPub::new(
+                fields_request.sparse_all(reviews_pub),
+                reviews.next_cursor,
+            )));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get latest reviews".to_string(),
-            ));
+            return Err(AppError::internal("unable to get latest reviews", error));
         }
     }
 }
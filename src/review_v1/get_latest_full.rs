@@ -1,71 +1,144 @@
 use crate::{
-    authorization::AuthenticatedUser, compound_types::CompoundReviewPub, db::get_latest_reviews,
-    review_v1::gather_compound_review, tracing::add_error_span,
+    authorization::AuthenticatedUser,
+    compound_types::{
+        CompoundReviewFieldsRequest, PagedCompoundReviewPub, PagedPub, REVIEW_PAGE_LIMITS,
+    },
+    db::{Cursor, Page, Review},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    etag::{Conditional, VersionTag, PRIVATE_REVALIDATE},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo, ReviewRepo},
+    review_v1::gather_compound_reviews,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
-    Responder, Result,
+    web::{Data, ReqData},
+    HttpRequest, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ReviewLatestRequest {
-    page: u32,
     category: Option<String>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for ReviewLatestRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Gets the latest reviews available to a given requesting user.
 /// Additionally resolves the 'fullreview' which includes likes, replies, and pic records.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(ReviewLatestRequest, CompoundReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of latest reviews with their components", body = PagedCompoundReviewPub),
+        (status = 304, description = "unchanged since the version in `If-None-Match`"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/latest_full")]
 pub async fn get_latest_full(
+    request: HttpRequest,
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    review_latest_request: Query<ReviewLatestRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_latest_reviews(
-        &pool,
-        &authenticated_user.0,
-        review_latest_request.page,
-        &review_latest_request.category,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    review_latest_request: ValidQuery<ReviewLatestRequest>,
+    fields_request: ValidQuery<CompoundReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request =
+        REVIEW_PAGE_LIMITS.page_request(&review_latest_request.cursor, review_latest_request.limit);
 
-    let mut compound_reviews: Vec<CompoundReviewPub> = vec![];
+    let review_res = review_repo
+        .get_latest_reviews(
+            &authenticated_user.0,
+            &page_request,
+            &review_latest_request.category,
+        )
+        .await;
 
+    let reviews: Page<Review>;
     match review_res {
-        Ok(reviews) => {
-            let reviews_pub: Vec<ReviewPub> = reviews
-                .into_iter()
-                .map(|f| -> ReviewPub { f.into() })
-                .collect();
+        Ok(reviews_tmp) => reviews = reviews_tmp,
+        Err(error) => {
+            return Err(AppError::internal("unable to get latest reviews", error));
+        }
+    }
 
-            for review_pub in reviews_pub.into_iter() {
-                let compound_review_res =
-                    gather_compound_review(&pool, &authenticated_user.0, review_pub).await;
+    let review_ids: Vec<String> = reviews.items.iter().map(|f| f.id.clone()).collect();
+    let version_res = review_repo
+        .get_compound_review_version(&authenticated_user.0, &review_ids)
+        .await;
 
-                match compound_review_res {
-                    Ok(compound_review) => compound_reviews.push(compound_review),
-                    Err(error) => {
-                        add_error_span(&error);
-                        return Err(ErrorInternalServerError(
-                            "failed gathering review contents".to_string(),
-                        ));
-                    }
-                }
-            }
+    let conditional: Conditional;
+    match version_res {
+        Ok(version) => {
+            let review_versions: Vec<_> = reviews.items.iter().map(Review::version).collect();
+            let etag = VersionTag::new(&request, &authenticated_user.0)
+                .add(&review_versions)
+                .add(&reviews.next_cursor.as_ref().map(Cursor::encode))
+                .add(&version)
+                .finish();
+            conditional = Conditional::new(&request, etag, PRIVATE_REVALIDATE);
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get latest reviews".to_string(),
+            return Err(AppError::internal(
+                "unable to get latest reviews version",
+                error,
             ));
         }
     }
 
-    return Ok(Json(compound_reviews));
+    if conditional.is_not_modified() {
+        return Ok(conditional.not_modified());
+    }
+
+    let reviews_pub: Vec<ReviewPub> = reviews
+        .items
+        .into_iter()
+        .map(|f| -> ReviewPub { f.into() })
+        .collect();
+
+    let compound_reviews_res = gather_compound_reviews(
+        like_repo.get_ref(),
+        reply_repo.get_ref(),
+        pic_repo.get_ref(),
+        bookmark_repo.get_ref(),
+        &authenticated_user.0,
+        reviews_pub,
+    )
+    .await;
+
+    match compound_reviews_res {
+        Ok(compound_reviews) => {
+            return Ok(conditional.ok(&PagedPub::new(
+                fields_request.sparse_all(compound_reviews),
+                reviews.next_cursor,
+            )));
+        }
+        Err(error) => {
+            return Err(AppError::internal(
+                "failed gathering review contents",
+                error,
+            ));
+        }
+    }
 }
@@ -1,19 +1,24 @@
 use crate::{
-    authorization::AuthenticatedUser, db::get_review, review_v1::gather_compound_review,
-    tracing::add_error_span,
+    authorization::AuthenticatedUser,
+    compound_types::{CompoundReviewFieldsRequest, CompoundReviewPub},
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    extract::ValidQuery,
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo, ReviewRepo},
+    review_v1::gather_compound_review,
 };
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ReviewRequest {
     review_id: String,
 }
@@ -21,39 +26,66 @@ pub struct ReviewRequest {
 /// Gets a review by the given id.
 /// The returned object contains all
 /// the initial information for the review.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(ReviewRequest, CompoundReviewFieldsRequest),
+    responses(
+        (status = 200, description = "review with its components", body = CompoundReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/review_by_id")]
 pub async fn get_review_by_id(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
     review_request: Query<ReviewRequest>,
-) -> Result<impl Responder> {
+    fields_request: ValidQuery<CompoundReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
     let review: ReviewPub;
-    let review_res = get_review(&pool, &authenticated_user.0, &review_request.review_id).await;
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &review_request.review_id)
+        .await;
 
     match review_res {
         Ok(review_opt) => {
             if let Some(review_tmp) = review_opt {
                 review = review_tmp.into();
             } else {
-                return Err(ErrorNotFound("unable to find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "unable to find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get review"));
+            return Err(AppError::internal("unable to get review", error));
         }
     }
 
-    let compound_review_res = gather_compound_review(&pool, &authenticated_user.0, review).await;
+    let compound_review_res = gather_compound_review(
+        like_repo.get_ref(),
+        reply_repo.get_ref(),
+        pic_repo.get_ref(),
+        bookmark_repo.get_ref(),
+        &authenticated_user.0,
+        review,
+    )
+    .await;
 
     match compound_review_res {
         Ok(compound_review) => {
-            return Ok(Json(compound_review));
+            return Ok(Json(fields_request.sparse(compound_review)));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "failed to gather review components",
+                error,
             ));
         }
     }
@@ -0,0 +1,88 @@
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{BatchPub, BatchRequest, CompoundReviewBatchPub, CompoundReviewFieldsRequest},
+    docs::ErrorResponses,
+    error::AppError,
+    extract::{ValidJson, ValidQuery},
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo, ReviewRepo},
+    review_v1::gather_compound_reviews,
+};
+use actix_web::{
+    post,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+
+use super::review_types::ReviewPub;
+
+/// Gets several reviews by id in one request.
+/// Follows the same visibility rules as `review_by_id`.
+/// Ids that aren't found or can't be seen come back in `missing` instead of failing the request.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(CompoundReviewFieldsRequest),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "found reviews with their components, and the ids that were not", body = CompoundReviewBatchPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/batch")]
+pub async fn get_review_batch(
+    authenticated_user: ReqData<AuthenticatedUser>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    like_repo: Data<dyn LikeRepo>,
+    reply_repo: Data<dyn ReplyRepo>,
+    bookmark_repo: Data<dyn BookmarkRepo>,
+    batch_request: ValidJson<BatchRequest>,
+    fields_request: ValidQuery<CompoundReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let review_ids = batch_request.unique_ids();
+
+    let reviews_res = review_repo
+        .get_reviews_by_ids(&authenticated_user.0, &review_ids)
+        .await;
+
+    match reviews_res {
+        Ok(reviews) => {
+            let batch = BatchPub::new(&review_ids, reviews, |review| review.id.clone());
+
+            let reviews_pub: Vec<ReviewPub> = batch
+                .items
+                .into_iter()
+                .map(|f| -> ReviewPub { f.into() })
+                .collect();
+
+            let compound_reviews_res = gather_compound_reviews(
+                like_repo.get_ref(),
+                reply_repo.get_ref(),
+                pic_repo.get_ref(),
+                bookmark_repo.get_ref(),
+                &authenticated_user.0,
+                reviews_pub,
+            )
+            .await;
+
+            match compound_reviews_res {
+                Ok(compound_reviews) => {
+                    return Ok(Json(BatchPub {
+                        items: fields_request.sparse_all(compound_reviews),
+                        missing: batch.missing,
+                    }));
+                }
+                Err(error) => {
+                    return Err(AppError::internal(
+                        "failed gathering review contents",
+                        error,
+                    ));
+                }
+            }
+        }
+        Err(error) => {
+            return Err(AppError::internal("unable to get reviews", error));
+        }
+    }
+}
@@ -1,17 +1,22 @@
 use crate::{
-    authorization::AuthenticatedUser, db::get_reviews_from_bounds_with_exclusions,
-    review_v1::ReviewAnnotationPub, tracing::add_error_span,
+    authorization::AuthenticatedUser,
+    compound_types::{PagedPub, PagedReviewAnnotationPub, ANNOTATION_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::ReviewRepo,
+    review_v1::ReviewAnnotationPub,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ReviewMapBoundWithExclusionRequest {
     latitude_north: f64,
     latitude_south: f64,
@@ -21,43 +26,66 @@ pub struct ReviewMapBoundWithExclusionRequest {
     latitude_south_e: f64,
     longitude_west_e: f64,
     longitude_east_e: f64,
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl Validate for ReviewMapBoundWithExclusionRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        ANNOTATION_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
 }
 
 /// Gets reviews you are able to see if a given map bounding box.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(ReviewMapBoundWithExclusionRequest),
+    responses(
+        (status = 200, description = "page of reviews inside the bounds", body = PagedReviewAnnotationPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/reviews_from_bounds_exclusions")]
 pub async fn get_reviews_from_map_bounds_with_exclusions(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    review_location_request: Query<ReviewMapBoundWithExclusionRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_reviews_from_bounds_with_exclusions(
-        &pool,
-        &authenticated_user.0,
-        review_location_request.latitude_north,
-        review_location_request.latitude_south,
-        review_location_request.longitude_west,
-        review_location_request.longitude_east,
-        review_location_request.latitude_north_e,
-        review_location_request.latitude_south_e,
-        review_location_request.longitude_west_e,
-        review_location_request.longitude_east_e,
-        review_location_request.page,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    review_location_request: ValidQuery<ReviewMapBoundWithExclusionRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = ANNOTATION_PAGE_LIMITS.page_request(
+        &review_location_request.cursor,
+        review_location_request.limit,
+    );
+
+    let review_res = review_repo
+        .get_reviews_from_bounds_with_exclusions(
+            &authenticated_user.0,
+            review_location_request.latitude_north,
+            review_location_request.latitude_south,
+            review_location_request.longitude_west,
+            review_location_request.longitude_east,
+            review_location_request.latitude_north_e,
+            review_location_request.latitude_south_e,
+            review_location_request.longitude_west_e,
+            review_location_request.longitude_east_e,
+            &page_request,
+        )
+        .await;
 
     match review_res {
         Ok(reviews) => {
-            let reviews_pub: Vec<ReviewAnnotationPub> = reviews
-                .into_iter()
-                .map(|f| -> ReviewAnnotationPub { f.into() })
-                .collect();
+            let reviews_pub: PagedPub<ReviewAnnotationPub> = reviews.into();
             Ok(Json(reviews_pub))
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "failed to fetch review annotations",
+                error,
             ));
         }
     }
@@ -1,18 +1,19 @@
 use crate::{
-    authorization::AuthenticatedUser, db::get_reviews_from_location, tracing::add_error_span,
+    authorization::AuthenticatedUser, compound_types::ReviewFieldsRequest, docs::ErrorResponses,
+    error::AppError, extract::ValidQuery, repo::ReviewRepo,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ReviewLocationRequest {
     latitude: f64,
     longitude: f64,
@@ -22,20 +23,31 @@ pub struct ReviewLocationRequest {
 /// Gets reviews you are able to see that qualify via close location.
 /// Accuracy required is exact for location name; but +- a range around
 /// the given coordinates.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(ReviewLocationRequest, ReviewFieldsRequest),
+    responses(
+        (status = 200, description = "reviews for the location", body = [ReviewPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/reviews_from_loc")]
 pub async fn get_reviews_from_loc(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
     review_location_request: Query<ReviewLocationRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_reviews_from_location(
-        &pool,
-        &authenticated_user.0,
-        &review_location_request.name,
-        review_location_request.latitude,
-        review_location_request.longitude,
-    )
-    .await;
+    fields_request: ValidQuery<ReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_reviews_from_location(
+            &authenticated_user.0,
+            &review_location_request.name,
+            review_location_request.latitude,
+            review_location_request.longitude,
+        )
+        .await;
 
     match review_res {
         Ok(reviews) => {
@@ -43,12 +55,12 @@ pub async fn get_reviews_from_loc(
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
-            Ok(Json(reviews_pub))
+            return Ok(Json(fields_request.sparse_all(reviews_pub)));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get reviews for location".to_string(),
+            return Err(AppError::internal(
+                "unable to get reviews for location",
+                error,
             ));
         }
     }
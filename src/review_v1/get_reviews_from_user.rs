@@ -1,47 +1,83 @@
-use crate::{authorization::AuthenticatedUser, db, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{PagedPub, PagedReviewPub, ReviewFieldsRequest, REVIEW_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::ReviewRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserReviewRequest {
     user_id: String,
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
 }
 
+impl Validate for UserReviewRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(UserReviewRequest, ReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of reviews by the user", body = PagedReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/reviews_from_user")]
 pub async fn get_reviews_from_user(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    user_review_request: Query<UserReviewRequest>,
-) -> Result<impl Responder> {
-    let review_res = db::get_reviews_from_user(
-        &pool,
-        &authenticated_user.0,
-        &user_review_request.user_id,
-        user_review_request.page,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    user_review_request: ValidQuery<UserReviewRequest>,
+    fields_request: ValidQuery<ReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request =
+        REVIEW_PAGE_LIMITS.page_request(&user_review_request.cursor, user_review_request.limit);
+
+    let review_res = review_repo
+        .get_reviews_from_user(
+            &authenticated_user.0,
+            &user_review_request.user_id,
+            &page_request,
+        )
+        .await;
 
     match review_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
-            Ok(Json(reviews_pub))
+            return Ok(Json(PagedPub::new(
+                fields_request.sparse_all(reviews_pub),
+                reviews.next_cursor,
+            )));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to get reviews for location".to_string(),
+            return Err(AppError::internal(
+                "unable to get reviews for location",
+                error,
             ));
         }
     }
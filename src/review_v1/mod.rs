@@ -43,5 +43,11 @@ pub use edit_review::*;
 pub mod get_review;
 pub use get_review::*;
 
+pub mod get_review_batch;
+pub use get_review_batch::*;
+
 pub mod search_latest;
 pub use search_latest::*;
+
+#[cfg(test)]
+mod tests;
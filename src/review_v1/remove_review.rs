@@ -1,8 +1,10 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{get_all_pics, get_review, remove_review_and_children, Review},
+    db::Review,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
     pic_v1::shared_utils::best_effort_delete_pic,
-    tracing::add_error_span,
+    repo::{PicRepo, ReviewRepo},
 };
 use actix_web::{
     post,
@@ -11,27 +13,35 @@ use actix_web::{
 };
 use images::S3Client;
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RemoveReviewRequest {
     pub review_id: String,
 }
 
 /// Allows a user to remove their review.
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    responses(
+        (status = 200, description = "review was removed"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/remove_review")]
 pub async fn remove_review(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
+    pic_repo: Data<dyn PicRepo>,
     s3_client: Data<S3Client>,
     remove_review_request: Query<RemoveReviewRequest>,
-) -> Result<impl Responder> {
-    let review_res = get_review(
-        &pool,
-        &authenticated_user.0,
-        &remove_review_request.review_id,
-    )
-    .await;
+) -> Result<impl Responder, AppError> {
+    let review_res = review_repo
+        .get_review(&authenticated_user.0, &remove_review_request.review_id)
+        .await;
 
     let review: Review;
     match review_res {
@@ -39,30 +49,37 @@ pub async fn remove_review(
             if let Some(review_tmp) = review_opt {
                 review = review_tmp;
             } else {
-                return Ok(HttpResponse::NotFound().body("could not find review"));
+                return Err(AppError::not_found(
+                    ErrorCode::ReviewNotFound,
+                    "could not find review",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Ok(HttpResponse::InternalServerError().body("failed to get review"));
+            return Err(AppError::internal("failed to get review", error));
         }
     }
 
     if review.user_id != authenticated_user.0 {
-        return Ok(HttpResponse::Forbidden().body("you did not create this review"));
+        return Err(AppError::forbidden(
+            ErrorCode::NotOwner,
+            "you did not create this review",
+        ));
     }
 
-    if let Err(error) = remove_review_and_children(&pool, &remove_review_request.review_id).await {
-        add_error_span(&error);
-        return Ok(HttpResponse::InternalServerError().body("unable to delete records"));
+    if let Err(error) = review_repo
+        .remove_review_and_children(&remove_review_request.review_id)
+        .await
+    {
+        return Err(AppError::internal("unable to delete records", error));
     }
 
-    let pics_res = get_all_pics(&pool, &review.id).await;
+    let pics_res = pic_repo.get_all_pics(&review.id).await;
 
     match pics_res {
         Ok(pics) => {
             for pic in pics {
-                best_effort_delete_pic(&s3_client, &pool, &pic.id).await;
+                best_effort_delete_pic(&s3_client, pic_repo.get_ref(), &pic.id).await;
             }
         }
         Err(_) => {}
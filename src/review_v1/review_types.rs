@@ -3,11 +3,12 @@ use crate::{
     pic_v1::PicPub,
 };
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReviewPub {
     pub id: String,
     pub user_id: String,
@@ -45,7 +46,7 @@ impl From<Review> for ReviewPub {
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReviewAnnotationPub {
     pub id: String,
     pub user_id: String,
@@ -1,48 +1,83 @@
-use crate::{authorization::AuthenticatedUser, db::search_latest_reviews, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{PagedPub, PagedReviewPub, ReviewFieldsRequest, REVIEW_PAGE_LIMITS},
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::ReviewRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
-    web::{Data, Json, Query, ReqData},
+    web::{Data, Json, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::review_types::ReviewPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchReviewLocationRequest {
     search_term: String,
-    page: u32,
+    cursor: Option<String>,
+    limit: Option<u32>,
 }
 
+impl Validate for SearchReviewLocationRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        REVIEW_PAGE_LIMITS.check(&mut errors, &self.cursor, self.limit);
+
+        errors.finish()
+    }
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    params(SearchReviewLocationRequest, ReviewFieldsRequest),
+    responses(
+        (status = 200, description = "page of reviews matching the search", body = PagedReviewPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/search_latest")]
 pub async fn search_latest(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    review_location_request: Query<SearchReviewLocationRequest>,
-) -> Result<impl Responder> {
-    let review_res = search_latest_reviews(
-        &pool,
-        &authenticated_user.0,
-        &review_location_request.search_term,
-        review_location_request.page,
-    )
-    .await;
+    review_repo: Data<dyn ReviewRepo>,
+    review_location_request: ValidQuery<SearchReviewLocationRequest>,
+    fields_request: ValidQuery<ReviewFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let page_request = REVIEW_PAGE_LIMITS.page_request(
+        &review_location_request.cursor,
+        review_location_request.limit,
+    );
+
+    let review_res = review_repo
+        .search_latest_reviews(
+            &authenticated_user.0,
+            &review_location_request.search_term,
+            &page_request,
+        )
+        .await;
 
     match review_res {
         Ok(reviews) => {
             let reviews_pub: Vec<ReviewPub> = reviews
+                .items
                 .into_iter()
                 .map(|f| -> ReviewPub { f.into() })
                 .collect();
-            Ok(Json(reviews_pub))
+            return Ok(Json(PagedPub::new(
+                fields_request.sparse_all(reviews_pub),
+                reviews.next_cursor,
+            )));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
-                "unable to search latest reviews".to_string(),
-            ));
+            return Err(AppError::internal("unable to search latest reviews", error));
         }
     }
 }
@@ -1,48 +1,105 @@
 use crate::{
     compound_types::CompoundReviewPub,
-    db::{does_bookmark_exist, get_all_likes, get_all_pics, get_all_replies},
     likes_v1::LikePub,
     pic_v1::PicPub,
     reply_v1::ReplyPub,
+    repo::{BookmarkRepo, LikeRepo, PicRepo, ReplyRepo},
 };
-use sqlx::{Error, MySqlPool};
+use sqlx::Error;
+use std::collections::{HashMap, HashSet};
 
 use super::ReviewPub;
 
 pub async fn gather_compound_review(
-    pool: &MySqlPool,
+    like_repo: &dyn LikeRepo,
+    reply_repo: &dyn ReplyRepo,
+    pic_repo: &dyn PicRepo,
+    bookmark_repo: &dyn BookmarkRepo,
     calling_user_id: &str,
     review: ReviewPub,
 ) -> Result<CompoundReviewPub, Error> {
-    let likes_pub: Vec<LikePub>;
-    let likes = get_all_likes(&pool, &review.id).await?;
-    likes_pub = likes.into_iter().map(|f| -> LikePub { f.into() }).collect();
-
-    let replies_pub: Vec<ReplyPub>;
-    let replies = get_all_replies(&pool, &review.id).await?;
-    replies_pub = replies
-        .into_iter()
-        .map(|f| -> ReplyPub { f.into() })
-        .collect();
-
-    let pics_pub: Vec<PicPub>;
-    let pics = get_all_pics(&pool, &review.id).await?;
-    pics_pub = pics.into_iter().map(|f| -> PicPub { f.into() }).collect();
-
-    let is_bookmarked = does_bookmark_exist(
-        &pool,
+    let mut compound_reviews = gather_compound_reviews(
+        like_repo,
+        reply_repo,
+        pic_repo,
+        bookmark_repo,
         calling_user_id,
-        &review.location_name,
-        review.latitude,
-        review.longitude,
+        vec![review],
     )
     .await?;
 
-    return Ok(CompoundReviewPub {
-        review,
-        bookmarked: is_bookmarked,
-        likes: likes_pub,
-        replies: replies_pub,
-        pics: pics_pub,
-    });
+    return Ok(compound_reviews.remove(0));
+}
+
+/// Resolves a whole page of reviews with one query per table rather than four per review.
+/// Keeps the order of `reviews`.
+pub async fn gather_compound_reviews(
+    like_repo: &dyn LikeRepo,
+    reply_repo: &dyn ReplyRepo,
+    pic_repo: &dyn PicRepo,
+    bookmark_repo: &dyn BookmarkRepo,
+    calling_user_id: &str,
+    reviews: Vec<ReviewPub>,
+) -> Result<Vec<CompoundReviewPub>, Error> {
+    if reviews.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let review_ids: Vec<String> = reviews.iter().map(|review| review.id.clone()).collect();
+
+    let (likes, replies, pics, bookmarked) = futures::try_join!(
+        like_repo.get_likes_for_reviews(&review_ids),
+        reply_repo.get_replies_for_reviews(&review_ids),
+        pic_repo.get_pics_for_reviews(&review_ids),
+        bookmark_repo.get_bookmarked_review_ids(calling_user_id, &review_ids),
+    )?;
+
+    let mut likes_by_review: HashMap<String, Vec<LikePub>> = HashMap::new();
+    for like in likes {
+        likes_by_review
+            .entry(like.review_id.clone())
+            .or_default()
+            .push(like.into());
+    }
+
+    let mut replies_by_review: HashMap<String, Vec<ReplyPub>> = HashMap::new();
+    for reply in replies {
+        replies_by_review
+            .entry(reply.review_id.clone())
+            .or_default()
+            .push(reply.into());
+    }
+
+    let mut pics_by_review: HashMap<String, Vec<PicPub>> = HashMap::new();
+    for pic in pics {
+        if let Some(review_id) = pic.review_id.clone() {
+            pics_by_review
+                .entry(review_id)
+                .or_default()
+                .push(pic.into());
+        }
+    }
+
+    let bookmarked: HashSet<String> = bookmarked.into_iter().collect();
+
+    let compound_reviews = reviews
+        .into_iter()
+        .map(|review| {
+            let likes = likes_by_review.remove(&review.id).unwrap_or_default();
+            let mut replies = replies_by_review.remove(&review.id).unwrap_or_default();
+            replies.sort_by(|a, b| (a.created, &a.id).cmp(&(b.created, &b.id)));
+
+            CompoundReviewPub {
+                bookmarked: bookmarked.contains(&review.id),
+                like_count: likes.len() as u32,
+                likes,
+                reply_count: replies.len() as u32,
+                replies,
+                pics: pics_by_review.remove(&review.id).unwrap_or_default(),
+                review,
+            }
+        })
+        .collect();
+
+    return Ok(compound_reviews);
 }
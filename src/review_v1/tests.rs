@@ -0,0 +1,136 @@
+//! Friend visibility through the review handlers, with a `MemoryRepo` behind them.
+
+use actix_web::{http::StatusCode, test, web, App};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{
+    db::{Review, User},
+    repo::{configure_repos, FriendRepo, MemoryRepo, ReviewRepo, UserRepo},
+    review_v1::{get_latest, get_review_batch, get_review_by_id},
+    test_support::as_user,
+};
+
+fn user(id: &str) -> User {
+    User {
+        id: id.to_string(),
+        name: id.to_string(),
+        display_name: id.to_string(),
+        phone: format!("+1555000{}", id.len()),
+        created: Utc::now().naive_utc(),
+        pic_id: "default".to_string(),
+        device_token: None,
+        email: None,
+        disabled: 0,
+    }
+}
+
+/// alice and bob aren't friends yet, and bob has posted `review`.
+async fn strangers() -> Arc<MemoryRepo> {
+    let repo = Arc::new(MemoryRepo::new());
+    repo.create_user(&user("alice")).await.unwrap();
+    repo.create_user(&user("bob")).await.unwrap();
+
+    let review = Review {
+        id: "review".to_string(),
+        user_id: "bob".to_string(),
+        created: Utc::now().naive_utc(),
+        category: "food".to_string(),
+        text: "good".to_string(),
+        stars: 5,
+        location_name: "Cafe".to_string(),
+        latitude: 47.6,
+        longitude: -122.3,
+        is_custom: 0,
+        delivered: 0,
+        recommended: 0,
+    };
+    repo.create_review(&review).await.unwrap();
+
+    return repo;
+}
+
+async fn befriend(repo: &MemoryRepo) {
+    repo.create_friend_request("alice", "bob").await.unwrap();
+    repo.accept_friend_request("bob", "alice").await.unwrap();
+}
+
+#[actix_web::test]
+async fn get_review_by_id_requires_friendship() {
+    let repo = strangers().await;
+    let app = test::init_service(
+        App::new()
+            .configure(configure_repos(repo.clone()))
+            .service(web::scope("/review").service(get_review_by_id)),
+    )
+    .await;
+
+    let request = test::TestRequest::get()
+        .uri("/review/review_by_id?review_id=review")
+        .to_request();
+    let response = test::call_service(&app, as_user(request, "alice")).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    befriend(&repo).await;
+
+    let request = test::TestRequest::get()
+        .uri("/review/review_by_id?review_id=review")
+        .to_request();
+    let response = test::call_service(&app, as_user(request, "alice")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body: Value = test::read_body_json(response).await;
+    assert_eq!(body["review"]["id"], "review");
+}
+
+#[actix_web::test]
+async fn get_latest_only_lists_friends_reviews() {
+    let repo = strangers().await;
+    let app = test::init_service(
+        App::new()
+            .configure(configure_repos(repo.clone()))
+            .service(web::scope("/review").service(get_latest)),
+    )
+    .await;
+
+    let request = test::TestRequest::get().uri("/review/latest").to_request();
+    let body: Value = test::call_and_read_body_json(&app, as_user(request, "alice")).await;
+    assert_eq!(body["items"], json!([]));
+
+    befriend(&repo).await;
+
+    let request = test::TestRequest::get().uri("/review/latest").to_request();
+    let body: Value = test::call_and_read_body_json(&app, as_user(request, "alice")).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["id"], "review");
+}
+
+#[actix_web::test]
+async fn get_review_batch_lists_non_friend_reviews_as_missing() {
+    let repo = strangers().await;
+    let app = test::init_service(
+        App::new()
+            .configure(configure_repos(repo.clone()))
+            .service(web::scope("/review").service(get_review_batch)),
+    )
+    .await;
+
+    let request = test::TestRequest::post()
+        .uri("/review/batch")
+        .set_json(json!({ "ids": ["review"] }))
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, as_user(request, "alice")).await;
+    assert_eq!(body["items"], json!([]));
+    assert_eq!(body["missing"], json!(["review"]));
+
+    befriend(&repo).await;
+
+    let request = test::TestRequest::post()
+        .uri("/review/batch")
+        .set_json(json!({ "ids": ["review"] }))
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, as_user(request, "alice")).await;
+    assert_eq!(body["items"][0]["review"]["id"], "review");
+    assert_eq!(body["missing"], json!([]));
+}
@@ -1,41 +1,50 @@
 use crate::{
-    authorization::AuthenticatedUser, db::update_review_recommended, tracing::add_error_span,
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::ReviewRepo,
 };
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UpdateReviewRecommended {
     review_id: String,
     recommended: bool,
 }
 
+#[utoipa::path(
+    context_path = "/api/v1/review",
+    tag = "review",
+    responses(
+        (status = 200, description = "review was updated"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/update_recommended")]
 pub async fn update_review_recommended_status(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    review_repo: Data<dyn ReviewRepo>,
     update_request: Query<UpdateReviewRecommended>,
-) -> Result<impl Responder> {
-    let update_res = update_review_recommended(
-        &pool,
-        &update_request.review_id,
-        &authenticated_user.0,
-        update_request.recommended,
-    )
-    .await;
+) -> Result<impl Responder, AppError> {
+    let update_res = review_repo
+        .update_review_recommended(
+            &update_request.review_id,
+            &authenticated_user.0,
+            update_request.recommended,
+        )
+        .await;
 
     match update_res {
         Ok(_) => return Ok(HttpResponse::Ok().finish()),
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError(
+            return Err(AppError::internal(
                 "failed to update recommended status",
+                error,
             ));
         }
     }
@@ -0,0 +1,157 @@
+use crate::{
+    authorization::AuthenticatedUser,
+    bookmark_v1::BookmarkPub,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    extract::ValidQuery,
+    friend_v1::gather_full_friends,
+    likes_v1::LikePub,
+    notifications_v1::NotificationPub,
+    pic_v1::PicPub,
+    reply_v1::ReplyPub,
+    repo::{FriendRepo, NotificationRepo, PicRepo, SyncRepo},
+    review_v1::ReviewPub,
+};
+use actix_web::{
+    get,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+use chrono::Utc;
+
+use super::{SyncPub, SyncRequest, SyncToken, TombstonePub};
+
+/// Gets everything the caller can see that changed since their last sync.
+/// Without `since` only friends, notifications and a token come back, for clients that just loaded everything.
+/// Friends and friend requests are always the full lists rather than what changed.
+#[utoipa::path(
+    context_path = "/api/v1/sync",
+    tag = "sync",
+    params(SyncRequest),
+    responses(
+        (status = 200, description = "changes since the token, and the token for the next sync", body = SyncPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[get("")]
+pub async fn get_sync(
+    authenticated_user: ReqData<AuthenticatedUser>,
+    sync_repo: Data<dyn SyncRepo>,
+    friend_repo: Data<dyn FriendRepo>,
+    notification_repo: Data<dyn NotificationRepo>,
+    pic_repo: Data<dyn PicRepo>,
+    sync_request: ValidQuery<SyncRequest>,
+) -> Result<impl Responder, AppError> {
+    // taken before reading, so anything written while this runs is sent again next time.
+    let now = Utc::now().naive_utc();
+    let next_token = SyncToken { synced: now };
+
+    let mut reviews: Vec<ReviewPub> = vec![];
+    let mut pics: Vec<PicPub> = vec![];
+    let mut likes: Vec<LikePub> = vec![];
+    let mut replies: Vec<ReplyPub> = vec![];
+    let mut bookmarks: Vec<BookmarkPub> = vec![];
+    let mut deleted: Vec<TombstonePub> = vec![];
+
+    if let Some(token) = sync_request.token() {
+        if token.is_expired(now) {
+            return Err(AppError::bad_request(
+                ErrorCode::SyncTokenExpired,
+                "sync token is too old, reload everything and sync without `since`",
+            ));
+        }
+
+        let since = token.since();
+        let user_id = &authenticated_user.0;
+
+        match sync_repo.get_reviews_changed_since(user_id, &since).await {
+            Ok(changed) => {
+                reviews = changed
+                    .into_iter()
+                    .map(|f| -> ReviewPub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get changed reviews", error)),
+        }
+
+        let review_ids: Vec<String> = reviews.iter().map(|review| review.id.clone()).collect();
+        match pic_repo.get_pics_for_reviews(&review_ids).await {
+            Ok(changed) => {
+                pics = changed
+                    .into_iter()
+                    .map(|f| -> PicPub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get review pics", error)),
+        }
+
+        match sync_repo.get_likes_since(user_id, &since).await {
+            Ok(changed) => {
+                likes = changed
+                    .into_iter()
+                    .map(|f| -> LikePub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get new likes", error)),
+        }
+
+        match sync_repo.get_replies_since(user_id, &since).await {
+            Ok(changed) => {
+                replies = changed
+                    .into_iter()
+                    .map(|f| -> ReplyPub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get new replies", error)),
+        }
+
+        match sync_repo.get_bookmarks_since(user_id, &since).await {
+            Ok(changed) => {
+                bookmarks = changed
+                    .into_iter()
+                    .map(|f| -> BookmarkPub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get new bookmarks", error)),
+        }
+
+        match sync_repo.get_tombstones_since(user_id, &since).await {
+            Ok(tombstones) => {
+                deleted = tombstones
+                    .into_iter()
+                    .map(|f| -> TombstonePub { f.into() })
+                    .collect();
+            }
+            Err(error) => return Err(AppError::internal("unable to get deletes", error)),
+        }
+    }
+
+    let friends = gather_full_friends(friend_repo.get_ref(), &authenticated_user.0).await?;
+
+    let notifications: Vec<NotificationPub>;
+    match notification_repo
+        .get_notifications(&authenticated_user.0)
+        .await
+    {
+        Ok(expanded) => {
+            notifications = expanded
+                .into_iter()
+                .map(|f| -> NotificationPub { f.into() })
+                .collect();
+        }
+        Err(error) => return Err(AppError::internal("unable to get notifications", error)),
+    }
+
+    return Ok(Json(SyncPub {
+        reviews,
+        pics,
+        likes,
+        replies,
+        bookmarks,
+        deleted,
+        friends,
+        notifications,
+        token: next_token.encode(),
+    }));
+}
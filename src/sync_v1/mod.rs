@@ -0,0 +1,5 @@
+pub mod sync_types;
+pub use sync_types::*;
+
+pub mod get_sync;
+pub use get_sync::*;
@@ -0,0 +1,139 @@
+use crate::{
+    bookmark_v1::BookmarkPub,
+    db::Tombstone,
+    error::FieldError,
+    extract::{FieldErrors, Validate},
+    friend_v1::FullFriendsPub,
+    likes_v1::LikePub,
+    notifications_v1::NotificationPub,
+    pic_v1::PicPub,
+    reply_v1::ReplyPub,
+    review_v1::ReviewPub,
+};
+use base64::{engine::general_purpose, Engine};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// How long before its token a sync starts reading.
+/// Rows are stamped before they commit, so one written during the last sync can carry an earlier time.
+pub const SYNC_OVERLAP_SECONDS: i64 = 30;
+
+/// Oldest token a sync accepts. Clients further behind reload everything instead.
+pub const SYNC_TOKEN_MAX_AGE_DAYS: i64 = 30;
+
+/// When a client last synced, handed to it as an opaque `token`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncToken {
+    pub synced: NaiveDateTime,
+}
+
+impl SyncToken {
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}.{}",
+            self.synced.timestamp(),
+            self.synced.timestamp_subsec_nanos()
+        );
+
+        return general_purpose::URL_SAFE_NO_PAD.encode(raw);
+    }
+
+    /// `None` for anything `encode` could not have produced.
+    pub fn decode(value: &str) -> Option<SyncToken> {
+        let raw = general_purpose::URL_SAFE_NO_PAD.decode(value).ok()?;
+        let raw = String::from_utf8(raw).ok()?;
+
+        let (seconds, nanos) = raw.split_once('.')?;
+        let synced = NaiveDateTime::from_timestamp_opt(seconds.parse().ok()?, nanos.parse().ok()?)?;
+
+        return Some(SyncToken { synced });
+    }
+
+    /// Changes at or after this are sent.
+    pub fn since(&self) -> NaiveDateTime {
+        self.synced - Duration::seconds(SYNC_OVERLAP_SECONDS)
+    }
+
+    /// Tombstones older than this are pruned, so older tokens could miss deletes.
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        now - self.synced > Duration::days(SYNC_TOKEN_MAX_AGE_DAYS)
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SyncRequest {
+    /// `token` from the last sync. Leave it out after loading everything, to get a token to start from.
+    pub since: Option<String>,
+}
+
+impl SyncRequest {
+    /// Expects the request to have passed `validate`.
+    pub fn token(&self) -> Option<SyncToken> {
+        self.since.as_deref().and_then(SyncToken::decode)
+    }
+}
+
+impl Validate for SyncRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(since) = &self.since {
+            errors.check(
+                "since",
+                SyncToken::decode(since).ok_or("invalid sync token".to_string()),
+            );
+        }
+
+        errors.finish()
+    }
+}
+
+/// A review, reply, like or bookmark that was deleted.
+#[derive(Serialize, ToSchema)]
+pub struct TombstonePub {
+    /// `review`, `reply`, `like` or `bookmark`.
+    pub item_type: String,
+    pub item_id: String,
+    pub deleted: i64,
+}
+
+impl From<Tombstone> for TombstonePub {
+    fn from(tombstone: Tombstone) -> TombstonePub {
+        TombstonePub {
+            item_type: tombstone.item_type,
+            item_id: tombstone.item_id,
+            deleted: tombstone.created.timestamp_millis(),
+        }
+    }
+}
+
+/// What changed since the last sync.
+/// Syncs overlap a little, so clients should upsert by id and expect some items they already have.
+#[derive(Serialize, ToSchema)]
+pub struct SyncPub {
+    /// Reviews created or edited, including ones from new friends.
+    pub reviews: Vec<ReviewPub>,
+
+    /// Every pic of each review in `reviews`, replacing the ones cached for it.
+    pub pics: Vec<PicPub>,
+
+    pub likes: Vec<LikePub>,
+    pub replies: Vec<ReplyPub>,
+    pub bookmarks: Vec<BookmarkPub>,
+
+    /// Reviews, replies, likes and bookmarks that were deleted.
+    /// Deleting a review deletes its likes and replies, which aren't listed separately.
+    pub deleted: Vec<TombstonePub>,
+
+    /// Not a delta: always every friend and friend request, as `/friends/full_friends` returns them.
+    /// Replace the cached lists with these, and drop anything from a user no longer in `friends.friends`.
+    pub friends: FullFriendsPub,
+
+    /// Always the 50 latest, as `/notification` returns them.
+    pub notifications: Vec<NotificationPub>,
+
+    /// Send back as `since` on the next sync.
+    pub token: String,
+}
@@ -0,0 +1,13 @@
+//! Fixtures shared by tests across modules.
+
+use actix_web::HttpMessage;
+
+use crate::authorization::AuthenticatedUser;
+
+/// Stands in for `Authentication`, which needs a signed JWT.
+pub fn as_user<R: HttpMessage>(request: R, user_id: &str) -> R {
+    request
+        .extensions_mut()
+        .insert(AuthenticatedUser(user_id.to_string()));
+    request
+}
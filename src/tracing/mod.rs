@@ -1,2 +1,8 @@
+pub mod propagation;
+pub use propagation::*;
+
+pub mod setup;
+pub use setup::*;
+
 pub mod utils;
 pub use utils::*;
@@ -0,0 +1,36 @@
+use opentelemetry_api::{global, propagation::Injector, Context};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    RequestBuilder,
+};
+
+/// Adds the current trace context to outgoing requests, so spans from the services we call
+/// are joined to the request that caused them.
+pub trait WithTraceContext {
+    fn with_trace_context(self) -> Self;
+}
+
+impl WithTraceContext for RequestBuilder {
+    fn with_trace_context(self) -> Self {
+        let mut injector = HeaderInjector(HeaderMap::new());
+
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&Context::current(), &mut injector)
+        });
+
+        self.headers(injector.0)
+    }
+}
+
+struct HeaderInjector(HeaderMap);
+
+impl Injector for HeaderInjector {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
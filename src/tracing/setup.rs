@@ -0,0 +1,105 @@
+use crate::config::{OtlpProtocol, TelemetryConfig};
+use opentelemetry::{
+    global,
+    sdk::{
+        export::trace::stdout,
+        propagation::TraceContextPropagator,
+        trace::{self, Sampler},
+        Resource,
+    },
+    trace::TraceError,
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use std::collections::HashMap;
+use tokio::task;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+
+/// Initialize tracing - OTLP when an endpoint is configured, stdout for dev
+pub fn setup_tracing(config: &TelemetryConfig) -> Result<(), TraceError> {
+    // w3c trace context, used both for incoming requests and the ones we make.
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let trace_config = trace::config()
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sampler_ratio,
+        ))))
+        .with_resource(build_resource(config));
+
+    let endpoint = match &config.endpoint {
+        Some(endpoint) => endpoint.trim_end_matches('/'),
+        None => {
+            stdout::new_pipeline()
+                .with_trace_config(trace_config)
+                .install_simple();
+            return Ok(());
+        }
+    };
+
+    match config.protocol {
+        OtlpProtocol::HttpProtobuf => {
+            let headers: HashMap<String, String> = config.headers.iter().cloned().collect();
+
+            opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .http()
+                        .with_endpoint(format!("{}/v1/traces", endpoint))
+                        .with_headers(headers),
+                )
+                .with_trace_config(trace_config)
+                .install_batch(opentelemetry::runtime::Tokio)?;
+        }
+        OtlpProtocol::Grpc => {
+            opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint)
+                        .with_metadata(build_metadata(&config.headers)?),
+                )
+                .with_trace_config(trace_config)
+                .install_batch(opentelemetry::runtime::Tokio)?;
+        }
+    }
+
+    return Ok(());
+}
+
+/// Flushes any spans still waiting in the batch exporter.
+/// Shutting down blocks until the export finishes, so it runs off the runtime thread the exporter needs.
+pub async fn shutdown_tracing() {
+    let _ = task::spawn_blocking(global::shutdown_tracer_provider).await;
+}
+
+fn build_resource(config: &TelemetryConfig) -> Resource {
+    let mut attributes: Vec<KeyValue> = config
+        .resource_attributes
+        .iter()
+        .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+        .collect();
+
+    attributes.push(KeyValue::new("service.name", config.service_name.clone()));
+
+    Resource::new(attributes)
+}
+
+fn build_metadata(headers: &[(String, String)]) -> Result<MetadataMap, TraceError> {
+    let mut metadata = MetadataMap::new();
+
+    for (name, value) in headers {
+        let key = name
+            .to_lowercase()
+            .parse::<MetadataKey<_>>()
+            .map_err(|_| TraceError::from(format!("invalid otlp header name: {}", name)))?;
+        let value = value
+            .parse::<MetadataValue<_>>()
+            .map_err(|_| TraceError::from(format!("invalid otlp header value for {}", name)))?;
+
+        metadata.insert(key, value);
+    }
+
+    return Ok(metadata);
+}
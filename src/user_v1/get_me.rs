@@ -1,33 +1,62 @@
-use crate::{authorization::AuthenticatedUser, db::get_user, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::UserFieldsRequest,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    etag::{Conditional, VersionTag, PRIVATE_REVALIDATE},
+    extract::ValidQuery,
+    repo::UserRepo,
+};
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     get,
-    web::{Data, Json, ReqData},
-    Responder, Result,
+    web::{Data, ReqData},
+    HttpRequest, Responder, Result,
 };
-use sqlx::MySqlPool;
 
 use super::user_types::UserPub;
 
 /// Fetches your own user record.
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    params(UserFieldsRequest),
+    responses(
+        (status = 200, description = "calling user", body = UserPub),
+        (status = 304, description = "unchanged since the version in `If-None-Match`"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/me")]
 pub async fn get_me(
+    request: HttpRequest,
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-) -> Result<impl Responder> {
-    let user_res = get_user(&pool, &authenticated_user.0).await;
+    user_repo: Data<dyn UserRepo>,
+    fields_request: ValidQuery<UserFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let user_res = user_repo.get_user(&authenticated_user.0).await;
 
     match user_res {
         Ok(user_opt) => {
             if let Some(user) = user_opt {
-                return Ok(Json(UserPub::from(user)));
+                let etag = VersionTag::new(&request, &authenticated_user.0)
+                    .add(&user.version())
+                    .finish();
+                let conditional = Conditional::new(&request, etag, PRIVATE_REVALIDATE);
+                if conditional.is_not_modified() {
+                    return Ok(conditional.not_modified());
+                }
+
+                return Ok(conditional.ok(&fields_request.sparse(UserPub::from(user))));
             } else {
-                return Err(ErrorNotFound("could not find user"));
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get user".to_string()));
+            return Err(AppError::internal("unable to get user", error));
         }
     }
 }
@@ -0,0 +1,54 @@
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::{BatchPub, BatchRequest, UserBatchPub, UserFieldsRequest},
+    docs::ErrorResponses,
+    error::AppError,
+    extract::{ValidJson, ValidQuery},
+    repo::UserRepo,
+};
+use actix_web::{
+    post,
+    web::{Data, Json, ReqData},
+    Responder, Result,
+};
+
+use super::user_types::UserPub;
+
+/// Fetches several users by id in one request.
+/// Ids that aren't found come back in `missing` instead of failing the request.
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    params(UserFieldsRequest),
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "found users, and the ids that were not", body = UserBatchPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
+#[post("/batch")]
+pub async fn get_user_batch(
+    _authenticated_user: ReqData<AuthenticatedUser>,
+    user_repo: Data<dyn UserRepo>,
+    batch_request: ValidJson<BatchRequest>,
+    fields_request: ValidQuery<UserFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let ids = batch_request.unique_ids();
+
+    let users_res = user_repo.get_users_by_ids(&ids).await;
+
+    match users_res {
+        Ok(users) => {
+            let batch = BatchPub::new(&ids, users, |user| user.id.clone());
+            let users_pub: Vec<UserPub> = batch.items.into_iter().map(UserPub::from).collect();
+            return Ok(Json(BatchPub {
+                items: fields_request.sparse_all(users_pub),
+                missing: batch.missing,
+            }));
+        }
+        Err(error) => {
+            return Err(AppError::internal("unable to get users", error));
+        }
+    }
+}
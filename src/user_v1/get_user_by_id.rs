@@ -1,40 +1,60 @@
-use crate::{authorization::AuthenticatedUser, db::get_user, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    compound_types::UserFieldsRequest,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode},
+    extract::ValidQuery,
+    repo::UserRepo,
+};
 use actix_web::{
-    error::{ErrorInternalServerError, ErrorNotFound},
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::user_types::UserPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetUserByIdRequest {
     id: String,
 }
 
 /// Fetches a user by id.
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    params(GetUserByIdRequest, UserFieldsRequest),
+    responses(
+        (status = 200, description = "matched user", body = UserPub),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/by_id")]
 pub async fn get_user_by_id(
     _authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
     get_user_request: Query<GetUserByIdRequest>,
-) -> Result<impl Responder> {
-    let user_res = get_user(&pool, &get_user_request.id).await;
+    fields_request: ValidQuery<UserFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let user_res = user_repo.get_user(&get_user_request.id).await;
 
     match user_res {
         Ok(user_opt) => {
             if let Some(user) = user_opt {
-                return Ok(Json(UserPub::from(user)));
+                return Ok(Json(fields_request.sparse(UserPub::from(user))));
             } else {
-                return Err(ErrorNotFound("could not find user"));
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get user".to_string()));
+            return Err(AppError::internal("unable to get user", error));
         }
     }
 }
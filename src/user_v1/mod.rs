@@ -1,6 +1,9 @@
 pub mod get_user_by_id;
 pub use get_user_by_id::*;
 
+pub mod get_user_batch;
+pub use get_user_batch::*;
+
 pub mod get_user_by_name;
 pub use get_user_by_name::*;
 
@@ -1,29 +1,43 @@
-use crate::{authorization::AuthenticatedUser, db::search_user_from_name, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser, compound_types::UserFieldsRequest, docs::ErrorResponses,
+    error::AppError, extract::ValidQuery, repo::UserRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     get,
     web::{Data, Json, Query, ReqData},
     Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
 use super::user_types::UserPub;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserSearchRequest {
     name: String,
 }
 
 /// Searches for users by name.
 /// Returns a list of the results.
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    params(UserSearchRequest, UserFieldsRequest),
+    responses(
+        (status = 200, description = "users matching the name", body = [UserPub]),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[get("/search_by_name")]
 pub async fn search_user_by_name(
     _authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
     search_request: Query<UserSearchRequest>,
-) -> Result<impl Responder> {
-    let search_result_res = search_user_from_name(&pool, &search_request.name).await;
+    fields_request: ValidQuery<UserFieldsRequest>,
+) -> Result<impl Responder, AppError> {
+    let search_result_res = user_repo.search_user_from_name(&search_request.name).await;
 
     match search_result_res {
         Ok(results) => {
@@ -31,11 +45,10 @@ pub async fn search_user_by_name(
                 .into_iter()
                 .map(|f| -> UserPub { f.into() })
                 .collect();
-            Ok(Json(friend_requests_pub))
+            return Ok(Json(fields_request.sparse_all(friend_requests_pub)));
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("could not complete user search"));
+            return Err(AppError::internal("could not complete user search", error));
         }
     }
 }
@@ -1,49 +1,80 @@
 use crate::{
     authorization::AuthenticatedUser,
-    db::{does_user_exist_by_name, get_user, update_usernames, User},
-    tracing::add_error_span,
+    db::User,
+    docs::ErrorResponses,
+    error::{AppError, ErrorCode, FieldError},
+    extract::{FieldErrors, ValidJson, Validate},
+    repo::UserRepo,
 };
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
     post,
-    web::{Data, Json, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::ToSchema;
 use validation::{validate_display_name, validate_name};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateUserRequest {
     display_name: Option<String>,
     name: Option<String>,
 }
 
+impl Validate for UpdateUserRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        if let Some(display_name) = &self.display_name {
+            errors.check("display_name", validate_display_name(display_name));
+        }
+
+        // names are stored lowercase, so that's what needs to be valid.
+        if let Some(name) = &self.name {
+            errors.check("name", validate_name(&name.to_lowercase()));
+        }
+
+        errors.finish()
+    }
+}
+
 /// Allows the updating of display_name and name fields.
 /// display_name isn't unique in the table; but name is.
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "user was updated"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("")]
 pub async fn update_user(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    update_request: Json<UpdateUserRequest>,
-) -> Result<impl Responder> {
+    user_repo: Data<dyn UserRepo>,
+    update_request: ValidJson<UpdateUserRequest>,
+) -> Result<impl Responder, AppError> {
     let new_display_name: String;
     let new_name: String;
 
     let user: User;
-    let user_res = get_user(&pool, &authenticated_user.0).await;
+    let user_res = user_repo.get_user(&authenticated_user.0).await;
 
     match user_res {
         Ok(user_opt) => {
             if let Some(user_tmp) = user_opt {
                 user = user_tmp;
             } else {
-                return Err(ErrorNotFound("could not find user"));
+                return Err(AppError::not_found(
+                    ErrorCode::UserNotFound,
+                    "could not find user",
+                ));
             }
         }
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("unable to get user"));
+            return Err(AppError::internal("unable to get user", error));
         }
     }
 
@@ -61,38 +92,32 @@ pub async fn update_user(
         None => new_name = user.name.clone(),
     }
 
-    if let Err(err) = validate_display_name(&new_display_name) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
-    if let Err(err) = validate_name(&new_name) {
-        return Err(ErrorBadRequest(err.to_string()));
-    }
-
     if new_name != user.name {
-        let existing_user_res = does_user_exist_by_name(&pool, &new_name).await;
+        let existing_user_res = user_repo.does_user_exist_by_name(&new_name).await;
 
         match existing_user_res {
             Ok(exists) => {
                 if exists {
-                    return Err(ErrorBadRequest("user already exists"));
+                    return Err(AppError::bad_request(
+                        ErrorCode::UserExists,
+                        "user already exists",
+                    ));
                 }
             }
             Err(error) => {
-                add_error_span(&error);
-                return Err(ErrorInternalServerError("failed to get existing users"));
+                return Err(AppError::internal("failed to get existing users", error));
             }
         }
     }
 
-    let update_res =
-        update_usernames(&pool, &authenticated_user.0, &new_display_name, &new_name).await;
+    let update_res = user_repo
+        .update_usernames(&authenticated_user.0, &new_display_name, &new_name)
+        .await;
 
     match update_res {
         Ok(_) => return Ok(HttpResponse::Ok().finish()),
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to update user"));
+            return Err(AppError::internal("failed to update user", error));
         }
     }
 }
@@ -1,32 +1,43 @@
-use crate::{authorization::AuthenticatedUser, db::update_device_token, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser, docs::ErrorResponses, error::AppError, repo::UserRepo,
+};
 use actix_web::{
-    error::ErrorInternalServerError,
     post,
     web::{Data, Query, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UpdateUserDeviceTokenRequest {
     device_token: String,
 }
 
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    responses(
+        (status = 200, description = "device token was stored"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/device_token")]
 pub async fn update_user_device_token(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
+    user_repo: Data<dyn UserRepo>,
     update_request: Query<UpdateUserDeviceTokenRequest>,
-) -> Result<impl Responder> {
-    let update_res =
-        update_device_token(&pool, &authenticated_user.0, &update_request.device_token).await;
+) -> Result<impl Responder, AppError> {
+    let update_res = user_repo
+        .update_device_token(&authenticated_user.0, &update_request.device_token)
+        .await;
 
     match update_res {
         Ok(_) => return Ok(HttpResponse::Ok().finish()),
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to update user"));
+            return Err(AppError::internal("failed to update user", error));
         }
     }
 }
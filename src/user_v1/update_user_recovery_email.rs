@@ -1,37 +1,61 @@
-use crate::{authorization::AuthenticatedUser, db::update_recovery_email, tracing::add_error_span};
+use crate::{
+    authorization::AuthenticatedUser,
+    docs::ErrorResponses,
+    error::{AppError, FieldError},
+    extract::{FieldErrors, ValidQuery, Validate},
+    repo::UserRepo,
+};
 use actix_web::{
-    error::{ErrorBadRequest, ErrorInternalServerError},
     post,
-    web::{Data, Query, ReqData},
+    web::{Data, ReqData},
     HttpResponse, Responder, Result,
 };
 use serde::Deserialize;
-use sqlx::MySqlPool;
+use utoipa::IntoParams;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UpdateUserRecoveryEmailRequest {
     recovery_email: String,
 }
 
+impl Validate for UpdateUserRecoveryEmailRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::new();
+
+        errors.check(
+            "recovery_email",
+            validation::validate_email(&self.recovery_email),
+        );
+
+        errors.finish()
+    }
+}
+
+#[utoipa::path(
+    context_path = "/api/v1/user",
+    tag = "user",
+    params(UpdateUserRecoveryEmailRequest),
+    responses(
+        (status = 200, description = "recovery email was stored"),
+        ErrorResponses
+    ),
+    security(("jwt" = []))
+)]
 #[post("/recovery_email")]
 pub async fn update_user_recovery_email(
     authenticated_user: ReqData<AuthenticatedUser>,
-    pool: Data<MySqlPool>,
-    update_request: Query<UpdateUserRecoveryEmailRequest>,
-) -> Result<impl Responder> {
-    let valid_email = validation::validate_email(&update_request.recovery_email);
-    if let Err(email_err) = valid_email {
-        return Err(ErrorBadRequest(email_err.to_string()));
-    }
-
-    let update_res =
-        update_recovery_email(&pool, &authenticated_user.0, &update_request.recovery_email).await;
+    user_repo: Data<dyn UserRepo>,
+    update_request: ValidQuery<UpdateUserRecoveryEmailRequest>,
+) -> Result<impl Responder, AppError> {
+    let update_res = user_repo
+        .update_recovery_email(&authenticated_user.0, &update_request.recovery_email)
+        .await;
 
     match update_res {
         Ok(_) => return Ok(HttpResponse::Ok().finish()),
         Err(error) => {
-            add_error_span(&error);
-            return Err(ErrorInternalServerError("failed to update user"));
+            return Err(AppError::internal("failed to update user", error));
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::{db::User, pic_v1::get_digital_ocean_url};
 use serde::Serialize;
+use utoipa::ToSchema;
 
 /// DB Types are purposefuly not serialized.
 /// We require DTO objects suffixed with 'Pub'
 /// to trim database object appropriately.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UserPub {
     pub id: String,
     pub name: String,